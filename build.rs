@@ -0,0 +1,10 @@
+fn main() {
+    // 启用grpc功能时根据proto文件生成服务代码, 使用内置的protoc, 无需另外安装
+    #[cfg(feature = "grpc")]
+    {
+        println!("cargo:rerun-if-changed=proto/jd_farm.proto");
+        let protoc = protoc_bin_vendored::protoc_bin_path().expect("protoc not available");
+        std::env::set_var("PROTOC", protoc);
+        tonic_build::compile_protos("proto/jd_farm.proto").expect("failed to compile protos");
+    }
+}
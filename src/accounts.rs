@@ -0,0 +1,368 @@
+use std::collections::{HashMap, HashSet};
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+
+use anyhow::Result;
+use chrono::{DateTime, Local, TimeZone};
+use jd_com::account::{get_accounts, JAccount};
+use tracing::{info, warn};
+
+use crate::report::AccountReport;
+use crate::state::{self, FileStateStore, MemoryStateStore, StateStore};
+
+// 保存的果树进度的有效期, 长时间未运行的账号不再参与排序
+const PROGRESS_TTL: Duration = Duration::from_secs(7 * 24 * 3600);
+
+// JD_COOKIE_FILE: 账号文件路径, 每行一个cookie, #开头的行为注释, 与JD_COOKIE中的账号合并
+pub fn file_from_env() -> Option<PathBuf> {
+    env::var("JD_COOKIE_FILE")
+        .ok()
+        .filter(|p| !p.trim().is_empty())
+        .map(PathBuf::from)
+}
+
+// 读取账号文件中的cookie, 以&连接
+pub fn read_file(path: &Path) -> Result<String> {
+    let content = fs::read_to_string(path)?;
+    let cookies: Vec<&str> = content
+        .lines()
+        .map(str::trim)
+        .filter(|l| !l.is_empty() && !l.starts_with('#'))
+        .map(|l| l.trim_matches('&'))
+        .collect();
+    Ok(cookies.join("&"))
+}
+
+// 合并JD_COOKIE与账号文件中的cookie, 每次调用都重新读取账号文件
+pub fn merge(jd_cookie: &str) -> String {
+    let Some(path) = file_from_env() else {
+        return jd_cookie.to_string();
+    };
+    match read_file(&path) {
+        Ok(file) => [jd_cookie.trim_matches('&'), file.as_str()]
+            .into_iter()
+            .filter(|c| !c.is_empty())
+            .collect::<Vec<_>>()
+            .join("&"),
+        Err(e) => {
+            info!("{}", t!("读取账号文件{}失败, {}", path.display(), e));
+            jd_cookie.to_string()
+        }
+    }
+}
+
+// JD_COOKIE及账号文件中的所有账号, 同名账号只保留第一个
+pub fn load(jd_cookie: &str) -> Vec<JAccount> {
+    pool(get_accounts(merge(jd_cookie))).0
+}
+
+// JD_COOKIE及账号文件中同名账号(同一pt_pin)的其他cookie, 如在不同设备登录获得的cookie
+pub fn backups(jd_cookie: &str) -> HashMap<String, Vec<JAccount>> {
+    pool(get_accounts(merge(jd_cookie))).1
+}
+
+// 按账号名称分组, 返回每个账号的第一个cookie, 及各账号按出现顺序排列的备用cookie, 重复的cookie只保留一个
+// 运行时主cookie失效后依次改用备用cookie
+pub fn pool(accounts: Vec<JAccount>) -> (Vec<JAccount>, HashMap<String, Vec<JAccount>>) {
+    let mut cookies = HashSet::new();
+    let mut primary = Vec::new();
+    let mut backups: HashMap<String, Vec<JAccount>> = HashMap::new();
+    for account in accounts {
+        if !cookies.insert(account.cookie()) {
+            continue;
+        }
+        let name = account.name();
+        match primary.iter().any(|a: &JAccount| a.name() == name) {
+            true => backups.entry(name).or_default().push(account),
+            false => primary.push(account),
+        }
+    }
+    (primary, backups)
+}
+
+// 账号的运行优先级, 优先级高的账号先开始运行, 运行结束时也优先重试
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct AccountPriority {
+    // 各账号的优先级, 数值越大越先运行, 未设置的账号为0
+    pub weights: HashMap<String, i32>,
+    // 优先级相同时按上次运行的果树进度排序, 越接近成熟越先运行
+    pub near_harvest: bool,
+}
+
+impl AccountPriority {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // 设置账号的优先级
+    pub fn weight(mut self, name: &str, weight: i32) -> Self {
+        self.weights.insert(name.to_string(), weight);
+        self
+    }
+
+    // 优先级相同时是否优先运行接近成熟的账号
+    pub fn near_harvest(mut self, enabled: bool) -> Self {
+        self.near_harvest = enabled;
+        self
+    }
+
+    // ACCOUNT_PRIORITY: 各账号的优先级, 如`账号1=10,账号2=5`, 数值越大越先运行, 未设置的账号为0
+    // PRIORITY_NEAR_HARVEST: 优先级相同时是否优先运行果树接近成熟的账号, 默认true, 设置为false按账号顺序运行
+    pub fn from_env() -> Self {
+        let weights = env::var("ACCOUNT_PRIORITY")
+            .unwrap_or_default()
+            .split(',')
+            .filter_map(|item| {
+                let (name, weight) = item.split_once('=')?;
+                Some((name.trim().to_string(), weight.trim().parse().ok()?))
+            })
+            .collect();
+        Self {
+            weights,
+            near_harvest: !matches!(
+                env::var("PRIORITY_NEAR_HARVEST").as_deref().map(str::trim),
+                Ok("false") | Ok("0")
+            ),
+        }
+    }
+
+    // 账号的优先级
+    pub fn weight_of(&self, name: &str) -> i32 {
+        self.weights.get(name).copied().unwrap_or_default()
+    }
+
+    // 按优先级排列的账号下标, progress为各账号上次运行的果树进度(%), 其余情况保持原有顺序
+    pub fn order(&self, names: &[String], progress: &HashMap<String, f64>) -> Vec<usize> {
+        let mut order: Vec<usize> = (0..names.len()).collect();
+        let progress_of = |index: usize| match self.near_harvest {
+            true => progress.get(&names[index]).copied().unwrap_or_default(),
+            false => 0.0,
+        };
+        order.sort_by(|a, b| {
+            self.weight_of(&names[*b])
+                .cmp(&self.weight_of(&names[*a]))
+                .then(progress_of(*b).total_cmp(&progress_of(*a)))
+        });
+        order
+    }
+}
+
+fn progress_key(name: &str) -> String {
+    format!("progress:{}", name)
+}
+
+// 读取各账号上次运行的果树进度(%)
+pub async fn load_progress(store: &dyn StateStore, names: &[String]) -> HashMap<String, f64> {
+    let mut progress = HashMap::new();
+    for name in names {
+        match state::load::<Option<f64>>(store, &progress_key(name)).await {
+            Ok(Some(value)) => {
+                progress.insert(name.clone(), value);
+            }
+            Ok(None) => {}
+            Err(e) => info!("{}", t!("{}, 读取果树进度失败, {}", name, e)),
+        }
+    }
+    progress
+}
+
+// 保存各账号本次运行的果树进度, 未获取到果树信息的账号保留上次的进度
+pub async fn save_progress(store: &dyn StateStore, reports: &[AccountReport]) {
+    for report in reports.iter().filter(|r| r.tree_total_energy > 0) {
+        let key = progress_key(&report.name);
+        if let Err(e) = state::save(store, &key, &report.tree_progress(), Some(PROGRESS_TTL)).await
+        {
+            info!("{}", t!("{}, 保存果树进度失败, {}", report.name, e));
+        }
+    }
+}
+
+// 触发风控或需要安全验证的账号暂停运行一段时间, 暂停期间的运行跳过该账号, 到期后自动恢复
+// 暂停的截止时间保存在cooldown_store中, 通过cron等多次启动运行时也会保留
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RiskCooldown {
+    // 暂停运行的时长
+    pub duration: Duration,
+    // 一次运行中触发风控的请求数达到该值时暂停, 需要安全验证时总是暂停
+    pub threshold: u32,
+}
+
+impl Default for RiskCooldown {
+    fn default() -> Self {
+        Self {
+            duration: Duration::from_secs(6 * 3600),
+            threshold: 1,
+        }
+    }
+}
+
+fn cooldown_key(name: &str) -> String {
+    format!("cooldown:{}", name)
+}
+
+// 默认的风控暂停状态文件: $XDG_STATE_HOME、%LOCALAPPDATA%或~/.local/state下的jd_farm/cooldown.json, 都不可用时在临时目录下
+fn default_cooldown_file() -> PathBuf {
+    env::var_os("XDG_STATE_HOME")
+        .or_else(|| env::var_os("LOCALAPPDATA"))
+        .map(PathBuf::from)
+        .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".local/state")))
+        .unwrap_or_else(env::temp_dir)
+        .join("jd_farm")
+        .join("cooldown.json")
+}
+
+// 保存风控暂停状态的存储, 设置STATE_STORE时与其他运行状态共用
+// RISK_COOLDOWN_FILE: 未设置STATE_STORE时保存暂停状态的文件, 默认见default_cooldown_file
+pub fn cooldown_store() -> Arc<dyn StateStore> {
+    if let Some(store) = state::from_env() {
+        return store;
+    }
+    let path = env::var("RISK_COOLDOWN_FILE")
+        .ok()
+        .filter(|p| !p.trim().is_empty())
+        .map(PathBuf::from)
+        .unwrap_or_else(default_cooldown_file);
+    match FileStateStore::open(&path) {
+        Ok(store) => Arc::new(store),
+        Err(e) => {
+            warn!(
+                "{}",
+                t!(
+                    "打开风控暂停状态文件{}失败, 暂停状态不会在多次运行之间保留, {}",
+                    path.display(),
+                    e
+                )
+            );
+            Arc::new(MemoryStateStore::default())
+        }
+    }
+}
+
+impl RiskCooldown {
+    // RISK_COOLDOWN: 触发风控后暂停运行该账号的时长(小时), 默认6, 设置为0关闭
+    // RISK_COOLDOWN_THRESHOLD: 一次运行中触发风控的请求数达到该值时暂停, 默认1
+    pub fn from_env() -> Option<Self> {
+        let mut cooldown = Self::default();
+        if let Some(hours) = env::var("RISK_COOLDOWN")
+            .ok()
+            .and_then(|v| v.trim().parse::<f64>().ok())
+        {
+            if hours <= 0.0 {
+                return None;
+            }
+            cooldown.duration = Duration::from_secs_f64(hours * 3600.0);
+        }
+        if let Some(threshold) = env::var("RISK_COOLDOWN_THRESHOLD")
+            .ok()
+            .and_then(|v| v.trim().parse::<u32>().ok())
+        {
+            cooldown.threshold = threshold.max(1);
+        }
+        Some(cooldown)
+    }
+
+    // 本次运行后是否需要暂停该账号
+    pub fn flagged(&self, report: &AccountReport) -> bool {
+        report.verification_url.is_some() || report.risk_control >= self.threshold
+    }
+
+    // 仍在暂停中的账号及其恢复运行的时间
+    pub async fn paused(
+        &self,
+        store: &dyn StateStore,
+        names: &[String],
+    ) -> HashMap<String, DateTime<Local>> {
+        let now = Local::now();
+        let mut paused = HashMap::new();
+        for name in names {
+            match state::load::<Option<i64>>(store, &cooldown_key(name)).await {
+                Ok(Some(until)) => {
+                    if let Some(until) = Local.timestamp_opt(until, 0).single() {
+                        if until > now {
+                            paused.insert(name.clone(), until);
+                        }
+                    }
+                }
+                Ok(None) => {}
+                Err(e) => info!("{}", t!("{}, 读取风控暂停状态失败, {}", name, e)),
+            }
+        }
+        paused
+    }
+
+    // 记录本次运行中触发风控的账号, 暂停运行至duration之后
+    pub async fn record(&self, store: &dyn StateStore, reports: &[AccountReport]) {
+        let until = Local::now() + chrono::Duration::seconds(self.duration.as_secs() as i64);
+        for report in reports.iter().filter(|r| self.flagged(r)) {
+            let key = cooldown_key(&report.name);
+            match state::save(store, &key, &until.timestamp(), Some(self.duration)).await {
+                Ok(()) => info!(
+                    "{}",
+                    t!(
+                        "{}, 触发风控, 暂停运行至{}",
+                        report.name,
+                        until.format("%m-%d %H:%M")
+                    )
+                ),
+                Err(e) => info!("{}", t!("{}, 保存风控暂停状态失败, {}", report.name, e)),
+            }
+        }
+    }
+}
+
+// 账号文件的修改时间及大小, 用于判断文件是否被修改
+fn stamp(path: &Path) -> Option<(SystemTime, u64)> {
+    let metadata = fs::metadata(path).ok()?;
+    Some((metadata.modified().ok()?, metadata.len()))
+}
+
+// 监视账号文件, 找出新增的账号
+pub struct AccountWatcher {
+    path: PathBuf,
+    stamp: Option<(SystemTime, u64)>,
+    // 已知的账号名称
+    known: HashSet<String>,
+}
+
+impl AccountWatcher {
+    pub fn new(path: PathBuf) -> Self {
+        Self {
+            stamp: stamp(&path),
+            path,
+            known: HashSet::new(),
+        }
+    }
+
+    // 未设置JD_COOKIE_FILE时为空
+    pub fn from_env() -> Option<Self> {
+        file_from_env().map(Self::new)
+    }
+
+    // 记录已运行的账号, 不再作为新增账号返回
+    pub fn observe(&mut self, accounts: &[JAccount]) {
+        self.known.extend(accounts.iter().map(|a| a.name()));
+    }
+
+    // 账号文件被修改后返回新增的账号
+    pub fn poll(&mut self) -> Vec<JAccount> {
+        let stamp = stamp(&self.path);
+        if stamp == self.stamp {
+            return Vec::new();
+        }
+        self.stamp = stamp;
+        let cookies = match read_file(&self.path) {
+            Ok(cookies) => cookies,
+            Err(e) => {
+                info!("{}", t!("读取账号文件{}失败, {}", self.path.display(), e));
+                return Vec::new();
+            }
+        };
+        get_accounts(cookies)
+            .into_iter()
+            .filter(|a| self.known.insert(a.name()))
+            .collect()
+    }
+}
@@ -0,0 +1,714 @@
+use std::collections::{HashSet, VecDeque};
+use std::sync::{Arc, Mutex};
+
+use anyhow::{anyhow, Result};
+use jd_com::account::JAccount;
+use reqwest::{
+    header::{HeaderMap, HeaderValue, USER_AGENT},
+    Client,
+};
+use serde::{de::DeserializeOwned, Serialize};
+use serde_json::{json, Value};
+use tokio::sync::broadcast;
+use tracing::{info, instrument, warn, Span};
+
+use crate::assist::AssistStore;
+use crate::clock;
+use crate::device::Device;
+use crate::error::{is_verification_url, ErrorPolicy, JError};
+use crate::event::RunEvent;
+#[cfg(feature = "metrics")]
+use crate::metrics;
+use crate::models::{
+    CardInfo, ClockInTask, FarmData, FriendInfo, FriendInfoList, JdFarmInfo, Response, TaskInfo,
+    Validate, WaterRecord, WaterRecordList,
+};
+use crate::network::NetworkLimiter;
+use crate::options::RunOptions;
+use crate::plugin::FarmTasks;
+use crate::report::{AccountReport, PrizeReport, TaskReport};
+use crate::simulate::Simulator;
+use crate::state::{self, MemoryStateStore, StateStore};
+use crate::version::{self, INIT_FARM_BODY};
+use crate::{i18n, logging, redact};
+
+// 领取奖励等变更类接口, 同一请求在本次运行中成功后不再重复发送, 避免重复领取被风控
+const ONCE_FUNCTION_IDS: [&str; 9] = [
+    "firstWaterTaskForFarm",
+    "totalWaterTaskForFarm",
+    "waterFriendGotAwardForFarm",
+    "gotThreeMealForFarm",
+    "gotStageAwardForFarm",
+    "ddnc_getTreasureBoxAward",
+    "browseAdTaskForFarm",
+    "clockInForFarm",
+    "clockInFollowForFarm",
+];
+
+// 运行中断时保存的检查点的保留时间, 在此期间重新运行时跳过已成功的变更类请求
+const CHECKPOINT_TTL: std::time::Duration = std::time::Duration::from_secs(3600);
+
+pub struct JClient {
+    // 使用当前cookie的客户端, 改用备用cookie时替换
+    pub(crate) client: Mutex<Client>,
+    pub(crate) base_url: String,
+    pub(crate) account: JAccount,
+    // 同一账号尚未使用的备用cookie, 当前cookie失效时依次改用
+    pub(crate) backups: Mutex<VecDeque<JAccount>>,
+    // 本次运行中已成功的变更类请求
+    pub(crate) succeeded: Mutex<HashSet<String>>,
+    // 本次运行结果
+    pub(crate) report: Mutex<AccountReport>,
+    // 运行事件
+    pub(crate) events: Option<broadcast::Sender<RunEvent>>,
+    // 任务出错时的处理策略
+    pub(crate) error_policy: ErrorPolicy,
+    // 本次运行的选项
+    pub(crate) options: Mutex<RunOptions>,
+    // 内置任务之后执行的外部任务
+    pub(crate) plugins: FarmTasks,
+    // 助力码池, 为空时不执行助力好友任务
+    pub(crate) assist: Option<Arc<dyn AssistStore>>,
+    // 请求频率、并发数限制及网络错误重试
+    pub(crate) network: Arc<NetworkLimiter>,
+    // 运行状态存储, 保存检查点、已浇水的好友等
+    pub(crate) state: Arc<dyn StateStore>,
+    // 模拟的接口, 设置后不发送任何网络请求
+    pub(crate) simulator: Option<Arc<Simulator>>,
+    // 请求模拟的客户端设备
+    pub(crate) device: Device,
+}
+
+impl JClient {
+    pub fn new(account: JAccount) -> Self {
+        let client = Self::build_client(&account);
+        let base_url = "https://api.m.jd.com/client.action".to_string();
+        Self {
+            client: Mutex::new(client),
+            base_url,
+            succeeded: Mutex::new(HashSet::new()),
+            report: Mutex::new(AccountReport::new(&account.name())),
+            account,
+            backups: Mutex::new(VecDeque::new()),
+            events: None,
+            error_policy: ErrorPolicy::default(),
+            options: Mutex::new(RunOptions::default()),
+            plugins: Vec::new(),
+            assist: None,
+            network: Arc::new(NetworkLimiter::default()),
+            state: Arc::new(MemoryStateStore::default()),
+            simulator: None,
+            device: Device::default(),
+        }
+    }
+
+    // 使用账号cookie的客户端
+    fn build_client(account: &JAccount) -> Client {
+        redact::register_cookie(&account.cookie());
+        let mut headers = HeaderMap::new();
+
+        headers.append(
+            "cookie",
+            HeaderValue::from_str(account.cookie().as_str()).unwrap(),
+        );
+        headers.append(
+            "referer",
+            HeaderValue::from_str("https://carry.m.jd.com/").unwrap(),
+        );
+
+        headers.append(
+            "referer",
+            HeaderValue::from_str("https://carry.m.jd.com").unwrap(),
+        );
+
+        Client::builder().default_headers(headers).build().unwrap()
+    }
+
+    // 设置同一账号的备用cookie, 当前cookie失效时按顺序改用, 之后的请求及任务使用新的cookie
+    pub fn with_backup_cookies(self, backups: Vec<JAccount>) -> Self {
+        for backup in &backups {
+            redact::register_cookie(&backup.cookie());
+        }
+        *self.backups.lock().unwrap() = backups.into();
+        self
+    }
+
+    // 将运行事件发送到指定通道
+    pub fn with_events(mut self, events: broadcast::Sender<RunEvent>) -> Self {
+        self.events = Some(events);
+        self
+    }
+
+    // 设置任务出错时的处理策略, 默认记录错误后继续执行
+    pub fn with_error_policy(mut self, policy: ErrorPolicy) -> Self {
+        self.error_policy = policy;
+        self
+    }
+
+    // 设置外部任务, 在内置任务之后依次执行
+    pub fn with_tasks(mut self, tasks: FarmTasks) -> Self {
+        self.plugins = tasks;
+        self
+    }
+
+    // 设置助力码池, 设置后执行助力好友任务
+    pub fn with_assist(mut self, store: Arc<dyn AssistStore>) -> Self {
+        self.assist = Some(store);
+        self
+    }
+
+    // 设置网络限制, 多个账号共用同一个限制器时共享请求频率及并发数限制
+    pub fn with_network(mut self, network: Arc<NetworkLimiter>) -> Self {
+        self.network = network;
+        self
+    }
+
+    // 设置运行状态存储, 默认保存在内存中
+    pub fn with_state(mut self, state: Arc<dyn StateStore>) -> Self {
+        self.state = state;
+        self
+    }
+
+    // 设置请求模拟的客户端设备, 默认iPhone
+    pub fn with_device(mut self, device: Device) -> Self {
+        self.device = device;
+        self
+    }
+
+    // 使用模拟的接口, 不发送任何网络请求
+    pub fn with_simulator(mut self, simulator: Arc<Simulator>) -> Self {
+        self.simulator = Some(simulator);
+        self
+    }
+
+    // 账号名称
+    pub fn account_name(&self) -> String {
+        self.account.name()
+    }
+
+    // 已执行任务的结果, 单独执行任务时可通过该方法获取
+    pub fn report(&self) -> AccountReport {
+        self.report.lock().unwrap().clone()
+    }
+
+    // 本次运行的选项
+    pub(crate) fn options(&self) -> RunOptions {
+        self.options.lock().unwrap().clone()
+    }
+
+    // 连续操作之间按等待策略等待, default为任务默认的等待时间, 模拟运行时不等待
+    pub(crate) async fn pause(&self, default: std::time::Duration) {
+        if self.simulator.is_some() {
+            return;
+        }
+        let delay = self.options.lock().unwrap().delay.apply(default);
+        tokio::time::sleep(delay).await;
+    }
+
+    // 按请求超时设置及模拟的设备构造请求
+    pub(crate) fn post(&self, url: String) -> reqwest::RequestBuilder {
+        let client = self.client.lock().unwrap().clone();
+        let builder = client
+            .post(url)
+            .header(USER_AGENT, self.device.user_agent());
+        match self.options.lock().unwrap().request_timeout {
+            Some(timeout) => builder.timeout(timeout),
+            None => builder,
+        }
+    }
+
+    fn checkpoint_key(&self) -> String {
+        format!("checkpoint:{}", self.account.name())
+    }
+
+    // 读取上次中断运行的检查点, 跳过其中已成功的变更类请求
+    pub(crate) async fn restore_checkpoint(&self) {
+        match state::load::<Vec<String>>(self.state.as_ref(), &self.checkpoint_key()).await {
+            Ok(keys) => self.succeeded.lock().unwrap().extend(keys),
+            Err(e) => info!("{}", t!("{}, 读取检查点失败, {}", self.account.name(), e)),
+        }
+    }
+
+    // 保存检查点
+    async fn save_checkpoint(&self) {
+        let keys: Vec<String> = self.succeeded.lock().unwrap().iter().cloned().collect();
+        let key = self.checkpoint_key();
+        if let Err(e) = state::save(self.state.as_ref(), &key, &keys, Some(CHECKPOINT_TTL)).await {
+            info!("{}", t!("{}, 保存检查点失败, {}", self.account.name(), e));
+        }
+    }
+
+    // 运行正常结束后删除检查点
+    pub(crate) async fn clear_checkpoint(&self) {
+        if let Err(e) = self.state.remove(&self.checkpoint_key()).await {
+            info!("{}", t!("{}, 删除检查点失败, {}", self.account.name(), e));
+        }
+    }
+
+    // 按网络限制发送请求并解析返回的JSON, 网络错误时按退避策略重试
+    pub(crate) async fn send(
+        &self,
+        endpoint: &str,
+        url: &str,
+        body: &str,
+    ) -> std::result::Result<Value, JError> {
+        // 需要完成安全验证时不再发送请求, 直到本次运行结束
+        if let Some(url) = self.report.lock().unwrap().verification_url.clone() {
+            return Err(JError::NeedsVerification { url });
+        }
+        let backoff = self.network.policy().backoff;
+        let mut attempt = 0;
+        loop {
+            let res = match &self.simulator {
+                Some(simulator) => simulator.respond(&self.account.name(), endpoint, body),
+                None => {
+                    let _permit = self.network.request().await;
+                    match self
+                        .post(url.to_string())
+                        .body(format!("body={:?}", body))
+                        .send()
+                        .await
+                    {
+                        // 被重定向到验证页面
+                        Ok(res) if is_verification_url(res.url().as_str()) => {
+                            Err(JError::NeedsVerification {
+                                url: res.url().to_string(),
+                            })
+                        }
+                        Ok(res) => {
+                            clock::observe(res.headers());
+                            res.json::<Value>()
+                                .await
+                                .map_err(|e| JError::parse(endpoint, e))
+                        }
+                        Err(e) => Err(JError::Network(e.to_string())),
+                    }
+                }
+            };
+            let res = res.and_then(|raw| {
+                let code = match &raw["code"] {
+                    Value::String(code) => code.clone(),
+                    Value::Number(code) => code.to_string(),
+                    _ => String::new(),
+                };
+                match JError::from_response(&code, &raw) {
+                    Some(e @ JError::NeedsVerification { .. }) => Err(e),
+                    _ => Ok(raw),
+                }
+            });
+            // cookie已失效时改用备用cookie重新发送该请求
+            if let Ok(raw) = &res {
+                if matches!(Self::response_error(raw), Some(JError::NotLoggedIn)) && self.failover()
+                {
+                    continue;
+                }
+            }
+            if let Err(JError::NeedsVerification { url }) = &res {
+                warn!(
+                    "{}",
+                    t!(
+                        "{}, 请求{}需要完成安全验证, 停止运行该账号",
+                        self.account.name(),
+                        endpoint
+                    )
+                );
+                self.report.lock().unwrap().verification_url = Some(url.clone());
+            }
+            match res {
+                Err(e) if e.is_retryable() && attempt < backoff.retries => {
+                    let delay = backoff.delay(attempt);
+                    attempt += 1;
+                    info!(
+                        "{}",
+                        t!(
+                            "{}, 请求{}失败, {}秒后第{}次重试, {}",
+                            self.account.name(),
+                            endpoint,
+                            delay.as_secs_f64(),
+                            attempt,
+                            e
+                        )
+                    );
+                    tokio::time::sleep(delay).await;
+                }
+                res => return res,
+            }
+        }
+    }
+
+    // 改用下一个备用cookie, 没有备用cookie时返回false
+    fn failover(&self) -> bool {
+        let Some(backup) = self.backups.lock().unwrap().pop_front() else {
+            return false;
+        };
+        *self.client.lock().unwrap() = Self::build_client(&backup);
+        let index = {
+            let mut report = self.report.lock().unwrap();
+            report.cookie_index += 1;
+            report.cookie_index
+        };
+        warn!(
+            "{}",
+            t!(
+                "{}, cookie已失效, 改用第{}个备用cookie",
+                self.account.name(),
+                index
+            )
+        );
+        true
+    }
+
+    // 发送运行事件, 没有订阅者时忽略
+    pub(crate) fn emit(&self, event: RunEvent) {
+        if let Some(events) = &self.events {
+            let _ = events.send(event);
+        }
+    }
+
+    // 记录开始执行任务
+    pub fn task_started(&self, name: &str) {
+        self.emit(RunEvent::TaskStarted {
+            account: self.account.name(),
+            task: name.to_string(),
+        });
+    }
+
+    // 请求数据
+    // function_id: &str
+    // body: &string
+    #[instrument(skip(self, body), fields(code))]
+    pub async fn request(&self, function_id: &str, body: &str) -> Result<Value> {
+        let body = &self.device.apply_body(&version::current().apply(body));
+        let once_key = match ONCE_FUNCTION_IDS.contains(&function_id) {
+            true => Some(format!("{}:{}", function_id, body)),
+            false => None,
+        };
+        if let Some(key) = &once_key {
+            if self.succeeded.lock().unwrap().contains(key) {
+                info!(
+                    "{}",
+                    t!(
+                        "{}, 请求{}本次运行已成功, 跳过重复请求!",
+                        self.account.name(),
+                        function_id
+                    )
+                );
+                return Err(anyhow!(JError::DuplicateRequest));
+            }
+        }
+
+        let sign = self.device.sign(function_id, body);
+        let url = format!("{}?{}&appid=signed_wh5", self.base_url, sign);
+        let res = self.send(function_id, &url, body).await;
+
+        match &res {
+            Ok(data) => {
+                Span::current().record("code", data["code"].as_str().unwrap_or("888"));
+                self.record_request(function_id, self.is_success(data));
+                self.record_risk(data["code"].as_str().unwrap_or("999"), data);
+            }
+            Err(_) => self.record_request(function_id, false),
+        }
+
+        let data = res?;
+        if data.get("code").is_none() {
+            return Err(anyhow!(JError::parse(function_id, t!("缺少返回码"))));
+        }
+        if let Some(key) = once_key {
+            if self.is_success(&data) {
+                self.succeeded.lock().unwrap().insert(key);
+                self.save_checkpoint().await;
+            }
+        }
+        Ok(data)
+    }
+
+    // 请求接口并解析返回数据
+    pub async fn call<T: DeserializeOwned + Serialize + Default>(
+        &self,
+        function_id: &str,
+        body: &str,
+    ) -> Result<Response<T>> {
+        let raw = self.request(function_id, body).await?;
+        let code = raw["code"].as_str().unwrap_or("999").to_string();
+        let data = match code == "0" {
+            true => {
+                let data = serde_json::from_value(raw.clone()).map_err(|e| {
+                    let e = JError::parse(function_id, e);
+                    info!("{}, {}", self.account.name(), e);
+                    e
+                })?;
+                self.check_schema(function_id, &raw, &data).await;
+                data
+            }
+            false => T::default(),
+        };
+        Ok(Response { code, data, raw })
+    }
+
+    // 请求萌宠、种豆得豆等其他活动的接口, 各活动的版本与农场不同, 不使用探测到的农场接口版本
+    // code_key为活动自己的返回码, code及code_key都为0时表示操作成功, 此时解析data_key中的业务数据
+    pub(crate) async fn activity_call<T: DeserializeOwned + Serialize + Default>(
+        &self,
+        function_id: &str,
+        body: Value,
+        code_key: Option<&str>,
+        data_key: &str,
+    ) -> Result<Response<T>> {
+        let body = self.device.apply_body(&body.to_string());
+        let url = format!(
+            "{}?{}&appid=signed_wh5",
+            self.base_url,
+            self.device.sign(function_id, &body)
+        );
+        let res = self.send(function_id, &url, &body).await;
+        let code = |raw: &Value| -> Option<String> {
+            let code = raw["code"].as_str()?;
+            Some(match (code, code_key) {
+                ("0", Some(key)) => raw[key].as_str().unwrap_or("999").to_string(),
+                _ => code.to_string(),
+            })
+        };
+        let success = matches!(&res, Ok(raw) if code(raw).as_deref() == Some("0"));
+        self.record_request(function_id, success);
+        if let Some((raw, code)) = res.as_ref().ok().and_then(|raw| Some((raw, code(raw)?))) {
+            self.record_risk(&code, raw);
+        }
+
+        let raw = res?;
+        let code =
+            code(&raw).ok_or_else(|| anyhow!(JError::parse(function_id, t!("缺少返回码"))))?;
+        let data = match code == "0" {
+            true => {
+                let data = serde_json::from_value(raw[data_key].clone())
+                    .map_err(|e| JError::parse(function_id, e))?;
+                self.check_schema(function_id, &raw[data_key], &data).await;
+                data
+            }
+            false => T::default(),
+        };
+        Ok(Response { code, data, raw })
+    }
+
+    // 获取农场数据
+    pub(crate) async fn get_farm_data(&self) -> Result<Response<FarmData>> {
+        // toBeginEnergy: 发芽需要的水滴
+        // toFlowEnergy:  开花状态需要的水滴
+        // toFruitTimes:  结果状态需要的浇水次数
+        let used = version::current();
+        let res = self.call("initForFarm", INIT_FARM_BODY).await?;
+        if !version::is_version_error(&res) {
+            return Ok(res);
+        }
+        // 京东提高要求的版本后改用探测到的可用版本重新请求
+        match self.reprobe(used).await {
+            Some(version) => {
+                info!("{}", t!("{}, 改用接口版本{}", self.account.name(), version));
+                self.call("initForFarm", INIT_FARM_BODY).await
+            }
+            None => Ok(res),
+        }
+    }
+
+    // 查询果树信息, 只读取数据, 不执行任何任务
+    pub async fn get_farm_info(&self) -> Result<JdFarmInfo> {
+        let farm_data = self.get_farm_data().await?;
+        Self::parse_farm_info(&farm_data)
+    }
+
+    // 检查cookie是否有效, 只读取数据, 不执行任何任务
+    pub async fn check_cookie(&self) -> Result<bool> {
+        let farm_data = self.get_farm_data().await?;
+        Ok(!matches!(farm_data.error(), Some(JError::NotLoggedIn)))
+    }
+
+    // 从农场数据中取出果树信息
+    pub(crate) fn parse_farm_info(farm_data: &Response<FarmData>) -> Result<JdFarmInfo> {
+        farm_data
+            .data
+            .farm_user_pro
+            .clone()
+            .ok_or_else(|| anyhow!(JError::parse("initForFarm", t!("缺少果树信息"))))
+    }
+
+    // 是否操作成功
+    pub(crate) fn is_success(&self, data: &Value) -> bool {
+        data["code"].as_str().unwrap_or("999") == "0"
+    }
+
+    // 返回数据对应的错误, 操作成功时为空
+    pub(crate) fn response_error(data: &Value) -> Option<JError> {
+        JError::from_response(data["code"].as_str().unwrap_or("999"), data)
+    }
+
+    // 记录接口请求结果
+    pub(crate) fn record_request(&self, function_id: &str, success: bool) {
+        #[cfg(feature = "metrics")]
+        metrics::record_request(function_id, success);
+        let mut report = self.report.lock().unwrap();
+        let stats = report.endpoints.entry(function_id.to_string()).or_default();
+        match success {
+            true => stats.success += 1,
+            false => stats.failure += 1,
+        }
+    }
+
+    // 记录触发风控的请求, 用于判断账号是否疑似被封
+    fn record_risk(&self, code: &str, data: &Value) {
+        if let Some(JError::RiskControl { .. }) = JError::from_response(code, data) {
+            self.report.lock().unwrap().risk_control += 1;
+        }
+    }
+
+    // 记录任务执行结果
+    pub fn record_task(&self, name: &str, success: bool, gain: u64) {
+        let outcome = if success { "success" } else { "failure" };
+        Span::current().record("outcome", outcome);
+        Span::current().record("gain", gain);
+        info!(target: logging::TASK_RESULT_TARGET, task = name, outcome, gain);
+        self.report.lock().unwrap().tasks.push(TaskReport {
+            name: name.to_string(),
+            success,
+            gain,
+        });
+
+        let account = self.account.name();
+        if !success {
+            self.emit(RunEvent::TaskFailed {
+                account,
+                task: name.to_string(),
+            });
+            return;
+        }
+        if gain > 0 {
+            self.emit(RunEvent::WaterGained {
+                account: account.clone(),
+                task: name.to_string(),
+                amount: gain,
+            });
+        }
+        self.emit(RunEvent::TaskCompleted {
+            account,
+            task: name.to_string(),
+            gain,
+        });
+    }
+
+    // 记录获得的奖品
+    pub fn record_prize(&self, source: &str, name: &str, level: Option<u8>, code: Option<String>) {
+        info!(
+            "{}",
+            t!(
+                "{}, 获得奖品: {}({})",
+                self.account.name(),
+                name,
+                i18n::tr(source)
+            )
+        );
+        self.report.lock().unwrap().prizes.push(PrizeReport {
+            source: source.to_string(),
+            name: name.to_string(),
+            level,
+            code,
+        });
+    }
+
+    // 查询今日任务完成情况
+    pub async fn get_task_info(&self) -> Result<TaskInfo> {
+        let res = self
+            .request(
+                "taskInitForFarm",
+                r#"{"version":18,"channel":1,"babelChannel":"121"}"#,
+            )
+            .await?;
+
+        if let Some(e) = Self::response_error(&res) {
+            return Err(anyhow!(e));
+        }
+        let task_info: TaskInfo =
+            serde_json::from_value(res.clone()).map_err(|e| JError::parse("taskInitForFarm", e))?;
+        self.check_schema("taskInitForFarm", &res, &task_info).await;
+        Ok(task_info)
+    }
+
+    // 查询背包道具卡数量
+    pub async fn get_card_info(&self) -> Result<CardInfo> {
+        let body = json!({"version":18,"channel":1,"babelChannel":"121"});
+        let data = self
+            .request("myCardInfoForFarm", body.to_string().as_str())
+            .await?;
+
+        let card_info: CardInfo = serde_json::from_value(data.clone())
+            .map_err(|e| JError::parse("myCardInfoForFarm", e))?;
+        self.check_schema("myCardInfoForFarm", &data, &card_info)
+            .await;
+        Ok(card_info)
+    }
+
+    // 查询好友列表
+    pub async fn get_friends(&self) -> Result<Vec<FriendInfo>> {
+        let url = format!(
+            "{}?functionId=friendListInitForFarm&appid=wh5&client=iOS&clientVersion=11.2.8",
+            self.base_url
+        );
+        let body = self.device.apply_body(
+            &version::current()
+                .apply(r#"{"lastId":null,"version":18,"channel":1,"babelChannel":"121"}"#),
+        );
+        let data = self.send("friendListInitForFarm", &url, &body).await?;
+        let friends: FriendInfoList = serde_json::from_value(data.clone())
+            .map_err(|e| JError::parse("friendListInitForFarm", e))?;
+        self.check_schema("friendListInitForFarm", &data, &friends)
+            .await;
+        // 缺少助力码的好友无法浇水, 直接忽略
+        Ok(friends
+            .friends
+            .into_iter()
+            .filter(|friend| friend.missing_fields().is_empty())
+            .collect())
+    }
+
+    // 查询最近好友为我浇水的记录
+    pub async fn get_water_records(&self) -> Result<Vec<WaterRecord>> {
+        let url = format!(
+            "{}?functionId=friendWaterRecordForFarm&appid=wh5&client=iOS&clientVersion=11.2.8",
+            self.base_url
+        );
+        let body = self.device.apply_body(
+            &version::current().apply(r#"{"version":18,"channel":1,"babelChannel":"121"}"#),
+        );
+        let data = self.send("friendWaterRecordForFarm", &url, &body).await?;
+        let records: WaterRecordList = serde_json::from_value(data.clone())
+            .map_err(|e| JError::parse("friendWaterRecordForFarm", e))?;
+        self.check_schema("friendWaterRecordForFarm", &data, &records)
+            .await;
+        Ok(records.records)
+    }
+
+    // 获取签到领水页面数据
+    pub(crate) async fn get_clock_in_data(&self) -> Result<Value> {
+        // clockInitForFarm
+        let data = self
+            .request(
+                "clockInInitForFarm",
+                r#"{"version":18,"channel":3,"babelChannel":"10"}"#,
+            )
+            .await?;
+        match Self::response_error(&data) {
+            None => Ok(data),
+            Some(e) => Err(anyhow!(e)),
+        }
+    }
+
+    // 获取签到领水页面任务
+    pub(crate) async fn get_clock_in_task(&self, data: Option<Value>) -> Result<ClockInTask> {
+        let data = match data {
+            Some(data) => data,
+            None => self.get_clock_in_data().await?,
+        };
+        let task: ClockInTask = serde_json::from_value(data.clone())
+            .map_err(|e| JError::parse("clockInInitForFarm", e))?;
+        self.check_schema("clockInInitForFarm", &data, &task).await;
+        Ok(task)
+    }
+}
@@ -0,0 +1,271 @@
+#[cfg(feature = "redis")]
+pub mod redis;
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::env;
+use std::sync::{Arc, Mutex};
+
+use anyhow::Result;
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use tokio::sync::Mutex as AsyncMutex;
+#[cfg(feature = "redis")]
+use tracing::info;
+
+use crate::state::{self, jd_date, StateStore};
+
+// 每个账号每天最多助力他人的次数
+const DEFAULT_GIVE_LIMIT: u32 = 3;
+
+// 每个账号每天最多被助力的次数
+const DEFAULT_RECEIVE_LIMIT: u32 = 5;
+
+// 每日助力次数上限
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AssistLimits {
+    // 每个账号每天最多助力他人的次数
+    pub give: u32,
+    // 每个账号每天最多被助力的次数
+    pub receive: u32,
+}
+
+impl Default for AssistLimits {
+    fn default() -> Self {
+        Self {
+            give: DEFAULT_GIVE_LIMIT,
+            receive: DEFAULT_RECEIVE_LIMIT,
+        }
+    }
+}
+
+impl AssistLimits {
+    // ASSIST_GIVE_LIMIT: 每个账号每天最多助力他人的次数, 默认3
+    // ASSIST_RECEIVE_LIMIT: 每个账号每天最多被助力的次数, 默认5
+    pub fn from_env() -> Self {
+        let limit = |name: &str, default: u32| {
+            env::var(name)
+                .ok()
+                .and_then(|v| v.trim().parse().ok())
+                .unwrap_or(default)
+        };
+        Self {
+            give: limit("ASSIST_GIVE_LIMIT", DEFAULT_GIVE_LIMIT),
+            receive: limit("ASSIST_RECEIVE_LIMIT", DEFAULT_RECEIVE_LIMIT),
+        }
+    }
+}
+
+// 助力码池及助力次数账本, 多台机器运行不同账号时通过共享存储互相助力
+#[async_trait]
+pub trait AssistStore: Send + Sync {
+    // 发布账号的助力码
+    async fn publish(&self, account: &str, share_code: &str) -> Result<()>;
+
+    // 所有已发布的助力码, (账号, 助力码)
+    async fn share_codes(&self) -> Result<Vec<(String, String)>>;
+
+    // 预占一次助力, helper今日未助力过target且双方均未达到次数上限时返回true
+    async fn reserve(&self, helper: &str, target: &str) -> Result<bool>;
+
+    // 助力未成功时释放预占的次数
+    async fn release(&self, helper: &str, target: &str) -> Result<()>;
+
+    // 对方今日已被助力满, 其他账号不再为其助力
+    async fn mark_full(&self, target: &str) -> Result<()>;
+}
+
+// 京东按北京时间重置每日助力次数
+pub(crate) fn assist_date() -> String {
+    jd_date()
+}
+
+// 当日的助力记录及次数
+#[derive(Serialize, Deserialize, Default)]
+struct LocalState {
+    date: String,
+    share_codes: BTreeMap<String, String>,
+    pairs: HashSet<(String, String)>,
+    given: HashMap<String, u32>,
+    received: HashMap<String, u32>,
+}
+
+impl LocalState {
+    // 日期变化后清空助力次数
+    fn refresh(&mut self) {
+        let today = assist_date();
+        if self.date != today {
+            self.date = today;
+            self.pairs.clear();
+            self.given.clear();
+            self.received.clear();
+        }
+    }
+
+    fn reserve(&mut self, limits: AssistLimits, helper: &str, target: &str) -> bool {
+        let pair = (helper.to_string(), target.to_string());
+        if self.pairs.contains(&pair)
+            || self.given.get(helper).copied().unwrap_or(0) >= limits.give
+            || self.received.get(target).copied().unwrap_or(0) >= limits.receive
+        {
+            return false;
+        }
+        self.pairs.insert(pair);
+        *self.given.entry(helper.to_string()).or_default() += 1;
+        *self.received.entry(target.to_string()).or_default() += 1;
+        true
+    }
+
+    fn release(&mut self, helper: &str, target: &str) {
+        if self.pairs.remove(&(helper.to_string(), target.to_string())) {
+            if let Some(given) = self.given.get_mut(helper) {
+                *given = given.saturating_sub(1);
+            }
+            if let Some(received) = self.received.get_mut(target) {
+                *received = received.saturating_sub(1);
+            }
+        }
+    }
+}
+
+// 内存中的助力码池, 只在同一进程的账号之间互相助力
+#[derive(Default)]
+pub struct LocalAssistStore {
+    limits: AssistLimits,
+    state: Mutex<LocalState>,
+}
+
+impl LocalAssistStore {
+    pub fn new(limits: AssistLimits) -> Self {
+        Self {
+            limits,
+            state: Mutex::default(),
+        }
+    }
+
+    // 当前状态, 日期变化后清空助力次数
+    fn state(&self) -> std::sync::MutexGuard<'_, LocalState> {
+        let mut state = self.state.lock().unwrap();
+        state.refresh();
+        state
+    }
+}
+
+#[async_trait]
+impl AssistStore for LocalAssistStore {
+    async fn publish(&self, account: &str, share_code: &str) -> Result<()> {
+        self.state()
+            .share_codes
+            .insert(account.to_string(), share_code.to_string());
+        Ok(())
+    }
+
+    async fn share_codes(&self) -> Result<Vec<(String, String)>> {
+        Ok(self.state().share_codes.clone().into_iter().collect())
+    }
+
+    async fn reserve(&self, helper: &str, target: &str) -> Result<bool> {
+        Ok(self.state().reserve(self.limits, helper, target))
+    }
+
+    async fn release(&self, helper: &str, target: &str) -> Result<()> {
+        self.state().release(helper, target);
+        Ok(())
+    }
+
+    async fn mark_full(&self, target: &str) -> Result<()> {
+        let receive = self.limits.receive;
+        self.state().received.insert(target.to_string(), receive);
+        Ok(())
+    }
+}
+
+// 状态存储中的键
+const STATE_KEY: &str = "assist";
+
+// 保存在运行状态存储中的助力码池, 同一台机器多次运行或多个进程共用同一个存储时不会重复助力
+pub struct StateAssistStore {
+    limits: AssistLimits,
+    state: Arc<dyn StateStore>,
+    // 读取、修改、写回依次进行
+    lock: AsyncMutex<()>,
+}
+
+impl StateAssistStore {
+    pub fn new(state: Arc<dyn StateStore>, limits: AssistLimits) -> Self {
+        Self {
+            limits,
+            state,
+            lock: AsyncMutex::new(()),
+        }
+    }
+
+    // 读取当前状态, 修改后写回
+    async fn update<T>(&self, f: impl FnOnce(&mut LocalState) -> T) -> Result<T> {
+        let _lock = self.lock.lock().await;
+        let mut local: LocalState = state::load(self.state.as_ref(), STATE_KEY).await?;
+        local.refresh();
+        let res = f(&mut local);
+        state::save(self.state.as_ref(), STATE_KEY, &local, None).await?;
+        Ok(res)
+    }
+}
+
+#[async_trait]
+impl AssistStore for StateAssistStore {
+    async fn publish(&self, account: &str, share_code: &str) -> Result<()> {
+        self.update(|local| {
+            local
+                .share_codes
+                .insert(account.to_string(), share_code.to_string());
+        })
+        .await
+    }
+
+    async fn share_codes(&self) -> Result<Vec<(String, String)>> {
+        let local: LocalState = state::load(self.state.as_ref(), STATE_KEY).await?;
+        Ok(local.share_codes.into_iter().collect())
+    }
+
+    async fn reserve(&self, helper: &str, target: &str) -> Result<bool> {
+        let limits = self.limits;
+        self.update(|local| local.reserve(limits, helper, target))
+            .await
+    }
+
+    async fn release(&self, helper: &str, target: &str) -> Result<()> {
+        self.update(|local| local.release(helper, target)).await
+    }
+
+    async fn mark_full(&self, target: &str) -> Result<()> {
+        let receive = self.limits.receive;
+        self.update(|local| {
+            local.received.insert(target.to_string(), receive);
+        })
+        .await
+    }
+}
+
+// REDIS_URL: Redis地址, 如redis://127.0.0.1:6379/0, 设置后启用《助力好友》任务, 需启用redis功能
+// ASSIST: 未设置REDIS_URL时, 设置为true在同一台机器的账号之间互相助力, 助力次数保存在STATE_STORE中
+pub fn from_env() -> Option<Arc<dyn AssistStore>> {
+    #[cfg(feature = "redis")]
+    if let Some(store) = redis::RedisAssistStore::from_env() {
+        return match store {
+            Ok(store) => Some(Arc::new(store)),
+            Err(e) => {
+                info!("{}", t!("连接Redis失败, {}", e));
+                None
+            }
+        };
+    }
+    let enabled = matches!(
+        env::var("ASSIST").as_deref().map(str::trim),
+        Ok("true") | Ok("1")
+    );
+    enabled.then(|| {
+        Arc::new(StateAssistStore::new(
+            state::from_env_or_memory(),
+            AssistLimits::from_env(),
+        )) as Arc<dyn AssistStore>
+    })
+}
@@ -0,0 +1,136 @@
+use std::env;
+
+use ::redis::aio::MultiplexedConnection;
+use ::redis::{AsyncCommands, Client, Script};
+use anyhow::Result;
+use async_trait::async_trait;
+
+use super::{assist_date, AssistLimits, AssistStore};
+
+// 每日助力记录的保留时间(秒)
+const RECORD_TTL: u64 = 2 * 24 * 3600;
+
+// 原子地检查并预占一次助力
+// KEYS: 助力记录集合, helper已助力次数, target已被助力次数
+// ARGV: 助力记录, helper次数上限, target次数上限, 保留时间(秒)
+const RESERVE_SCRIPT: &str = r"
+if redis.call('SISMEMBER', KEYS[1], ARGV[1]) == 1 then return 0 end
+if tonumber(redis.call('GET', KEYS[2]) or '0') >= tonumber(ARGV[2]) then return 0 end
+if tonumber(redis.call('GET', KEYS[3]) or '0') >= tonumber(ARGV[3]) then return 0 end
+redis.call('SADD', KEYS[1], ARGV[1])
+redis.call('INCR', KEYS[2])
+redis.call('INCR', KEYS[3])
+for i = 1, 3 do redis.call('EXPIRE', KEYS[i], ARGV[4]) end
+return 1
+";
+
+// 释放预占的助力, 参数同RESERVE_SCRIPT
+const RELEASE_SCRIPT: &str = r"
+if redis.call('SREM', KEYS[1], ARGV[1]) == 1 then
+    redis.call('DECR', KEYS[2])
+    redis.call('DECR', KEYS[3])
+end
+return 1
+";
+
+// Redis助力码池, 多台机器共享助力码及每日助力次数, 避免重复助力或超出上限
+pub struct RedisAssistStore {
+    client: Client,
+    prefix: String,
+    limits: AssistLimits,
+}
+
+impl RedisAssistStore {
+    pub fn new(url: &str, limits: AssistLimits) -> Result<Self> {
+        Ok(Self {
+            client: Client::open(url)?,
+            prefix: "jd_farm".to_string(),
+            limits,
+        })
+    }
+
+    // 设置键前缀, 多套程序共用同一个Redis时区分, 默认jd_farm
+    pub fn prefix(mut self, prefix: &str) -> Self {
+        self.prefix = prefix.to_string();
+        self
+    }
+
+    // REDIS_URL: Redis地址
+    // REDIS_PREFIX: 可选, 键前缀
+    pub fn from_env() -> Option<Result<Self>> {
+        let url = env::var("REDIS_URL").ok().filter(|u| !u.is_empty())?;
+        let store = Self::new(&url, AssistLimits::from_env()).map(|store| {
+            match env::var("REDIS_PREFIX").ok().filter(|p| !p.is_empty()) {
+                Some(prefix) => store.prefix(&prefix),
+                None => store,
+            }
+        });
+        Some(store)
+    }
+
+    async fn connection(&self) -> Result<MultiplexedConnection> {
+        Ok(self.client.get_multiplexed_tokio_connection().await?)
+    }
+
+    fn share_codes_key(&self) -> String {
+        format!("{}:share_codes", self.prefix)
+    }
+
+    // 当日的助力记录集合、helper已助力次数、target已被助力次数
+    fn assist_keys(&self, helper: &str, target: &str) -> [String; 3] {
+        let prefix = format!("{}:assist:{}", self.prefix, assist_date());
+        [
+            format!("{}:pairs", prefix),
+            format!("{}:given:{}", prefix, helper),
+            format!("{}:received:{}", prefix, target),
+        ]
+    }
+}
+
+#[async_trait]
+impl AssistStore for RedisAssistStore {
+    async fn publish(&self, account: &str, share_code: &str) -> Result<()> {
+        let mut con = self.connection().await?;
+        let _: () = con
+            .hset(self.share_codes_key(), account, share_code)
+            .await?;
+        Ok(())
+    }
+
+    async fn share_codes(&self) -> Result<Vec<(String, String)>> {
+        let mut con = self.connection().await?;
+        Ok(con.hgetall(self.share_codes_key()).await?)
+    }
+
+    async fn reserve(&self, helper: &str, target: &str) -> Result<bool> {
+        let mut con = self.connection().await?;
+        let reserved: i32 = Script::new(RESERVE_SCRIPT)
+            .key(&self.assist_keys(helper, target)[..])
+            .arg(format!("{}->{}", helper, target))
+            .arg(self.limits.give)
+            .arg(self.limits.receive)
+            .arg(RECORD_TTL)
+            .invoke_async(&mut con)
+            .await?;
+        Ok(reserved == 1)
+    }
+
+    async fn release(&self, helper: &str, target: &str) -> Result<()> {
+        let mut con = self.connection().await?;
+        let _: i32 = Script::new(RELEASE_SCRIPT)
+            .key(&self.assist_keys(helper, target)[..])
+            .arg(format!("{}->{}", helper, target))
+            .invoke_async(&mut con)
+            .await?;
+        Ok(())
+    }
+
+    async fn mark_full(&self, target: &str) -> Result<()> {
+        let mut con = self.connection().await?;
+        let [_, _, received] = self.assist_keys("", target);
+        let _: () = con
+            .set_ex(received, self.limits.receive, RECORD_TTL as usize)
+            .await?;
+        Ok(())
+    }
+}
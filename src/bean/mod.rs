@@ -0,0 +1,44 @@
+mod models;
+mod tasks;
+
+use std::env;
+use std::sync::Arc;
+
+use anyhow::Result;
+use async_trait::async_trait;
+
+use crate::plugin::{FarmTask, FarmTasks};
+use crate::JClient;
+
+pub use self::models::{
+    BeanAward, BeanIndex, BeanRound, BeanTaskItem, NutrientBubble, TimeNutrients,
+};
+
+// 任务名称, 可通过ONLY_TASKS/SKIP_TASKS过滤
+pub const BEAN_TASK: &str = "种豆得豆";
+
+// 种豆得豆, 与农场共用账号、签名及网络限制, 在农场任务之后执行
+pub struct BeanTask;
+
+#[async_trait]
+impl FarmTask for BeanTask {
+    fn name(&self) -> &str {
+        BEAN_TASK
+    }
+
+    async fn run(&self, client: &JClient) -> Result<()> {
+        client.run_bean().await
+    }
+}
+
+// BEAN: 是否同时执行种豆得豆任务, 默认false
+pub fn from_env() -> FarmTasks {
+    let enabled = matches!(
+        env::var("BEAN").as_deref().map(str::trim),
+        Ok("true") | Ok("1")
+    );
+    match enabled {
+        true => vec![Arc::new(BeanTask)],
+        false => Vec::new(),
+    }
+}
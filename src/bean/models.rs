@@ -0,0 +1,104 @@
+use serde::{Deserialize, Deserializer, Serialize};
+use serde_json::Value;
+
+// 种豆得豆接口中的数量有时为字符串, 无法解析时为0
+fn number<'de, D: Deserializer<'de>>(deserializer: D) -> Result<u32, D::Error> {
+    Ok(match Value::deserialize(deserializer)? {
+        Value::Number(n) => n.as_u64().unwrap_or_default() as u32,
+        Value::String(s) => s.trim().parse().unwrap_or_default(),
+        _ => 0,
+    })
+}
+
+// 种豆得豆首页数据(plantBeanIndex)
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(default, rename_all = "camelCase")]
+pub struct BeanIndex {
+    // 依次为上期、本期、下期
+    pub round_list: Vec<BeanRound>,
+    // 任务列表
+    pub task_list: Vec<BeanTaskItem>,
+    // 定时领取的营养液
+    pub time_nutrients_res: Option<TimeNutrients>,
+}
+
+impl BeanIndex {
+    // 上期, 开奖后可收取京豆
+    pub fn last_round(&self) -> Option<&BeanRound> {
+        self.round_list.first()
+    }
+
+    // 本期, 培养营养液的一期
+    pub fn current_round(&self) -> Option<&BeanRound> {
+        self.round_list.get(1)
+    }
+}
+
+// 一期种豆
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(default, rename_all = "camelCase")]
+pub struct BeanRound {
+    // 期号
+    pub round_id: String,
+
+    // 京豆状态, 5: 可收取, 6: 已收取
+    pub award_state: String,
+
+    // 本期营养液
+    #[serde(deserialize_with = "number")]
+    pub nutrients: u32,
+
+    // 本期成长值
+    #[serde(deserialize_with = "number")]
+    pub growth: u32,
+
+    // 瓜分到的京豆
+    #[serde(deserialize_with = "number")]
+    pub award_beans: u32,
+
+    // 待培养的营养液
+    pub bubble_infos: Vec<NutrientBubble>,
+}
+
+// 待培养的营养液
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(default, rename_all = "camelCase")]
+pub struct NutrientBubble {
+    // 来源
+    pub name: String,
+    // 营养液类型, 培养时使用
+    pub nutrients_type: String,
+}
+
+// 种豆得豆任务
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(default, rename_all = "camelCase")]
+pub struct BeanTaskItem {
+    // 任务名称
+    pub task_name: String,
+    // 1: 已完成
+    #[serde(deserialize_with = "number")]
+    pub is_finished: u32,
+    // 领取方式, 1: 直接领取(如签到)
+    pub award_type: String,
+}
+
+// 定时领取的营养液
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(default, rename_all = "camelCase")]
+pub struct TimeNutrients {
+    // 1: 可领取
+    pub state: String,
+    // 可领取的营养液
+    #[serde(deserialize_with = "number")]
+    pub nutr_count: u32,
+}
+
+// 领取营养液、收取京豆(receiveNutrients、receiveNutrientsTask、cultureBean、receivedBean)
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(default, rename_all = "camelCase")]
+pub struct BeanAward {
+    // 获得的营养液或京豆
+    #[serde(alias = "nutrNum", alias = "awardBean", deserialize_with = "number")]
+    pub nutrients: u32,
+}
@@ -0,0 +1,170 @@
+use std::time::Duration;
+
+use anyhow::{anyhow, Result};
+use serde::{de::DeserializeOwned, Serialize};
+use serde_json::{json, Value};
+use tracing::info;
+
+use super::models::{BeanAward, BeanIndex};
+use super::BEAN_TASK;
+use crate::models::Response;
+use crate::{i18n, shutdown, JClient};
+
+// 种豆得豆接口的版本
+const BEAN_VERSION: &str = "9.2.4.1";
+
+impl JClient {
+    // 请求种豆得豆接口, code为0时表示操作成功, 业务数据在data中
+    async fn bean_call<T: DeserializeOwned + Serialize + Default>(
+        &self,
+        function_id: &str,
+        mut body: Value,
+    ) -> Result<Response<T>> {
+        body["version"] = BEAN_VERSION.into();
+        body["monitor_source"] = "plant_app_plant_index".into();
+        self.activity_call(function_id, body, None, "data").await
+    }
+
+    // 执行种豆得豆任务: 收取上期京豆、领取营养液并培养
+    pub async fn run_bean(&self) -> Result<()> {
+        let index = self.bean_index().await?;
+
+        // 上期开奖后收取京豆
+        if let Some(round) = index.last_round().filter(|r| r.award_state == "5") {
+            let res: Response<BeanAward> = self
+                .bean_call("receivedBean", json!({"roundId": round.round_id}))
+                .await?;
+            match res.is_success() {
+                true => {
+                    let beans = res.data.nutrients.max(round.award_beans);
+                    self.record_prize(BEAN_TASK, &t!("{}京豆", beans), None, None);
+                    self.record_task("种豆得豆->收取京豆", true, 0);
+                }
+                false => {
+                    info!(
+                        "{}",
+                        t!("{}, 收取上期京豆失败, {}", self.account.name(), res)
+                    );
+                    self.record_task("种豆得豆->收取京豆", false, 0);
+                }
+            }
+        }
+
+        let Some(round_id) = index.current_round().map(|r| r.round_id.clone()) else {
+            info!(
+                "{}",
+                t!("{}, 种豆得豆没有进行中的一期!", self.account.name())
+            );
+            return Ok(());
+        };
+
+        if let Some(time) = index.time_nutrients_res.as_ref().filter(|t| t.state == "1") {
+            let res: Response<BeanAward> = self
+                .bean_call("receiveNutrients", json!({"roundId": round_id}))
+                .await?;
+            let amount = res.data.nutrients.max(time.nutr_count);
+            self.bean_result("种豆得豆->定时领取营养液", &res, amount);
+        }
+
+        for task in &index.task_list {
+            // 只领取无需浏览等操作的任务, 如签到
+            if task.is_finished == 1 || task.award_type != "1" || shutdown::is_requested() {
+                continue;
+            }
+            let res: Response<BeanAward> = self
+                .bean_call(
+                    "receiveNutrientsTask",
+                    json!({"awardType": task.award_type}),
+                )
+                .await?;
+            let name = format!("种豆得豆->{}", task.task_name);
+            self.bean_result(&name, &res, res.data.nutrients);
+            self.pause(Duration::from_secs(1)).await;
+        }
+
+        // 领取的营养液需培养后才计入本期
+        let index = self.bean_index().await?;
+        let bubbles = index
+            .current_round()
+            .map(|r| r.bubble_infos.clone())
+            .unwrap_or_default();
+        for bubble in bubbles {
+            if shutdown::is_requested() {
+                break;
+            }
+            let res: Response<BeanAward> = self
+                .bean_call(
+                    "cultureBean",
+                    json!({"roundId": round_id, "nutrientsType": bubble.nutrients_type}),
+                )
+                .await?;
+            if !res.is_success() {
+                info!(
+                    "{}",
+                    t!(
+                        "{}, 培养营养液《{}》失败, {}",
+                        self.account.name(),
+                        bubble.name,
+                        res
+                    )
+                );
+            }
+            self.pause(Duration::from_secs(1)).await;
+        }
+
+        let index = self.bean_index().await?;
+        if let Some(round) = index.current_round() {
+            info!(
+                "{}",
+                t!(
+                    "{}: 种豆得豆:\n\t本期营养液: {}\n\t本期成长值: {}",
+                    self.account.name(),
+                    round.nutrients,
+                    round.growth
+                )
+            );
+        }
+        Ok(())
+    }
+
+    // 查询种豆得豆首页数据
+    async fn bean_index(&self) -> Result<BeanIndex> {
+        let res: Response<BeanIndex> = self
+            .bean_call("plantBeanIndex", json!({"monitor_refer": ""}))
+            .await?;
+        match res.error() {
+            None => Ok(res.data),
+            Some(e) => Err(anyhow!(e)),
+        }
+    }
+
+    // 记录领取营养液的结果
+    fn bean_result(&self, task: &str, res: &Response<BeanAward>, amount: u32) {
+        match res.is_success() {
+            true => {
+                info!(
+                    "{}",
+                    t!(
+                        "{}, 完成任务《{}》, 获得营养液:{}!",
+                        self.account.name(),
+                        i18n::tr(task),
+                        amount
+                    )
+                );
+                self.record_task(task, true, 0);
+            }
+            false => {
+                info!(
+                    "{}",
+                    t!(
+                        "{}, 无法完成任务《{}》, {}",
+                        self.account.name(),
+                        i18n::tr(task),
+                        res
+                    )
+                );
+                self.record_task(task, false, 0);
+            }
+        }
+    }
+}
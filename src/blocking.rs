@@ -0,0 +1,81 @@
+use anyhow::Result;
+use jd_com::account::JAccount;
+use tokio::runtime::{Builder, Runtime};
+
+use crate::error::ErrorPolicy;
+use crate::models::{Card, CardInfo, FriendInfo, JdFarmInfo, TaskInfo, WaterRecord};
+use crate::options::RunOptions;
+use crate::report::AccountReport;
+
+// 同步版本的JClient, 内部自带tokio运行时, 适用于同步的命令行工具或GUI程序
+// 不能在tokio异步上下文中调用, 否则会panic
+pub struct JClient {
+    inner: crate::JClient,
+    runtime: Runtime,
+}
+
+impl JClient {
+    pub fn new(account: JAccount) -> Result<Self> {
+        let runtime = Builder::new_current_thread().enable_all().build()?;
+        Ok(Self {
+            inner: crate::JClient::new(account),
+            runtime,
+        })
+    }
+
+    // 设置任务出错时的处理策略, 默认记录错误后继续执行
+    pub fn with_error_policy(mut self, policy: ErrorPolicy) -> Self {
+        self.inner = self.inner.with_error_policy(policy);
+        self
+    }
+
+    // 异步版本的JClient
+    pub fn inner(&self) -> &crate::JClient {
+        &self.inner
+    }
+
+    // 查询果树信息
+    pub fn get_farm_info(&self) -> Result<JdFarmInfo> {
+        self.runtime.block_on(self.inner.get_farm_info())
+    }
+
+    // 查询任务列表
+    pub fn get_task_info(&self) -> Result<TaskInfo> {
+        self.runtime.block_on(self.inner.get_task_info())
+    }
+
+    // 查询道具卡
+    pub fn get_card_info(&self) -> Result<CardInfo> {
+        self.runtime.block_on(self.inner.get_card_info())
+    }
+
+    // 查询好友列表
+    pub fn get_friends(&self) -> Result<Vec<FriendInfo>> {
+        self.runtime.block_on(self.inner.get_friends())
+    }
+
+    // 查询最近好友为我浇水的记录
+    pub fn get_water_records(&self) -> Result<Vec<WaterRecord>> {
+        self.runtime.block_on(self.inner.get_water_records())
+    }
+
+    // 使用道具卡
+    pub fn use_card(&self, card: Card) -> Result<()> {
+        self.runtime.block_on(self.inner.use_card(card))
+    }
+
+    // 使用默认选项执行所有任务
+    pub fn run(&self) -> Result<AccountReport> {
+        self.runtime.block_on(self.inner.run())
+    }
+
+    // 按指定选项执行所有任务
+    pub fn run_with(&self, opts: RunOptions) -> Result<AccountReport> {
+        self.runtime.block_on(self.inner.run_with(opts))
+    }
+
+    // 已执行任务的结果
+    pub fn report(&self) -> AccountReport {
+        self.inner.report()
+    }
+}
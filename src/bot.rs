@@ -0,0 +1,241 @@
+use std::env;
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::{anyhow, Result};
+use reqwest::Client;
+use serde_json::{json, Value};
+use tokio::sync::broadcast::error::RecvError;
+use tracing::{info, warn};
+
+use crate::control::Controller;
+use crate::event::RunEvent;
+use crate::logging;
+use crate::options::RunOptions;
+use crate::redact::Secret;
+
+// 长轮询等待新消息的时间(秒)
+const POLL_TIMEOUT: u64 = 30;
+
+// 获取消息失败后重试的间隔
+const RETRY_DELAY: Duration = Duration::from_secs(5);
+
+// Telegram交互机器人, 通过命令触发运行、查询结果, 只响应TG_USER_ID发送的消息
+pub struct TelegramBot {
+    client: Client,
+    bot_token: Secret,
+    chat_id: String,
+    api_host: String,
+}
+
+impl TelegramBot {
+    pub fn new(bot_token: &str, chat_id: &str) -> Self {
+        Self {
+            client: Client::new(),
+            bot_token: Secret::new(bot_token),
+            chat_id: chat_id.to_string(),
+            api_host: "https://api.telegram.org".to_string(),
+        }
+    }
+
+    // TG_BOT_MODE: 设置为true时以机器人模式运行
+    // TG_BOT_TOKEN/TG_USER_ID/TG_API_HOST: 与Telegram通知相同
+    pub fn from_env() -> Option<Self> {
+        let enabled = env::var("TG_BOT_MODE").ok()?;
+        if matches!(enabled.trim(), "false" | "0" | "") {
+            return None;
+        }
+        let bot_token = env::var("TG_BOT_TOKEN").ok()?;
+        let chat_id = env::var("TG_USER_ID").ok()?;
+        let mut bot = Self::new(&bot_token, &chat_id);
+        if let Ok(api_host) = env::var("TG_API_HOST") {
+            bot.api_host = api_host.trim_end_matches('/').to_string();
+        }
+        Some(bot)
+    }
+
+    // 调用Bot API, 返回result字段
+    async fn call(&self, method: &str, body: Value) -> Result<Value> {
+        let url = format!(
+            "{}/bot{}/{}",
+            self.api_host,
+            self.bot_token.expose(),
+            method
+        );
+        let mut res = self
+            .client
+            .post(url)
+            .json(&body)
+            .send()
+            .await?
+            .json::<Value>()
+            .await?;
+
+        match res["ok"].as_bool().unwrap_or(false) {
+            true => Ok(res["result"].take()),
+            false => Err(anyhow!("{}", res["description"])),
+        }
+    }
+
+    // 发送纯文本消息
+    async fn send_text(&self, text: &str) -> Result<()> {
+        self.call(
+            "sendMessage",
+            json!({ "chat_id": self.chat_id, "text": text }),
+        )
+        .await?;
+        Ok(())
+    }
+
+    // 启动机器人, 持续接收命令并推送各账号的运行结果
+    pub async fn serve(self, controller: Arc<Controller>) -> Result<()> {
+        info!("{}", t!("Telegram机器人已启动"));
+        tokio::join!(self.poll(&controller), self.forward(&controller));
+        Ok(())
+    }
+
+    // 长轮询接收命令
+    async fn poll(&self, controller: &Arc<Controller>) {
+        let mut offset = 0;
+        loop {
+            let body = json!({
+                "offset": offset,
+                "timeout": POLL_TIMEOUT,
+                "allowed_updates": ["message"],
+            });
+            let updates = match self.call("getUpdates", body).await {
+                Ok(updates) => updates,
+                Err(e) => {
+                    warn!("{}", t!("获取Telegram消息失败, {}", e));
+                    tokio::time::sleep(RETRY_DELAY).await;
+                    continue;
+                }
+            };
+            for update in updates.as_array().into_iter().flatten() {
+                if let Some(id) = update["update_id"].as_i64() {
+                    offset = offset.max(id + 1);
+                }
+                let message = &update["message"];
+                let chat_id = message["chat"]["id"].as_i64().map(|id| id.to_string());
+                if chat_id.as_deref() != Some(self.chat_id.as_str()) {
+                    continue;
+                }
+                let Some(text) = message["text"].as_str() else {
+                    continue;
+                };
+                let reply = self.handle(controller, text);
+                if let Err(e) = self.send_text(&reply).await {
+                    warn!("{}", t!("发送Telegram消息失败, {}", e));
+                }
+            }
+        }
+    }
+
+    // 运行期间每个账号结束后推送该账号的运行结果
+    async fn forward(&self, controller: &Controller) {
+        let mut events = controller.subscribe();
+        loop {
+            let event = match events.recv().await {
+                Ok(event) => event,
+                Err(RecvError::Lagged(_)) => continue,
+                Err(RecvError::Closed) => break,
+            };
+            if let RunEvent::AccountFinished { report } = event {
+                if let Err(e) = self.send_text(&report.to_text()).await {
+                    warn!("{}", t!("发送Telegram消息失败, {}", e));
+                }
+            }
+        }
+    }
+
+    // 处理命令, 返回回复内容
+    fn handle(&self, controller: &Arc<Controller>, text: &str) -> String {
+        let mut args = text.split_whitespace();
+        // 群组中的命令可能带有机器人名称, 如/run@jd_farm_bot
+        let command = args.next().unwrap_or_default();
+        let command = command.split('@').next().unwrap_or_default();
+        let args: Vec<String> = args.map(|a| a.to_string()).collect();
+        match command {
+            "/run" => run(controller, &args),
+            "/status" => status(controller),
+            "/cookies" => cookies(controller),
+            "/loglevel" => log_level(&args),
+            _ => t!("可用命令:\n/run - 运行所有账号\n/run 账号1 账号2 - 运行指定账号\n/status - 运行状态及最近一次运行结果\n/cookies - 各账号cookie状态\n/loglevel 子系统 级别 - 修改日志级别"),
+        }
+    }
+}
+
+// /run [账号...]
+fn run(controller: &Arc<Controller>, names: &[String]) -> String {
+    let accounts: Vec<String> = controller.accounts().iter().map(|a| a.name()).collect();
+    let unknown: Vec<&str> = names
+        .iter()
+        .filter(|n| !accounts.contains(n))
+        .map(|n| n.as_str())
+        .collect();
+    if !unknown.is_empty() {
+        return t!("未找到账号: {}", unknown.join(", "));
+    }
+    match controller.trigger(names, RunOptions::from_env()) {
+        true => t!("已开始运行"),
+        false => t!("上一次运行尚未结束"),
+    }
+}
+
+// /status
+fn status(controller: &Controller) -> String {
+    let state = match controller.is_running() {
+        true => t!("正在运行"),
+        false => t!("空闲"),
+    };
+    match controller.last_report() {
+        Some(report) => format!("{}\n\n{}", state, report.to_text()),
+        None => format!("{}\n\n{}", state, t!("尚未运行")),
+    }
+}
+
+// /cookies, 根据最近一次运行结果判断cookie是否有效
+fn cookies(controller: &Controller) -> String {
+    let report = controller.last_report();
+    let lines: Vec<String> = controller
+        .accounts()
+        .iter()
+        .map(|account| {
+            let name = account.name();
+            let state = report
+                .as_ref()
+                .and_then(|r| r.accounts.iter().find(|a| a.name == name))
+                .map(|a| match a.cookie_expired {
+                    true => t!("cookie已失效"),
+                    false => t!("正常"),
+                })
+                .unwrap_or_else(|| t!("未检测"));
+            format!("{}: {}", name, state)
+        })
+        .collect();
+    match lines.is_empty() {
+        true => t!("未配置账号"),
+        false => lines.join("\n"),
+    }
+}
+
+// /loglevel [子系统 级别], 不带参数时列出各子系统单独设置的日志级别
+fn log_level(args: &[String]) -> String {
+    match args {
+        [] => {}
+        [subsystem, level] => {
+            if let Err(e) = logging::set_level(subsystem, level) {
+                return e.to_string();
+            }
+        }
+        _ => return t!("用法: /loglevel 子系统 级别, 如/loglevel transport debug"),
+    }
+    let lines: Vec<String> = logging::levels()
+        .iter()
+        .map(|(subsystem, level)| format!("{}: {}", subsystem, level))
+        .collect();
+    match lines.is_empty() {
+        true => t!("各子系统均使用RUST_LOG中的日志级别"),
+        false => lines.join("\n"),
+    }
+}
@@ -0,0 +1,66 @@
+use std::env;
+use std::sync::atomic::{AtomicBool, AtomicI64, Ordering};
+
+use chrono::{DateTime, Duration, Utc};
+use reqwest::header::{HeaderMap, DATE};
+use tracing::warn;
+
+// 相差超过该时间(毫秒)时提示本机时间不准确
+const WARN_SKEW_MILLIS: i64 = 30_000;
+
+// 服务器时间减去本机时间(毫秒)
+static SKEW_MILLIS: AtomicI64 = AtomicI64::new(0);
+
+// 是否已提示过本机时间不准确
+static WARNED: AtomicBool = AtomicBool::new(false);
+
+// SERVER_TIME: 是否按京东服务器时间计算水滴雨冷却、三餐时间段及活动日期, 默认true
+// 路由器、NAS等设备的系统时间往往不准确, 设置为false使用本机时间
+pub fn is_enabled() -> bool {
+    !matches!(
+        env::var("SERVER_TIME").as_deref().map(str::trim),
+        Ok("false") | Ok("0")
+    )
+}
+
+// 根据响应头中的Date校准服务器时间, 未启用或没有Date时忽略
+pub fn observe(headers: &HeaderMap) {
+    if !is_enabled() {
+        return;
+    }
+    let server = headers
+        .get(DATE)
+        .and_then(|date| date.to_str().ok())
+        .and_then(|date| DateTime::parse_from_rfc2822(date).ok());
+    if let Some(server) = server {
+        set_server_time(server.with_timezone(&Utc));
+    }
+}
+
+// 记录服务器时间, Date只精确到秒, 按该秒的中间计算
+pub fn set_server_time(server: DateTime<Utc>) {
+    let skew = (server + Duration::milliseconds(500) - Utc::now()).num_milliseconds();
+    SKEW_MILLIS.store(skew, Ordering::Relaxed);
+    if skew.abs() > WARN_SKEW_MILLIS && !WARNED.swap(true, Ordering::Relaxed) {
+        warn!(
+            "{}",
+            t!(
+                "本机时间与京东服务器相差{}秒, 时间相关的任务按服务器时间计算",
+                skew / 1000
+            )
+        );
+    }
+}
+
+// 服务器时间减去本机时间, 尚未校准或未启用时为0
+pub fn skew() -> Duration {
+    match is_enabled() {
+        true => Duration::milliseconds(SKEW_MILLIS.load(Ordering::Relaxed)),
+        false => Duration::zero(),
+    }
+}
+
+// 当前的服务器时间
+pub fn now() -> DateTime<Utc> {
+    Utc::now() + skew()
+}
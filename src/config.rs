@@ -0,0 +1,247 @@
+use std::collections::BTreeMap;
+use std::env;
+use std::fs;
+use std::sync::{OnceLock, RwLock};
+use std::time::Duration;
+
+use serde::{Deserialize, Deserializer};
+use toml::value::{Table, Value};
+use tracing::info;
+
+use crate::device::Device;
+use crate::error::ErrorPolicy;
+use crate::options::{DelayPolicy, RunOptions, WaterRainRange};
+
+// 默认配置文件
+const DEFAULT_CONFIG_FILE: &str = "jd_farm.toml";
+
+// 覆盖配置的环境变量前缀, 以双下划线分隔层级, 如JD_FARM__DELAY__MIN=5
+const ENV_PREFIX: &str = "JD_FARM__";
+
+// 等待时间设置(秒), fixed优先, 其次min/max, 最后scale
+#[derive(Deserialize, Debug, Clone, Default)]
+pub struct DelayConfig {
+    // 所有操作之间固定等待
+    pub fixed: Option<f64>,
+    // 按比例缩放各任务默认的等待时间
+    pub scale: Option<f64>,
+    // 各任务默认的等待时间限制在min~max之间
+    pub min: Option<f64>,
+    pub max: Option<f64>,
+}
+
+impl DelayConfig {
+    fn policy(&self) -> Option<DelayPolicy> {
+        let seconds = |v: f64| Duration::from_secs_f64(v.max(0.0));
+        if let Some(fixed) = self.fixed {
+            return Some(DelayPolicy::Fixed(seconds(fixed)));
+        }
+        if self.min.is_some() || self.max.is_some() {
+            let min = self.min.map(seconds).unwrap_or_default();
+            let max = self.max.map(seconds).unwrap_or(Duration::MAX);
+            return Some(DelayPolicy::Range(min, max));
+        }
+        self.scale.map(DelayPolicy::Scale)
+    }
+}
+
+// 剩余水滴阈值, 数字或off
+#[derive(Deserialize, Debug, Clone)]
+#[serde(untagged)]
+pub enum Threshold {
+    Water(u32),
+    Off(String),
+}
+
+// 配置档案, 未设置的项保持环境变量中的设置
+#[derive(Deserialize, Debug, Clone, Default)]
+pub struct Profile {
+    pub dry_run: Option<bool>,
+    #[serde(default, deserialize_with = "list")]
+    pub only_tasks: Option<Vec<String>>,
+    #[serde(default, deserialize_with = "list")]
+    pub skip_tasks: Option<Vec<String>>,
+    // 单个账号/单个请求的超时时间(秒)
+    pub account_timeout: Option<f64>,
+    pub request_timeout: Option<f64>,
+    pub delay: Option<DelayConfig>,
+    pub double_card_min_water: Option<Threshold>,
+    pub sign_card: Option<bool>,
+    // 水滴雨提交的接到红包数范围, 如"40-60"
+    pub water_rain_score: Option<String>,
+    // continue或fail_fast
+    pub error_policy: Option<String>,
+    // 请求模拟的设备, iphone或android
+    pub device: Option<String>,
+    // 各子系统的日志级别, 如transport = "debug", 启动时读取
+    pub log_levels: Option<BTreeMap<String, String>>,
+}
+
+// 任务列表, 数组或以逗号分隔的字符串
+fn list<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<Vec<String>>, D::Error> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum List {
+        Items(Vec<String>),
+        Text(String),
+    }
+    Ok(Some(match List::deserialize(deserializer)? {
+        List::Items(items) => items,
+        List::Text(text) => text
+            .split(',')
+            .map(|t| t.trim().to_string())
+            .filter(|t| !t.is_empty())
+            .collect(),
+    }))
+}
+
+impl Profile {
+    // 将档案中的设置应用到运行选项
+    pub fn apply(&self, options: &mut RunOptions) {
+        let seconds = |v: f64| Duration::from_secs_f64(v.max(0.0));
+        if let Some(dry_run) = self.dry_run {
+            options.dry_run = dry_run;
+        }
+        if let Some(tasks) = &self.only_tasks {
+            options.only_tasks = tasks.clone();
+        }
+        if let Some(tasks) = &self.skip_tasks {
+            options.skip_tasks = tasks.clone();
+        }
+        if let Some(timeout) = self.account_timeout {
+            options.account_timeout = Some(seconds(timeout));
+        }
+        if let Some(timeout) = self.request_timeout {
+            options.request_timeout = Some(seconds(timeout));
+        }
+        if let Some(delay) = self.delay.as_ref().and_then(|d| d.policy()) {
+            options.delay = delay;
+        }
+        match &self.double_card_min_water {
+            Some(Threshold::Water(water)) => options.cards.double_card_min_water = Some(*water),
+            Some(Threshold::Off(_)) => options.cards.double_card_min_water = None,
+            None => {}
+        }
+        if let Some(sign_card) = self.sign_card {
+            options.cards.sign_card = sign_card;
+        }
+        if let Some(range) = self
+            .water_rain_score
+            .as_deref()
+            .and_then(WaterRainRange::parse)
+        {
+            options.water_rain = range;
+        }
+    }
+
+    pub fn error_policy(&self) -> Option<ErrorPolicy> {
+        self.error_policy.as_deref().and_then(ErrorPolicy::parse)
+    }
+
+    pub fn device(&self) -> Option<Device> {
+        self.device.as_deref().and_then(Device::parse)
+    }
+}
+
+fn selected() -> &'static RwLock<Option<String>> {
+    static PROFILE: OnceLock<RwLock<Option<String>>> = OnceLock::new();
+    PROFILE.get_or_init(|| RwLock::new(env::var("JD_FARM_PROFILE").ok().filter(|p| !p.is_empty())))
+}
+
+// 切换配置档案, 之后的运行使用该档案
+pub fn set_profile(name: Option<&str>) {
+    *selected().write().unwrap() = name.map(|n| n.to_string());
+}
+
+// 当前配置档案名称, 未设置时读取JD_FARM_PROFILE
+pub fn profile_name() -> Option<String> {
+    selected().read().unwrap().clone()
+}
+
+// 配置文件内容, JD_FARM_CONFIG指定路径, 默认为当前目录下的jd_farm.toml, 不存在时为空
+fn file() -> &'static Table {
+    static FILE: OnceLock<Table> = OnceLock::new();
+    FILE.get_or_init(|| {
+        let path = env::var("JD_FARM_CONFIG").unwrap_or_else(|_| DEFAULT_CONFIG_FILE.to_string());
+        let Ok(text) = fs::read_to_string(&path) else {
+            return Table::new();
+        };
+        match toml::from_str(&text) {
+            Ok(table) => table,
+            Err(e) => {
+                info!("{}", t!("读取配置文件{}失败, {}", path, e));
+                Table::new()
+            }
+        }
+    })
+}
+
+// 远程配置内容, 见remote_config模块, 未设置远程配置时为空
+fn remote() -> &'static RwLock<Option<Table>> {
+    static REMOTE: OnceLock<RwLock<Option<Table>>> = OnceLock::new();
+    REMOTE.get_or_init(Default::default)
+}
+
+// 替换远程配置, 之后的运行使用新的配置
+pub(crate) fn set_remote(table: Option<Table>) {
+    *remote().write().unwrap() = table;
+}
+
+// 将overlay合并到base, 表按键递归合并, 其他值直接覆盖
+fn merge(base: &mut Table, overlay: Table) {
+    for (key, value) in overlay {
+        match (base.get_mut(&key), value) {
+            (Some(Value::Table(base)), Value::Table(overlay)) => merge(base, overlay),
+            (_, value) => {
+                base.insert(key, value);
+            }
+        }
+    }
+}
+
+// 以JD_FARM__开头的环境变量, 值按TOML解析, 无法解析时作为字符串
+fn env_overrides() -> Table {
+    let mut overrides = Table::new();
+    for (key, raw) in env::vars() {
+        let Some(path) = key.strip_prefix(ENV_PREFIX) else {
+            continue;
+        };
+        let keys: Vec<String> = path.split("__").map(|k| k.to_lowercase()).collect();
+        let value = toml::from_str::<Table>(&format!("v = {}", raw))
+            .ok()
+            .and_then(|mut t| t.remove("v"))
+            .unwrap_or(Value::String(raw));
+        let mut table = Table::new();
+        let mut value = value;
+        for key in keys.iter().skip(1).rev() {
+            table.insert(key.clone(), value);
+            value = Value::Table(std::mem::take(&mut table));
+        }
+        table.insert(keys[0].clone(), value);
+        merge(&mut overrides, table);
+    }
+    overrides
+}
+
+// 当前生效的配置: 配置文件顶层设置, 远程配置, 选中档案(profiles.<名称>)的设置, JD_FARM__环境变量依次覆盖
+pub fn profile() -> Profile {
+    let mut table = file().clone();
+    if let Some(remote) = remote().read().unwrap().clone() {
+        merge(&mut table, remote);
+    }
+    let profiles = table.remove("profiles");
+    if let Some(name) = profile_name() {
+        match profiles.as_ref().and_then(|p| p.get(&name)) {
+            Some(Value::Table(profile)) => merge(&mut table, profile.clone()),
+            _ => info!("{}", t!("未找到配置档案{}", name)),
+        }
+    }
+    merge(&mut table, env_overrides());
+    match Value::Table(table).try_into() {
+        Ok(profile) => profile,
+        Err(e) => {
+            info!("{}", t!("解析配置失败, {}", e));
+            Profile::default()
+        }
+    }
+}
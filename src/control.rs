@@ -0,0 +1,160 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, RwLock};
+
+use jd_com::account::{get_accounts, JAccount};
+use tokio::sync::broadcast::{self, error::RecvError};
+use tokio::sync::Notify;
+use tokio::task::JoinHandle;
+use tracing::error;
+
+use crate::event::RunEvent;
+use crate::options::RunOptions;
+use crate::report::RunReport;
+use crate::runner::Runner;
+
+// 转发给远程订阅者的事件通道容量
+const EVENT_CAPACITY: usize = 1024;
+
+// 远程控制共用的运行状态, 供gRPC、HTTP等服务模式使用
+pub struct Controller {
+    // 各账号的cookie
+    cookies: RwLock<Vec<String>>,
+    // 是否正在运行
+    running: AtomicBool,
+    // 最近一次运行结果
+    last_report: Mutex<Option<RunReport>>,
+    // 所有运行的事件
+    events: broadcast::Sender<RunEvent>,
+    // 运行结束通知
+    finished: Notify,
+    // trigger启动的后台运行任务, 退出时由shutdown等待其结束
+    task: Mutex<Option<JoinHandle<()>>>,
+}
+
+impl Controller {
+    pub fn new(jd_cookie: String) -> Arc<Self> {
+        let cookies = jd_cookie
+            .split('&')
+            .map(|c| c.trim().to_string())
+            .filter(|c| !c.is_empty())
+            .collect();
+        Arc::new(Self {
+            cookies: RwLock::new(cookies),
+            running: AtomicBool::new(false),
+            last_report: Mutex::new(None),
+            events: broadcast::channel(EVENT_CAPACITY).0,
+            finished: Notify::new(),
+            task: Mutex::new(None),
+        })
+    }
+
+    // 已配置的账号
+    pub fn accounts(&self) -> Vec<JAccount> {
+        get_accounts(self.cookies.read().unwrap().join("&"))
+    }
+
+    // 添加账号, 返回账号名称, cookie无效或账号已存在时为空, 只在本次运行期间有效
+    pub fn add_account(&self, cookie: &str) -> Option<String> {
+        let cookie = cookie.trim().trim_end_matches('&').to_string();
+        let account = get_accounts(cookie.clone()).into_iter().next()?;
+        let name = account.name();
+        if self.accounts().iter().any(|a| a.name() == name) {
+            return None;
+        }
+        self.cookies.write().unwrap().push(cookie);
+        Some(name)
+    }
+
+    // 删除账号, 账号不存在时返回false
+    pub fn remove_account(&self, name: &str) -> bool {
+        let mut cookies = self.cookies.write().unwrap();
+        let count = cookies.len();
+        cookies.retain(|c| {
+            get_accounts(c.clone())
+                .first()
+                .is_none_or(|a| a.name() != name)
+        });
+        cookies.len() != count
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.running.load(Ordering::SeqCst)
+    }
+
+    // 最近一次运行结果, 尚未运行时为空
+    pub fn last_report(&self) -> Option<RunReport> {
+        self.last_report.lock().unwrap().clone()
+    }
+
+    // 订阅之后所有运行的事件
+    pub fn subscribe(&self) -> broadcast::Receiver<RunEvent> {
+        self.events.subscribe()
+    }
+
+    // 在后台触发一次运行, names为空时运行所有账号, 已有运行未结束时返回false
+    // 运行在tokio::spawn启动的任务中执行, 任务句柄由Controller持有, 退出前需调用shutdown等待其结束
+    pub fn trigger(self: &Arc<Self>, names: &[String], options: RunOptions) -> bool {
+        if self.running.swap(true, Ordering::SeqCst) {
+            return false;
+        }
+        let accounts: Vec<JAccount> = self
+            .accounts()
+            .into_iter()
+            .filter(|a| names.is_empty() || names.contains(&a.name()))
+            .collect();
+        // 服务模式下请求立即返回, 运行在单独的任务中执行
+        let controller = self.clone();
+        let task = tokio::spawn(async move {
+            let report = controller.run(accounts, options).await;
+            *controller.last_report.lock().unwrap() = Some(report);
+            controller.running.store(false, Ordering::SeqCst);
+            controller.finished.notify_waiters();
+        });
+        *self.task.lock().unwrap() = Some(task);
+        true
+    }
+
+    // 等待trigger启动的运行任务结束, 运行收到退出信号后会在当前任务完成后停止
+    pub async fn shutdown(&self) {
+        let Some(task) = self.task.lock().unwrap().take() else {
+            return;
+        };
+        if let Err(e) = task.await {
+            // 运行任务panic时running不会被重置, 在此补上以免wait_idle一直等待
+            self.running.store(false, Ordering::SeqCst);
+            self.finished.notify_waiters();
+            error!("{}", t!("后台运行任务异常结束, {}", e));
+        }
+    }
+
+    // 等待进行中的运行结束, 没有运行时立即返回
+    pub async fn wait_idle(&self) {
+        loop {
+            let finished = self.finished.notified();
+            if !self.is_running() {
+                return;
+            }
+            finished.await;
+        }
+    }
+
+    // 运行指定账号, 并将运行事件转发给订阅者
+    async fn run(&self, accounts: Vec<JAccount>, options: RunOptions) -> RunReport {
+        let (accounts, backups) = crate::accounts::pool(accounts);
+        let runner = Runner::new(accounts).cookie_pool(backups).options(options);
+        let mut receiver = runner.subscribe();
+        let events = self.events.clone();
+        let forward = async move {
+            loop {
+                match receiver.recv().await {
+                    Ok(event) => {
+                        let _ = events.send(event);
+                    }
+                    Err(RecvError::Lagged(_)) => continue,
+                    Err(RecvError::Closed) => break,
+                }
+            }
+        };
+        tokio::join!(runner.run(), forward).0
+    }
+}
@@ -0,0 +1,377 @@
+use std::env;
+#[cfg(feature = "metrics")]
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::Result;
+use chrono::{DateTime, Local};
+use jd_com::account::JAccount;
+use tokio::time::Instant;
+use tracing::{info, info_span, Instrument};
+
+use crate::accounts::{self, AccountWatcher, RiskCooldown};
+use crate::health;
+use crate::lock::RunLock;
+#[cfg(feature = "metrics")]
+use crate::metrics;
+use crate::notify::Dispatcher;
+use crate::plugin::{self, FarmTask, FarmTasks};
+use crate::report::RunReport;
+use crate::runner::Runner;
+use crate::shutdown;
+use crate::simulate::Simulator;
+use crate::JClient;
+
+// 水滴雨开始后延迟处理的时间, 避免与服务器时间误差导致未到时间
+const WATER_RAIN_DELAY: Duration = Duration::from_secs(60);
+
+// 检查账号文件是否被修改的间隔
+const ACCOUNT_WATCH_INTERVAL: Duration = Duration::from_secs(30);
+
+// 容器运行时默认的运行间隔(分钟)及指标接口监听地址
+const CONTAINER_INTERVAL_MINUTES: u64 = 60;
+#[cfg(feature = "metrics")]
+const CONTAINER_METRICS_ADDR: &str = "0.0.0.0:9100";
+
+// 新一轮水滴雨开始后的处理方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WaterRainMode {
+    // 发送提醒
+    Notify,
+    // 只执行水滴雨任务
+    Run,
+}
+
+impl WaterRainMode {
+    fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_lowercase().as_str() {
+            "notify" => Some(Self::Notify),
+            "run" => Some(Self::Run),
+            _ => None,
+        }
+    }
+}
+
+// 常驻运行模式
+pub struct Daemon {
+    // 两次运行的间隔
+    interval: Duration,
+    // 指标接口监听地址
+    #[cfg(feature = "metrics")]
+    metrics_addr: Option<SocketAddr>,
+    // 两次运行之间新一轮水滴雨开始时的处理方式, 为空时不处理
+    water_rain: Option<WaterRainMode>,
+    // 外部任务
+    tasks: FarmTasks,
+    // 每次运行后是否在标准输出打印结果表格
+    print_table: bool,
+}
+
+impl Daemon {
+    pub fn new(interval: Duration) -> Self {
+        Self {
+            interval,
+            #[cfg(feature = "metrics")]
+            metrics_addr: None,
+            water_rain: None,
+            tasks: plugin::builtin_from_env(),
+            print_table: true,
+        }
+    }
+
+    // 设置指标接口监听地址
+    #[cfg(feature = "metrics")]
+    pub fn metrics_addr(mut self, addr: Option<SocketAddr>) -> Self {
+        self.metrics_addr = addr;
+        self
+    }
+
+    // 设置每次运行后是否打印结果表格, 日志为JSON格式时关闭, 避免标准输出混入非JSON内容
+    pub fn print_table(mut self, print_table: bool) -> Self {
+        self.print_table = print_table;
+        self
+    }
+
+    // 注册外部任务, 每次运行时在内置任务之后执行
+    pub fn register_task<T: FarmTask + 'static>(mut self, task: T) -> Self {
+        self.tasks.push(Arc::new(task));
+        self
+    }
+
+    // 设置新一轮水滴雨开始时的处理方式
+    pub fn water_rain(mut self, mode: Option<WaterRainMode>) -> Self {
+        self.water_rain = mode;
+        self
+    }
+
+    // DAEMON_INTERVAL: 运行间隔(分钟), 设置后以常驻模式运行
+    // METRICS_ADDR: 可选, 指标接口监听地址, 如0.0.0.0:9100
+    // WATER_RAIN_ALERT: 可选, 两次运行之间新一轮水滴雨开始时发送提醒(notify)或只执行水滴雨任务(run)
+    pub fn from_env() -> Option<Self> {
+        let minutes: u64 = env::var("DAEMON_INTERVAL").ok()?.parse().ok()?;
+        Some(Self::with_env(minutes))
+    }
+
+    // 容器运行(--loop)时的设置, 与from_env相同, 但DAEMON_INTERVAL默认60,
+    // METRICS_ADDR默认0.0.0.0:9100, 且不打印结果表格, 标准输出只有JSON日志
+    pub fn container_from_env() -> Self {
+        let minutes = env::var("DAEMON_INTERVAL")
+            .ok()
+            .and_then(|m| m.trim().parse().ok())
+            .unwrap_or(CONTAINER_INTERVAL_MINUTES);
+        #[allow(unused_mut)]
+        let mut daemon = Self::with_env(minutes).print_table(false);
+        #[cfg(feature = "metrics")]
+        if daemon.metrics_addr.is_none() {
+            daemon.metrics_addr = CONTAINER_METRICS_ADDR.parse().ok();
+        }
+        #[cfg(not(feature = "metrics"))]
+        info!("{}", t!("未启用metrics功能, 不提供/healthz及/metrics接口"));
+        daemon
+    }
+
+    fn with_env(minutes: u64) -> Self {
+        let mut daemon = Self::new(Duration::from_secs(minutes.max(1) * 60));
+        #[cfg(feature = "metrics")]
+        {
+            daemon.metrics_addr = env::var("METRICS_ADDR").ok().and_then(|a| a.parse().ok());
+        }
+        daemon.water_rain = env::var("WATER_RAIN_ALERT")
+            .ok()
+            .and_then(|m| WaterRainMode::parse(&m));
+        daemon
+    }
+
+    // 按间隔循环运行所有账号
+    pub async fn run(self, jd_cookie: String) -> Result<()> {
+        health::set_interval(self.interval);
+        health::beat();
+        // 指标接口与运行循环在同一任务中并发执行, 不在后台创建任务
+        #[cfg(feature = "metrics")]
+        if let Some(addr) = self.metrics_addr {
+            let server = async move {
+                if let Err(e) = metrics::server::serve(addr).await {
+                    info!("{}", t!("指标接口启动失败, {}", e));
+                }
+                // 指标接口退出后继续运行
+                std::future::pending::<()>().await
+            };
+            tokio::select! {
+                res = self.run_loop(&jd_cookie) => return res,
+                _ = server => return Ok(()),
+            }
+        }
+        self.run_loop(&jd_cookie).await
+    }
+
+    async fn run_loop(&self, jd_cookie: &str) -> Result<()> {
+        let mut watcher = AccountWatcher::from_env();
+        loop {
+            let accounts = accounts::load(jd_cookie);
+            if let Some(watcher) = watcher.as_mut() {
+                watcher.observe(&accounts);
+            }
+            health::beat();
+            let report = Runner::new(accounts)
+                .cookie_pool(accounts::backups(jd_cookie))
+                .tasks(self.tasks.clone())
+                .run()
+                .await;
+            health::record_run(&report).await;
+            if self.print_table {
+                println!("\n{}", report.to_table());
+            }
+            if shutdown::is_requested() {
+                return Ok(());
+            }
+            info!(
+                "{}",
+                t!("{}分钟后再次运行...", self.interval.as_secs() / 60)
+            );
+            let next_run = Instant::now() + self.interval;
+            let wait = async {
+                match self.water_rain {
+                    Some(mode) => {
+                        self.wait_water_rain(jd_cookie, &report, mode, next_run)
+                            .await
+                    }
+                    None => tokio::time::sleep_until(next_run).await,
+                }
+            };
+            let watch = self.watch_accounts(jd_cookie, watcher.as_mut(), next_run);
+            // 等待期间收到退出信号时立即退出
+            tokio::select! {
+                _ = async { tokio::join!(wait, watch) } => {},
+                _ = shutdown::requested() => return Ok(()),
+            }
+        }
+    }
+
+    // 等待下次运行期间监视账号文件, 新增的账号立即运行一次, 之后与其他账号一起按间隔运行
+    async fn watch_accounts(
+        &self,
+        jd_cookie: &str,
+        watcher: Option<&mut AccountWatcher>,
+        next_run: Instant,
+    ) {
+        let Some(watcher) = watcher else {
+            return;
+        };
+        loop {
+            let now = Instant::now();
+            if now >= next_run {
+                return;
+            }
+            tokio::time::sleep_until((now + ACCOUNT_WATCH_INTERVAL).min(next_run)).await;
+            let added = watcher.poll();
+            if !added.is_empty() {
+                self.onboard(jd_cookie, added).await;
+            }
+        }
+    }
+
+    // 新增的账号先检查cookie, 只运行cookie有效的账号
+    async fn onboard(&self, jd_cookie: &str, accounts: Vec<JAccount>) {
+        let simulator = Simulator::from_env().map(Arc::new);
+        let backups = accounts::backups(jd_cookie);
+        let mut valid = Vec::new();
+        for account in accounts {
+            let name = account.name();
+            let mut client = JClient::new(account.clone())
+                .with_backup_cookies(backups.get(&name).cloned().unwrap_or_default());
+            if let Some(simulator) = &simulator {
+                client = client.with_simulator(simulator.clone());
+            }
+            match client.check_cookie().await {
+                Ok(true) => {
+                    info!("{}", t!("{}, 发现新增账号, 立即运行!", name));
+                    valid.push(account);
+                }
+                Ok(false) => info!("{}", t!("{}, 新增账号的cookie已失效!", name)),
+                Err(e) => info!(
+                    "{}",
+                    t!("{}, 检查新增账号的cookie失败, 下次运行时重试, {}", name, e)
+                ),
+            }
+        }
+        if valid.is_empty() {
+            return;
+        }
+        health::beat();
+        let report = Runner::new(valid)
+            .cookie_pool(backups)
+            .tasks(self.tasks.clone())
+            .run()
+            .await;
+        if self.print_table {
+            println!("\n{}", report.to_table());
+        }
+    }
+
+    // 等待下次运行, 期间每轮水滴雨开始时按设置提醒或执行水滴雨任务
+    async fn wait_water_rain(
+        &self,
+        jd_cookie: &str,
+        report: &RunReport,
+        mode: WaterRainMode,
+        next_run: Instant,
+    ) {
+        let mut pending: Vec<(String, DateTime<Local>)> = report
+            .accounts
+            .iter()
+            .filter_map(|a| a.next_water_rain.map(|t| (a.name.clone(), t)))
+            .collect();
+        let dispatcher = Dispatcher::from_env();
+
+        loop {
+            pending.sort_by_key(|(_, t)| *t);
+            let due = match pending.first() {
+                Some((_, t)) => {
+                    Instant::now()
+                        + (*t - Local::now()).to_std().unwrap_or_default()
+                        + WATER_RAIN_DELAY
+                }
+                None => next_run,
+            };
+            if due >= next_run {
+                tokio::time::sleep_until(next_run).await;
+                return;
+            }
+            tokio::time::sleep_until(due).await;
+            health::beat();
+
+            let (name, _) = pending.remove(0);
+            match mode {
+                WaterRainMode::Notify => {
+                    if dispatcher.is_empty() {
+                        continue;
+                    }
+                    dispatcher
+                        .alert(
+                            &t!("东东农场: 水滴雨已开始"),
+                            &t!("【{}】新一轮水滴雨已开始, 请及时收集!", name),
+                        )
+                        .await;
+                }
+                WaterRainMode::Run => {
+                    let account = accounts::load(jd_cookie)
+                        .into_iter()
+                        .find(|a| a.name() == name);
+                    let Some(account) = account else {
+                        continue;
+                    };
+                    // 因触发风控暂停中的账号不执行水滴雨任务
+                    let cooldown =
+                        RiskCooldown::from_env().map(|c| (c, accounts::cooldown_store()));
+                    if let Some((cooldown, store)) = &cooldown {
+                        let paused = cooldown
+                            .paused(store.as_ref(), std::slice::from_ref(&name))
+                            .await;
+                        if let Some(until) = paused.get(&name) {
+                            info!(
+                                "{}",
+                                t!(
+                                    "{}, 触发风控, 暂停运行至{}, 本次跳过",
+                                    name,
+                                    until.format("%m-%d %H:%M")
+                                )
+                            );
+                            continue;
+                        }
+                    }
+                    // 与运行所有账号时使用相同的设置, 包括模拟运行、设备、运行状态存储及运行选项
+                    let client = Runner::new(Vec::new())
+                        .cookie_pool(accounts::backups(jd_cookie))
+                        .tasks(self.tasks.clone())
+                        .client(account);
+                    let simulated = client.simulator.is_some();
+                    // 账号正在被另一个实例运行时不执行水滴雨任务
+                    let _lock = match RunLock::for_account(&name).filter(|_| !simulated) {
+                        Some(Err(e)) => {
+                            info!("{}", e);
+                            continue;
+                        }
+                        lock => lock,
+                    };
+                    let span = info_span!("account", account = %name);
+                    match client.run_water_rain().instrument(span).await {
+                        Ok(account) => {
+                            if let Some((cooldown, store)) =
+                                cooldown.as_ref().filter(|_| !simulated)
+                            {
+                                cooldown
+                                    .record(store.as_ref(), std::slice::from_ref(&account))
+                                    .await;
+                            }
+                            if let Some(t) = account.next_water_rain {
+                                pending.push((name, t));
+                            }
+                        }
+                        Err(e) => info!("{}", t!("{}, 执行水滴雨任务失败, {}", name, e)),
+                    }
+                }
+            }
+        }
+    }
+}
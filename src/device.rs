@@ -0,0 +1,144 @@
+use std::collections::HashMap;
+use std::env;
+use std::fmt;
+
+use jd_com::sign::get_sign;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+// 安卓客户端的版本信息
+const ANDROID_CLIENT_VERSION: &str = "11.2.8";
+const ANDROID_OS_VERSION: &str = "12";
+const ANDROID_USER_AGENT: &str = "okhttp/3.12.1;jdmall;android;version/11.2.8;build/98450;";
+
+// 请求模拟的客户端设备, 与账号平时登录的设备一致时部分账号更不容易触发风控
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum Device {
+    // iPhone客户端, 请求保持原样
+    #[default]
+    Iphone,
+    // 安卓客户端, 替换User-Agent, 签名中没有client/clientVersion时附加, 请求参数中附加osVersion
+    Android,
+}
+
+impl fmt::Display for Device {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Iphone => write!(f, "iphone"),
+            Self::Android => write!(f, "android"),
+        }
+    }
+}
+
+impl Device {
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.trim().to_lowercase().as_str() {
+            "iphone" | "ios" => Some(Self::Iphone),
+            "android" => Some(Self::Android),
+            _ => None,
+        }
+    }
+
+    // 请求头中的User-Agent
+    pub fn user_agent(&self) -> &'static str {
+        match self {
+            Self::Iphone => "JD4iPhone/168328 (iPhone; iOS; Scale/3.00)",
+            Self::Android => ANDROID_USER_AGENT,
+        }
+    }
+
+    // 生成请求的签名参数, body需已经过apply_body处理
+    // 签名参数原样保留, 签名中已有的client/clientVersion不能修改, 否则签名与请求参数不一致
+    pub fn sign(&self, function_id: &str, body: &str) -> String {
+        let signed = get_sign(function_id, body);
+        if *self == Self::Iphone {
+            return signed;
+        }
+        let has = |key: &str| signed.split('&').any(|p| p.split('=').next() == Some(key));
+        let mut params = vec![signed.clone()];
+        if !has("client") {
+            params.push("client=android".to_string());
+        }
+        if !has("clientVersion") {
+            params.push(format!("clientVersion={}", ANDROID_CLIENT_VERSION));
+        }
+        params.join("&")
+    }
+
+    // 请求参数中附加osVersion, 已有该字段或不是JSON对象时保持不变
+    pub fn apply_body(&self, body: &str) -> String {
+        if *self == Self::Iphone {
+            return body.to_string();
+        }
+        let mut data: Value = match serde_json::from_str(body) {
+            Ok(data @ Value::Object(_)) => data,
+            _ => return body.to_string(),
+        };
+        if data.get("osVersion").is_none() {
+            data["osVersion"] = ANDROID_OS_VERSION.into();
+        }
+        data.to_string()
+    }
+
+    // DEVICE: 请求模拟的设备, iphone或android, 默认iphone, 配置档案中的device优先
+    pub fn from_env() -> Self {
+        crate::config::profile()
+            .device()
+            .or_else(|| env::var("DEVICE").ok().and_then(|d| Self::parse(&d)))
+            .unwrap_or_default()
+    }
+
+    // DEVICE_ACCOUNTS: 单独设置部分账号的设备, 如`账号1=android,账号2=iphone`
+    pub fn accounts_from_env() -> HashMap<String, Self> {
+        env::var("DEVICE_ACCOUNTS")
+            .unwrap_or_default()
+            .split(',')
+            .filter_map(|item| {
+                let (name, device) = item.split_once('=')?;
+                Some((name.trim().to_string(), Self::parse(device)?))
+            })
+            .collect()
+    }
+
+    // 账号使用的设备, 未单独设置时使用DEVICE
+    pub fn for_account(name: &str) -> Self {
+        Self::accounts_from_env()
+            .get(name)
+            .copied()
+            .unwrap_or_else(Self::from_env)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn params(query: &str) -> Vec<(&str, &str)> {
+        query
+            .split('&')
+            .map(|p| p.split_once('=').unwrap_or((p, "")))
+            .collect()
+    }
+
+    // 发送的参数须包含签名中的全部参数且值不变, 每个参数只出现一次
+    #[test]
+    fn sent_params_match_signed_params() {
+        let function_id = "initForFarm";
+        for device in [Device::Iphone, Device::Android] {
+            let body = device.apply_body(r#"{"version":14,"channel":1}"#);
+            let signed = get_sign(function_id, &body);
+            let sent = device.sign(function_id, &body);
+            let sent = params(&sent);
+            for param in params(&signed) {
+                assert!(sent.contains(&param), "{}: {:?}", device, param);
+            }
+            for (key, _) in &sent {
+                assert_eq!(sent.iter().filter(|(k, _)| k == key).count(), 1, "{}", key);
+            }
+            if device == Device::Android {
+                assert!(sent.iter().any(|(k, _)| *k == "client"));
+            }
+        }
+    }
+}
@@ -0,0 +1,312 @@
+use std::env;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use jd_com::sign::get_sign;
+use reqwest::{Client, Proxy, Response};
+use serde::Serialize;
+
+use crate::device::Device;
+use crate::redact::redact;
+use crate::simulate::Simulator;
+use crate::JClient;
+
+// 诊断使用的接口地址
+const API_URL: &str = "https://api.m.jd.com/client.action";
+
+// 签名校验使用的固定参数, 生成的请求参数中必须包含这些字段
+const SIGN_VECTOR: (&str, &str) = (
+    "initForFarm",
+    r#"{"version":14,"channel":1,"babelChannel":"120"}"#,
+);
+const SIGN_FIELDS: [&str; 1] = ["functionId=initForFarm"];
+
+// 读取代理地址的环境变量, 与reqwest默认读取的一致
+const PROXY_VARS: [&str; 6] = [
+    "HTTPS_PROXY",
+    "https_proxy",
+    "ALL_PROXY",
+    "all_proxy",
+    "HTTP_PROXY",
+    "http_proxy",
+];
+
+// 诊断项的结果
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum CheckStatus {
+    // 正常
+    Pass,
+    // 可以运行, 但可能影响结果
+    Warn,
+    // 无法正常运行
+    Fail,
+    // 未检查
+    Skip,
+}
+
+impl CheckStatus {
+    fn mark(&self) -> &'static str {
+        match self {
+            Self::Pass => "[✓]",
+            Self::Warn => "[!]",
+            Self::Fail => "[✗]",
+            Self::Skip => "[-]",
+        }
+    }
+}
+
+// 单个诊断项
+#[derive(Serialize, Debug, Clone)]
+pub struct Check {
+    pub name: String,
+    pub status: CheckStatus,
+    pub detail: String,
+}
+
+impl Check {
+    // 说明中的cookie等敏感信息替换为******
+    fn new(name: String, status: CheckStatus, detail: String) -> Self {
+        Self {
+            name,
+            status,
+            detail: redact(&detail).into_owned(),
+        }
+    }
+}
+
+// 诊断结果, 可直接粘贴到问题反馈中
+#[derive(Serialize, Debug, Clone)]
+pub struct DoctorReport {
+    pub version: String,
+    pub os: String,
+    pub checks: Vec<Check>,
+}
+
+impl DoctorReport {
+    // 没有无法正常运行的诊断项
+    pub fn passed(&self) -> bool {
+        self.checks.iter().all(|c| c.status != CheckStatus::Fail)
+    }
+
+    // 纯文本清单, 每个诊断项一行
+    pub fn to_text(&self) -> String {
+        let mut lines = vec![format!("jd_farm {} ({})", self.version, self.os)];
+        lines.extend(
+            self.checks
+                .iter()
+                .map(|c| format!("{} {}: {}", c.status.mark(), c.name, c.detail)),
+        );
+        lines.join("\n")
+    }
+}
+
+// 运行环境诊断: 网络连通性、系统时间、代理、签名及cookie
+#[derive(Debug, Clone)]
+pub struct Doctor {
+    // 单个请求的超时时间
+    timeout: Duration,
+    // 允许的系统时间误差
+    max_clock_skew: Duration,
+}
+
+impl Default for Doctor {
+    fn default() -> Self {
+        Self {
+            timeout: Duration::from_secs(10),
+            max_clock_skew: Duration::from_secs(30),
+        }
+    }
+}
+
+impl Doctor {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // 设置单个请求的超时时间
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    // 设置允许的系统时间误差, 超过时提示
+    pub fn max_clock_skew(mut self, skew: Duration) -> Self {
+        self.max_clock_skew = skew;
+        self
+    }
+
+    // 依次执行各诊断项, jd_cookie为空时不检查cookie
+    pub async fn run(&self, jd_cookie: &str) -> DoctorReport {
+        let mut checks = Vec::new();
+        let response = self.connect(&mut checks).await;
+        checks.push(self.clock(response.as_ref()));
+        checks.push(self.proxy().await);
+        checks.push(sign());
+        checks.push(cookie(jd_cookie).await);
+        DoctorReport {
+            version: env!("CARGO_PKG_VERSION").to_string(),
+            os: format!("{} {}", env::consts::OS, env::consts::ARCH),
+            checks,
+        }
+    }
+
+    // 使用与运行时相同的网络设置连接接口, 返回的响应用于检查系统时间
+    async fn connect(&self, checks: &mut Vec<Check>) -> Option<Response> {
+        let name = t!("连接{}", "api.m.jd.com");
+        let client = match Client::builder().timeout(self.timeout).build() {
+            Ok(client) => client,
+            Err(e) => {
+                checks.push(Check::new(name, CheckStatus::Fail, e.to_string()));
+                return None;
+            }
+        };
+        let started = Instant::now();
+        match client.get(API_URL).send().await {
+            Ok(response) => {
+                checks.push(Check::new(
+                    name,
+                    CheckStatus::Pass,
+                    t!(
+                        "HTTP {}, 耗时{}ms",
+                        response.status().as_u16(),
+                        started.elapsed().as_millis()
+                    ),
+                ));
+                Some(response)
+            }
+            Err(e) => {
+                checks.push(Check::new(name, CheckStatus::Fail, e.to_string()));
+                None
+            }
+        }
+    }
+
+    // 对比服务器时间(响应头Date)与本机时间
+    fn clock(&self, response: Option<&Response>) -> Check {
+        let name = t!("系统时间");
+        let Some(response) = response else {
+            return Check::new(name, CheckStatus::Skip, t!("无法连接服务器"));
+        };
+        let server = response
+            .headers()
+            .get(reqwest::header::DATE)
+            .and_then(|date| date.to_str().ok())
+            .and_then(|date| DateTime::parse_from_rfc2822(date).ok());
+        let Some(server) = server else {
+            return Check::new(name, CheckStatus::Skip, t!("服务器未返回时间"));
+        };
+        // 响应头的时间精确到秒
+        let skew = Utc::now().signed_duration_since(server).num_seconds();
+        let status = match skew.unsigned_abs() > self.max_clock_skew.as_secs() {
+            true => CheckStatus::Warn,
+            false => CheckStatus::Pass,
+        };
+        Check::new(name, status, t!("与服务器相差{}秒", skew))
+    }
+
+    // 通过环境变量中设置的代理连接接口
+    async fn proxy(&self) -> Check {
+        let name = t!("代理");
+        let Some((var, url)) = PROXY_VARS
+            .iter()
+            .find_map(|var| Some((*var, env::var(var).ok().filter(|v| !v.is_empty())?)))
+        else {
+            return Check::new(name, CheckStatus::Skip, t!("未设置代理"));
+        };
+        // 代理地址中可能包含用户名和密码
+        crate::redact::register(&url);
+        let proxy = match Proxy::all(&url) {
+            Ok(proxy) => proxy,
+            Err(e) => {
+                return Check::new(name, CheckStatus::Fail, t!("{}无效, {}", var, e));
+            }
+        };
+        let client = match Client::builder().proxy(proxy).timeout(self.timeout).build() {
+            Ok(client) => client,
+            Err(e) => return Check::new(name, CheckStatus::Fail, e.to_string()),
+        };
+        let started = Instant::now();
+        match client.get(API_URL).send().await {
+            Ok(response) => Check::new(
+                name,
+                CheckStatus::Pass,
+                t!(
+                    "{}可用, HTTP {}, 耗时{}ms",
+                    var,
+                    response.status().as_u16(),
+                    started.elapsed().as_millis()
+                ),
+            ),
+            Err(e) => Check::new(name, CheckStatus::Fail, t!("{}不可用, {}", var, e)),
+        }
+    }
+
+    // DOCTOR_TIMEOUT: 诊断时单个请求的超时时间(秒), 默认10
+    // DOCTOR_MAX_CLOCK_SKEW: 允许的系统时间误差(秒), 默认30
+    pub fn from_env() -> Self {
+        let seconds = |key: &str| {
+            env::var(key)
+                .ok()
+                .and_then(|v| v.trim().parse::<f64>().ok())
+                .filter(|v| *v > 0.0)
+                .map(Duration::from_secs_f64)
+        };
+        let mut doctor = Self::new();
+        if let Some(timeout) = seconds("DOCTOR_TIMEOUT") {
+            doctor = doctor.timeout(timeout);
+        }
+        if let Some(skew) = seconds("DOCTOR_MAX_CLOCK_SKEW") {
+            doctor = doctor.max_clock_skew(skew);
+        }
+        doctor
+    }
+}
+
+// 使用固定参数生成签名, 检查请求参数是否完整
+fn sign() -> Check {
+    let name = t!("签名");
+    let (function_id, body) = SIGN_VECTOR;
+    let signed = match std::panic::catch_unwind(|| get_sign(function_id, body)) {
+        Ok(signed) => signed,
+        Err(_) => return Check::new(name, CheckStatus::Fail, t!("生成签名时出现异常")),
+    };
+    let missing: Vec<&str> = SIGN_FIELDS
+        .iter()
+        .filter(|field| !signed.split('&').any(|p| p == **field))
+        .copied()
+        .collect();
+    match missing.is_empty() {
+        true => Check::new(name, CheckStatus::Pass, t!("签名参数完整")),
+        false => Check::new(
+            name,
+            CheckStatus::Fail,
+            t!("签名参数缺少{}", missing.join(", ")),
+        ),
+    }
+}
+
+// 检查第一个账号的cookie是否有效, 模拟运行时使用模拟接口
+async fn cookie(jd_cookie: &str) -> Check {
+    let name = t!("cookie");
+    let Some(account) = crate::accounts::load(jd_cookie).into_iter().next() else {
+        return Check::new(name, CheckStatus::Skip, t!("未配置账号"));
+    };
+    let account_name = account.name();
+    let mut client = JClient::new(account).with_device(Device::for_account(&account_name));
+    if let Some(simulator) = Simulator::from_env() {
+        client = client.with_simulator(Arc::new(simulator));
+    }
+    let res: Result<bool> = client.check_cookie().await;
+    match res {
+        Ok(true) => Check::new(name, CheckStatus::Pass, t!("{}, cookie有效", account_name)),
+        Ok(false) => Check::new(
+            name,
+            CheckStatus::Fail,
+            t!("{}, cookie已失效", account_name),
+        ),
+        Err(e) => Check::new(name, CheckStatus::Fail, format!("{}, {}", account_name, e)),
+    }
+}
@@ -0,0 +1,183 @@
+use std::collections::{BTreeSet, HashMap};
+use std::env;
+use std::sync::{Mutex, OnceLock};
+use std::time::Duration;
+
+use serde::Serialize;
+use serde_json::Value;
+use tracing::{info, warn};
+
+use crate::api::JClient;
+use crate::state::{self, StateStore};
+
+// 接口字段基线的保存时间, 长时间未请求的接口重新记录
+const BASELINE_TTL: Duration = Duration::from_secs(30 * 24 * 3600);
+
+// SCHEMA_DRIFT: 是否检测接口返回数据的变化, 默认false
+pub fn is_enabled() -> bool {
+    matches!(
+        env::var("SCHEMA_DRIFT").as_deref().map(str::trim),
+        Ok("true") | Ok("1")
+    )
+}
+
+// 各接口已出现过的字段, 首次请求时从运行状态中读取
+fn baselines() -> &'static Mutex<HashMap<String, BTreeSet<String>>> {
+    static BASELINES: OnceLock<Mutex<HashMap<String, BTreeSet<String>>>> = OnceLock::new();
+    BASELINES.get_or_init(Default::default)
+}
+
+fn baseline_key(function_id: &str) -> String {
+    format!("schema:{}", function_id)
+}
+
+// JSON中所有字段的路径, 如`farmUserPro.treeEnergy`, 数组元素的字段为`list[].name`
+// skip_null为true时不包括值为null的字段(模型中未设置的可选字段)
+pub fn paths(value: &Value, skip_null: bool) -> BTreeSet<String> {
+    fn walk(value: &Value, prefix: &str, skip_null: bool, out: &mut BTreeSet<String>) {
+        match value {
+            Value::Object(map) => {
+                for (key, value) in map {
+                    if skip_null && value.is_null() {
+                        continue;
+                    }
+                    let path = match prefix.is_empty() {
+                        true => key.clone(),
+                        false => format!("{}.{}", prefix, key),
+                    };
+                    walk(value, &path, skip_null, out);
+                    out.insert(path);
+                }
+            }
+            Value::Array(items) => {
+                let path = format!("{}[]", prefix);
+                for item in items {
+                    walk(item, &path, skip_null, out);
+                }
+            }
+            _ => {}
+        }
+    }
+    let mut out = BTreeSet::new();
+    walk(value, "", skip_null, &mut out);
+    out
+}
+
+// 接口返回数据与模型及之前返回数据的差异
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SchemaDrift {
+    // 模型中没有且之前未出现过的字段
+    pub added: Vec<String>,
+    // 之前出现过、模型中使用但本次缺少的字段, 解析时使用了默认值
+    pub missing: Vec<String>,
+}
+
+impl SchemaDrift {
+    // raw为接口返回数据, expected为解析后的模型重新序列化的数据, baseline为之前出现过的字段
+    pub fn compare(raw: &Value, expected: &Value, baseline: &BTreeSet<String>) -> Self {
+        let observed = paths(raw, false);
+        let known = paths(expected, false);
+        Self {
+            added: observed
+                .iter()
+                .filter(|p| !known.contains(*p) && !baseline.contains(*p))
+                .cloned()
+                .collect(),
+            missing: paths(expected, true)
+                .into_iter()
+                .filter(|p| !observed.contains(p) && baseline.contains(p))
+                .collect(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.missing.is_empty()
+    }
+
+    // 文本描述, 如`接口initForFarm新增字段a, b; 缺少字段c`
+    pub fn to_text(&self, function_id: &str) -> String {
+        let mut parts = Vec::new();
+        if !self.added.is_empty() {
+            parts.push(t!("新增字段{}", self.added.join(", ")));
+        }
+        if !self.missing.is_empty() {
+            parts.push(t!("缺少字段{}", self.missing.join(", ")));
+        }
+        t!("接口{}{}", function_id, parts.join("; "))
+    }
+}
+
+impl JClient {
+    // 对比接口返回数据与模型及之前返回数据的字段, 有变化时记录到运行结果中, 未启用时不检测
+    // 首次请求的接口只记录出现的字段, 之后出现的新字段、消失的模型字段才视为变化
+    pub(crate) async fn check_schema<T: Serialize>(
+        &self,
+        function_id: &str,
+        raw: &Value,
+        data: &T,
+    ) {
+        if !is_enabled() || self.simulator.is_some() {
+            return;
+        }
+        let Ok(expected) = serde_json::to_value(data) else {
+            return;
+        };
+        let cached = baselines().lock().unwrap().get(function_id).cloned();
+        let loaded = cached.is_some();
+        let baseline = match cached {
+            Some(baseline) => Some(baseline),
+            None => self.load_baseline(function_id).await,
+        };
+        let observed = paths(raw, false);
+        let drift = match &baseline {
+            Some(baseline) => SchemaDrift::compare(raw, &expected, baseline),
+            None => SchemaDrift::default(),
+        };
+        // 缺少的字段从基线中移除, 同一变化只提示一次
+        let mut updated = baseline.clone().unwrap_or_default();
+        updated.extend(observed);
+        updated.retain(|p| !drift.missing.contains(p));
+        let changed = baseline.as_ref() != Some(&updated);
+        if changed || !loaded {
+            baselines()
+                .lock()
+                .unwrap()
+                .insert(function_id.to_string(), updated.clone());
+        }
+        if changed {
+            if let Err(e) = state::save(
+                self.state.as_ref(),
+                &baseline_key(function_id),
+                &updated,
+                Some(BASELINE_TTL),
+            )
+            .await
+            {
+                info!("{}", t!("保存接口字段失败, {}", e));
+            }
+        }
+        if drift.is_empty() {
+            return;
+        }
+        let text = drift.to_text(function_id);
+        warn!(
+            "{}",
+            t!("{}, 接口返回数据发生变化: {}", self.account.name(), text)
+        );
+        let mut report = self.report.lock().unwrap();
+        if !report.schema_drift.contains(&text) {
+            report.schema_drift.push(text);
+        }
+    }
+
+    async fn load_baseline(&self, function_id: &str) -> Option<BTreeSet<String>> {
+        let store: &dyn StateStore = self.state.as_ref();
+        match state::load::<Option<BTreeSet<String>>>(store, &baseline_key(function_id)).await {
+            Ok(baseline) => baseline,
+            Err(e) => {
+                info!("{}", t!("读取接口字段失败, {}", e));
+                None
+            }
+        }
+    }
+}
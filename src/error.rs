@@ -0,0 +1,175 @@
+use std::collections::HashMap;
+use std::env;
+
+use serde_json::Value;
+use thiserror::Error;
+
+use crate::error_codes;
+
+// 返回信息包含这些关键字时视为触发风控
+const RISK_KEYWORDS: [&str; 3] = ["火爆", "风控", "风险"];
+
+// 返回信息包含这些关键字时视为需要完成安全验证
+const VERIFY_KEYWORDS: [&str; 3] = ["安全验证", "滑块", "验证码"];
+
+// 返回数据中可能包含验证页面地址的字段
+const VERIFY_URL_KEYS: [&str; 5] = ["url", "verifyUrl", "riskUrl", "redirectUrl", "jumpUrl"];
+
+// 验证页面地址包含这些关键字, 如滑块验证码、风控处理页面
+const VERIFY_URL_KEYWORDS: [&str; 4] = ["verify", "captcha", "risk_handler", "safe.jd.com"];
+
+// 定义错误类型
+#[derive(Error, Debug)]
+pub enum JError {
+    // 未登录或cookie已失效
+    #[error("{}", t!("未登录或cookie已失效"))]
+    NotLoggedIn,
+
+    // 触发风控, 如提示活动太火爆
+    #[error("{}", t!("触发风控, 返回码: {}, {}", .code, .message))]
+    RiskControl { code: String, message: String },
+
+    // 接口返回的业务错误
+    #[error("{}", t!("接口返回错误, 返回码: {}, {}", .code, .message))]
+    BusinessError { code: String, message: String },
+
+    // 网络错误, 如连接超时
+    #[error("{}", t!("网络请求失败, {}", .0))]
+    Network(String),
+
+    // 返回数据无法解析, 往往是京东修改了接口
+    #[error("{}", t!("解析接口{}返回数据失败, {}", .endpoint, .message))]
+    Parse { endpoint: String, message: String },
+
+    #[error("{}", t!("该请求本次运行已成功, 不再重复发送"))]
+    DuplicateRequest,
+
+    // 需要完成滑块验证码等安全验证, 验证前继续请求无意义, url为验证页面地址, 未返回时为空
+    #[error("{}", verification_message(.url))]
+    NeedsVerification { url: String },
+}
+
+fn verification_message(url: &str) -> String {
+    match url.is_empty() {
+        true => t!("需要完成安全验证, 请打开京东App完成验证"),
+        false => t!("需要完成安全验证, 请在京东App中打开链接完成验证: {}", url),
+    }
+}
+
+// 地址是否为验证页面
+pub fn is_verification_url(url: &str) -> bool {
+    VERIFY_URL_KEYWORDS.iter().any(|k| url.contains(k))
+}
+
+impl JError {
+    // 根据返回码及返回数据判断错误类型, 请求成功时为空
+    // 返回数据中没有错误信息时使用返回码的说明, 未知的返回码才附上原始数据
+    pub fn from_response(code: &str, data: &Value) -> Option<Self> {
+        let message = ["message", "msg", "echo"]
+            .iter()
+            .find_map(|key| data[*key].as_str())
+            .map(|m| m.to_string())
+            .or_else(|| error_codes::explain(code))
+            .unwrap_or_else(|| truncate(&data.to_string()));
+        if !matches!(code, "0" | "3") {
+            if let Some(url) = Self::verification(data) {
+                return Some(Self::NeedsVerification { url });
+            }
+        }
+        match code {
+            "0" => None,
+            "3" => Some(Self::NotLoggedIn),
+            _ if code == "403" || RISK_KEYWORDS.iter().any(|k| message.contains(k)) => {
+                Some(Self::RiskControl {
+                    code: code.to_string(),
+                    message,
+                })
+            }
+            _ => Some(Self::BusinessError {
+                code: code.to_string(),
+                message,
+            }),
+        }
+    }
+
+    // 返回数据是否要求完成安全验证, 返回验证页面地址, 未返回地址时为空字符串
+    // 只检查返回信息及顶层、data、result中的地址字段, 操作成功的数据中的任务链接不会误判
+    pub fn verification(data: &Value) -> Option<String> {
+        let url = [data, &data["data"], &data["result"]]
+            .into_iter()
+            .flat_map(|object| VERIFY_URL_KEYS.iter().map(move |key| &object[*key]))
+            .filter_map(Value::as_str)
+            .find(|url| is_verification_url(url));
+        if let Some(url) = url {
+            return Some(url.to_string());
+        }
+        ["message", "msg", "echo"]
+            .iter()
+            .filter_map(|key| data[*key].as_str())
+            .any(|m| VERIFY_KEYWORDS.iter().any(|k| m.contains(k)))
+            .then(String::new)
+    }
+
+    // 解析返回数据失败
+    pub fn parse(endpoint: &str, e: impl ToString) -> Self {
+        Self::Parse {
+            endpoint: endpoint.to_string(),
+            message: e.to_string(),
+        }
+    }
+
+    // 是否可以重试, 网络错误可以稍后重试, 风控、未登录等重试无意义
+    pub fn is_retryable(&self) -> bool {
+        matches!(self, Self::Network(_))
+    }
+}
+
+// 原始数据最多保留的字符数
+const MAX_RAW_CHARS: usize = 200;
+
+fn truncate(raw: &str) -> String {
+    match raw.char_indices().nth(MAX_RAW_CHARS) {
+        Some((end, _)) => format!("{}...", &raw[..end]),
+        None => raw.to_string(),
+    }
+}
+
+// 任务出错时的处理策略
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ErrorPolicy {
+    // 记录错误后继续执行后续任务
+    #[default]
+    ContinueOnError,
+    // 遇到第一个错误时停止运行该账号, 并返回该错误
+    FailFast,
+}
+
+impl ErrorPolicy {
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.trim().to_lowercase().replace('-', "_").as_str() {
+            "continue" | "continue_on_error" => Some(Self::ContinueOnError),
+            "fail_fast" => Some(Self::FailFast),
+            _ => None,
+        }
+    }
+
+    // ERROR_POLICY: 默认策略, continue或fail_fast, 默认continue, 配置档案中的error_policy优先
+    pub fn from_env() -> Self {
+        crate::config::profile()
+            .error_policy()
+            .or_else(|| env::var("ERROR_POLICY").ok().and_then(|p| Self::parse(&p)))
+            .unwrap_or_default()
+    }
+
+    // ERROR_POLICY_ACCOUNTS: 单独设置部分账号的策略, 如`账号1=fail_fast,账号2=continue`
+    pub fn accounts_from_env() -> HashMap<String, Self> {
+        env::var("ERROR_POLICY_ACCOUNTS")
+            .unwrap_or_default()
+            .split(',')
+            .filter_map(|item| {
+                let (name, policy) = item.split_once('=')?;
+                Some((name.trim().to_string(), Self::parse(policy)?))
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,96 @@
+use crate::error::JError;
+use crate::i18n::tr;
+
+// 京东接口返回码的说明及建议的处理方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ErrorCode {
+    // 返回码
+    pub code: &'static str,
+    // 返回码的含义
+    pub explanation: &'static str,
+    // 建议的处理方式
+    pub action: &'static str,
+}
+
+// 已知的返回码, 同一返回码在各接口中含义基本一致
+pub const ERROR_CODES: [ErrorCode; 9] = [
+    ErrorCode {
+        code: "-1",
+        explanation: "系统繁忙",
+        action: "稍后重试",
+    },
+    ErrorCode {
+        code: "1",
+        explanation: "系统繁忙",
+        action: "稍后重试",
+    },
+    ErrorCode {
+        code: "2",
+        explanation: "请求参数错误, 可能是接口版本过旧",
+        action: "升级程序, 或通过API_VERSION指定接口版本",
+    },
+    ErrorCode {
+        code: "3",
+        explanation: "未登录或cookie已失效",
+        action: "重新登录京东并更新JD_COOKIE",
+    },
+    ErrorCode {
+        code: "7",
+        explanation: "任务已完成或奖励已领取",
+        action: "无需处理",
+    },
+    ErrorCode {
+        code: "10",
+        explanation: "今日次数已达上限",
+        action: "无需处理, 明日再运行",
+    },
+    ErrorCode {
+        code: "11",
+        explanation: "水滴不足",
+        action: "无需处理, 获得水滴后会继续浇水",
+    },
+    ErrorCode {
+        code: "400",
+        explanation: "请求参数错误, 可能是接口版本过旧",
+        action: "升级程序, 或通过API_VERSION指定接口版本",
+    },
+    ErrorCode {
+        code: "403",
+        explanation: "请求被拒绝, 触发风控",
+        action: "增大DELAY等请求间隔, 暂停运行几小时后再试",
+    },
+];
+
+// 查询返回码的说明, 未知的返回码为空
+pub fn lookup(code: &str) -> Option<&'static ErrorCode> {
+    ERROR_CODES.iter().find(|c| c.code == code)
+}
+
+// 返回码的含义, 如`未登录或cookie已失效`
+pub fn explain(code: &str) -> Option<String> {
+    lookup(code).map(|c| tr(c.explanation).to_string())
+}
+
+impl JError {
+    // 建议的处理方式, 重复请求等无需处理的错误为空
+    pub fn advice(&self) -> Option<String> {
+        let action = match self {
+            Self::NotLoggedIn => lookup("3")?.action,
+            // 风控也可能由返回信息中的关键字判断, 返回码不一定是403
+            Self::RiskControl { .. } => lookup("403")?.action,
+            Self::BusinessError { code, .. } => lookup(code)?.action,
+            Self::Network(_) => "检查网络连接及代理, 可运行doctor命令诊断",
+            Self::Parse { .. } => "京东可能修改了接口, 请升级程序",
+            Self::NeedsVerification { .. } => "在京东App中完成验证后重新运行",
+            Self::DuplicateRequest => return None,
+        };
+        Some(tr(action).to_string())
+    }
+}
+
+// 错误链中第一个接口错误的处理建议
+pub fn advice(e: &anyhow::Error) -> Option<String> {
+    e.chain()
+        .find_map(|c| c.downcast_ref::<JError>())
+        .and_then(JError::advice)
+}
@@ -0,0 +1,53 @@
+use std::env;
+use std::sync::Arc;
+
+use sentry::{protocol::Event, ClientInitGuard, ClientOptions, Level};
+
+use crate::redact::redact;
+use crate::report::RunReport;
+
+// 上报前隐藏事件中的敏感信息
+fn scrub(mut event: Event<'static>) -> Option<Event<'static>> {
+    if let Some(message) = &event.message {
+        event.message = Some(redact(message).into_owned());
+    }
+    for exception in event.exception.values.iter_mut() {
+        if let Some(value) = &exception.value {
+            exception.value = Some(redact(value).into_owned());
+        }
+    }
+    // 不上报请求、用户等可能包含cookie的信息
+    event.request = None;
+    event.user = None;
+    Some(event)
+}
+
+// SENTRY_DSN: 设置后启用Sentry错误上报, 同时捕获panic
+pub fn init() -> Option<ClientInitGuard> {
+    let dsn = env::var("SENTRY_DSN").ok()?;
+    let guard = sentry::init((
+        dsn,
+        ClientOptions {
+            release: sentry::release_name!(),
+            send_default_pii: false,
+            before_send: Some(Arc::new(scrub)),
+            ..Default::default()
+        },
+    ));
+    Some(guard)
+}
+
+// 上报运行中断或cookie失效的账号, 只携带账号备注名
+pub fn capture_report(report: &RunReport) {
+    for account in &report.accounts {
+        let (message, level) = match (&account.error, account.cookie_expired) {
+            (Some(error), _) => (error.clone(), Level::Error),
+            (None, true) => (t!("cookie已失效"), Level::Warning),
+            _ => continue,
+        };
+        sentry::with_scope(
+            |scope| scope.set_tag("account", &account.name),
+            || sentry::capture_message(&message, level),
+        );
+    }
+}
@@ -0,0 +1,72 @@
+use std::io::Write;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use serde::Serialize;
+use tokio::sync::broadcast::{self, error::RecvError};
+
+use crate::report::AccountReport;
+
+// 运行过程中的事件, 供GUI/Web等前端展示实时进度
+#[derive(Serialize, Debug, Clone)]
+#[serde(tag = "event", rename_all = "snake_case")]
+#[non_exhaustive]
+pub enum RunEvent {
+    // 开始执行任务
+    TaskStarted {
+        account: String,
+        task: String,
+    },
+    // 任务执行成功
+    TaskCompleted {
+        account: String,
+        task: String,
+        gain: u64,
+    },
+    // 获得水滴
+    WaterGained {
+        account: String,
+        task: String,
+        amount: u64,
+    },
+    // 任务执行失败
+    TaskFailed {
+        account: String,
+        task: String,
+    },
+    // 账号运行结束
+    // 运行结果较大, 装箱后事件在通道中复制的开销较小
+    AccountFinished {
+        report: Box<AccountReport>,
+    },
+}
+
+// 是否将运行事件以NDJSON格式输出到标准输出
+static NDJSON: AtomicBool = AtomicBool::new(false);
+
+// 运行事件以NDJSON格式(每行一个JSON)输出到标准输出, 便于通过管道交给jq等程序处理
+// 日志需同时改为输出到标准错误, 见logging::use_stderr
+pub fn output_ndjson() {
+    NDJSON.store(true, Ordering::Relaxed);
+}
+
+// 是否输出NDJSON格式的运行事件
+pub fn is_ndjson() -> bool {
+    NDJSON.load(Ordering::Relaxed)
+}
+
+// 将运行事件逐行写入标准输出, 事件通道关闭后结束
+pub(crate) async fn write_ndjson(mut events: broadcast::Receiver<RunEvent>) {
+    loop {
+        match events.recv().await {
+            Ok(event) => {
+                if let Ok(line) = serde_json::to_string(&event) {
+                    let mut stdout = std::io::stdout().lock();
+                    let _ = writeln!(stdout, "{}", line);
+                    let _ = stdout.flush();
+                }
+            }
+            Err(RecvError::Lagged(_)) => continue,
+            Err(RecvError::Closed) => break,
+        }
+    }
+}
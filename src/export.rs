@@ -0,0 +1,514 @@
+use std::env;
+use std::fs::{self, OpenOptions};
+use std::path::{Path, PathBuf};
+
+use anyhow::Result;
+use chrono::{DateTime, Duration, FixedOffset, NaiveDate, TimeZone, Utc};
+
+use crate::clock;
+use crate::i18n::tr;
+use crate::report::{escape_html, progress_cell, AccountReport, RunReport, Severity};
+use crate::share::ShareCode;
+use crate::tasks::THREE_MEAL_WINDOWS;
+
+const CSV_HEADER: [&str; 8] = [
+    "date",
+    "account",
+    "water_gained",
+    "tasks_ok",
+    "tasks_failed",
+    "water_balance",
+    "cookie_expired",
+    "error",
+];
+
+// 以CSV格式追加运行结果, 每次运行每个账号一行
+pub struct CsvExporter {
+    path: PathBuf,
+}
+
+impl CsvExporter {
+    pub fn new<P: Into<PathBuf>>(path: P) -> Self {
+        Self { path: path.into() }
+    }
+
+    // CSV_EXPORT: CSV文件路径, 未设置时不导出
+    pub fn from_env() -> Option<Self> {
+        let path = env::var("CSV_EXPORT").ok().filter(|p| !p.is_empty())?;
+        Some(Self::new(path))
+    }
+
+    pub fn export(&self, report: &RunReport) -> Result<()> {
+        if let Some(dir) = self.path.parent() {
+            if !dir.as_os_str().is_empty() {
+                fs::create_dir_all(dir)?;
+            }
+        }
+        // 新文件先写入表头
+        let is_new = fs::metadata(&self.path)
+            .map(|m| m.len() == 0)
+            .unwrap_or(true);
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)?;
+        let mut writer = csv::Writer::from_writer(file);
+        if is_new {
+            writer.write_record(CSV_HEADER)?;
+        }
+
+        let date = report.started_at.format("%Y-%m-%d %H:%M:%S").to_string();
+        for account in &report.accounts {
+            let failed = account.failed_tasks().len();
+            writer.write_record([
+                date.clone(),
+                account.name.clone(),
+                account.water_gained().to_string(),
+                (account.tasks.len() - failed).to_string(),
+                failed.to_string(),
+                account.water_after.to_string(),
+                account.cookie_expired.to_string(),
+                account.error.clone().unwrap_or_default(),
+            ])?;
+        }
+        writer.flush()?;
+        Ok(())
+    }
+}
+
+// 以CSV格式导出各账号的助力码及分享链接, 覆盖已有文件
+pub fn export_share_codes<P: AsRef<Path>>(path: P, codes: &[ShareCode]) -> Result<()> {
+    let path = path.as_ref();
+    if let Some(dir) = path.parent() {
+        if !dir.as_os_str().is_empty() {
+            fs::create_dir_all(dir)?;
+        }
+    }
+    let mut writer = csv::Writer::from_path(path)?;
+    writer.write_record(["account", "share_code", "link"])?;
+    for code in codes {
+        writer.write_record([&code.account, &code.share_code, &code.link])?;
+    }
+    writer.flush()?;
+    Ok(())
+}
+
+// 日历中水滴雨事件的时长(分钟)
+const WATER_RAIN_EVENT_MINUTES: i64 = 30;
+
+// 以iCalendar格式导出接下来可执行的限时任务: 下一轮水滴雨、三餐定时领水及果树预计成熟日期, 覆盖已有文件
+// 手动运行时可导入日历, 按提醒时间运行
+pub struct IcsExporter {
+    path: PathBuf,
+    // 导出几天内的定时领水时间段
+    days: u32,
+}
+
+impl IcsExporter {
+    pub fn new<P: Into<PathBuf>>(path: P) -> Self {
+        Self {
+            path: path.into(),
+            days: 2,
+        }
+    }
+
+    // 设置导出几天内的定时领水时间段, 包括今天
+    pub fn days(mut self, days: u32) -> Self {
+        self.days = days;
+        self
+    }
+
+    // ICS_EXPORT: 日历文件路径, 未设置时不导出
+    // ICS_DAYS: 导出几天内的定时领水时间段, 默认2(今天和明天)
+    pub fn from_env() -> Option<Self> {
+        let path = env::var("ICS_EXPORT").ok().filter(|p| !p.is_empty())?;
+        let mut exporter = Self::new(path);
+        if let Some(days) = env::var("ICS_DAYS")
+            .ok()
+            .and_then(|d| d.trim().parse().ok())
+        {
+            exporter = exporter.days(days);
+        }
+        Some(exporter)
+    }
+
+    pub fn export(&self, report: &RunReport) -> Result<()> {
+        if let Some(dir) = self.path.parent() {
+            if !dir.as_os_str().is_empty() {
+                fs::create_dir_all(dir)?;
+            }
+        }
+        fs::write(&self.path, render_ics(report, clock::now(), self.days))?;
+        Ok(())
+    }
+}
+
+// 日历中的单个事件
+struct IcsEvent {
+    uid: String,
+    summary: String,
+    description: String,
+    // 开始及结束时间, 全天事件为日期
+    start: IcsTime,
+    end: IcsTime,
+}
+
+enum IcsTime {
+    At(DateTime<Utc>),
+    Date(NaiveDate),
+}
+
+impl IcsTime {
+    fn property(&self, name: &str) -> String {
+        match self {
+            Self::At(t) => format!("{}:{}", name, t.format("%Y%m%dT%H%M%SZ")),
+            Self::Date(d) => format!("{};VALUE=DATE:{}", name, d.format("%Y%m%d")),
+        }
+    }
+}
+
+// 转义文本中的特殊字符
+fn escape_ics(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace('\n', "\\n")
+}
+
+// 超过75字节的行折行, 后续行以空格开头
+fn fold_ics(line: &str) -> String {
+    let mut folded = String::new();
+    let mut width = 0;
+    for c in line.chars() {
+        if width + c.len_utf8() > 75 {
+            folded.push_str("\r\n ");
+            width = 1;
+        }
+        folded.push(c);
+        width += c.len_utf8();
+    }
+    folded
+}
+
+// 账号名称的哈希, 用于生成稳定的事件UID
+fn account_id(name: &str) -> String {
+    let hash = name.bytes().fold(0xcbf2_9ce4_8422_2325u64, |h, b| {
+        (h ^ b as u64).wrapping_mul(0x100_0000_01b3)
+    });
+    format!("{:016x}", hash)
+}
+
+// 生成iCalendar文件内容, now为当前服务器时间, days为导出几天内的定时领水时间段
+pub fn render_ics(report: &RunReport, now: DateTime<Utc>, days: u32) -> String {
+    let china_timezone = FixedOffset::east_opt(8 * 3600).unwrap();
+    let today = now.with_timezone(&china_timezone).date_naive();
+    let mut events = Vec::new();
+
+    for account in &report.accounts {
+        let id = account_id(&account.name);
+        // next_water_rain已换算为本机时间, 换算回服务器时间
+        if let Some(rain) = account.next_water_rain {
+            let start = rain.with_timezone(&Utc) + clock::skew();
+            events.push(IcsEvent {
+                uid: format!("water-rain-{}-{}@jd_farm", id, start.timestamp()),
+                summary: t!("收集水滴雨: {}", account.name),
+                description: t!("新一轮水滴雨已开始, 运行jd_farm收集"),
+                start: IcsTime::At(start),
+                end: IcsTime::At(start + Duration::minutes(WATER_RAIN_EVENT_MINUTES)),
+            });
+        }
+        if let Some(eta) = report.harvest_eta_days(account) {
+            let date = today + Duration::days(eta.ceil() as i64);
+            events.push(IcsEvent {
+                uid: format!("harvest-{}@jd_farm", id),
+                summary: t!("果树预计成熟: {}", account.name),
+                description: report.progress_text(account).unwrap_or_default(),
+                start: IcsTime::Date(date),
+                end: IcsTime::Date(date + Duration::days(1)),
+            });
+        }
+    }
+
+    // 定时领水的时间段所有账号相同, 已结束的时间段不导出
+    for day in 0..days {
+        let date = today + Duration::days(day as i64);
+        for (start_hour, end_hour) in THREE_MEAL_WINDOWS {
+            let at = |hour: u32| {
+                china_timezone
+                    .from_local_datetime(&date.and_hms_opt(hour, 0, 0).unwrap())
+                    .unwrap()
+                    .with_timezone(&Utc)
+            };
+            let (start, end) = (at(start_hour), at(end_hour));
+            if end <= now {
+                continue;
+            }
+            events.push(IcsEvent {
+                uid: format!(
+                    "three-meal-{}-{}@jd_farm",
+                    date.format("%Y%m%d"),
+                    start_hour
+                ),
+                summary: t!("定时领水"),
+                description: t!("三餐定时领水时间段, 运行jd_farm领取"),
+                start: IcsTime::At(start),
+                end: IcsTime::At(end),
+            });
+        }
+    }
+
+    let stamp = now.format("%Y%m%dT%H%M%SZ");
+    let mut lines = vec![
+        "BEGIN:VCALENDAR".to_string(),
+        "VERSION:2.0".to_string(),
+        "PRODID:-//jd_farm//jd_farm//CN".to_string(),
+        "CALSCALE:GREGORIAN".to_string(),
+    ];
+    for event in events {
+        lines.push("BEGIN:VEVENT".to_string());
+        lines.push(format!("UID:{}", event.uid));
+        lines.push(format!("DTSTAMP:{}", stamp));
+        lines.push(event.start.property("DTSTART"));
+        lines.push(event.end.property("DTEND"));
+        lines.push(format!("SUMMARY:{}", escape_ics(&event.summary)));
+        lines.push(format!("DESCRIPTION:{}", escape_ics(&event.description)));
+        lines.push("END:VEVENT".to_string());
+    }
+    lines.push("END:VCALENDAR".to_string());
+    // iCalendar要求以CRLF换行
+    let lines: Vec<String> = lines.iter().map(|l| fold_ics(l)).collect();
+    lines.join("\r\n") + "\r\n"
+}
+
+const REPORT_HTML_TEMPLATE: &str = r#"<!DOCTYPE html>
+<html>
+<head>
+<meta charset="utf-8">
+<title>{title}</title>
+<style>
+body { font-family: sans-serif; font-size: 14px; margin: 2em; }
+table { border-collapse: collapse; margin-bottom: 1em; }
+th, td { border: 1px solid #ccc; padding: 4px 8px; text-align: left; }
+th { background: #f5f5f5; }
+.failed { color: #c0392b; }
+</style>
+</head>
+<body>
+{content}
+</body>
+</html>"#;
+
+// 运行报告格式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReportFormat {
+    Markdown,
+    Html,
+}
+
+// 每次运行结束后生成的报告文件
+pub struct ReportFile {
+    path: String,
+    format: ReportFormat,
+}
+
+impl ReportFile {
+    pub fn new(path: &str, format: ReportFormat) -> Self {
+        Self {
+            path: path.to_string(),
+            format,
+        }
+    }
+
+    // REPORT_FILE: 报告文件路径, 以.html/.htm结尾时生成HTML, 否则生成Markdown; 路径中的{date}会替换为运行日期
+    pub fn from_env() -> Option<Self> {
+        let path = env::var("REPORT_FILE").ok().filter(|p| !p.is_empty())?;
+        let lower = path.to_lowercase();
+        let format = if lower.ends_with(".html") || lower.ends_with(".htm") {
+            ReportFormat::Html
+        } else {
+            ReportFormat::Markdown
+        };
+        Some(Self::new(&path, format))
+    }
+
+    // 写入报告文件, 返回文件路径
+    pub fn write(&self, report: &RunReport) -> Result<PathBuf> {
+        let path = PathBuf::from(
+            self.path
+                .replace("{date}", &report.started_at.format("%Y%m%d").to_string()),
+        );
+        if let Some(dir) = path.parent() {
+            if !dir.as_os_str().is_empty() {
+                fs::create_dir_all(dir)?;
+            }
+        }
+        let content = match self.format {
+            ReportFormat::Markdown => render_markdown(report),
+            ReportFormat::Html => render_html(report),
+        };
+        fs::write(&path, content)?;
+        Ok(path)
+    }
+}
+
+// 报告中显示的账号状态
+fn account_status(account: &AccountReport) -> String {
+    let failed = account.failed_tasks().len();
+    if account.is_ok() && failed > 0 {
+        t!("{}个任务失败", failed)
+    } else {
+        account.status()
+    }
+}
+
+// 生成Markdown格式的运行报告
+pub fn render_markdown(report: &RunReport) -> String {
+    let mut lines = vec![
+        t!("# {}运行报告", report.title()),
+        String::new(),
+        t!(
+            "运行时间: {} ~ {}",
+            report.started_at.format("%Y-%m-%d %H:%M:%S"),
+            report.finished_at.format("%H:%M:%S")
+        ),
+        String::new(),
+        t!("## 汇总"),
+        String::new(),
+        t!("| 账号 | 获得水滴(g) | 剩余水滴(g) | 果树进度 | 成功任务 | 失败任务 | 状态 |"),
+        "| --- | --- | --- | --- | --- | --- | --- |".to_string(),
+    ];
+    for account in &report.accounts {
+        let failed = account.failed_tasks().len();
+        lines.push(format!(
+            "| {} | {} | {} | {} | {} | {} | {} |",
+            account.name,
+            account.water_gained(),
+            account.water_after,
+            progress_cell(account),
+            account.tasks.len() - failed,
+            failed,
+            account_status(account)
+        ));
+    }
+    lines.push(t!(
+        "| **合计** | **{}** | | | | | {}个账号, cookie失效{}个 |",
+        report.water_gained(),
+        report.accounts.len(),
+        report.expired_accounts().len()
+    ));
+    if let Some(trends) = &report.trends {
+        lines.push(String::new());
+        lines.extend(trends.to_text().lines().map(|l| format!("> {}", l)));
+    }
+
+    for account in &report.accounts {
+        lines.push(String::new());
+        lines.push(format!("## {}", account.name));
+        lines.push(String::new());
+        if let Some(e) = &account.error {
+            lines.push(t!("> 错误: {}", e));
+            lines.push(String::new());
+        }
+        if let Some(progress) = report.progress_text(account) {
+            lines.push(progress);
+            lines.push(String::new());
+        }
+        if account.tasks.is_empty() {
+            lines.push(t!("未执行任务"));
+            continue;
+        }
+        lines.push(t!("| 任务 | 结果 | 获得水滴(g) |"));
+        lines.push("| --- | --- | --- |".to_string());
+        for task in &account.tasks {
+            lines.push(format!(
+                "| {} | {} | {} |",
+                tr(&task.name),
+                if task.success {
+                    tr("成功")
+                } else {
+                    tr("**失败**")
+                },
+                task.gain
+            ));
+        }
+    }
+    lines.push(String::new());
+    lines.join("\n")
+}
+
+// 生成HTML格式的运行报告
+pub fn render_html(report: &RunReport) -> String {
+    let mut content = t!(
+        "<h1>{}运行报告</h1><p>运行时间: {} ~ {}</p><h2>汇总</h2>",
+        escape_html(&report.title()),
+        report.started_at.format("%Y-%m-%d %H:%M:%S"),
+        report.finished_at.format("%H:%M:%S")
+    );
+    content.push_str(&t!("<table><tr><th>账号</th><th>获得水滴(g)</th><th>剩余水滴(g)</th><th>果树进度</th><th>成功任务</th><th>失败任务</th><th>状态</th></tr>"));
+    for account in &report.accounts {
+        let failed = account.failed_tasks().len();
+        let class = if account.severity() == Severity::Info {
+            ""
+        } else {
+            " class=\"failed\""
+        };
+        content.push_str(&format!(
+            "<tr><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td{}>{}</td></tr>",
+            escape_html(&account.name),
+            account.water_gained(),
+            account.water_after,
+            escape_html(&progress_cell(account)),
+            account.tasks.len() - failed,
+            failed,
+            class,
+            escape_html(&account_status(account))
+        ));
+    }
+    content.push_str(&t!(
+        "<tr><th>合计</th><th>{}</th><th></th><th></th><th></th><th></th><th>{}个账号, cookie失效{}个</th></tr></table>",
+        report.water_gained(),
+        report.accounts.len(),
+        report.expired_accounts().len()
+    ));
+    if let Some(trends) = &report.trends {
+        content.push_str(&format!(
+            "<p>{}</p>",
+            escape_html(&trends.to_text()).replace('\n', "<br>")
+        ));
+    }
+
+    for account in &report.accounts {
+        content.push_str(&format!("<h2>{}</h2>", escape_html(&account.name)));
+        if let Some(e) = &account.error {
+            content.push_str(&t!("<p class=\"failed\">错误: {}</p>", escape_html(e)));
+        }
+        if let Some(progress) = report.progress_text(account) {
+            content.push_str(&format!("<p>{}</p>", escape_html(&progress)));
+        }
+        if account.tasks.is_empty() {
+            content.push_str(&t!("<p>未执行任务</p>"));
+            continue;
+        }
+        content.push_str(&t!(
+            "<table><tr><th>任务</th><th>结果</th><th>获得水滴(g)</th></tr>"
+        ));
+        for task in &account.tasks {
+            let result = if task.success {
+                tr("<td>成功</td>")
+            } else {
+                tr("<td class=\"failed\">失败</td>")
+            };
+            content.push_str(&format!(
+                "<tr><td>{}</td>{}<td>{}</td></tr>",
+                escape_html(tr(&task.name)),
+                result,
+                task.gain
+            ));
+        }
+        content.push_str("</table>");
+    }
+
+    REPORT_HTML_TEMPLATE
+        .replace("{title}", &escape_html(&report.title()))
+        .replace("{content}", &content)
+}
@@ -0,0 +1,174 @@
+// tonic的接口要求返回tonic::Status
+#![allow(clippy::result_large_err)]
+
+use std::env;
+use std::net::SocketAddr;
+use std::pin::Pin;
+use std::sync::Arc;
+
+use anyhow::Result;
+use tokio_stream::wrappers::BroadcastStream;
+use tokio_stream::{Stream, StreamExt};
+use tonic::transport::Server;
+use tonic::{Request, Response, Status};
+use tracing::info;
+
+use crate::control::Controller;
+use crate::event::RunEvent;
+use crate::options::RunOptions;
+use crate::redact::Secret;
+
+// 根据proto/jd_farm.proto生成的消息及服务定义
+pub mod proto {
+    tonic::include_proto!("jd_farm");
+}
+
+use proto::farm_control_server::{FarmControl, FarmControlServer};
+use proto::{
+    AccountStatus, GetStatusRequest, ListAccountsRequest, ListAccountsResponse, RunEventMessage,
+    RunEventsRequest, StatusResponse, TriggerRunRequest, TriggerRunResponse,
+};
+
+// gRPC控制服务, 供中心控制端统一调度多台机器
+pub struct GrpcServer {
+    addr: SocketAddr,
+    token: Option<Secret>,
+}
+
+impl GrpcServer {
+    pub fn new(addr: SocketAddr) -> Self {
+        Self { addr, token: None }
+    }
+
+    // 设置访问令牌, 请求需携带`authorization: Bearer <token>`
+    pub fn token(mut self, token: &str) -> Self {
+        self.token = Some(Secret::new(token));
+        self
+    }
+
+    // GRPC_ADDR: 监听地址, 如0.0.0.0:50051, 设置后以gRPC服务模式运行
+    // GRPC_TOKEN: 可选, 访问令牌
+    pub fn from_env() -> Option<Self> {
+        let addr = env::var("GRPC_ADDR").ok()?.parse().ok()?;
+        let mut server = Self::new(addr);
+        if let Some(token) = env::var("GRPC_TOKEN").ok().filter(|t| !t.is_empty()) {
+            server = server.token(&token);
+        }
+        Some(server)
+    }
+
+    // 启动服务, 直到出错才返回
+    pub async fn serve(self, controller: Arc<Controller>) -> Result<()> {
+        let expected = self.token.map(|t| format!("Bearer {}", t.expose()));
+        let check = move |request: Request<()>| -> Result<Request<()>, Status> {
+            let Some(expected) = &expected else {
+                return Ok(request);
+            };
+            let token = request
+                .metadata()
+                .get("authorization")
+                .and_then(|v| v.to_str().ok());
+            match token == Some(expected.as_str()) {
+                true => Ok(request),
+                false => Err(Status::unauthenticated(t!("访问令牌无效"))),
+            }
+        };
+        info!("{}", t!("gRPC服务已启动: {}", self.addr));
+        let service = FarmControlServer::with_interceptor(FarmControlService { controller }, check);
+        Server::builder()
+            .add_service(service)
+            .serve(self.addr)
+            .await?;
+        Ok(())
+    }
+}
+
+struct FarmControlService {
+    controller: Arc<Controller>,
+}
+
+// 运行事件转换为gRPC消息
+fn event_message(event: &RunEvent) -> RunEventMessage {
+    let value = serde_json::to_value(event).unwrap_or_default();
+    let account = match event {
+        RunEvent::AccountFinished { report } => report.name.clone(),
+        _ => value["account"].as_str().unwrap_or_default().to_string(),
+    };
+    RunEventMessage {
+        event: value["event"].as_str().unwrap_or_default().to_string(),
+        account,
+        json: value.to_string(),
+    }
+}
+
+#[tonic::async_trait]
+impl FarmControl for FarmControlService {
+    async fn trigger_run(
+        &self,
+        request: Request<TriggerRunRequest>,
+    ) -> Result<Response<TriggerRunResponse>, Status> {
+        let request = request.into_inner();
+        let mut options = RunOptions::from_env();
+        options.dry_run = options.dry_run || request.dry_run;
+        let started = self.controller.trigger(&request.accounts, options);
+        let message = match started {
+            true => t!("已开始运行"),
+            false => t!("上一次运行尚未结束"),
+        };
+        Ok(Response::new(TriggerRunResponse { started, message }))
+    }
+
+    async fn get_status(
+        &self,
+        _request: Request<GetStatusRequest>,
+    ) -> Result<Response<StatusResponse>, Status> {
+        let mut status = StatusResponse {
+            running: self.controller.is_running(),
+            ..Default::default()
+        };
+        if let Some(report) = self.controller.last_report() {
+            status.started_at = report.started_at.to_rfc3339();
+            status.finished_at = report.finished_at.to_rfc3339();
+            status.accounts = report
+                .accounts
+                .iter()
+                .map(|a| AccountStatus {
+                    name: a.name.clone(),
+                    water_gained: a.water_gained(),
+                    water_balance: a.water_after,
+                    tree_progress: a.tree_progress(),
+                    cookie_expired: a.cookie_expired,
+                    harvest_ready: a.harvest_ready,
+                    status: a.status(),
+                })
+                .collect();
+        }
+        Ok(Response::new(status))
+    }
+
+    async fn list_accounts(
+        &self,
+        _request: Request<ListAccountsRequest>,
+    ) -> Result<Response<ListAccountsResponse>, Status> {
+        let accounts = self
+            .controller
+            .accounts()
+            .iter()
+            .map(|a| a.name())
+            .collect();
+        Ok(Response::new(ListAccountsResponse { accounts }))
+    }
+
+    type RunEventsStream = Pin<Box<dyn Stream<Item = Result<RunEventMessage, Status>> + Send>>;
+
+    async fn run_events(
+        &self,
+        _request: Request<RunEventsRequest>,
+    ) -> Result<Response<Self::RunEventsStream>, Status> {
+        // 订阅者处理过慢时跳过丢失的事件
+        let stream = BroadcastStream::new(self.controller.subscribe())
+            .filter_map(|event| event.ok())
+            .map(|event| Ok(event_message(&event)));
+        Ok(Response::new(Box::pin(stream)))
+    }
+}
@@ -0,0 +1,295 @@
+use std::env;
+use std::fs;
+use std::future::Future;
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
+use anyhow::Result;
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+use tracing::info;
+
+use crate::report::RunReport;
+use crate::trend::{AccountTrend, Trends};
+
+// 调度器心跳允许超出运行间隔的时间
+const HEARTBEAT_GRACE: Duration = Duration::from_secs(5 * 60);
+
+// 运行期间运行器心跳的间隔, 超过两个间隔没有心跳(如运行所在的线程被阻塞)时不再视为运行中
+const RUN_HEARTBEAT: Duration = Duration::from_secs(30);
+
+// 最近连续该次数的运行都触发风控时视为疑似被封
+const BANNED_RISK_RUNS: usize = 3;
+
+// 常驻运行的健康状态
+#[derive(Serialize, Debug, Clone, Default)]
+pub struct Health {
+    // 调度器最近一次心跳时间
+    pub last_heartbeat: Option<DateTime<Local>>,
+    // 最近一次运行结束时间
+    pub last_run: Option<DateTime<Local>>,
+    // 最近一次有账号运行成功的时间
+    pub last_success: Option<DateTime<Local>>,
+    // 运行间隔(秒)
+    pub interval_secs: u64,
+}
+
+impl Health {
+    // 调度器是否存活, 心跳超过两个运行间隔未更新视为已卡死
+    pub fn is_alive(&self) -> bool {
+        let max_age = Duration::from_secs(self.interval_secs * 2) + HEARTBEAT_GRACE;
+        match self.last_heartbeat {
+            Some(t) => age(t) <= max_age,
+            None => false,
+        }
+    }
+
+    // 最近一次成功运行距今的时间
+    pub fn success_age(&self) -> Option<Duration> {
+        self.last_success.map(age)
+    }
+
+    // 是否健康: 调度器存活, 且尚未运行完成或最近两个运行间隔内有成功运行
+    pub fn is_healthy(&self) -> bool {
+        if !self.is_alive() {
+            return false;
+        }
+        match (self.last_run, self.success_age()) {
+            (None, _) => true,
+            (Some(_), None) => false,
+            (Some(_), Some(a)) => {
+                a <= Duration::from_secs(self.interval_secs * 2) + HEARTBEAT_GRACE
+            }
+        }
+    }
+}
+
+// 账号健康状态, 按严重程度升序
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum AccountStatus {
+    // 正常
+    Healthy,
+    // 最近运行出错、触发过风控或收益骤降, 需要关注
+    Degraded,
+    // 连续触发风控或触发风控后收益骤降, 疑似被封
+    BannedSuspected,
+    // cookie已失效, 需要更新
+    Expired,
+}
+
+impl AccountStatus {
+    pub fn label(&self) -> String {
+        match self {
+            Self::Healthy => t!("健康"),
+            Self::Degraded => t!("异常"),
+            Self::BannedSuspected => t!("疑似被封"),
+            Self::Expired => t!("cookie已失效"),
+        }
+    }
+}
+
+// 单个账号的健康状态, 由最近的运行历史汇总
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct AccountHealth {
+    // 账号名称
+    pub name: String,
+    // 健康状态
+    pub status: AccountStatus,
+    // cookie是否有效, 以最近一次运行为准
+    pub cookie_valid: bool,
+    // 最近触发风控的运行次数
+    pub risk_runs: usize,
+    // 最近一次运行成功的时间
+    pub last_success: Option<DateTime<Local>>,
+    // 收益趋势, 最近一次运行失败时为空
+    pub trend: Option<AccountTrend>,
+}
+
+impl AccountHealth {
+    // 纯文本摘要
+    pub fn to_text(&self) -> String {
+        let last_success = match self.last_success {
+            Some(t) => t.format("%Y-%m-%d %H:%M").to_string(),
+            None => t!("无"),
+        };
+        let mut text = t!(
+            "【{}】{}, 最近成功运行: {}, 触发风控: {}次",
+            self.name,
+            self.status.label(),
+            last_success,
+            self.risk_runs
+        );
+        if let Some(trend) = &self.trend {
+            text.push_str(&t!(
+                ", 本次获得水滴: {}g, 平均: {}g",
+                trend.this_run,
+                format!("{:.0}", trend.run_average)
+            ));
+        }
+        text
+    }
+}
+
+// 根据运行历史(按时间升序)汇总最近一次运行中各账号的健康状态
+pub fn account_health(runs: &[RunReport]) -> Vec<AccountHealth> {
+    let Some((latest, history)) = runs.split_last() else {
+        return Vec::new();
+    };
+    let trends = Trends::compute(history, latest);
+    latest
+        .accounts
+        .iter()
+        .map(|account| {
+            let reports: Vec<_> = runs
+                .iter()
+                .filter_map(|run| {
+                    run.accounts
+                        .iter()
+                        .find(|a| a.name == account.name)
+                        .map(|a| (run.finished_at, a))
+                })
+                .collect();
+            let risk_runs = reports.iter().filter(|(_, a)| a.risk_control > 0).count();
+            let last_success = reports
+                .iter()
+                .rev()
+                .find(|(_, a)| a.is_ok())
+                .map(|(t, _)| *t);
+            let banned = reports.len() >= BANNED_RISK_RUNS
+                && reports
+                    .iter()
+                    .rev()
+                    .take(BANNED_RISK_RUNS)
+                    .all(|(_, a)| a.risk_control > 0);
+            let trend = trends
+                .accounts
+                .iter()
+                .find(|t| t.name == account.name)
+                .cloned();
+            let dropped = trend.as_ref().is_some_and(|t| t.dropped());
+
+            let status = if account.cookie_expired {
+                AccountStatus::Expired
+            } else if banned || (account.risk_control > 0 && dropped) {
+                AccountStatus::BannedSuspected
+            } else if !account.is_ok()
+                || risk_runs > 0
+                || dropped
+                || !account.failed_tasks().is_empty()
+                || !account.task_errors.is_empty()
+            {
+                AccountStatus::Degraded
+            } else {
+                AccountStatus::Healthy
+            };
+            AccountHealth {
+                name: account.name.clone(),
+                status,
+                cookie_valid: !account.cookie_expired,
+                risk_runs,
+                last_success,
+                trend,
+            }
+        })
+        .collect()
+}
+
+fn age(t: DateTime<Local>) -> Duration {
+    (Local::now() - t).to_std().unwrap_or_default()
+}
+
+fn state() -> &'static Mutex<Health> {
+    static STATE: OnceLock<Mutex<Health>> = OnceLock::new();
+    STATE.get_or_init(|| Mutex::new(Health::default()))
+}
+
+// 运行器最近一次心跳的时间, 没有进行中的运行时为空
+fn run_heartbeat() -> &'static Mutex<Option<Instant>> {
+    static HEARTBEAT: OnceLock<Mutex<Option<Instant>>> = OnceLock::new();
+    HEARTBEAT.get_or_init(|| Mutex::new(None))
+}
+
+// 运行期间按固定间隔发送运行器心跳, 与调度器的运行间隔无关
+pub async fn beating<F: Future>(future: F) -> F::Output {
+    tokio::pin!(future);
+    let mut ticker = tokio::time::interval(RUN_HEARTBEAT);
+    let output = loop {
+        tokio::select! {
+            output = &mut future => break output,
+            _ = ticker.tick() => *run_heartbeat().lock().unwrap() = Some(Instant::now()),
+        }
+    };
+    *run_heartbeat().lock().unwrap() = None;
+    output
+}
+
+// 是否有进行中的运行且运行器心跳未超时, 耗时超过调度间隔的运行期间看门狗据此继续发送心跳
+pub fn is_running() -> bool {
+    run_heartbeat()
+        .lock()
+        .unwrap()
+        .is_some_and(|t| t.elapsed() <= RUN_HEARTBEAT * 2)
+}
+
+// 当前健康状态
+pub fn current() -> Health {
+    state().lock().unwrap().clone()
+}
+
+// 设置运行间隔
+pub fn set_interval(interval: Duration) {
+    state().lock().unwrap().interval_secs = interval.as_secs();
+}
+
+// 调度器心跳
+pub fn beat() {
+    state().lock().unwrap().last_heartbeat = Some(Local::now());
+}
+
+// 记录运行结果, 并更新心跳文件、发送healthchecks.io等心跳请求
+pub async fn record_run(report: &RunReport) {
+    let success = report.accounts.iter().any(|a| a.is_ok());
+    {
+        let mut health = state().lock().unwrap();
+        let now = Local::now();
+        health.last_heartbeat = Some(now);
+        health.last_run = Some(now);
+        if success {
+            health.last_success = Some(now);
+        }
+    }
+
+    if let Some(Err(e)) = touch_heartbeat_file_from_env() {
+        info!("{}", t!("更新心跳文件失败, {}", e));
+    }
+    if let Err(e) = ping_from_env(success).await {
+        info!("{}", t!("发送心跳请求失败, {}", e));
+    }
+}
+
+// HEARTBEAT_FILE: 每次运行成功后更新该文件, 内容为运行结束时间
+fn touch_heartbeat_file_from_env() -> Option<Result<()>> {
+    let path = env::var("HEARTBEAT_FILE").ok().filter(|p| !p.is_empty())?;
+    let health = current();
+    let last_success = health.last_success?;
+    Some(fs::write(path, last_success.to_rfc3339()).map_err(Into::into))
+}
+
+// HEALTHCHECK_URL: 每次运行结束后请求该地址, 全部账号失败时请求{url}/fail, 适用于healthchecks.io
+async fn ping_from_env(success: bool) -> Result<()> {
+    let url = match env::var("HEALTHCHECK_URL") {
+        Ok(url) if !url.is_empty() => url,
+        _ => return Ok(()),
+    };
+    let url = match success {
+        true => url,
+        false => format!("{}/fail", url.trim_end_matches('/')),
+    };
+    reqwest::Client::new()
+        .get(url)
+        .timeout(Duration::from_secs(10))
+        .send()
+        .await?
+        .error_for_status()?;
+    Ok(())
+}
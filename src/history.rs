@@ -0,0 +1,390 @@
+use std::env;
+use std::path::Path;
+
+use anyhow::Result;
+use chrono::{DateTime, Duration, Local};
+use rusqlite::{params, Connection};
+
+use crate::health::{account_health, AccountHealth};
+use crate::report::{AccountReport, PrizeReport, RunReport, TaskReport};
+use crate::trend::TREND_DAYS;
+
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS runs (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    started_at TEXT NOT NULL,
+    finished_at TEXT NOT NULL
+);
+CREATE TABLE IF NOT EXISTS accounts (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    run_id INTEGER NOT NULL REFERENCES runs(id) ON DELETE CASCADE,
+    name TEXT NOT NULL,
+    water_before INTEGER NOT NULL,
+    water_after INTEGER NOT NULL,
+    water_gained INTEGER NOT NULL,
+    cookie_expired INTEGER NOT NULL,
+    harvest_ready INTEGER NOT NULL,
+    error TEXT,
+    tree_energy INTEGER NOT NULL DEFAULT 0,
+    tree_total_energy INTEGER NOT NULL DEFAULT 0,
+    risk_control INTEGER NOT NULL DEFAULT 0
+);
+CREATE TABLE IF NOT EXISTS tasks (
+    account_id INTEGER NOT NULL REFERENCES accounts(id) ON DELETE CASCADE,
+    name TEXT NOT NULL,
+    success INTEGER NOT NULL,
+    gain INTEGER NOT NULL
+);
+CREATE TABLE IF NOT EXISTS prizes (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    account_id INTEGER NOT NULL REFERENCES accounts(id) ON DELETE CASCADE,
+    account TEXT NOT NULL,
+    source TEXT NOT NULL,
+    name TEXT NOT NULL,
+    level INTEGER,
+    code TEXT,
+    won_at TEXT NOT NULL
+);
+CREATE INDEX IF NOT EXISTS idx_runs_started_at ON runs(started_at);
+CREATE INDEX IF NOT EXISTS idx_accounts_run_id ON accounts(run_id);
+CREATE INDEX IF NOT EXISTS idx_tasks_account_id ON tasks(account_id);
+CREATE INDEX IF NOT EXISTS idx_prizes_account_id ON prizes(account_id);
+";
+
+// 果树成熟后未下单前每次运行都会记录, 该天数内的相同奖品只保存一次
+const HARVEST_DEDUP_DAYS: i64 = 30;
+
+// 奖品记录
+#[derive(Debug, Clone)]
+pub struct PrizeRecord {
+    // 获得时间
+    pub won_at: DateTime<Local>,
+    // 账号名称
+    pub account: String,
+    // 奖品
+    pub prize: PrizeReport,
+}
+
+// 运行历史, 保存在SQLite数据库中
+pub struct History {
+    conn: Connection,
+}
+
+impl History {
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self> {
+        if let Some(dir) = path.as_ref().parent() {
+            if !dir.as_os_str().is_empty() {
+                std::fs::create_dir_all(dir)?;
+            }
+        }
+        let conn = Connection::open(path)?;
+        conn.execute_batch("PRAGMA foreign_keys = ON;")?;
+        conn.execute_batch(SCHEMA)?;
+        migrate(&conn)?;
+        Ok(Self { conn })
+    }
+
+    // HISTORY_DB: 运行历史数据库路径, 未设置时不保存
+    pub fn from_env() -> Option<Result<Self>> {
+        let path = env::var("HISTORY_DB").ok().filter(|p| !p.is_empty())?;
+        Some(Self::open(path))
+    }
+
+    // 保存一次运行结果, 返回运行ID
+    pub fn save(&mut self, report: &RunReport) -> Result<i64> {
+        let tx = self.conn.transaction()?;
+        tx.execute(
+            "INSERT INTO runs (started_at, finished_at) VALUES (?1, ?2)",
+            params![
+                report.started_at.to_rfc3339(),
+                report.finished_at.to_rfc3339()
+            ],
+        )?;
+        let run_id = tx.last_insert_rowid();
+
+        // 因风控暂停或正在被另一个实例运行而未运行的账号不保存, 避免影响收益统计
+        for account in report.accounts.iter().filter(|a| !a.skipped()) {
+            tx.execute(
+                "INSERT INTO accounts (run_id, name, water_before, water_after, water_gained, cookie_expired, harvest_ready, error, tree_energy, tree_total_energy, risk_control)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)",
+                params![
+                    run_id,
+                    account.name,
+                    account.water_before,
+                    account.water_after,
+                    account.water_gained() as i64,
+                    account.cookie_expired,
+                    account.harvest_ready,
+                    account.error,
+                    account.tree_energy,
+                    account.tree_total_energy,
+                    account.risk_control,
+                ],
+            )?;
+            let account_id = tx.last_insert_rowid();
+            for task in &account.tasks {
+                tx.execute(
+                    "INSERT INTO tasks (account_id, name, success, gain) VALUES (?1, ?2, ?3, ?4)",
+                    params![account_id, task.name, task.success, task.gain as i64],
+                )?;
+            }
+            for prize in &account.prizes {
+                if prize.level.is_some() {
+                    let since = report.started_at - Duration::days(HARVEST_DEDUP_DAYS);
+                    let exists: bool = tx.query_row(
+                        "SELECT EXISTS(SELECT 1 FROM prizes WHERE account = ?1 AND source = ?2 AND name = ?3 AND level IS ?4 AND won_at >= ?5)",
+                        params![account.name, prize.source, prize.name, prize.level, since.to_rfc3339()],
+                        |row| row.get(0),
+                    )?;
+                    if exists {
+                        continue;
+                    }
+                }
+                tx.execute(
+                    "INSERT INTO prizes (account_id, account, source, name, level, code, won_at) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+                    params![
+                        account_id,
+                        account.name,
+                        prize.source,
+                        prize.name,
+                        prize.level,
+                        prize.code,
+                        report.finished_at.to_rfc3339()
+                    ],
+                )?;
+            }
+        }
+        tx.commit()?;
+        Ok(run_id)
+    }
+
+    // 查询指定时间之后开始的运行结果, 按时间升序
+    pub fn runs_since(&self, since: DateTime<Local>) -> Result<Vec<RunReport>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT id, started_at, finished_at FROM runs WHERE started_at >= ?1 ORDER BY started_at",
+        )?;
+        let runs = stmt
+            .query_map(params![since.to_rfc3339()], |row| {
+                Ok((
+                    row.get::<_, i64>(0)?,
+                    row.get::<_, DateTime<Local>>(1)?,
+                    row.get::<_, DateTime<Local>>(2)?,
+                ))
+            })?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+
+        let mut reports = Vec::new();
+        for (id, started_at, finished_at) in runs {
+            reports.push(RunReport {
+                started_at,
+                finished_at,
+                accounts: self.accounts(id)?,
+                trends: None,
+                interrupted: false,
+                simulated: false,
+            });
+        }
+        Ok(reports)
+    }
+
+    // 最近一次运行结果
+    pub fn last_run(&self) -> Result<Option<RunReport>> {
+        let mut stmt = self
+            .conn
+            .prepare("SELECT id, started_at, finished_at FROM runs ORDER BY id DESC LIMIT 1")?;
+        let mut rows = stmt.query([])?;
+        match rows.next()? {
+            Some(row) => {
+                let id: i64 = row.get(0)?;
+                Ok(Some(RunReport {
+                    started_at: row.get(1)?,
+                    finished_at: row.get(2)?,
+                    accounts: self.accounts(id)?,
+                    trends: None,
+                    interrupted: false,
+                    simulated: false,
+                }))
+            }
+            None => Ok(None),
+        }
+    }
+
+    // 查询指定时间之后获得的奖品, 按时间倒序
+    pub fn prizes_since(&self, since: DateTime<Local>) -> Result<Vec<PrizeRecord>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT won_at, account, source, name, level, code FROM prizes WHERE won_at >= ?1 ORDER BY won_at DESC, id DESC",
+        )?;
+        let prizes = stmt
+            .query_map(params![since.to_rfc3339()], |row| {
+                Ok(PrizeRecord {
+                    won_at: row.get(0)?,
+                    account: row.get(1)?,
+                    prize: PrizeReport {
+                        source: row.get(2)?,
+                        name: row.get(3)?,
+                        level: row.get(4)?,
+                        code: row.get(5)?,
+                    },
+                })
+            })?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+        Ok(prizes)
+    }
+
+    // 最近一次运行中各账号的健康状态, 汇总近7日的运行结果
+    pub fn health(&self) -> Result<Vec<AccountHealth>> {
+        let since = Local::now() - Duration::days(TREND_DAYS + 1);
+        Ok(account_health(&self.runs_since(since)?))
+    }
+
+    // 生成最近几天的运行统计及奖品记录, 供report命令输出
+    pub fn summary(&self, days: i64) -> Result<String> {
+        let since = Local::now() - Duration::days(days);
+        let runs = self.runs_since(since)?;
+
+        // 账号 -> (运行次数, 获得水滴, 失败任务数, 最近剩余水滴)
+        let mut stats: Vec<(String, usize, u64, usize, u32)> = Vec::new();
+        for run in &runs {
+            for account in &run.accounts {
+                let index = match stats.iter().position(|s| s.0 == account.name) {
+                    Some(i) => i,
+                    None => {
+                        stats.push((account.name.clone(), 0, 0, 0, 0));
+                        stats.len() - 1
+                    }
+                };
+                let stat = &mut stats[index];
+                stat.1 += 1;
+                stat.2 += account.water_gained();
+                stat.3 += account.failed_tasks().len();
+                if account.is_ok() {
+                    stat.4 = account.water_after;
+                }
+            }
+        }
+
+        let mut lines = vec![t!("近{}天共运行{}次", days, runs.len())];
+        for (name, count, gained, failed, balance) in &stats {
+            lines.push(t!(
+                "【{}】运行: {}次, 获得水滴: {}g, 失败任务: {}个, 剩余水滴: {}g",
+                name,
+                count,
+                gained,
+                failed,
+                balance
+            ));
+        }
+
+        let prizes = self.prizes_since(since)?;
+        lines.push(t!("\n获得奖品: {}个", prizes.len()));
+        for record in &prizes {
+            let mut line = t!(
+                "{} 【{}】{}: {}",
+                record.won_at.format("%Y-%m-%d %H:%M"),
+                record.account,
+                crate::i18n::tr(&record.prize.source),
+                record.prize.name
+            );
+            if let Some(level) = record.prize.level {
+                line.push_str(&t!(", 等级: {}", level));
+            }
+            if let Some(code) = &record.prize.code {
+                line.push_str(&t!(", 券码: {}", code));
+            }
+            lines.push(line);
+        }
+        Ok(lines.join("\n"))
+    }
+
+    fn accounts(&self, run_id: i64) -> Result<Vec<AccountReport>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT id, name, water_before, water_after, cookie_expired, harvest_ready, error, tree_energy, tree_total_energy, risk_control
+             FROM accounts WHERE run_id = ?1 ORDER BY id",
+        )?;
+        let rows = stmt
+            .query_map(params![run_id], |row| {
+                Ok((
+                    row.get::<_, i64>(0)?,
+                    AccountReport {
+                        name: row.get(1)?,
+                        water_before: row.get(2)?,
+                        water_after: row.get(3)?,
+                        tasks: Vec::new(),
+                        cookie_expired: row.get(4)?,
+                        harvest_ready: row.get(5)?,
+                        tree_energy: row.get(7)?,
+                        tree_total_energy: row.get(8)?,
+                        error: row.get(6)?,
+                        task_errors: Vec::new(),
+                        prizes: Vec::new(),
+                        endpoints: Default::default(),
+                        next_water_rain: None,
+                        water_rain_score: None,
+                        watered_by: Vec::new(),
+                        idle_friends: Vec::new(),
+                        risk_control: row.get(9)?,
+                        share_code: None,
+                        transient: false,
+                        retried: false,
+                        plan: None,
+                        plan_mismatches: Vec::new(),
+                        schema_drift: Vec::new(),
+                        advice: None,
+                        verification_url: None,
+                        cookie_index: 0,
+                        paused_until: None,
+                        running_elsewhere: false,
+                    },
+                ))
+            })?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+
+        let mut task_stmt = self.conn.prepare(
+            "SELECT name, success, gain FROM tasks WHERE account_id = ?1 ORDER BY rowid",
+        )?;
+        let mut prize_stmt = self.conn.prepare(
+            "SELECT source, name, level, code FROM prizes WHERE account_id = ?1 ORDER BY id",
+        )?;
+        let mut accounts = Vec::new();
+        for (id, mut account) in rows {
+            account.prizes = prize_stmt
+                .query_map(params![id], |row| {
+                    Ok(PrizeReport {
+                        source: row.get(0)?,
+                        name: row.get(1)?,
+                        level: row.get(2)?,
+                        code: row.get(3)?,
+                    })
+                })?
+                .collect::<rusqlite::Result<Vec<_>>>()?;
+            account.tasks = task_stmt
+                .query_map(params![id], |row| {
+                    Ok(TaskReport {
+                        name: row.get(0)?,
+                        success: row.get(1)?,
+                        gain: row.get::<_, i64>(2)? as u64,
+                    })
+                })?
+                .collect::<rusqlite::Result<Vec<_>>>()?;
+            accounts.push(account);
+        }
+        Ok(accounts)
+    }
+}
+
+// 为旧版本创建的数据库补充新增的列
+fn migrate(conn: &Connection) -> Result<()> {
+    let mut stmt = conn.prepare("PRAGMA table_info(accounts)")?;
+    let columns = stmt
+        .query_map([], |row| row.get::<_, String>(1))?
+        .collect::<rusqlite::Result<Vec<_>>>()?;
+    for column in ["tree_energy", "tree_total_energy", "risk_control"] {
+        if !columns.iter().any(|c| c == column) {
+            conn.execute_batch(&format!(
+                "ALTER TABLE accounts ADD COLUMN {} INTEGER NOT NULL DEFAULT 0",
+                column
+            ))?;
+        }
+    }
+    Ok(())
+}
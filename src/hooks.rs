@@ -0,0 +1,233 @@
+use std::env;
+use std::fs;
+use std::io::Read;
+use std::path::Path;
+use std::process::{Command, Stdio};
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use anyhow::{anyhow, Result};
+use tokio::sync::broadcast::{self, error::RecvError};
+use tracing::info;
+
+use crate::event::RunEvent;
+use crate::report::{RunReport, Severity, TaskReport};
+
+type TaskStartHook = Arc<dyn Fn(&str, &str) + Send + Sync>;
+type TaskCompleteHook = Arc<dyn Fn(&str, &TaskReport) + Send + Sync>;
+type ErrorHook = Arc<dyn Fn(&str, &str) + Send + Sync>;
+
+// 任务生命周期回调, 适用于不想消费事件通道的简单集成
+#[derive(Default, Clone)]
+pub struct Hooks {
+    task_start: Vec<TaskStartHook>,
+    task_complete: Vec<TaskCompleteHook>,
+    error: Vec<ErrorHook>,
+}
+
+impl Hooks {
+    pub fn is_empty(&self) -> bool {
+        self.task_start.is_empty() && self.task_complete.is_empty() && self.error.is_empty()
+    }
+
+    // 开始执行任务时调用, 参数为账号名称、任务名称
+    pub fn on_task_start<F: Fn(&str, &str) + Send + Sync + 'static>(&mut self, f: F) {
+        self.task_start.push(Arc::new(f));
+    }
+
+    // 任务执行结束(成功或失败)时调用, 参数为账号名称、任务结果
+    pub fn on_task_complete<F: Fn(&str, &TaskReport) + Send + Sync + 'static>(&mut self, f: F) {
+        self.task_complete.push(Arc::new(f));
+    }
+
+    // 账号运行中断或cookie失效时调用, 参数为账号名称、错误描述
+    pub fn on_error<F: Fn(&str, &str) + Send + Sync + 'static>(&mut self, f: F) {
+        self.error.push(Arc::new(f));
+    }
+
+    fn handle(&self, event: &RunEvent) {
+        match event {
+            RunEvent::TaskStarted { account, task } => {
+                self.task_start.iter().for_each(|f| f(account, task));
+            }
+            RunEvent::TaskCompleted {
+                account,
+                task,
+                gain,
+            } => {
+                let report = TaskReport {
+                    name: task.clone(),
+                    success: true,
+                    gain: *gain,
+                };
+                self.task_complete.iter().for_each(|f| f(account, &report));
+            }
+            RunEvent::TaskFailed { account, task } => {
+                let report = TaskReport {
+                    name: task.clone(),
+                    success: false,
+                    gain: 0,
+                };
+                self.task_complete.iter().for_each(|f| f(account, &report));
+            }
+            RunEvent::AccountFinished { report } if !report.is_ok() => {
+                let status = report.status();
+                self.error.iter().for_each(|f| f(&report.name, &status));
+            }
+            _ => {}
+        }
+    }
+
+    // 消费运行事件并调用回调, 所有发送端关闭后结束
+    pub(crate) async fn consume(self, mut events: broadcast::Receiver<RunEvent>) {
+        loop {
+            match events.recv().await {
+                Ok(event) => self.handle(&event),
+                Err(RecvError::Lagged(_)) => continue,
+                Err(RecvError::Closed) => break,
+            }
+        }
+    }
+}
+
+// 运行结束后执行的外部命令, 通过环境变量及临时JSON文件传入运行结果
+// 可用于提交运行历史、触发其他脚本等, 命令失败不影响运行结果
+#[derive(Debug, Clone)]
+pub struct PostRunCommand {
+    command: String,
+    // 命令的最长执行时间, 超时后结束命令
+    timeout: Duration,
+}
+
+impl PostRunCommand {
+    pub fn new(command: &str) -> Self {
+        Self {
+            command: command.to_string(),
+            timeout: Duration::from_secs(300),
+        }
+    }
+
+    // 设置命令的最长执行时间
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    // POST_RUN_COMMAND: 运行结束后执行的命令, Windows使用cmd执行, 其他系统使用sh执行
+    // POST_RUN_TIMEOUT: 命令的最长执行时间(秒), 默认300
+    pub fn from_env() -> Option<Self> {
+        let command = env::var("POST_RUN_COMMAND")
+            .ok()
+            .filter(|c| !c.trim().is_empty())?;
+        let mut hook = Self::new(&command);
+        if let Some(timeout) = env::var("POST_RUN_TIMEOUT")
+            .ok()
+            .and_then(|v| v.trim().parse::<f64>().ok())
+            .filter(|v| *v > 0.0)
+        {
+            hook = hook.timeout(Duration::from_secs_f64(timeout));
+        }
+        Some(hook)
+    }
+
+    fn shell(&self) -> Command {
+        #[cfg(windows)]
+        let (shell, flag) = ("cmd", "/C");
+        #[cfg(not(windows))]
+        let (shell, flag) = ("sh", "-c");
+        let mut command = Command::new(shell);
+        command.args([flag, &self.command]);
+        command
+    }
+
+    // 执行命令, 运行结果写入临时文件, 命令结束后删除
+    // 传入的环境变量: JD_FARM_REPORT_FILE(运行结果JSON文件路径), JD_FARM_SEVERITY(info/warning/error),
+    // JD_FARM_ACCOUNTS, JD_FARM_SUCCEEDED, JD_FARM_EXPIRED, JD_FARM_WATER_GAINED, JD_FARM_SIMULATED
+    pub async fn run(&self, report: &RunReport) -> Result<()> {
+        let path = env::temp_dir().join(format!(
+            "jd_farm-report-{}-{}.json",
+            std::process::id(),
+            report.started_at.timestamp_millis()
+        ));
+        fs::write(&path, serde_json::to_string_pretty(report)?)?;
+        let res = self.execute(report, &path).await;
+        let _ = fs::remove_file(&path);
+        res
+    }
+
+    async fn execute(&self, report: &RunReport, path: &Path) -> Result<()> {
+        let totals = report.totals();
+        let severity = match report.severity() {
+            Severity::Info => "info",
+            Severity::Warning => "warning",
+            Severity::Error => "error",
+        };
+        let mut child = self
+            .shell()
+            .env("JD_FARM_REPORT_FILE", path)
+            .env("JD_FARM_SEVERITY", severity)
+            .env("JD_FARM_ACCOUNTS", totals.accounts.to_string())
+            .env("JD_FARM_SUCCEEDED", totals.succeeded.to_string())
+            .env("JD_FARM_EXPIRED", totals.expired.to_string())
+            .env("JD_FARM_WATER_GAINED", totals.water_gained.to_string())
+            .env("JD_FARM_SIMULATED", report.simulated.to_string())
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()?;
+        // 输出在单独的线程中读取, 避免输出较多时命令阻塞
+        let readers: Vec<_> = [
+            child
+                .stdout
+                .take()
+                .map(|s| Box::new(s) as Box<dyn Read + Send>),
+            child
+                .stderr
+                .take()
+                .map(|s| Box::new(s) as Box<dyn Read + Send>),
+        ]
+        .into_iter()
+        .flatten()
+        .map(|mut stream| {
+            thread::spawn(move || {
+                let mut output = String::new();
+                let _ = stream.read_to_string(&mut output);
+                output
+            })
+        })
+        .collect();
+
+        let started = Instant::now();
+        let status = loop {
+            if let Some(status) = child.try_wait()? {
+                break Some(status);
+            }
+            if started.elapsed() >= self.timeout {
+                let _ = child.kill();
+                let _ = child.wait();
+                break None;
+            }
+            tokio::time::sleep(Duration::from_millis(100)).await;
+        };
+        // 命令在后台启动的进程可能一直占用输出, 最多再等待1秒
+        let deadline = Instant::now() + Duration::from_secs(1);
+        while readers.iter().any(|r| !r.is_finished()) && Instant::now() < deadline {
+            tokio::time::sleep(Duration::from_millis(50)).await;
+        }
+        for reader in readers.into_iter().filter(|r| r.is_finished()) {
+            let output = reader.join().unwrap_or_default();
+            for line in output.lines().filter(|l| !l.trim().is_empty()) {
+                info!("{}", t!("运行后命令: {}", line));
+            }
+        }
+        match status {
+            Some(status) if status.success() => Ok(()),
+            Some(status) => Err(anyhow!(t!("运行后命令退出码: {}", status))),
+            None => Err(anyhow!(t!(
+                "运行后命令超过{}秒未结束, 已终止",
+                self.timeout.as_secs()
+            ))),
+        }
+    }
+}
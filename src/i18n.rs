@@ -0,0 +1,772 @@
+use std::collections::HashMap;
+use std::env;
+use std::fmt::Display;
+use std::sync::atomic::{AtomicU8, Ordering};
+use std::sync::OnceLock;
+
+// 界面语言
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Lang {
+    ZhCn,
+    EnUs,
+}
+
+impl Lang {
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.trim().to_lowercase().replace('_', "-").as_str() {
+            "zh" | "zh-cn" => Some(Self::ZhCn),
+            "en" | "en-us" => Some(Self::EnUs),
+            _ => None,
+        }
+    }
+}
+
+// 0: 未设置, 1: 简体中文, 2: 英文
+static LANG: AtomicU8 = AtomicU8::new(0);
+
+// 设置界面语言
+pub fn set_lang(lang: Lang) {
+    let value = match lang {
+        Lang::ZhCn => 1,
+        Lang::EnUs => 2,
+    };
+    LANG.store(value, Ordering::Relaxed);
+}
+
+// 当前界面语言, 未设置时读取JD_FARM_LANG(zh-CN/en-US), 默认简体中文
+pub fn lang() -> Lang {
+    match LANG.load(Ordering::Relaxed) {
+        1 => Lang::ZhCn,
+        2 => Lang::EnUs,
+        _ => {
+            let lang = env::var("JD_FARM_LANG")
+                .ok()
+                .and_then(|l| Lang::parse(&l))
+                .unwrap_or(Lang::ZhCn);
+            set_lang(lang);
+            lang
+        }
+    }
+}
+
+fn en_us() -> &'static HashMap<&'static str, &'static str> {
+    static TABLE: OnceLock<HashMap<&'static str, &'static str>> = OnceLock::new();
+    TABLE.get_or_init(|| EN_US.iter().copied().collect())
+}
+
+// 翻译文本, 以中文原文作为键, 没有对应翻译时返回原文
+pub fn tr(text: &str) -> &str {
+    match lang() {
+        Lang::ZhCn => text,
+        Lang::EnUs => en_us().get(text).copied().unwrap_or(text),
+    }
+}
+
+// 依次将模板中的{}替换为参数
+pub fn format(template: &str, args: &[&dyn Display]) -> String {
+    let mut result = String::with_capacity(template.len());
+    let mut args = args.iter();
+    let mut rest = template;
+    while let Some(pos) = rest.find("{}") {
+        result.push_str(&rest[..pos]);
+        if let Some(arg) = args.next() {
+            result.push_str(&arg.to_string());
+        }
+        rest = &rest[pos + 2..];
+    }
+    result.push_str(rest);
+    result
+}
+
+// 翻译并格式化文本, 模板只支持{}占位符
+#[macro_export]
+macro_rules! t {
+    ($text:expr) => {
+        $crate::i18n::tr($text).to_string()
+    };
+    ($text:expr, $($arg:expr),+ $(,)?) => {
+        $crate::i18n::format($crate::i18n::tr($text), &[$(&$arg as &dyn ::std::fmt::Display),+])
+    };
+}
+
+// 英文翻译表
+static EN_US: &[(&str, &str)] = &[
+    ("、", ", "),
+    ("指标接口启动失败, {}", "Failed to start metrics endpoint, {}"),
+    ("{}分钟后再次运行...", "Running again in {} minutes..."),
+    ("cookie已失效", "cookie expired"),
+    ("{}个任务失败", "{} tasks failed"),
+    ("# {}运行报告", "# {} run report"),
+    ("运行时间: {} ~ {}", "Run time: {} ~ {}"),
+    ("## 汇总", "## Summary"),
+    ("| 账号 | 获得水滴(g) | 剩余水滴(g) | 果树进度 | 成功任务 | 失败任务 | 状态 |", "| Account | Water gained (g) | Water balance (g) | Tree progress | Tasks ok | Tasks failed | Status |"),
+    ("| **合计** | **{}** | | | | | {}个账号, cookie失效{}个 |", "| **Total** | **{}** | | | | | {} accounts, {} cookies expired |"),
+    ("> 错误: {}", "> Error: {}"),
+    ("未执行任务", "No tasks executed"),
+    ("| 任务 | 结果 | 获得水滴(g) |", "| Task | Result | Water gained (g) |"),
+    ("成功", "OK"),
+    ("**失败**", "**Failed**"),
+    ("<h1>{}运行报告</h1><p>运行时间: {} ~ {}</p><h2>汇总</h2>", "<h1>{} run report</h1><p>Run time: {} ~ {}</p><h2>Summary</h2>"),
+    ("<table><tr><th>账号</th><th>获得水滴(g)</th><th>剩余水滴(g)</th><th>果树进度</th><th>成功任务</th><th>失败任务</th><th>状态</th></tr>", "<table><tr><th>Account</th><th>Water gained (g)</th><th>Water balance (g)</th><th>Tree progress</th><th>Tasks ok</th><th>Tasks failed</th><th>Status</th></tr>"),
+    ("<tr><th>合计</th><th>{}</th><th></th><th></th><th></th><th></th><th>{}个账号, cookie失效{}个</th></tr></table>", "<tr><th>Total</th><th>{}</th><th></th><th></th><th></th><th></th><th>{} accounts, {} cookies expired</th></tr></table>"),
+    ("<p class=\"failed\">错误: {}</p>", "<p class=\"failed\">Error: {}</p>"),
+    ("<p>未执行任务</p>", "<p>No tasks executed</p>"),
+    ("<table><tr><th>任务</th><th>结果</th><th>获得水滴(g)</th></tr>", "<table><tr><th>Task</th><th>Result</th><th>Water gained (g)</th></tr>"),
+    ("<td>成功</td>", "<td>OK</td>"),
+    ("<td class=\"failed\">失败</td>", "<td class=\"failed\">Failed</td>"),
+    ("更新心跳文件失败, {}", "Failed to update heartbeat file, {}"),
+    ("发送心跳请求失败, {}", "Failed to send heartbeat ping, {}"),
+    ("近{}天共运行{}次", "Last {} days: {} runs"),
+    ("【{}】运行: {}次, 获得水滴: {}g, 失败任务: {}个, 剩余水滴: {}g", "[{}] runs: {}, water gained: {}g, failed tasks: {}, water balance: {}g"),
+    ("
+获得奖品: {}个", "
+Prizes won: {}"),
+    (", 等级: {}", ", level: {}"),
+    (", 券码: {}", ", coupon code: {}"),
+    ("该请求本次运行已成功, 不再重复发送", "request already succeeded in this run, not sending again"),
+    ("{}, 请求{}本次运行已成功, 跳过重复请求!", "{}, request {} already succeeded in this run, skipping duplicate!"),
+    ("{}, 获得奖品: {}({})", "{}, won prize: {} ({})"),
+    ("{}, 成功完成弹出任务, 获得水滴:{}g!", "{}, completed pop-up task, water gained: {}g!"),
+    ("{}, 无法完成弹出任务, {}", "{}, failed to complete pop-up task, {}"),
+    ("{}, 成功浇水一次, 剩余水滴:{}g!", "{}, watered once, water balance: {}g!"),
+    ("{}, 浇水失败, {}", "{}, watering failed, {}"),
+    ("{}, 成功领取浇水任务奖励, 获得水滴:{}g!", "{}, claimed watering task reward, water gained: {}g!"),
+    ("{}, 领取浇水任务奖励失败, {}", "{}, failed to claim watering task reward, {}"),
+    ("{}, 首次浇水任务失败.", "{}, first watering task failed."),
+    ("{}, 完成任务:《通过“免费水果”访问农场》, 获得水滴:{}g!", "{}, completed task \"Visit farm via Free Fruit\", water gained: {}g!"),
+    ("{}, 无法完成任务:《通过“免费水果”访问农场》,{}", "{}, failed to complete task \"Visit farm via Free Fruit\", {}"),
+    ("{}, 今日已完成任务《{}》!", "{}, task \"{}\" already completed today!"),
+    ("{}, 正在进行任务:《{}》, 等待{}秒...", "{}, running task \"{}\", waiting {} seconds..."),
+    ("{}, 执行任务:《{}》失败.", "{}, task \"{}\" failed."),
+    ("{}, 执行任务:《{}》成功, 获得水滴:{}g!", "{}, task \"{}\" succeeded, water gained: {}g!"),
+    ("{}, 第{}次水滴雨任务未到时间!", "{}, water rain #{} is not due yet!"),
+    ("{}, 成功完成第{}次水滴雨任务, 获得水滴:{}g!", "{}, completed water rain #{}, water gained: {}g!"),
+    ("{}, 执行第{}次水滴雨任务失败.", "{}, water rain #{} failed."),
+    ("{}, 成功领取任务:《为两位好友浇水》奖励, 获得水滴:{}g!", "{}, claimed \"Water two friends\" reward, water gained: {}g!"),
+    ("{}, 领取任务:《为两位好友浇水》奖励失败!", "{}, failed to claim \"Water two friends\" reward!"),
+    ("{}, 成功完成任务:《签到领水->签到》, {}", "{}, completed task \"Check-in water -> check-in\", {}"),
+    ("{}, 任务:《签到领水->签到》执行失败!", "{}, task \"Check-in water -> check-in\" failed!"),
+    ("{}, 关注《{}》!", "{}, followed \"{}\"!"),
+    ("{}, 成功领取任务《关注{}》奖励, 获得水滴:{}g!", "{}, claimed \"Follow {}\" reward, water gained: {}g!"),
+    ("{}, 领取任务《关注{}》奖励失败!", "{}, failed to claim \"Follow {}\" reward!"),
+    ("{}, 使用{}成功!", "{}, used {}!"),
+    ("{}, 使用{}失败!", "{}, failed to use {}!"),
+    ("{}, 领取浇水阶段性奖励失败, {}", "{}, failed to claim watering stage reward, {}"),
+    ("{}, 第{}次点鸭子成功, {}", "{}, duck click #{} succeeded, {}"),
+    ("{}, 今日点鸭子次数已达上限!", "{}, daily duck click limit reached!"),
+    ("{}, 第{}次点击鸭子出错, {}!", "{}, duck click #{} failed, {}!"),
+    ("{}, 当前时间不在任务《定时领水》时间范围内!", "{}, \"Mealtime water\" is not available at this time!"),
+    ("{}, 完成任务《定时领水》, 获得水滴:{}g!", "{}, completed \"Mealtime water\", water gained: {}g!"),
+    ("{}, 无法完成任务《定时领水》, {}", "{}, failed to complete \"Mealtime water\", {}"),
+    ("{}, cookie已失效!", "{}, cookie expired!"),
+    ("{}: 奖品信息:
+	奖品名称: {}
+	奖品等级: {}
+	剩余水滴(g): {}
+	已浇水滴(g): {}
+	还需浇水(g): {}
+	果树进度(%): {}", "{}: prize info:
+	Prize: {}
+	Prize level: {}
+	Water balance (g): {}
+	Watered (g): {}
+	Still needed (g): {}
+	Tree progress (%): {}"),
+    ("{}, 背包信息: 
+	水滴换豆卡: {}
+	快速浇水卡: {}
+	水滴翻倍卡: {}
+	加签卡: {}", "{}, cards: 
+	Water-to-beans card: {}
+	Fast watering card: {}
+	Water doubling card: {}
+	Extra check-in card: {}"),
+    ("{}, 获取背包信息失败, {}", "{}, failed to fetch cards, {}"),
+    ("{}, 无法获取任务列表, {}", "{}, failed to fetch task list, {}"),
+    ("{}, 今日已完成《签到》任务!", "{}, \"Check-in\" already completed today!"),
+    ("{}, 今日已完成《定时领水》任务!", "{}, \"Mealtime water\" already completed today!"),
+    ("{}, 今日已完成《通过“免费水果”访问农场》任务!", "{}, \"Visit farm via Free Fruit\" already completed today!"),
+    ("{}, 今日已完成所有《浏览xxx》任务!", "{}, all browsing tasks already completed today!"),
+    ("{}, 今日已完成《收集水滴雨》任务!", "{}, \"Collect water rain\" already completed today!"),
+    ("{}, 今日已完成《为两位好友浇水》任务!", "{}, \"Water two friends\" already completed today!"),
+    ("{}, 今日已完成《签到领水->签到》任务!", "{}, \"Check-in water -> check-in\" already completed today!"),
+    ("{}, 今日已完成《首次浇水》任务!", "{}, \"First watering\" already completed today!"),
+    ("{}, 今日已完成《十次浇水》任务!", "{}, \"Water ten times\" already completed today!"),
+    ("无法创建日志目录{}, {}", "Cannot create log directory {}, {}"),
+    ("无法打开账号{}的日志文件, {}", "Cannot open log file for account {}, {}"),
+    ("写入账号{}的日志文件失败, {}", "Failed to write log file for account {}, {}"),
+    ("未设置环境变量HISTORY_DB.", "Environment variable HISTORY_DB is not set."),
+    (
+        "未启用history功能, 请使用--features history重新编译.",
+        "The history feature is not enabled, rebuild with --features history.",
+    ),
+    ("未找到环境变量JD_COOKIE.", "Environment variable JD_COOKIE not found."),
+    ("指标接口已启动: http://{}/metrics", "Metrics endpoint started: http://{}/metrics"),
+    ("东东农场 {}", "JD Farm {}"),
+    ("MQTT连接失败, {}", "MQTT connection failed, {}"),
+    ("MQTT发布超时", "MQTT publish timed out"),
+    ("东东农场", "JD Farm"),
+    ("共{}个账号, 获得水滴: {}g", "{} accounts, water gained: {}g"),
+    ("获得水滴", "Water gained"),
+    ("剩余水滴", "Water balance"),
+    ("状态", "Status"),
+    ("失败任务", "Failed tasks"),
+    ("无", "None"),
+    ("**{}**
+共{}个账号, 获得水滴: {}g, cookie失效: {}个", "**{}**
+{} accounts, water gained: {}g, cookies expired: {}"),
+    ("加载通知模板失败, 使用默认格式, {}", "Failed to load notification template, using default format, {}"),
+    ("渲染通知模板失败, 使用默认格式, {}", "Failed to render notification template, using default format, {}"),
+    ("通知渠道《{}》发送成功!", "Notification channel \"{}\" sent successfully!"),
+    ("通知渠道《{}》发送失败, {}", "Notification channel \"{}\" failed, {}"),
+    ("*{}*: 获得水滴 `{}g`, 剩余水滴 `{}g`", "*{}*: water gained `{}g`, water balance `{}g`"),
+    (", _cookie已失效_", ", _cookie expired_"),
+    (", 错误: {}", ", error: {}"),
+    ("
+共{}个账号, 获得水滴 `{}g`", "
+{} accounts, water gained `{}g`"),
+    ("cookie已失效: {}", "cookie expired: {}"),
+    ("果树已成熟", "fruit tree is ripe"),
+    ("东东农场: 果树已成熟", "JD Farm: fruit tree is ripe"),
+    ("解析接口{}返回数据失败, {}", "Failed to parse the response of {}, {}"),
+    ("未登录或cookie已失效", "Not logged in or cookie expired"),
+    ("触发风控, 返回码: {}, {}", "Risk control triggered, code: {}, {}"),
+    ("接口返回错误, 返回码: {}, {}", "API returned an error, code: {}, {}"),
+    ("网络请求失败, {}", "Network request failed, {}"),
+    ("缺少返回码", "missing response code"),
+    ("缺少果树信息", "missing fruit tree info"),
+    ("读取通知队列失败, {}", "Failed to read the notification queue, {}"),
+    ("保存通知队列失败, {}", "Failed to save the notification queue, {}"),
+    (
+        "通知渠道《{}》的消息已加入队列, 稍后重试",
+        "Message for channel \"{}\" queued for a later retry",
+    ),
+    (
+        "通知渠道《{}》有{}条消息重试次数过多或已过期, 已丢弃",
+        "Channel \"{}\": dropped {} queued messages after too many retries or expiry",
+    ),
+    ("通知渠道《{}》补发消息成功!", "Channel \"{}\" delivered a queued message!"),
+    ("通知渠道《{}》补发消息失败, {}", "Channel \"{}\" failed to deliver a queued message, {}"),
+    ("东东农场: 水滴雨已开始", "JD Farm: water rain has started"),
+    (
+        "【{}】新一轮水滴雨已开始, 请及时收集!",
+        "[{}] A new round of water rain has started, collect it soon!",
+    ),
+    ("{}, 执行水滴雨任务失败, {}", "{}, failed to run the water rain task, {}"),
+    (
+        "【{}】果树已成熟, 奖品: {}, 请尽快领取!",
+        "[{}] Fruit tree is ripe, prize: {}, claim it as soon as possible!",
+    ),
+    (
+        "【{}】果树已成熟, 请尽快领取!",
+        "[{}] Fruit tree is ripe, claim it as soon as possible!",
+    ),
+    ("正常", "OK"),
+    ("【{}】获得水滴: {}g, 剩余水滴: {}g", "[{}] water gained: {}g, water balance: {}g"),
+    (", cookie已失效", ", cookie expired"),
+    (", 果树已成熟, 请及时领取", ", fruit tree is ripe, please claim it soon"),
+    (", 失败任务: {}", ", failed tasks: {}"),
+    (", 获得奖品: {}", ", prizes won: {}"),
+    ("共{}个账号, 获得水滴: {}g, cookie失效: {}个", "{} accounts, water gained: {}g, cookies expired: {}"),
+    ("需关注账号: {}", "Accounts needing attention: {}"),
+    ("cookie失效账号: {}", "Accounts with expired cookies: {}"),
+    ("{}({}/{}成功)", "{} ({}/{} ok)"),
+    ("异常接口: {}", "Failing endpoints: {}"),
+    ("
+**共{}个账号, 获得水滴: {}g, cookie失效: {}个**", "
+**{} accounts, water gained: {}g, cookies expired: {}**"),
+    ("`{}`({}/{}成功)", "`{}` ({}/{} ok)"),
+    ("
+异常接口: {}", "
+Failing endpoints: {}"),
+    ("<table border=\"1\" cellspacing=\"0\" cellpadding=\"4\"><tr><th>账号</th><th>获得水滴(g)</th><th>剩余水滴(g)</th><th>果树进度</th><th>失败任务</th><th>状态</th></tr>", "<table border=\"1\" cellspacing=\"0\" cellpadding=\"4\"><tr><th>Account</th><th>Water gained (g)</th><th>Water balance (g)</th><th>Tree progress</th><th>Failed tasks</th><th>Status</th></tr>"),
+    ("<p>共{}个账号, 获得水滴: {}g, cookie失效: {}个</p>", "<p>{} accounts, water gained: {}g, cookies expired: {}</p>"),
+    ("计算收益趋势失败, {}", "Failed to compute trends, {}"),
+    ("保存运行历史失败, {}", "Failed to save run history, {}"),
+    ("打开运行历史失败, {}", "Failed to open run history, {}"),
+    ("运行结束:
+{}", "Run finished:
+{}"),
+    ("写入指标文件失败, {}", "Failed to write metrics file, {}"),
+    ("导出CSV失败, {}", "Failed to export CSV, {}"),
+    ("运行报告已写入: {}", "Run report written to: {}"),
+    ("写入运行报告失败, {}", "Failed to write run report, {}"),
+    ("发布MQTT消息失败, {}", "Failed to publish MQTT messages, {}"),
+    ("OTLP追踪导出初始化失败, {}", "Failed to initialize OTLP trace export, {}"),
+    ("OTLP指标导出初始化失败, {}", "Failed to initialize OTLP metrics export, {}"),
+    ("今日获得水滴: {}g, 近{}日平均: {}g", "Water gained today: {}g, {}-day average: {}g"),
+    ("{}({}g, 平均{}g)", "{} ({}g, average {}g)"),
+    ("收益骤降账号: {}, 可能已被风控", "Accounts with a sudden yield drop: {}, possibly rate-limited by risk control"),
+    ("弹出任务", "Pop-up task"),
+    ("十次浇水", "Water ten times"),
+    ("首次浇水", "First watering"),
+    ("通过“免费水果”访问农场", "Visit farm via Free Fruit"),
+    ("浏览广告", "Browse ads"),
+    ("收集水滴雨", "Collect water rain"),
+    ("为两位好友浇水", "Water two friends"),
+    ("签到领水->签到", "Check-in water -> check-in"),
+    ("签到领水->关注", "Check-in water -> follow"),
+    ("使用道具卡", "Use card"),
+    ("点鸭子", "Click duck"),
+    ("定时领水", "Mealtime water"),
+    ("签到", "Check-in"),
+    ("背包信息", "Cards"),
+    ("浇水阶段奖励", "Watering stage reward"),
+    (", 出错任务: {}", ", tasks with errors: {}"),
+    ("访问令牌无效", "invalid access token"),
+    ("gRPC服务已启动: {}", "gRPC server started: {}"),
+    ("已开始运行", "run started"),
+    ("HTTP接口已启动: http://{}", "HTTP API started: http://{}"),
+    ("cookie无效或账号已存在", "invalid cookie or account already exists"),
+    ("上一次运行尚未结束", "the previous run has not finished yet"),
+    ("{}, 试运行, 跳过任务《{}》", "{}, dry run, skipping task \"{}\""),
+    ("账号运行超时({}秒)", "account run timed out ({}s)"),
+    ("加签卡", "extra check-in card"),
+    ("水滴翻倍卡", "water doubling card"),
+    ("快速浇水卡", "fast watering card"),
+    ("水滴换豆卡", "water-for-beans card"),
+    ("果树成熟", "Fruit tree ripe"),
+    ("本次获得水滴", "Water gained this run"),
+    ("果树进度", "Tree progress"),
+    ("预计成熟天数", "Days until ripe"),
+    ("运行状态", "Run status"),
+    ("账号", "Account"),
+    ("运行前(g)", "Before (g)"),
+    ("运行后(g)", "After (g)"),
+    ("净增(g)", "Net (g)"),
+    ("获得(g)", "Gained (g)"),
+    ("成熟(天)", "Ripe in (d)"),
+    ("已成熟", "Ripe"),
+    ("{}%(还需{}g)", "{}% ({}g to go)"),
+    ("果树进度: {}%, 还需浇水: {}g", "tree progress: {}%, {}g left to water"),
+    (", 预计{}天后成熟", ", ripe in about {} days"),
+    ("Telegram机器人已启动", "Telegram bot started"),
+    ("获取Telegram消息失败, {}", "Failed to fetch Telegram messages, {}"),
+    ("发送Telegram消息失败, {}", "Failed to send Telegram message, {}"),
+    (
+        "可用命令:\n/run - 运行所有账号\n/run 账号1 账号2 - 运行指定账号\n/status - 运行状态及最近一次运行结果\n/cookies - 各账号cookie状态\n/loglevel 子系统 级别 - 修改日志级别",
+        "Commands:\n/run - run all accounts\n/run account1 account2 - run the given accounts\n/status - run status and the latest result\n/cookies - cookie status of each account\n/loglevel subsystem level - change the log level",
+    ),
+    ("未找到账号: {}", "Accounts not found: {}"),
+    ("正在运行", "Running"),
+    ("空闲", "Idle"),
+    ("尚未运行", "No runs yet"),
+    ("未检测", "Not checked yet"),
+    ("未配置账号", "No accounts configured"),
+    ("连接Redis失败, {}", "Failed to connect to Redis, {}"),
+    ("助力好友", "Assist friends"),
+    ("{}, 成功助力好友: {}!", "{}, assisted friend: {}!"),
+    ("{}, 今日助力次数已用完!", "{}, no assists left today!"),
+    ("{}, 助力好友: {}失败!", "{}, failed to assist friend: {}!"),
+    (
+        "cron表达式需为5段(分 时 日 月 星期): {}",
+        "A cron expression needs 5 fields (minute hour day month weekday): {}",
+    ),
+    ("无效的cron表达式{}, {}", "Invalid cron expression {}, {}"),
+    ("未设置定时规则", "No schedule configured"),
+    ("定时规则没有下次运行时间", "The schedule has no upcoming run time"),
+    ("下次运行时间: {}", "Next run at: {}"),
+    ("执行定时规则: {}", "Running schedule: {}"),
+    ("Windows服务运行失败, {}", "Windows service failed, {}"),
+    (
+        "收到退出信号, 等待进行中的任务完成后退出, 再次按Ctrl+C立即退出",
+        "Received a shutdown signal, exiting after in-flight tasks finish; press Ctrl+C again to exit immediately",
+    ),
+    ("{}, 正在退出, 跳过任务《{}》", "{}, shutting down, skipping task \"{}\""),
+    ("运行被中断, 部分任务未执行", "Run interrupted, some tasks were not executed"),
+    ("读取配置文件{}失败, {}", "Failed to read config file {}, {}"),
+    ("未找到配置档案{}", "Config profile {} not found"),
+    ("解析配置失败, {}", "Failed to parse config, {}"),
+    (
+        "{}, 请求{}失败, {}秒后第{}次重试, {}",
+        "{}, request {} failed, retrying in {}s (attempt {}), {}",
+    ),
+    ("使用SQLite保存运行状态需启用history功能", "Storing run state in SQLite requires the history feature"),
+    ("打开运行状态存储{}失败, {}", "Failed to open state store {}, {}"),
+    ("{}, 读取检查点失败, {}", "{}, failed to read checkpoint, {}"),
+    ("{}, 保存检查点失败, {}", "{}, failed to save checkpoint, {}"),
+    ("{}, 删除检查点失败, {}", "{}, failed to delete checkpoint, {}"),
+    ("{}, 读取已浇水的好友失败, {}", "{}, failed to read watered friends, {}"),
+    ("{}, 保存已浇水的好友失败, {}", "{}, failed to save watered friends, {}"),
+    ("模拟网络错误: {}", "Simulated network error: {}"),
+    ("模拟运行, 以上数据均为虚构", "Simulated run, all data above is fictional"),
+    (
+        "以服务方式运行时需设置DAEMON_INTERVAL或SCHEDULE",
+        "DAEMON_INTERVAL or SCHEDULE must be set when running as a service",
+    ),
+    ("缺少字段{}", "missing field {}"),
+    ("{}, 果树信息不完整, {}", "{}, incomplete tree info, {}"),
+    ("{}, 任务列表不完整, {}", "{}, incomplete task list, {}"),
+    ("{}, 执行任务:《{}》失败, {}", "{}, task \"{}\" failed, {}"),
+    (
+        "{}, 领取任务《关注{}》奖励失败, {}",
+        "{}, failed to claim reward for \"follow {}\", {}",
+    ),
+    (
+        "{}, 获取签到领水任务失败, {}",
+        "{}, failed to fetch check-in water tasks, {}",
+    ),
+    ("签到领水", "Check-in water"),
+    ("读取接口版本失败, {}", "failed to load API version, {}"),
+    ("保存接口版本失败, {}", "failed to save API version, {}"),
+    ("{}, 接口版本{}可用", "{}, API version {} works"),
+    ("{}, 接口版本{}不可用, {}", "{}, API version {} does not work, {}"),
+    ("没有可用的接口版本", "no working API version"),
+    ("{}, 探测接口版本失败, {}", "{}, failed to probe API version, {}"),
+    ("{}, 改用接口版本{}", "{}, switched to API version {}"),
+    ("东东萌宠", "JD pet"),
+    ("东东萌宠->签到", "JD pet -> check-in"),
+    ("东东萌宠->定时领狗粮", "JD pet -> meal-time food"),
+    ("东东萌宠->首次喂食", "JD pet -> first feeding"),
+    ("东东萌宠->每日喂食", "JD pet -> daily feeding"),
+    ("东东萌宠->遛狗", "JD pet -> walk the dog"),
+    ("{}, 未开通东东萌宠!", "{}, JD pet is not activated!"),
+    (
+        "{}, 萌宠已集齐勋章, 请及时兑换奖品!",
+        "{}, all pet medals collected, redeem the prize soon!",
+    ),
+    ("{}, 萌宠未选择奖品!", "{}, no pet prize selected!"),
+    ("{}, 今日已喂食{}g, 目标{}g", "{}, fed {}g today, target {}g"),
+    (
+        "{}: 萌宠信息:\n\t奖品名称: {}\n\t已获得勋章: {}/{}\n\t勋章进度(%): {}\n\t剩余狗粮(g): {}",
+        "{}: pet info:\n\tprize: {}\n\tmedals: {}/{}\n\tmedal progress(%): {}\n\tfood left(g): {}",
+    ),
+    ("{}, 喂食萌宠失败, {}", "{}, failed to feed pet, {}"),
+    ("{}, 完成任务《{}》, 获得狗粮:{}g!", "{}, completed \"{}\", got {}g of pet food!"),
+    ("{}, 无法完成任务《{}》, {}", "{}, unable to complete \"{}\", {}"),
+    ("种豆得豆", "Plant beans"),
+    ("种豆得豆->收取京豆", "Plant beans -> harvest beans"),
+    ("种豆得豆->定时领取营养液", "Plant beans -> timed nutrients"),
+    ("{}京豆", "{} JD beans"),
+    ("{}, 收取上期京豆失败, {}", "{}, failed to harvest last round's beans, {}"),
+    (
+        "{}, 种豆得豆没有进行中的一期!",
+        "{}, no plant beans round in progress!",
+    ),
+    (
+        "{}, 培养营养液《{}》失败, {}",
+        "{}, failed to cultivate nutrients \"{}\", {}",
+    ),
+    (
+        "{}: 种豆得豆:\n\t本期营养液: {}\n\t本期成长值: {}",
+        "{}: plant beans:\n\tnutrients this round: {}\n\tgrowth this round: {}",
+    ),
+    (
+        "{}, 完成任务《{}》, 获得营养液:{}!",
+        "{}, completed \"{}\", got {} nutrients!",
+    ),
+    (", 分享链接: {}", ", share link: {}"),
+    ("助力码已导出到{}", "Share codes exported to {}"),
+    ("健康", "Healthy"),
+    ("异常", "Degraded"),
+    ("疑似被封", "Banned suspected"),
+    (
+        "【{}】{}, 最近成功运行: {}, 触发风控: {}次",
+        "[{}] {}, last success: {}, risk control: {} time(s)",
+    ),
+    (", 本次获得水滴: {}g, 平均: {}g", ", this run: {}g, average: {}g"),
+    ("暂无运行记录.", "No runs recorded yet."),
+    (
+        "奖品名称: {}, 剩余水滴: {}g, 已浇水滴: {}g, 还需浇水: {}g",
+        "prize: {}, water: {}g, watered: {}g, remaining: {}g",
+    ),
+    ("好友{}个: {}", "{} friend(s): {}"),
+    (
+        "水滴换豆卡: {}, 快速浇水卡: {}, 水滴翻倍卡: {}, 加签卡: {}",
+        "bean cards: {}, fast watering cards: {}, double water cards: {}, sign cards: {}",
+    ),
+    ("cookie有效", "cookie valid"),
+    ("# 东东农场配置, 由`jd_farm init`生成, 可直接修改", "# jd_farm config, generated by `jd_farm init`, feel free to edit"),
+    ("# 日志级别", "# Log level"),
+    ("# 京东cookie, 多个账号用&分隔, remark为可选的账号备注", "# JD cookies, separate accounts with &, remark is an optional account note"),
+    ("# 通知渠道: {}", "# Notification channel: {}"),
+    ("# 单次运行, 由系统cron、青龙面板等定时调用; 设置DAEMON_INTERVAL(分钟)后常驻运行", "# Run once, triggered by cron, Qinglong panel, etc.; set DAEMON_INTERVAL (minutes) to keep running"),
+    ("# 常驻运行, 每隔DAEMON_INTERVAL分钟运行一次", "# Keep running, once every DAEMON_INTERVAL minutes"),
+    ("# 按定时规则常驻运行, 以分号分隔, 每条为`分 时 日 月 星期 [任务1,任务2] [@账号1,账号2]`", "# Keep running on a schedule, entries separated by semicolons, each `min hour day month weekday [task1,task2] [@account1,account2]`"),
+    ("{}已存在, 是否覆盖?", "{} already exists, overwrite?"),
+    ("已取消.", "Cancelled."),
+    ("配置已写入{}.", "Config written to {}."),
+    ("输入已结束, 未写入配置.", "Input ended, config not written."),
+    ("请输入京东cookie(pt_pin=xxx;pt_key=xxx;remark=备注;), 每行一个, 直接回车结束:", "Enter JD cookies (pt_pin=xxx;pt_key=xxx;remark=note;), one per line, press Enter on an empty line to finish:"),
+    ("账号{}", "Account {}"),
+    ("至少需要一个账号.", "At least one account is required."),
+    ("cookie格式错误, 需包含pt_pin及pt_key.", "Invalid cookie, pt_pin and pt_key are required."),
+    ("{}, cookie有效.", "{}, cookie valid."),
+    ("{}, cookie已失效, 是否仍然保存?", "{}, cookie expired, save anyway?"),
+    ("{}, 检查cookie失败, {}, 是否仍然保存?", "{}, failed to check cookie, {}, save anyway?"),
+    ("通知渠道:", "Notification channel:"),
+    ("不发送通知", "No notifications"),
+    ("请选择", "Choose"),
+    ("机器人token", "bot token"),
+    ("接收消息的chat id", "chat id to receive messages"),
+    ("推送地址或设备key", "push URL or device key"),
+    ("加签密钥, 未开启加签时留空", "signing secret, leave empty if signing is disabled"),
+    ("机器人key", "bot key"),
+    ("主题", "topic"),
+    ("配置不完整, 请重新选择.", "Incomplete configuration, please choose again."),
+    ("测试通知, 收到该消息说明通知配置正确.", "Test notification, receiving this means notifications are set up correctly."),
+    ("已发送测试通知, 请检查是否收到.", "Test notification sent, please check that it arrived."),
+    ("发送测试通知失败, {}", "Failed to send test notification, {}"),
+    ("是否仍然保存?", "Save anyway?"),
+    ("未启用notify功能, 跳过通知配置, 请使用--features notify重新编译.", "notify feature not enabled, skipping notifications, rebuild with --features notify."),
+    ("运行方式:", "Run mode:"),
+    ("单次运行, 由系统cron、青龙面板等定时调用", "Run once, triggered by cron, Qinglong panel, etc."),
+    ("常驻运行, 每隔一段时间运行一次", "Keep running at a fixed interval"),
+    ("按定时规则常驻运行", "Keep running on a schedule"),
+    ("运行间隔(分钟)", "Interval (minutes)"),
+    ("请输入大于0的整数.", "Please enter a positive integer."),
+    ("读取账号文件{}失败, {}", "Failed to read accounts file {}, {}"),
+    ("{}, 发现新增账号, 立即运行!", "{}, new account found, running now!"),
+    ("运行时出现异常(panic), {}", "panicked while running, {}"),
+    ("{}个账号因临时错误运行失败, {}秒后重试", "{} accounts failed with transient errors, retrying in {}s"),
+    ("{}, 读取果树进度失败, {}", "{}, failed to load tree progress, {}"),
+    ("{}, 保存果树进度失败, {}", "{}, failed to save tree progress, {}"),
+    (
+        "{}, 正在被另一个实例运行(进程ID: {}, 锁文件: {}), 本次跳过",
+        "{}, being run by another instance (pid: {}, lock file: {}), skipping it this run",
+    ),
+    ("{}个账号在{}分钟内分散运行", "spreading {} accounts over {} minutes"),
+    ("无效的时间窗口: {}", "Invalid time window: {}"),
+    ("执行任务: {}", "tasks: {}"),
+    ("浇水{}次(剩余水滴{}g, 消耗{}g)", "water {} times ({}g left, uses {}g)"),
+    ("使用水滴翻倍卡", "use the water doubling card"),
+    ("今日任务均已完成, 无需执行", "all tasks done today, nothing to run"),
+    ("任务《{}》没有执行结果", "task \"{}\" produced no result"),
+    ("预计浇水{}次, 实际成功{}次", "expected to water {} times, {} succeeded"),
+    ("预计使用水滴翻倍卡, 实际未使用", "expected to use the water doubling card, but it was not used"),
+    ("{}, 运行计划: {}", "{}, plan: {}"),
+    ("{}, 与预计不符: {}", "{}, differs from plan: {}"),
+    (
+        "合计: 运行成功{}个, 剩余水滴净增{}g, 失败任务{}个, 出错任务{}个, 果树成熟{}个, 奖品{}个, 触发风控{}次",
+        "Totals: {} succeeded, net water {}g, {} failed tasks, {} task errors, {} ready to harvest, {} prizes, {} risk-control hits",
+    ),
+    ("水滴榜: {}", "Most water: {}"),
+    ("成熟榜: {}", "Closest to harvest: {}"),
+    ("失败榜: {}", "Most failures: {}"),
+    (", 与预计不符: {}", ", differs from plan: {}"),
+    ("{}, 新增账号的cookie已失效!", "{}, the new account's cookie has expired!"),
+    (
+        "{}, 检查新增账号的cookie失败, 下次运行时重试, {}",
+        "{}, failed to check the new account's cookie, will retry on the next run, {}",
+    ),
+    ("定时规则, 如`10 7 * * *; 0 */3 * * * 收集水滴雨`", "Schedule, e.g. `10 7 * * *; 0 */3 * * * 收集水滴雨`"),
+    ("未知的日志子系统{}, 可选: {}", "Unknown log subsystem {}, available: {}"),
+    ("无效的日志级别{}", "Invalid log level {}"),
+    ("各子系统均使用RUST_LOG中的日志级别", "All subsystems use the log level from RUST_LOG"),
+    (
+        "用法: /loglevel 子系统 级别, 如/loglevel transport debug",
+        "Usage: /loglevel subsystem level, e.g. /loglevel transport debug",
+    ),
+    ("连接{}", "Connect to {}"),
+    ("HTTP {}, 耗时{}ms", "HTTP {}, took {}ms"),
+    ("系统时间", "System clock"),
+    ("无法连接服务器", "cannot reach the server"),
+    ("服务器未返回时间", "the server returned no time"),
+    ("与服务器相差{}秒", "{}s off from the server"),
+    ("代理", "Proxy"),
+    ("未设置代理", "no proxy set"),
+    ("{}无效, {}", "{} is invalid, {}"),
+    ("{}可用, HTTP {}, 耗时{}ms", "{} works, HTTP {}, took {}ms"),
+    ("{}不可用, {}", "{} does not work, {}"),
+    ("签名", "Sign"),
+    ("生成签名时出现异常", "signing panicked"),
+    ("签名参数完整", "signed parameters are complete"),
+    ("签名参数缺少{}", "signed parameters are missing {}"),
+    ("{}, cookie有效", "{}, cookie valid"),
+    ("{}, cookie已失效", "{}, cookie expired"),
+    ("新增字段{}", "new fields {}"),
+    ("接口{}{}", "endpoint {}: {}"),
+    ("保存接口字段失败, {}", "Failed to save endpoint fields, {}"),
+    ("读取接口字段失败, {}", "Failed to load endpoint fields, {}"),
+    ("{}, 接口返回数据发生变化: {}", "{}, endpoint response changed: {}"),
+    (", 接口变化: {}", ", endpoint changes: {}"),
+    ("系统繁忙", "System busy"),
+    ("稍后重试", "Retry later"),
+    ("请求参数错误, 可能是接口版本过旧", "Invalid request parameters, the API version may be outdated"),
+    ("升级程序, 或通过API_VERSION指定接口版本", "Upgrade, or set the API version with API_VERSION"),
+    ("重新登录京东并更新JD_COOKIE", "Log in to JD again and update JD_COOKIE"),
+    ("任务已完成或奖励已领取", "Task already done or reward already claimed"),
+    ("无需处理", "Nothing to do"),
+    ("今日次数已达上限", "Daily limit reached"),
+    ("无需处理, 明日再运行", "Nothing to do, run again tomorrow"),
+    ("水滴不足", "Not enough water"),
+    ("无需处理, 获得水滴后会继续浇水", "Nothing to do, watering resumes once more water is collected"),
+    ("请求被拒绝, 触发风控", "Request rejected by risk control"),
+    ("增大DELAY等请求间隔, 暂停运行几小时后再试", "Increase request intervals such as DELAY and pause for a few hours"),
+    ("检查网络连接及代理, 可运行doctor命令诊断", "Check the network and proxy, or run the doctor command"),
+    ("京东可能修改了接口, 请升级程序", "JD may have changed the API, please upgrade"),
+    ("(建议: {})", " (advice: {})"),
+    ("需要完成安全验证, 请打开京东App完成验证", "Security verification required, please complete it in the JD app"),
+    (
+        "需要完成安全验证, 请在京东App中打开链接完成验证: {}",
+        "Security verification required, please open this link in the JD app: {}",
+    ),
+    (
+        "{}, 请求{}需要完成安全验证, 停止运行该账号",
+        "{}, request {} requires security verification, stopping this account",
+    ),
+    (
+        "【{}】需要完成安全验证, 已停止运行该账号, 请在京东App中打开链接完成验证: {}",
+        "[{}] Security verification required, the account was stopped, please open this link in the JD app: {}",
+    ),
+    (
+        "【{}】需要完成安全验证, 已停止运行该账号, 请打开京东App完成验证",
+        "[{}] Security verification required, the account was stopped, please complete it in the JD app",
+    ),
+    ("东东农场: 需要安全验证", "JD Farm: security verification required"),
+    ("在京东App中完成验证后重新运行", "Complete the verification in the JD app and run again"),
+    (
+        "本机时间与京东服务器相差{}秒, 时间相关的任务按服务器时间计算",
+        "The local clock is {}s off from the JD server, time-gated tasks use server time",
+    ),
+    ("好友浇水记录", "Friend watering records"),
+    (
+        "{}, 本周有{}位好友为你浇水, {}位好友未浇水",
+        "{}, {} friends watered your tree this week, {} did not",
+    ),
+    ("{}, 获取好友浇水记录失败, {}", "{}, failed to get friend watering records, {}"),
+    ("{}(今日{}次/本周{}次)", "{}({} today/{} this week)"),
+    (", 为你浇水的好友: {}", ", watered by: {}"),
+    (", 本周未为你浇水的好友: {}位", ", friends who did not water this week: {}"),
+    ("收集水滴雨: {}", "Collect water rain: {}"),
+    ("新一轮水滴雨已开始, 运行jd_farm收集", "A new water rain round has started, run jd_farm to collect it"),
+    ("果树预计成熟: {}", "Tree expected to be ripe: {}"),
+    ("三餐定时领水时间段, 运行jd_farm领取", "Mealtime water window, run jd_farm to claim it"),
+    ("导出日历失败, {}", "Failed to export the calendar, {}"),
+    ("{}个账号果树已成熟, 请尽快领取!", "{} trees are ripe, claim them soon!"),
+    ("等{}个账号运行失败", "{} accounts failed in total"),
+    ("发送桌面通知失败, {}", "Failed to send desktop notification, {}"),
+    ("运行后命令: {}", "Post-run command: {}"),
+    ("运行后命令退出码: {}", "Post-run command exited with {}"),
+    ("运行后命令超过{}秒未结束, 已终止", "Post-run command did not finish within {}s and was killed"),
+    ("执行运行后命令失败, {}", "Post-run command failed, {}"),
+    ("远程配置地址必须以https://开头", "The remote config URL must start with https://"),
+    ("已加载远程配置", "Remote config loaded"),
+    ("获取远程配置失败, {}", "Failed to fetch the remote config, {}"),
+    ("无效的密钥", "Invalid key"),
+    ("生成随机数失败", "Failed to generate a nonce"),
+    ("加密失败", "Encryption failed"),
+    ("远程配置不是加密内容, {}", "The remote config is not encrypted, {}"),
+    ("远程配置内容过短, 不是加密内容", "The remote config is too short to be encrypted"),
+    ("解密失败", "Decryption failed"),
+    ("解密失败, 请检查JD_FARM_CONFIG_KEY", "Decryption failed, check JD_FARM_CONFIG_KEY"),
+    ("未找到环境变量JD_FARM_CONFIG_KEY.", "Environment variable JD_FARM_CONFIG_KEY not found."),
+    (
+        "未启用metrics功能, 不提供/healthz及/metrics接口",
+        "The metrics feature is not enabled, /healthz and /metrics are not served",
+    ),
+    ("{}, 读取风控暂停状态失败, {}", "{}, failed to read risk-control cool-down, {}"),
+    ("{}, 保存风控暂停状态失败, {}", "{}, failed to save risk-control cool-down, {}"),
+    ("{}, 触发风控, 暂停运行至{}", "{}, risk control triggered, paused until {}"),
+    (
+        "{}, 触发风控, 暂停运行至{}, 本次跳过",
+        "{}, paused until {} after risk control, skipped this run",
+    ),
+    ("触发风控, 暂停运行", "Paused after risk control"),
+    (
+        "【{}】触发风控, 暂停运行至{}, 本次已跳过",
+        "[{}] Paused until {} after risk control, skipped this run",
+    ),
+    (
+        "{}, cookie已失效, 改用第{}个备用cookie",
+        "{}, cookie expired, switching to backup cookie #{}",
+    ),
+    (
+        ", 主cookie已失效, 已改用第{}个备用cookie",
+        ", primary cookie expired, switched to backup cookie #{}",
+    ),
+    (", {}个备用cookie也已失效", ", {} backup cookie(s) also expired"),
+    (
+        "打开风控暂停状态文件{}失败, 暂停状态不会在多次运行之间保留, {}",
+        "Failed to open risk-control cool-down file {}, pauses will not persist between runs, {}",
+    ),
+    (
+        "远程配置不是加密内容, 请使用encrypt-config重新加密",
+        "Remote config is not encrypted content, please re-encrypt it with encrypt-config",
+    ),
+    ("不支持的加密格式版本: {}", "Unsupported encryption format version: {}"),
+    ("后台运行任务异常结束, {}", "Background run task ended abnormally, {}"),
+    ("监听非本机地址{}时必须设置API_TOKEN", "API_TOKEN must be set to listen on non-loopback address {}"),
+    ("等待进行中的运行结束超时, 停止服务", "Timed out waiting for the run in progress to finish, stopping the service"),
+    ("正在被另一个实例运行", "Being run by another instance"),
+    ("【{}】正在被另一个实例运行, 本次已跳过", "[{}] Being run by another instance, skipped this run"),
+    ("{} 【{}】{}: {}", "{} [{}] {}: {}"),
+    ("cookie", "Cookie"),
+    ("关注{}", "Follow {}"),
+    ("WEBHOOK_BODY不是有效的JSON, {}", "WEBHOOK_BODY is not valid JSON, {}"),
+    ("加载Webhook通知失败, {}", "Failed to load the Webhook notifier, {}"),
+];
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+    use std::path::{Path, PathBuf};
+
+    use super::*;
+
+    // 递归列出目录下的所有.rs文件
+    fn sources(dir: &Path, files: &mut Vec<PathBuf>) {
+        for entry in fs::read_dir(dir).unwrap() {
+            let path = entry.unwrap().path();
+            if path.is_dir() {
+                sources(&path, files);
+            } else if path.extension().is_some_and(|e| e == "rs") {
+                files.push(path);
+            }
+        }
+    }
+
+    // 解析从"开始的字符串字面量, 返回其内容
+    fn literal(source: &str) -> String {
+        let mut text = String::new();
+        let mut chars = source[1..].chars();
+        while let Some(c) = chars.next() {
+            match c {
+                '"' => break,
+                '\\' => match chars.next().unwrap() {
+                    'n' => text.push('\n'),
+                    't' => text.push('\t'),
+                    // 行尾的\忽略换行及下一行开头的空白
+                    '\n' => {
+                        let rest = chars.as_str().trim_start();
+                        chars = rest.chars();
+                    }
+                    c => text.push(c),
+                },
+                c => text.push(c),
+            }
+        }
+        text
+    }
+
+    // src下所有t!使用的文本都需要有英文翻译
+    #[test]
+    fn every_text_has_translation() {
+        let mut files = Vec::new();
+        sources(
+            &Path::new(env!("CARGO_MANIFEST_DIR")).join("src"),
+            &mut files,
+        );
+        let mut missing = Vec::new();
+        for file in files {
+            let source = fs::read_to_string(&file).unwrap();
+            for (pos, _) in source.match_indices("t!(") {
+                // 跳过其他宏名称的结尾及字符串中的t!(
+                let before = source[..pos].chars().next_back();
+                if before.is_some_and(|c| c.is_alphanumeric() || c == '_' || c == '"') {
+                    continue;
+                }
+                let rest = source[pos + 3..].trim_start();
+                if !rest.starts_with('"') {
+                    continue;
+                }
+                let text = literal(rest);
+                if !en_us().contains_key(text.as_str()) {
+                    let line = source[..pos].lines().count();
+                    missing.push(format!("{}:{}: {:?}", file.display(), line, text));
+                }
+            }
+        }
+        assert!(missing.is_empty(), "{}", missing.join("\n"));
+    }
+}
@@ -0,0 +1,71 @@
+use std::env;
+use std::fs::{self, File, OpenOptions, TryLockError};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use anyhow::{anyhow, Result};
+
+// 防止重复运行的锁, 每个账号一个锁文件, 持有期间其他实例无法运行该账号, 进程退出后由系统自动释放
+#[derive(Debug)]
+pub struct RunLock {
+    // 持有锁的文件, 关闭时释放锁
+    _file: File,
+    path: PathBuf,
+}
+
+impl RunLock {
+    // 获取锁, 已被其他实例持有时返回错误
+    pub fn acquire(name: &str, path: &Path) -> Result<Self> {
+        let mut file = OpenOptions::new()
+            .create(true)
+            .truncate(false)
+            .write(true)
+            .open(path)?;
+        match file.try_lock() {
+            Ok(()) => {}
+            Err(TryLockError::WouldBlock) => {
+                // 锁文件中记录了持有锁的进程ID
+                let pid = fs::read_to_string(path).unwrap_or_default();
+                return Err(anyhow!(
+                    "{}",
+                    t!(
+                        "{}, 正在被另一个实例运行(进程ID: {}, 锁文件: {}), 本次跳过",
+                        name,
+                        pid.trim(),
+                        path.display()
+                    )
+                ));
+            }
+            Err(TryLockError::Error(e)) => return Err(e.into()),
+        }
+        file.set_len(0)?;
+        write!(file, "{}", std::process::id())?;
+        Ok(Self {
+            _file: file,
+            path: path.to_path_buf(),
+        })
+    }
+
+    // 账号开始运行时获取该账号的锁, 不加锁时为空
+    // LOCK_DIR: 锁文件所在目录, 默认为临时目录, 每个账号对应其中的jd_farm-*.lock, 设置为off不加锁
+    pub fn for_account(name: &str) -> Option<Result<Self>> {
+        let dir = match env::var("LOCK_DIR").as_deref().map(str::trim) {
+            Ok("off") | Ok("false") | Ok("0") => return None,
+            Ok(dir) if !dir.is_empty() => PathBuf::from(dir),
+            _ => env::temp_dir(),
+        };
+        Some(Self::acquire(name, &dir.join(file_name(name))))
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+// 锁文件名由账号名称的哈希生成, 避免账号名称中的特殊字符
+fn file_name(name: &str) -> String {
+    let hash = name.bytes().fold(0xcbf2_9ce4_8422_2325u64, |h, b| {
+        (h ^ b as u64).wrapping_mul(0x100_0000_01b3)
+    });
+    format!("jd_farm-{:016x}.lock", hash)
+}
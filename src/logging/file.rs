@@ -0,0 +1,272 @@
+use std::collections::HashMap;
+use std::fmt::Write as _;
+use std::fs::{self, File, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::{env, fmt};
+
+use chrono::{Local, NaiveDate};
+use tracing::{
+    field::{Field, Visit},
+    span, Event, Subscriber,
+};
+use tracing_subscriber::{layer::Context, registry::LookupSpan, Layer};
+
+use crate::redact::redact;
+
+// 日志文件切割方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Rotation {
+    // 每天切割
+    Daily,
+    // 超过指定字节数时切割
+    Size(u64),
+}
+
+impl Rotation {
+    // 支持daily, 或形如10M/512K/1048576的文件大小
+    fn parse(value: &str) -> Option<Self> {
+        let value = value.trim().to_uppercase();
+        if value == "DAILY" {
+            return Some(Self::Daily);
+        }
+        let (number, unit) = match value.chars().last()? {
+            'K' => (&value[..value.len() - 1], 1024),
+            'M' => (&value[..value.len() - 1], 1024 * 1024),
+            'G' => (&value[..value.len() - 1], 1024 * 1024 * 1024),
+            _ => (value.as_str(), 1),
+        };
+        number.parse::<u64>().ok().map(|n| Self::Size(n * unit))
+    }
+}
+
+// 单个账号的日志文件
+struct RotatingFile {
+    dir: PathBuf,
+    name: String,
+    rotation: Rotation,
+    max_files: usize,
+    file: File,
+    size: u64,
+    date: NaiveDate,
+}
+
+impl RotatingFile {
+    fn open(dir: &Path, name: &str, rotation: Rotation, max_files: usize) -> std::io::Result<Self> {
+        let path = dir.join(format!("{}.log", name));
+        let file = OpenOptions::new().create(true).append(true).open(&path)?;
+        let size = file.metadata()?.len();
+        Ok(Self {
+            dir: dir.to_path_buf(),
+            name: name.to_string(),
+            rotation,
+            max_files,
+            file,
+            size,
+            date: Local::now().date_naive(),
+        })
+    }
+
+    fn path(&self, suffix: Option<&str>) -> PathBuf {
+        match suffix {
+            Some(suffix) => self.dir.join(format!("{}.{}.log", self.name, suffix)),
+            None => self.dir.join(format!("{}.log", self.name)),
+        }
+    }
+
+    // 切割当前文件, 并删除超出数量的旧文件
+    fn rotate(&mut self) -> std::io::Result<()> {
+        match self.rotation {
+            Rotation::Daily => {
+                let suffix = self.date.format("%Y-%m-%d").to_string();
+                fs::rename(self.path(None), self.path(Some(&suffix)))?;
+                let prefix = format!("{}.", self.name);
+                let mut rotated: Vec<PathBuf> = fs::read_dir(&self.dir)?
+                    .filter_map(|entry| entry.ok().map(|e| e.path()))
+                    .filter(|path| {
+                        path.file_name()
+                            .and_then(|n| n.to_str())
+                            .map(|n| n.starts_with(&prefix) && n.ends_with(".log"))
+                            .unwrap_or(false)
+                    })
+                    .collect();
+                rotated.sort();
+                while rotated.len() > self.max_files {
+                    fs::remove_file(rotated.remove(0))?;
+                }
+            }
+            Rotation::Size(_) => {
+                let _ = fs::remove_file(self.path(Some(&self.max_files.to_string())));
+                for i in (1..self.max_files).rev() {
+                    let from = self.path(Some(&i.to_string()));
+                    if from.exists() {
+                        fs::rename(from, self.path(Some(&(i + 1).to_string())))?;
+                    }
+                }
+                fs::rename(self.path(None), self.path(Some("1")))?;
+            }
+        }
+        self.file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(self.path(None))?;
+        self.size = 0;
+        self.date = Local::now().date_naive();
+        Ok(())
+    }
+
+    fn write_line(&mut self, line: &str) -> std::io::Result<()> {
+        let need_rotate = match self.rotation {
+            Rotation::Daily => Local::now().date_naive() != self.date,
+            Rotation::Size(max) => self.size > 0 && self.size + line.len() as u64 > max,
+        };
+        if need_rotate {
+            self.rotate()?;
+        }
+        self.file.write_all(line.as_bytes())?;
+        self.size += line.len() as u64;
+        Ok(())
+    }
+}
+
+// 账号名称, 保存在account span的扩展数据中
+struct AccountName(String);
+
+// 收集事件和span的字段
+#[derive(Default)]
+struct FieldVisitor {
+    account: Option<String>,
+    message: String,
+    fields: String,
+}
+
+impl Visit for FieldVisitor {
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        match field.name() {
+            "message" => self.message = format!("{:?}", value),
+            "account" => self.account = Some(format!("{:?}", value)),
+            name => {
+                let _ = write!(self.fields, " {}={:?}", name, value);
+            }
+        }
+    }
+
+    fn record_str(&mut self, field: &Field, value: &str) {
+        match field.name() {
+            "account" => self.account = Some(value.to_string()),
+            _ => self.record_debug(field, &value),
+        }
+    }
+}
+
+// 按账号写入独立日志文件的layer
+pub struct AccountFileLayer {
+    dir: PathBuf,
+    rotation: Rotation,
+    max_files: usize,
+    files: Mutex<HashMap<String, RotatingFile>>,
+}
+
+impl AccountFileLayer {
+    pub fn new(dir: &Path, rotation: Rotation, max_files: usize) -> std::io::Result<Self> {
+        fs::create_dir_all(dir)?;
+        Ok(Self {
+            dir: dir.to_path_buf(),
+            rotation,
+            max_files,
+            files: Mutex::new(HashMap::new()),
+        })
+    }
+
+    // LOG_DIR: 账号日志目录, 未设置时不写入文件
+    // LOG_ROTATION: 可选, 切割方式daily或文件大小(如10M), 默认daily
+    // LOG_MAX_FILES: 可选, 每个账号保留的历史文件数, 默认7
+    pub fn from_env() -> Option<Self> {
+        let dir = env::var("LOG_DIR").ok()?;
+        let rotation = env::var("LOG_ROTATION")
+            .ok()
+            .and_then(|r| Rotation::parse(&r))
+            .unwrap_or(Rotation::Daily);
+        let max_files = env::var("LOG_MAX_FILES")
+            .ok()
+            .and_then(|n| n.parse().ok())
+            .unwrap_or(7);
+        match Self::new(Path::new(&dir), rotation, max_files) {
+            Ok(layer) => Some(layer),
+            Err(e) => {
+                eprintln!("{}", t!("无法创建日志目录{}, {}", dir, e));
+                None
+            }
+        }
+    }
+
+    fn write(&self, account: &str, line: &str) {
+        // 账号名作为文件名, 替换掉路径分隔符等字符
+        let name: String = account
+            .chars()
+            .map(|c| match c {
+                '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|' => '_',
+                c => c,
+            })
+            .collect();
+        let mut files = self.files.lock().unwrap();
+        if !files.contains_key(&name) {
+            match RotatingFile::open(&self.dir, &name, self.rotation, self.max_files) {
+                Ok(file) => {
+                    files.insert(name.clone(), file);
+                }
+                Err(e) => {
+                    eprintln!("{}", t!("无法打开账号{}的日志文件, {}", account, e));
+                    return;
+                }
+            }
+        }
+        if let Some(file) = files.get_mut(&name) {
+            if let Err(e) = file.write_line(line) {
+                eprintln!("{}", t!("写入账号{}的日志文件失败, {}", account, e));
+            }
+        }
+    }
+}
+
+impl<S> Layer<S> for AccountFileLayer
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    fn on_new_span(&self, attrs: &span::Attributes<'_>, id: &span::Id, ctx: Context<'_, S>) {
+        if attrs.metadata().name() != "account" {
+            return;
+        }
+        let mut visitor = FieldVisitor::default();
+        attrs.record(&mut visitor);
+        if let (Some(account), Some(span)) = (visitor.account, ctx.span(id)) {
+            span.extensions_mut().insert(AccountName(account));
+        }
+    }
+
+    fn on_event(&self, event: &Event<'_>, ctx: Context<'_, S>) {
+        let account = ctx.event_scope(event).and_then(|scope| {
+            scope
+                .from_root()
+                .find_map(|span| span.extensions().get::<AccountName>().map(|a| a.0.clone()))
+        });
+        let account = match account {
+            Some(account) => account,
+            None => return,
+        };
+
+        let mut visitor = FieldVisitor::default();
+        event.record(&mut visitor);
+        let metadata = event.metadata();
+        let line = format!(
+            "{} {:>5} {}: {}{}\n",
+            Local::now().format("%Y-%m-%d %H:%M:%S%.3f"),
+            metadata.level(),
+            metadata.target(),
+            visitor.message,
+            visitor.fields
+        );
+        self.write(&account, &redact(&line));
+    }
+}
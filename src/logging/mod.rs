@@ -0,0 +1,241 @@
+pub mod file;
+
+use std::collections::BTreeMap;
+use std::env;
+use std::str::FromStr;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Mutex, OnceLock};
+
+use crate::redact::RedactingMakeWriter;
+use anyhow::{anyhow, Result};
+use file::AccountFileLayer;
+use tracing_subscriber::filter::LevelFilter;
+use tracing_subscriber::fmt::writer::BoxMakeWriter;
+use tracing_subscriber::{fmt, layer::SubscriberExt, reload, util::SubscriberInitExt};
+use tracing_subscriber::{EnvFilter, Registry};
+
+// 任务结果事件的target, 仅在JSON格式日志中输出
+pub const TASK_RESULT_TARGET: &str = "jd_farm::task_result";
+
+// 日志是否输出到标准错误
+static STDERR: AtomicBool = AtomicBool::new(false);
+
+// 日志改为输出到标准错误, 标准输出只保留命令结果(如JSON), 需在init之前调用
+pub fn use_stderr() {
+    STDERR.store(true, Ordering::Relaxed);
+}
+
+// 终端日志的输出位置, 默认标准输出
+fn console() -> BoxMakeWriter {
+    match STDERR.load(Ordering::Relaxed) {
+        true => BoxMakeWriter::new(std::io::stderr),
+        false => BoxMakeWriter::new(std::io::stdout),
+    }
+}
+
+// 是否强制使用JSON格式
+static FORCE_JSON: AtomicBool = AtomicBool::new(false);
+
+// 日志强制使用JSON格式, 忽略LOG_FORMAT, 需在init之前调用
+pub fn use_json() {
+    FORCE_JSON.store(true, Ordering::Relaxed);
+}
+
+// 日志格式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LogFormat {
+    // 文本格式
+    Text,
+    // 每行一个JSON事件
+    Json,
+}
+
+impl LogFormat {
+    // LOG_FORMAT: text/json, 默认text
+    pub fn from_env() -> Self {
+        if FORCE_JSON.load(Ordering::Relaxed) {
+            return Self::Json;
+        }
+        match env::var("LOG_FORMAT")
+            .unwrap_or_default()
+            .to_lowercase()
+            .as_str()
+        {
+            "json" => Self::Json,
+            _ => Self::Text,
+        }
+    }
+}
+
+// 可单独设置日志级别的子系统及其包含的模块
+const SUBSYSTEMS: [(&str, &[&str]); 4] = [
+    (
+        "transport",
+        &["jd_farm::api", "jd_farm::network", "jd_farm::version"],
+    ),
+    (
+        "tasks",
+        &[
+            "jd_farm::tasks",
+            "jd_farm::bean",
+            "jd_farm::pet",
+            "jd_farm::plugin",
+        ],
+    ),
+    (
+        "scheduler",
+        &["jd_farm::scheduler", "jd_farm::daemon", "jd_farm::runner"],
+    ),
+    ("notify", &["jd_farm::notify"]),
+];
+
+// 当前日志格式及各子系统的日志级别, 修改后重新生成过滤器
+struct Levels {
+    format: LogFormat,
+    levels: BTreeMap<String, LevelFilter>,
+    handle: Option<reload::Handle<EnvFilter, Registry>>,
+}
+
+fn state() -> &'static Mutex<Levels> {
+    static LEVELS: OnceLock<Mutex<Levels>> = OnceLock::new();
+    LEVELS.get_or_init(|| {
+        Mutex::new(Levels {
+            format: LogFormat::Text,
+            levels: BTreeMap::new(),
+            handle: None,
+        })
+    })
+}
+
+// 以RUST_LOG为基础, 子系统的日志级别优先于RUST_LOG中的设置
+fn filter(format: LogFormat, levels: &BTreeMap<String, LevelFilter>) -> EnvFilter {
+    let mut filter = EnvFilter::from_default_env();
+    if format == LogFormat::Text {
+        // 文本日志中已有中文描述, 不再重复输出任务结果事件
+        filter = filter.add_directive(
+            format!("{}=off", TASK_RESULT_TARGET)
+                .parse()
+                .expect("invalid directive"),
+        );
+    }
+    for (subsystem, modules) in SUBSYSTEMS {
+        let Some(level) = levels.get(subsystem) else {
+            continue;
+        };
+        for module in modules {
+            filter = filter.add_directive(
+                format!("{}={}", module, level)
+                    .parse()
+                    .expect("invalid directive"),
+            );
+        }
+    }
+    filter
+}
+
+// 解析子系统及日志级别
+fn parse_level(subsystem: &str, level: &str) -> Result<(String, LevelFilter)> {
+    let subsystem = subsystem.trim().to_lowercase();
+    if !SUBSYSTEMS.iter().any(|(name, _)| *name == subsystem) {
+        let names: Vec<&str> = SUBSYSTEMS.iter().map(|(name, _)| *name).collect();
+        return Err(anyhow!(
+            "{}",
+            t!("未知的日志子系统{}, 可选: {}", subsystem, names.join(", "))
+        ));
+    }
+    let level = LevelFilter::from_str(level.trim())
+        .map_err(|_| anyhow!("{}", t!("无效的日志级别{}", level.trim())))?;
+    Ok((subsystem, level))
+}
+
+// LOG_LEVELS: 各子系统的日志级别, 如`transport=debug,notify=warn`, 也可在配置文件的[log_levels]中设置
+// 子系统: transport(接口请求)、tasks(任务)、scheduler(调度及运行)、notify(通知)
+fn levels_from_env() -> BTreeMap<String, LevelFilter> {
+    let mut items: Vec<(String, String)> = crate::config::profile()
+        .log_levels
+        .unwrap_or_default()
+        .into_iter()
+        .collect();
+    items.extend(
+        env::var("LOG_LEVELS")
+            .unwrap_or_default()
+            .split(',')
+            .filter_map(|item| item.split_once('='))
+            .map(|(subsystem, level)| (subsystem.to_string(), level.to_string())),
+    );
+    let mut levels = BTreeMap::new();
+    for (subsystem, level) in items {
+        match parse_level(&subsystem, &level) {
+            Ok((subsystem, level)) => {
+                levels.insert(subsystem, level);
+            }
+            Err(e) => eprintln!("{}", e),
+        }
+    }
+    levels
+}
+
+// 当前各子系统单独设置的日志级别, 未设置的子系统使用RUST_LOG
+pub fn levels() -> BTreeMap<String, String> {
+    state()
+        .lock()
+        .unwrap()
+        .levels
+        .iter()
+        .map(|(subsystem, level)| (subsystem.clone(), level.to_string().to_lowercase()))
+        .collect()
+}
+
+// 运行期间修改子系统的日志级别, level为default时恢复使用RUST_LOG
+pub fn set_level(subsystem: &str, level: &str) -> Result<()> {
+    let mut state = state().lock().unwrap();
+    if level.trim().eq_ignore_ascii_case("default") {
+        let (subsystem, _) = parse_level(subsystem, "info")?;
+        state.levels.remove(&subsystem);
+    } else {
+        let (subsystem, level) = parse_level(subsystem, level)?;
+        state.levels.insert(subsystem, level);
+    }
+    let filter = filter(state.format, &state.levels);
+    match &state.handle {
+        Some(handle) => handle.reload(filter).map_err(|e| anyhow!(e)),
+        None => Ok(()),
+    }
+}
+
+// 初始化日志, 日志级别由RUST_LOG及LOG_LEVELS控制
+pub fn init() {
+    let format = LogFormat::from_env();
+    let levels = levels_from_env();
+    let (filter, handle) = reload::Layer::new(filter(format, &levels));
+    *state().lock().unwrap() = Levels {
+        format,
+        levels,
+        handle: Some(handle),
+    };
+    let (json, text) = match format {
+        LogFormat::Json => (
+            Some(
+                fmt::layer()
+                    .with_writer(RedactingMakeWriter(console()))
+                    .json()
+                    .with_current_span(true)
+                    .with_span_list(true),
+            ),
+            None,
+        ),
+        LogFormat::Text => (
+            None,
+            Some(fmt::layer().with_writer(RedactingMakeWriter(console()))),
+        ),
+    };
+
+    let registry = tracing_subscriber::registry()
+        .with(filter)
+        .with(json)
+        .with(text)
+        .with(AccountFileLayer::from_env());
+    #[cfg(feature = "otlp")]
+    let registry = registry.with(crate::telemetry::layer());
+    registry.init();
+}
@@ -1,36 +1,492 @@
 use std::env;
 
+#[cfg(all(feature = "cli", feature = "history"))]
+use anyhow::anyhow;
 use anyhow::Result;
-use futures::future::join_all;
-use jd_com::account::get_accounts;
-use jd_farm::JClient;
-use log::info;
+#[cfg(feature = "cli")]
+use clap::{Parser, Subcommand, ValueEnum};
+use jd_farm::prelude::*;
+use jd_farm::t;
+use jd_farm::{service, shutdown};
+#[cfg(feature = "cli")]
+use serde::Serialize;
+use tracing::info;
+
+#[cfg(feature = "cli")]
+#[derive(Parser)]
+#[command(version, about = "东东农场")]
+struct Cli {
+    /// 使用配置文件中的配置档案, 如cautious
+    #[arg(long, global = true)]
+    profile: Option<String>,
+    /// 模拟运行, 使用内置的模拟接口执行所有任务, 不访问真实账号
+    #[arg(long, global = true)]
+    simulate: bool,
+    /// 以JSON格式输出结果, 日志改为输出到标准错误, 便于其他程序处理
+    #[arg(long, global = true)]
+    json: bool,
+    /// 运行事件的输出格式, ndjson: 每行一个JSON事件输出到标准输出, 日志改为输出到标准错误
+    #[arg(long, global = true, value_enum)]
+    events: Option<EventFormat>,
+    /// 容器运行模式: 只读取环境变量, 按DAEMON_INTERVAL(默认60分钟)循环运行, 提供/healthz及/metrics, 日志以JSON格式输出到标准输出
+    #[arg(long = "loop")]
+    loop_mode: bool,
+    #[command(subcommand)]
+    command: Option<Command>,
+}
+
+#[cfg(feature = "cli")]
+#[derive(Subcommand)]
+enum Command {
+    /// 交互式生成配置文件: 填写cookie、通知渠道及运行方式, 并实时校验
+    Init {
+        /// 配置文件路径
+        #[arg(long, default_value = ".env")]
+        output: std::path::PathBuf,
+    },
+    /// 查看各账号的果树信息
+    Info,
+    /// 查看各账号的好友
+    Friends,
+    /// 查看各账号的道具卡
+    Cards,
+    /// 检查各账号的cookie是否有效
+    Check,
+    /// 使用JD_FARM_CONFIG_KEY加密配置文件, 输出可放到远程配置地址上的内容
+    EncryptConfig {
+        /// 配置文件路径
+        #[arg(default_value = "jd_farm.toml")]
+        input: std::path::PathBuf,
+    },
+    /// 诊断运行环境: 网络连通性、系统时间、代理、签名及第一个账号的cookie, 结果可粘贴到问题反馈中
+    Doctor,
+    /// 查看运行统计及获得的奖品, 需设置HISTORY_DB
+    Report {
+        /// 统计最近几天
+        #[arg(long, default_value_t = 30)]
+        days: i64,
+    },
+    /// 查看各账号的健康状态: 健康、异常、疑似被封、cookie已失效, 需设置HISTORY_DB
+    Health,
+    /// 输出各账号的助力码及分享链接, 可发送给好友助力
+    ShareCodes {
+        /// 同时导出到CSV文件
+        #[arg(long)]
+        output: Option<std::path::PathBuf>,
+    },
+    /// 启动HTTP接口服务, 通过接口触发运行、查询结果及管理账号
+    #[cfg(feature = "rest")]
+    Serve {
+        /// 监听地址
+        #[arg(long, default_value = "127.0.0.1:8080")]
+        addr: std::net::SocketAddr,
+    },
+    /// 作为Windows服务运行, 由服务管理器启动, 需设置DAEMON_INTERVAL或SCHEDULE
+    #[cfg(all(feature = "service", windows))]
+    Service,
+}
+
+// 运行事件的输出格式
+#[cfg(feature = "cli")]
+#[derive(Clone, Copy, ValueEnum)]
+enum EventFormat {
+    /// 每行一个JSON事件
+    Ndjson,
+}
+
+// 以JSON格式输出命令结果
+#[cfg(feature = "cli")]
+fn print_json<T: Serialize>(value: &T) -> Result<()> {
+    println!("{}", serde_json::to_string_pretty(value)?);
+    Ok(())
+}
+
+// 查询命令中单个账号的结果, 查询失败时只有error
+#[cfg(feature = "cli")]
+#[derive(Serialize)]
+struct AccountOutput<T> {
+    account: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    data: Option<T>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+// 依次查询每个账号, 只读取数据, 不执行任何任务; 模拟运行时使用模拟接口
+#[cfg(feature = "cli")]
+async fn query_accounts<T, F, Fut>(jd_cookie: String, query: F) -> Vec<AccountOutput<T>>
+where
+    F: Fn(JClient) -> Fut,
+    Fut: std::future::Future<Output = Result<T>>,
+{
+    let simulator = jd_farm::simulate::Simulator::from_env().map(std::sync::Arc::new);
+    let mut outputs = Vec::new();
+    for account in jd_farm::accounts::load(&jd_cookie) {
+        let name = account.name();
+        let mut client = JClient::new(account).with_device(Device::for_account(&name));
+        if let Some(simulator) = &simulator {
+            client = client.with_simulator(simulator.clone());
+        }
+        let (data, error) = match query(client).await {
+            Ok(data) => (Some(data), None),
+            Err(e) => (None, Some(jd_farm::redact::scrub(&e.to_string()))),
+        };
+        outputs.push(AccountOutput {
+            account: name,
+            data,
+            error,
+        });
+    }
+    outputs
+}
+
+// 输出各账号的查询结果, 文本格式时每个账号一行
+#[cfg(feature = "cli")]
+fn print_accounts<T: Serialize>(
+    json: bool,
+    outputs: &[AccountOutput<T>],
+    text: impl Fn(&T) -> String,
+) -> Result<()> {
+    if json {
+        return print_json(&outputs);
+    }
+    for output in outputs {
+        match (&output.data, &output.error) {
+            (Some(data), _) => println!("{}: {}", output.account, text(data)),
+            (None, error) => println!(
+                "{}: {}",
+                output.account,
+                error.as_deref().unwrap_or_default()
+            ),
+        }
+    }
+    Ok(())
+}
+
+// 执行只读查询命令, 返回false时不是查询命令
+#[cfg(feature = "cli")]
+async fn query(command: &Command, jd_cookie: String, json: bool) -> Result<bool> {
+    match command {
+        Command::Info => {
+            let outputs =
+                query_accounts(
+                    jd_cookie,
+                    |client| async move { client.get_farm_info().await },
+                )
+                .await;
+            print_accounts(json, &outputs, |farm| {
+                t!(
+                    "奖品名称: {}, 剩余水滴: {}g, 已浇水滴: {}g, 还需浇水: {}g",
+                    farm.name,
+                    farm.total_energy,
+                    farm.tree_energy,
+                    farm.tree_total_energy.saturating_sub(farm.tree_energy)
+                )
+            })?;
+        }
+        Command::Friends => {
+            let outputs = query_accounts(
+                jd_cookie,
+                |client| async move { client.get_friends().await },
+            )
+            .await;
+            print_accounts(json, &outputs, |friends| {
+                let names: Vec<&str> = friends.iter().map(|f| f.nick_name.as_str()).collect();
+                t!("好友{}个: {}", friends.len(), names.join(&t!("、")))
+            })?;
+        }
+        Command::Cards => {
+            let outputs =
+                query_accounts(
+                    jd_cookie,
+                    |client| async move { client.get_card_info().await },
+                )
+                .await;
+            print_accounts(json, &outputs, |card| {
+                t!(
+                    "水滴换豆卡: {}, 快速浇水卡: {}, 水滴翻倍卡: {}, 加签卡: {}",
+                    card.bean_card,
+                    card.fast_card,
+                    card.double_card,
+                    card.sign_card
+                )
+            })?;
+        }
+        Command::Check => {
+            let outputs = query_accounts(
+                jd_cookie,
+                |client| async move { client.check_cookie().await },
+            )
+            .await;
+            print_accounts(json, &outputs, |valid| match valid {
+                true => t!("cookie有效"),
+                false => t!("cookie已失效"),
+            })?;
+        }
+        Command::ShareCodes { output } => {
+            let outputs =
+                query_accounts(jd_cookie, |client| async move { client.share_code().await }).await;
+            print_accounts(json, &outputs, |code| {
+                format!("{}\t{}", code.share_code, code.link)
+            })?;
+            if let Some(path) = output {
+                let codes: Vec<ShareCode> = outputs.into_iter().filter_map(|o| o.data).collect();
+                jd_farm::export::export_share_codes(path, &codes)?;
+                info!("{}", t!("助力码已导出到{}", path.display()));
+            }
+        }
+        _ => return Ok(false),
+    }
+    Ok(true)
+}
+
+// 输出运行历史统计
+#[cfg(all(feature = "cli", feature = "history"))]
+fn report(days: i64, json: bool) -> Result<()> {
+    let history =
+        History::from_env().ok_or_else(|| anyhow!("{}", t!("未设置环境变量HISTORY_DB.")))??;
+    match json {
+        true => {
+            print_json(&history.runs_since(chrono::Local::now() - chrono::Duration::days(days))?)
+        }
+        false => {
+            println!("{}", history.summary(days)?);
+            Ok(())
+        }
+    }
+}
+
+#[cfg(all(feature = "cli", not(feature = "history")))]
+fn report(_days: i64, _json: bool) -> Result<()> {
+    info!(
+        "{}",
+        t!("未启用history功能, 请使用--features history重新编译.")
+    );
+    Ok(())
+}
+
+// 输出各账号的健康状态
+#[cfg(all(feature = "cli", feature = "history"))]
+fn health(json: bool) -> Result<()> {
+    let history =
+        History::from_env().ok_or_else(|| anyhow!("{}", t!("未设置环境变量HISTORY_DB.")))??;
+    let accounts = history.health()?;
+    if json {
+        return print_json(&accounts);
+    }
+    if accounts.is_empty() {
+        println!("{}", t!("暂无运行记录."));
+    }
+    for account in accounts {
+        println!("{}", account.to_text());
+    }
+    Ok(())
+}
+
+#[cfg(all(feature = "cli", not(feature = "history")))]
+fn health(json: bool) -> Result<()> {
+    report(0, json)
+}
+
+// 诊断运行环境, 未配置账号时不检查cookie
+#[cfg(feature = "cli")]
+async fn doctor(json: bool) -> Result<()> {
+    let jd_cookie = match env::var("JD_COOKIE") {
+        Ok(jd_cookie) => jd_cookie,
+        Err(_) if jd_farm::simulate::is_enabled() => jd_farm::simulate::cookies(),
+        Err(_) => String::new(),
+    };
+    let report = jd_farm::doctor::Doctor::from_env().run(&jd_cookie).await;
+    match json {
+        true => print_json(&report),
+        false => {
+            println!("{}", report.to_text());
+            Ok(())
+        }
+    }
+}
+
+// 服务模式收到退出信号后不再接受请求, 等待进行中的运行结束后退出
+#[cfg(any(
+    all(feature = "cli", feature = "rest"),
+    feature = "grpc",
+    feature = "bot"
+))]
+async fn serve_until_shutdown(
+    controller: std::sync::Arc<jd_farm::control::Controller>,
+    server: impl std::future::Future<Output = Result<()>>,
+) -> Result<()> {
+    tokio::select! {
+        res = server => res,
+        _ = shutdown::requested() => {
+            controller.shutdown().await;
+            Ok(())
+        }
+    }
+}
 
 #[tokio::main]
 async fn main() -> Result<()> {
+    #[cfg(feature = "cli")]
+    let cli = Cli::parse();
+    #[cfg(all(feature = "cli", feature = "service", windows))]
+    if let Some(Command::Service) = cli.command {
+        jd_farm::service::enter_exe_dir()?;
+    }
     dotenv::dotenv().ok();
-    pretty_env_logger::init();
+    #[cfg(feature = "cli")]
+    if cli.profile.is_some() {
+        jd_farm::config::set_profile(cli.profile.as_deref());
+    }
+    #[cfg(feature = "cli")]
+    if cli.simulate {
+        jd_farm::simulate::enable();
+    }
+    #[cfg(feature = "cli")]
+    if cli.json {
+        jd_farm::logging::use_stderr();
+    }
+    #[cfg(feature = "cli")]
+    if let Some(EventFormat::Ndjson) = cli.events {
+        jd_farm::logging::use_stderr();
+        jd_farm::event::output_ndjson();
+    }
+    #[cfg(feature = "cli")]
+    if cli.loop_mode {
+        jd_farm::logging::use_json();
+    }
+    jd_farm::logging::init();
+    #[cfg(feature = "sentry")]
+    let _sentry = jd_farm::error_report::init();
 
-    let jd_cookie = env::var("JD_COOKIE");
+    #[cfg(feature = "cli")]
+    if let Some(Command::EncryptConfig { input }) = &cli.command {
+        let Ok(key) = env::var("JD_FARM_CONFIG_KEY") else {
+            info!("{}", t!("未找到环境变量JD_FARM_CONFIG_KEY."));
+            return Ok(());
+        };
+        let text = std::fs::read_to_string(input)?;
+        println!("{}", jd_farm::remote_config::encrypt(&text, &key)?);
+        return Ok(());
+    }
+
+    // 远程配置在启动时获取一次, 常驻运行、定时运行及服务模式下之后按刷新间隔更新
+    let remote = jd_farm::remote_config::RemoteConfig::from_env();
+    if let Some(remote) = &remote {
+        remote.load().await;
+    }
 
-    if jd_cookie.is_err() {
-        info!("未找到环境变量JD_COOKIE.");
+    #[cfg(feature = "cli")]
+    if let Some(Command::Init { output }) = &cli.command {
+        jd_farm::wizard::Wizard::stdin().run(output).await?;
         return Ok(());
     }
 
-    let accounts = get_accounts(jd_cookie.unwrap());
+    #[cfg(feature = "cli")]
+    if let Some(Command::Report { days }) = cli.command {
+        return report(days, cli.json);
+    }
 
-    let mut handles = Vec::new();
+    #[cfg(feature = "cli")]
+    if let Some(Command::Health) = cli.command {
+        return health(cli.json);
+    }
 
-    for account in accounts {
-        let handle = tokio::spawn(async move {
-            let client = JClient::new(account);
-            let _ = client.run().await;
-        });
-        handles.push(handle);
+    #[cfg(feature = "cli")]
+    if let Some(Command::Doctor) = cli.command {
+        return doctor(cli.json).await;
+    }
+
+    #[cfg(all(feature = "cli", feature = "service", windows))]
+    if let Some(Command::Service) = cli.command {
+        return jd_farm::service::run_windows_service();
+    }
+
+    let jd_cookie = match env::var("JD_COOKIE") {
+        Ok(jd_cookie) => jd_cookie,
+        // 只使用账号文件中的账号
+        Err(_) if jd_farm::accounts::file_from_env().is_some() => String::new(),
+        // 模拟运行时未设置JD_COOKIE使用模拟账号
+        Err(_) if jd_farm::simulate::is_enabled() => jd_farm::simulate::cookies(),
+        Err(_) => {
+            info!("{}", t!("未找到环境变量JD_COOKIE."));
+            return Ok(());
+        }
+    };
+
+    #[cfg(feature = "cli")]
+    if let Some(command) = &cli.command {
+        if query(command, jd_cookie.clone(), cli.json).await? {
+            return Ok(());
+        }
+    }
+
+    #[cfg(feature = "cli")]
+    if cli.loop_mode {
+        let daemon = Daemon::container_from_env();
+        let run = service::supervise(daemon.run(jd_cookie));
+        return shutdown::with_signals(jd_farm::remote_config::refreshing(remote.as_ref(), run))
+            .await;
+    }
+
+    #[cfg(all(feature = "cli", feature = "rest"))]
+    if let Some(Command::Serve { addr }) = cli.command {
+        let controller = jd_farm::control::Controller::new(jd_farm::accounts::merge(&jd_cookie));
+        let server = jd_farm::rest::RestServer::new(addr)
+            .with_env()
+            .serve(controller.clone());
+        let run = serve_until_shutdown(controller, server);
+        return shutdown::with_signals(jd_farm::remote_config::refreshing(remote.as_ref(), run))
+            .await;
+    }
+
+    #[cfg(feature = "grpc")]
+    if let Some(server) = jd_farm::grpc::GrpcServer::from_env() {
+        let controller = jd_farm::control::Controller::new(jd_farm::accounts::merge(&jd_cookie));
+        let server = server.serve(controller.clone());
+        let run = serve_until_shutdown(controller, server);
+        return shutdown::with_signals(jd_farm::remote_config::refreshing(remote.as_ref(), run))
+            .await;
+    }
+
+    #[cfg(feature = "bot")]
+    if let Some(bot) = jd_farm::bot::TelegramBot::from_env() {
+        let controller = jd_farm::control::Controller::new(jd_farm::accounts::merge(&jd_cookie));
+        let server = bot.serve(controller.clone());
+        let run = serve_until_shutdown(controller, server);
+        return shutdown::with_signals(jd_farm::remote_config::refreshing(remote.as_ref(), run))
+            .await;
+    }
+
+    #[cfg(feature = "scheduler")]
+    if let Some(scheduler) = jd_farm::scheduler::Scheduler::from_env() {
+        let run = service::supervise(scheduler?.run(jd_cookie));
+        return shutdown::with_signals(jd_farm::remote_config::refreshing(remote.as_ref(), run))
+            .await;
+    }
+
+    if let Some(daemon) = Daemon::from_env() {
+        let run = service::supervise(daemon.run(jd_cookie));
+        return shutdown::with_signals(jd_farm::remote_config::refreshing(remote.as_ref(), run))
+            .await;
+    }
+
+    let accounts = jd_farm::accounts::load(&jd_cookie);
+    let runner = Runner::new(accounts).cookie_pool(jd_farm::accounts::backups(&jd_cookie));
+
+    let report = shutdown::with_signals(runner.run()).await;
+    #[cfg(feature = "cli")]
+    match (cli.events, cli.json) {
+        // 输出运行事件时标准输出只保留事件
+        (Some(_), _) => {}
+        (None, true) => print_json(&report)?,
+        (None, false) => println!("\n{}", report.to_table()),
     }
+    #[cfg(not(feature = "cli"))]
+    println!("\n{}", report.to_table());
 
-    join_all(handles).await;
+    #[cfg(feature = "otlp")]
+    jd_farm::telemetry::shutdown();
 
     Ok(())
 }
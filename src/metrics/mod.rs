@@ -0,0 +1,143 @@
+pub mod server;
+
+use std::path::Path;
+use std::sync::OnceLock;
+use std::{env, fs};
+
+use anyhow::Result;
+use chrono::Utc;
+use prometheus::{Encoder, IntCounterVec, IntGauge, IntGaugeVec, Opts, Registry, TextEncoder};
+
+use crate::report::RunReport;
+
+// 运行指标
+pub struct Metrics {
+    registry: Registry,
+    // 获得的水滴(g)
+    water_gained: IntCounterVec,
+    // 任务执行次数, 按结果区分
+    tasks: IntCounterVec,
+    // cookie是否有效
+    cookie_valid: IntGaugeVec,
+    // 剩余水滴(g)
+    water_balance: IntGaugeVec,
+    // 上次运行结束时间
+    last_run: IntGauge,
+    // 接口请求次数, 按结果区分
+    requests: IntCounterVec,
+}
+
+impl Metrics {
+    fn new() -> Self {
+        let registry = Registry::new_custom(Some("jd_farm".to_string()), None)
+            .expect("invalid metrics prefix");
+        let water_gained = IntCounterVec::new(
+            Opts::new("water_gained_total", "获得的水滴(g)"),
+            &["account"],
+        )
+        .unwrap();
+        let tasks = IntCounterVec::new(
+            Opts::new("tasks_total", "任务执行次数"),
+            &["account", "outcome"],
+        )
+        .unwrap();
+        let cookie_valid =
+            IntGaugeVec::new(Opts::new("cookie_valid", "cookie是否有效"), &["account"]).unwrap();
+        let water_balance =
+            IntGaugeVec::new(Opts::new("water_balance", "剩余水滴(g)"), &["account"]).unwrap();
+        let last_run = IntGauge::new("last_run_timestamp_seconds", "上次运行结束时间").unwrap();
+        let requests = IntCounterVec::new(
+            Opts::new("requests_total", "接口请求次数"),
+            &["function_id", "outcome"],
+        )
+        .unwrap();
+
+        registry.register(Box::new(water_gained.clone())).unwrap();
+        registry.register(Box::new(tasks.clone())).unwrap();
+        registry.register(Box::new(cookie_valid.clone())).unwrap();
+        registry.register(Box::new(water_balance.clone())).unwrap();
+        registry.register(Box::new(last_run.clone())).unwrap();
+        registry.register(Box::new(requests.clone())).unwrap();
+
+        Self {
+            registry,
+            water_gained,
+            tasks,
+            cookie_valid,
+            water_balance,
+            last_run,
+            requests,
+        }
+    }
+}
+
+// 全局指标
+fn metrics() -> &'static Metrics {
+    static METRICS: OnceLock<Metrics> = OnceLock::new();
+    METRICS.get_or_init(Metrics::new)
+}
+
+// 记录一次接口请求
+pub fn record_request(function_id: &str, success: bool) {
+    let outcome = if success { "success" } else { "failure" };
+    metrics()
+        .requests
+        .with_label_values(&[function_id, outcome])
+        .inc();
+    #[cfg(feature = "otlp")]
+    crate::telemetry::record_request(function_id, outcome);
+}
+
+// 记录一次运行结果
+pub fn record_report(report: &RunReport) {
+    let metrics = metrics();
+    for account in &report.accounts {
+        let name = account.name.as_str();
+        metrics
+            .water_gained
+            .with_label_values(&[name])
+            .inc_by(account.water_gained());
+        for task in &account.tasks {
+            let outcome = if task.success { "success" } else { "failure" };
+            metrics.tasks.with_label_values(&[name, outcome]).inc();
+            #[cfg(feature = "otlp")]
+            crate::telemetry::record_task(name, outcome, task.gain);
+        }
+        metrics
+            .cookie_valid
+            .with_label_values(&[name])
+            .set(!account.cookie_expired as i64);
+        metrics
+            .water_balance
+            .with_label_values(&[name])
+            .set(account.water_after as i64);
+    }
+    metrics.last_run.set(Utc::now().timestamp());
+}
+
+// 以Prometheus文本格式输出所有指标
+pub fn render() -> String {
+    let mut buffer = Vec::new();
+    let encoder = TextEncoder::new();
+    if encoder
+        .encode(&metrics().registry.gather(), &mut buffer)
+        .is_err()
+    {
+        return String::new();
+    }
+    String::from_utf8(buffer).unwrap_or_default()
+}
+
+// 以node_exporter textfile collector格式写入文件, 先写临时文件再重命名, 避免采集到不完整的内容
+pub fn write_textfile(path: &Path) -> Result<()> {
+    let tmp = path.with_extension("prom.tmp");
+    fs::write(&tmp, render())?;
+    fs::rename(tmp, path)?;
+    Ok(())
+}
+
+// METRICS_TEXTFILE: 可选, 每次运行后写入指标的文件路径, 如/var/lib/node_exporter/jd_farm.prom
+pub fn write_textfile_from_env() -> Option<Result<()>> {
+    let path = env::var("METRICS_TEXTFILE").ok()?;
+    Some(write_textfile(Path::new(&path)))
+}
@@ -0,0 +1,473 @@
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::error::JError;
+
+// 接口返回数据缺少字段时使用默认值, 不影响其他数据的解析
+// 默认值会导致请求出错的关键字段在解析后通过Validate校验, 只有用到该数据的任务失败
+pub trait Validate {
+    // 缺少或无效的字段, 为空时表示数据完整
+    fn missing_fields(&self) -> Vec<&'static str>;
+
+    // 校验解析后的数据, 数据不完整时返回解析错误
+    fn validate(&self, function_id: &str) -> Result<(), JError> {
+        let fields = self.missing_fields();
+        match fields.is_empty() {
+            true => Ok(()),
+            false => Err(JError::parse(
+                function_id,
+                t!("缺少字段{}", fields.join(", ")),
+            )),
+        }
+    }
+}
+
+// 取出缺少的字段
+fn missing(fields: &[(&'static str, bool)]) -> Vec<&'static str> {
+    fields
+        .iter()
+        .filter(|(_, missing)| *missing)
+        .map(|(field, _)| *field)
+        .collect()
+}
+
+// 果树信息
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(default, rename_all = "camelCase")]
+pub struct JdFarmInfo {
+    // 当前剩余的总水滴
+    pub total_energy: u32,
+
+    // 果树状态
+    pub tree_state: u8,
+
+    // 当前树已浇水滴
+    pub tree_energy: u32,
+
+    // 果树升级/成熟需要的水滴
+    pub tree_total_energy: u32,
+
+    // 助力码
+    pub share_code: String,
+
+    // 用户昵称
+    pub nick_name: String,
+
+    // 奖品名称
+    pub name: String,
+
+    // 奖品等级
+    pub prize_level: u8,
+}
+
+impl JdFarmInfo {
+    // 果树成熟进度(%)
+    pub fn progress(&self) -> f64 {
+        if self.tree_total_energy == 0 {
+            return 0.0;
+        }
+        (self.tree_energy as f64 / self.tree_total_energy as f64 * 100.0).min(100.0)
+    }
+}
+
+impl Validate for JdFarmInfo {
+    fn missing_fields(&self) -> Vec<&'static str> {
+        missing(&[
+            ("shareCode", self.share_code.is_empty()),
+            ("treeTotalEnergy", self.tree_total_energy == 0),
+        ])
+    }
+}
+
+// 签到任务
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(default, rename_all = "camelCase")]
+pub struct SignInTask {
+    // 是否已完成
+    pub f: bool,
+}
+
+// 首次浇水任务
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(default, rename_all = "camelCase")]
+pub struct FirstWaterTask {
+    // 是否已完成
+    pub f: bool,
+}
+
+// 十次浇水任务
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(default, rename_all = "camelCase")]
+pub struct TotalWaterTask {
+    // 是否已完成
+    pub f: bool,
+    // 总共需要浇水次数
+    pub total_water_task_limit: u16,
+    // 当前已浇水次数
+    pub total_water_task_times: u16,
+}
+
+// 给好友浇水任务
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(default, rename_all = "camelCase")]
+pub struct WaterFriendTask {
+    // 总共需要为好友浇水的次数
+    pub water_friend_max: u8,
+
+    // 当前为好友浇水的次数
+    pub water_friend_count_key: u8,
+
+    // 是否已完成
+    pub f: bool,
+
+    // 奖励是否已领取
+    pub water_friend_got_award: bool,
+}
+
+// 浏览任务
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(default, rename_all = "camelCase")]
+pub struct BrowseTaskItem {
+    // 广告ID
+    pub advert_id: String,
+    // 任务名称
+    pub main_title: String,
+    // 最多完成次数
+    pub limit: u8,
+    // 已完成次数
+    pub had_finished_times: u8,
+    // 任务等待时间
+    pub time: u16,
+    // 领取奖励的次数
+    pub had_got_times: u8,
+}
+
+impl Validate for BrowseTaskItem {
+    fn missing_fields(&self) -> Vec<&'static str> {
+        missing(&[("advertId", self.advert_id.is_empty())])
+    }
+}
+
+// 浏览类型任务列表
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(default, rename_all = "camelCase")]
+pub struct BrowseTask {
+    // 是否完成
+    pub f: bool,
+    // 子任务列表
+    pub user_browse_task_ads: Vec<BrowseTaskItem>,
+}
+
+// 从App首页进入农场
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(default, rename_all = "camelCase")]
+pub struct TreasureBoxTask {
+    pub line: String,
+    pub f: bool,
+}
+
+impl Validate for TreasureBoxTask {
+    fn missing_fields(&self) -> Vec<&'static str> {
+        missing(&[("line", self.line.is_empty())])
+    }
+}
+
+// 水滴雨任务
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(default, rename_all = "camelCase")]
+pub struct WaterRainTask {
+    pub f: bool,
+    pub win_times: u8,
+    pub last_time: u64,
+}
+
+// 好友信息
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(default, rename_all = "camelCase")]
+pub struct FriendInfo {
+    // 好友昵称
+    pub nick_name: String,
+    // 好友助力码
+    pub share_code: String,
+    // 是否可以帮他浇水
+    pub friend_state: u8,
+}
+
+impl Validate for FriendInfo {
+    fn missing_fields(&self) -> Vec<&'static str> {
+        missing(&[("shareCode", self.share_code.is_empty())])
+    }
+}
+
+// 好友列表
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(default, rename_all = "camelCase")]
+pub struct FriendInfoList {
+    // 好友信息列表
+    pub friends: Vec<FriendInfo>,
+}
+
+// 好友为我浇水的记录
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(default, rename_all = "camelCase")]
+pub struct WaterRecord {
+    // 好友昵称
+    pub nick_name: String,
+    // 好友助力码
+    pub share_code: String,
+    // 浇水时间(毫秒时间戳)
+    pub time: u64,
+}
+
+// 最近的好友浇水记录(friendWaterRecordForFarm)
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(default, rename_all = "camelCase")]
+pub struct WaterRecordList {
+    pub records: Vec<WaterRecord>,
+}
+
+// 三餐定时领水
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(default, rename_all = "camelCase")]
+pub struct ThreeMealTask {
+    // 是否已完成
+    pub f: bool,
+}
+
+// 任务信息, 缺少某项任务的数据时只跳过该任务
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(default, rename_all = "camelCase")]
+pub struct TaskInfo {
+    // 签到任务
+    pub sign_init: Option<SignInTask>,
+    // 首次浇水任务
+    pub first_water_init: Option<FirstWaterTask>,
+    // 十次浇水任务
+    pub total_water_task_init: Option<TotalWaterTask>,
+    // 为两位好友浇水任务
+    pub water_friend_task_init: Option<WaterFriendTask>,
+    // 浏览商品任务
+    pub got_browse_task_ad_init: Option<BrowseTask>,
+    // 从首页免费水果进入农场
+    pub treasure_box_init: Option<TreasureBoxTask>,
+    // 水滴雨任务
+    pub water_rain_init: Option<WaterRainTask>,
+    // 三餐定时领水任务
+    pub got_three_meal_init: Option<ThreeMealTask>,
+}
+
+impl Validate for TaskInfo {
+    fn missing_fields(&self) -> Vec<&'static str> {
+        missing(&[
+            ("signInit", self.sign_init.is_none()),
+            ("firstWaterInit", self.first_water_init.is_none()),
+            ("totalWaterTaskInit", self.total_water_task_init.is_none()),
+            ("waterFriendTaskInit", self.water_friend_task_init.is_none()),
+            (
+                "gotBrowseTaskAdInit",
+                self.got_browse_task_ad_init.is_none(),
+            ),
+            ("treasureBoxInit", self.treasure_box_init.is_none()),
+            ("waterRainInit", self.water_rain_init.is_none()),
+            ("gotThreeMealInit", self.got_three_meal_init.is_none()),
+        ])
+    }
+}
+
+// 签到领水->关注任务
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(default, rename_all = "camelCase")]
+pub struct FollowTask {
+    // 广告ID
+    pub advert_id: String,
+    // 任务ID
+    pub id: String,
+    // 任务名称
+    pub name: String,
+    // 是否领取奖励
+    pub had_got: bool,
+    // 是否已关注
+    pub had_follow: bool,
+}
+
+impl Validate for FollowTask {
+    fn missing_fields(&self) -> Vec<&'static str> {
+        missing(&[("id", self.id.is_empty())])
+    }
+}
+
+// 签到领水任务信息
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(default, rename_all = "camelCase")]
+pub struct ClockInTask {
+    // 是否已签到
+    pub today_signed: bool,
+    // 限时关注领水滴任务列表
+    pub themes: Vec<FollowTask>,
+}
+
+// 背包道具卡信息
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(default, rename_all = "camelCase")]
+pub struct CardInfo {
+    // 水滴翻倍卡
+    pub double_card: u16,
+    // 快速浇水卡
+    pub fast_card: u16,
+    // 加签卡
+    pub sign_card: u16,
+    // 水滴换豆卡
+    pub bean_card: u16,
+}
+
+// 道具卡
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Card {
+    // 水滴翻倍卡
+    Double,
+    // 快速浇水卡
+    Fast,
+    // 加签卡
+    Sign,
+    // 水滴换豆卡
+    Bean,
+}
+
+impl Card {
+    // 接口中的道具卡类型
+    pub fn card_type(&self) -> &'static str {
+        match self {
+            Self::Double => "doubleCard",
+            Self::Fast => "fastCard",
+            Self::Sign => "signCard",
+            Self::Bean => "beanCard",
+        }
+    }
+
+    // 道具卡名称
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Double => "水滴翻倍卡",
+            Self::Fast => "快速浇水卡",
+            Self::Sign => "加签卡",
+            Self::Bean => "水滴换豆卡",
+        }
+    }
+}
+
+// 接口返回数据, data为请求成功时解析的业务数据, 请求失败时为默认值
+#[derive(Debug, Clone)]
+pub struct Response<T> {
+    // 返回码, 0表示成功
+    pub code: String,
+    // 业务数据
+    pub data: T,
+    // 原始数据
+    pub raw: Value,
+}
+
+impl<T> Response<T> {
+    // 是否操作成功
+    pub fn is_success(&self) -> bool {
+        self.code == "0"
+    }
+
+    // 返回码对应的错误, 操作成功时为空
+    pub fn error(&self) -> Option<JError> {
+        JError::from_response(&self.code, &self.raw)
+    }
+}
+
+impl<T> fmt::Display for Response<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.raw)
+    }
+}
+
+// 是否可以完成弹出的领水任务
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(default, rename_all = "camelCase")]
+pub struct PopTaskState {
+    pub can_pop: bool,
+}
+
+// 农场数据(initForFarm)
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(default, rename_all = "camelCase")]
+pub struct FarmData {
+    // 果树信息, 未登录时为空
+    pub farm_user_pro: Option<JdFarmInfo>,
+    pub today_got_water_goal_task: PopTaskState,
+}
+
+// 浇水(waterGoodForFarm)
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(default, rename_all = "camelCase")]
+pub struct WaterResponse {
+    // 剩余水滴
+    pub total_energy: u64,
+}
+
+// 领取水滴(gotWaterGoalTaskForFarm、waterRainForFarm)
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(default, rename_all = "camelCase")]
+pub struct EnergyResponse {
+    // 获得水滴
+    pub add_energy: u64,
+}
+
+// 领取任务奖励(browseAdTaskForFarm、clockInFollowForFarm、gotThreeMealForFarm)
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(default, rename_all = "camelCase")]
+pub struct ClaimResponse {
+    // 获得水滴
+    pub amount: u64,
+    pub today_got_water_goal_task: PopTaskState,
+}
+
+// 领取浇水任务奖励(firstWaterTaskForFarm、totalWaterTaskForFarm)
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(default, rename_all = "camelCase")]
+pub struct WaterTaskAwardResponse {
+    // 首次浇水获得水滴
+    pub amount: u64,
+    // 十次浇水获得水滴
+    pub total_water_task_energy: u64,
+    pub today_got_water_goal_task: PopTaskState,
+}
+
+// 签到领水(clockInForFarm)
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(default, rename_all = "camelCase")]
+pub struct ClockInResponse {
+    // 获得水滴, 连续签到奖励等情况下为空
+    pub amount: u64,
+}
+
+// 领取免费水果奖励(ddnc_getTreasureBoxAward)
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(default, rename_all = "camelCase")]
+pub struct TreasureBoxResponse {
+    // 获得水滴
+    pub water_gram: u64,
+}
+
+// 领取为好友浇水奖励(waterFriendGotAwardForFarm)
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(default, rename_all = "camelCase")]
+pub struct WaterFriendAwardResponse {
+    // 获得水滴
+    pub add_water: u64,
+}
+
+// 点鸭子(getFullCollectionReward)
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(default, rename_all = "camelCase")]
+pub struct DuckResponse {
+    // 奖励描述
+    pub title: String,
+    // 获得水滴, 获得优惠券等奖品时为0
+    pub add_water: u64,
+}
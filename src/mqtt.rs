@@ -0,0 +1,167 @@
+use std::env;
+use std::time::Duration;
+
+use anyhow::{anyhow, Result};
+use rumqttc::{AsyncClient, Event, MqttOptions, Outgoing, QoS};
+use serde_json::json;
+use sha2::{Digest, Sha256};
+
+use crate::redact::Secret;
+use crate::report::{AccountReport, RunReport};
+
+// 等待消息发送完成的最长时间
+const PUBLISH_TIMEOUT: Duration = Duration::from_secs(10);
+
+// 传感器: (字段, 名称, 单位, 图标)
+const SENSORS: [(&str, &str, Option<&str>, &str); 5] = [
+    ("water_balance", "剩余水滴", Some("g"), "mdi:water"),
+    ("water_gained", "本次获得水滴", Some("g"), "mdi:water-plus"),
+    ("tree_progress", "果树进度", Some("%"), "mdi:tree"),
+    (
+        "harvest_eta_days",
+        "预计成熟天数",
+        Some("d"),
+        "mdi:calendar-clock",
+    ),
+    ("status", "运行状态", None, "mdi:information"),
+];
+
+// 通过MQTT发布农场状态, 并发送Home Assistant自动发现消息
+pub struct MqttPublisher {
+    host: String,
+    port: u16,
+    user: Option<String>,
+    password: Option<Secret>,
+    topic_prefix: String,
+    discovery_prefix: String,
+}
+
+impl MqttPublisher {
+    pub fn new(host: &str, port: u16) -> Self {
+        Self {
+            host: host.to_string(),
+            port,
+            user: None,
+            password: None,
+            topic_prefix: "jd_farm".to_string(),
+            discovery_prefix: "homeassistant".to_string(),
+        }
+    }
+
+    // MQTT_HOST: 服务器地址, MQTT_PORT: 端口, 默认1883
+    // MQTT_USER/MQTT_PASSWORD: 用户名/密码
+    // MQTT_TOPIC_PREFIX: 状态主题前缀, 默认jd_farm
+    // MQTT_DISCOVERY_PREFIX: Home Assistant自动发现前缀, 默认homeassistant
+    pub fn from_env() -> Option<Self> {
+        let host = env::var("MQTT_HOST").ok().filter(|h| !h.is_empty())?;
+        let port = env::var("MQTT_PORT")
+            .ok()
+            .and_then(|p| p.parse().ok())
+            .unwrap_or(1883);
+        let mut publisher = Self::new(&host, port);
+        publisher.user = env::var("MQTT_USER").ok().filter(|u| !u.is_empty());
+        publisher.password = env::var("MQTT_PASSWORD").ok().map(|p| Secret::new(&p));
+        if let Ok(prefix) = env::var("MQTT_TOPIC_PREFIX") {
+            publisher.topic_prefix = prefix;
+        }
+        if let Ok(prefix) = env::var("MQTT_DISCOVERY_PREFIX") {
+            publisher.discovery_prefix = prefix;
+        }
+        Some(publisher)
+    }
+
+    // 账号名可能包含中文, 使用哈希作为主题及实体ID
+    fn object_id(name: &str) -> String {
+        let digest = Sha256::digest(name.as_bytes());
+        let hex: String = digest.iter().map(|b| format!("{:02x}", b)).collect();
+        hex[..12].to_string()
+    }
+
+    // 生成需要发布的消息: (主题, 内容)
+    fn messages(&self, report: &RunReport) -> Vec<(String, String)> {
+        let mut messages = Vec::new();
+        for account in &report.accounts {
+            let id = Self::object_id(&account.name);
+            let state_topic = format!("{}/{}/state", self.topic_prefix, id);
+
+            for (key, name, unit, icon) in SENSORS {
+                let mut config = json!({
+                    "name": t!(name),
+                    "unique_id": format!("jd_farm_{}_{}", id, key),
+                    "object_id": format!("jd_farm_{}_{}", id, key),
+                    "state_topic": state_topic,
+                    "value_template": format!("{{{{ value_json.{} }}}}", key),
+                    "icon": icon,
+                    "device": {
+                        "identifiers": [format!("jd_farm_{}", id)],
+                        "name": t!("东东农场 {}", account.name),
+                        "manufacturer": "jd_farm",
+                    },
+                });
+                if let Some(unit) = unit {
+                    config["unit_of_measurement"] = json!(unit);
+                    config["state_class"] = json!("measurement");
+                }
+                messages.push((
+                    format!(
+                        "{}/sensor/jd_farm_{}/{}/config",
+                        self.discovery_prefix, id, key
+                    ),
+                    config.to_string(),
+                ));
+            }
+
+            messages.push((state_topic, self.state(report, account).to_string()));
+        }
+        messages
+    }
+
+    fn state(&self, report: &RunReport, account: &AccountReport) -> serde_json::Value {
+        json!({
+            "water_balance": account.water_after,
+            "water_gained": account.water_gained(),
+            "tree_progress": (account.tree_progress() * 10.0).round() / 10.0,
+            "harvest_eta_days": report.harvest_eta_days(account).map(|d| d.ceil()),
+            "status": account.status(),
+            "last_run": report.finished_at.to_rfc3339(),
+        })
+    }
+
+    // 发布本次运行结果, 消息均为保留消息
+    pub async fn publish(&self, report: &RunReport) -> Result<()> {
+        let client_id = format!("jd_farm_{}", std::process::id());
+        let mut options = MqttOptions::new(client_id, &self.host, self.port);
+        options.set_keep_alive(Duration::from_secs(30));
+        if let Some(user) = &self.user {
+            let password = self
+                .password
+                .as_ref()
+                .map(|p| p.expose().to_string())
+                .unwrap_or_default();
+            options.set_credentials(user, password);
+        }
+
+        let messages = self.messages(report);
+        let (client, mut eventloop) = AsyncClient::new(options, messages.len() + 1);
+        for (topic, payload) in messages {
+            client
+                .publish(topic, QoS::AtLeastOnce, true, payload)
+                .await?;
+        }
+        client.disconnect().await?;
+
+        // 事件循环按顺序发送, 发出断开连接请求时消息已全部发送
+        let drive = async {
+            loop {
+                match eventloop.poll().await {
+                    Ok(Event::Outgoing(Outgoing::Disconnect)) => return Ok(()),
+                    Ok(_) => {}
+                    Err(e) => return Err(anyhow!("{}", t!("MQTT连接失败, {}", e))),
+                }
+            }
+        };
+        tokio::time::timeout(PUBLISH_TIMEOUT, drive)
+            .await
+            .map_err(|_| anyhow!("{}", t!("MQTT发布超时")))?
+    }
+}
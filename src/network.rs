@@ -0,0 +1,215 @@
+use std::env;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use tokio::sync::{Semaphore, SemaphorePermit};
+use tokio::time::Instant;
+
+// 网络错误时的重试策略, 每次重试前的等待时间从base开始加倍, 不超过max
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Backoff {
+    // 最多重试次数, 0为不重试
+    pub retries: u32,
+    // 第一次重试前的等待时间
+    pub base: Duration,
+    // 单次等待时间上限
+    pub max: Duration,
+}
+
+impl Default for Backoff {
+    fn default() -> Self {
+        Self {
+            retries: 0,
+            base: Duration::from_secs(1),
+            max: Duration::from_secs(30),
+        }
+    }
+}
+
+impl Backoff {
+    // 不重试
+    pub fn none() -> Self {
+        Self::default()
+    }
+
+    // 指数退避, 最多重试retries次
+    pub fn exponential(retries: u32, base: Duration, max: Duration) -> Self {
+        Self { retries, base, max }
+    }
+
+    // 第attempt次(从0开始)重试前的等待时间
+    pub fn delay(&self, attempt: u32) -> Duration {
+        self.base.saturating_mul(1 << attempt.min(16)).min(self.max)
+    }
+}
+
+// 运行结束时重试因网络错误、超时等临时错误失败的账号, 每个账号只重试一次
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AccountRetry {
+    // 所有账号运行结束后, 开始重试前的等待时间
+    pub wait: Duration,
+    // 重试时操作之间等待时间的倍数
+    pub delay_scale: f64,
+}
+
+impl Default for AccountRetry {
+    fn default() -> Self {
+        Self {
+            wait: Duration::from_secs(60),
+            delay_scale: 2.0,
+        }
+    }
+}
+
+impl AccountRetry {
+    // RETRY_FAILED_ACCOUNTS: 是否在运行结束时重试因临时错误失败的账号, 默认true, 设置为false关闭
+    // RETRY_FAILED_WAIT: 开始重试前的等待时间(秒), 默认60
+    // RETRY_DELAY_SCALE: 重试时操作之间等待时间的倍数, 默认2
+    pub fn from_env() -> Option<Self> {
+        if matches!(
+            env::var("RETRY_FAILED_ACCOUNTS").as_deref().map(str::trim),
+            Ok("false") | Ok("0")
+        ) {
+            return None;
+        }
+        let parse = |name: &str| {
+            env::var(name)
+                .ok()
+                .and_then(|v| v.trim().parse::<f64>().ok())
+                .filter(|v| *v >= 0.0)
+        };
+        let mut retry = Self::default();
+        if let Some(wait) = parse("RETRY_FAILED_WAIT") {
+            retry.wait = Duration::from_secs_f64(wait);
+        }
+        if let Some(scale) = parse("RETRY_DELAY_SCALE") {
+            retry.delay_scale = scale;
+        }
+        Some(retry)
+    }
+}
+
+// 网络策略, 限制请求频率、并发数及网络错误时的重试, 同一次运行的所有账号共享
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct NetworkPolicy {
+    // 所有账号合计每秒最多发送的请求数, 为空时不限制
+    pub requests_per_second: Option<f64>,
+    // 同时运行的账号数上限, 为空时所有账号同时运行
+    pub max_concurrent_accounts: Option<usize>,
+    // 同时进行的请求数上限, 为空时不限制
+    pub max_concurrent_requests: Option<usize>,
+    // 网络错误时的重试策略
+    pub backoff: Backoff,
+}
+
+impl NetworkPolicy {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // 所有账号合计每秒最多发送的请求数
+    pub fn rate_limit(mut self, requests_per_second: f64) -> Self {
+        self.requests_per_second = Some(requests_per_second);
+        self
+    }
+
+    // 同时运行的账号数上限
+    pub fn max_concurrent_accounts(mut self, max: usize) -> Self {
+        self.max_concurrent_accounts = Some(max);
+        self
+    }
+
+    // 同时进行的请求数上限
+    pub fn max_concurrent_requests(mut self, max: usize) -> Self {
+        self.max_concurrent_requests = Some(max);
+        self
+    }
+
+    // 网络错误时的重试策略
+    pub fn backoff(mut self, backoff: Backoff) -> Self {
+        self.backoff = backoff;
+        self
+    }
+
+    // RATE_LIMIT: 所有账号合计每秒最多发送的请求数, 如0.5表示每2秒一个请求
+    // MAX_CONCURRENT_ACCOUNTS/MAX_CONCURRENT_REQUESTS: 同时运行的账号数/同时进行的请求数上限
+    // REQUEST_RETRIES: 网络错误时的重试次数, 默认0
+    // RETRY_BACKOFF: 第一次重试前的等待时间(秒), 之后每次加倍, 默认1
+    // RETRY_BACKOFF_MAX: 单次等待时间上限(秒), 默认30
+    pub fn from_env() -> Self {
+        fn parse<T: std::str::FromStr>(name: &str) -> Option<T> {
+            env::var(name).ok().and_then(|v| v.trim().parse().ok())
+        }
+        let seconds = |name: &str| parse::<f64>(name).map(|v| Duration::from_secs_f64(v.max(0.0)));
+        let mut backoff = Backoff::default();
+        if let Some(retries) = parse("REQUEST_RETRIES") {
+            backoff.retries = retries;
+        }
+        if let Some(base) = seconds("RETRY_BACKOFF") {
+            backoff.base = base;
+        }
+        if let Some(max) = seconds("RETRY_BACKOFF_MAX") {
+            backoff.max = max;
+        }
+        Self {
+            requests_per_second: parse::<f64>("RATE_LIMIT").filter(|r| *r > 0.0),
+            max_concurrent_accounts: parse::<usize>("MAX_CONCURRENT_ACCOUNTS").filter(|n| *n > 0),
+            max_concurrent_requests: parse::<usize>("MAX_CONCURRENT_REQUESTS").filter(|n| *n > 0),
+            backoff,
+        }
+    }
+}
+
+// 按网络策略限制账号及请求
+#[derive(Debug)]
+pub struct NetworkLimiter {
+    policy: NetworkPolicy,
+    accounts: Option<Semaphore>,
+    requests: Option<Semaphore>,
+    // 下一个请求最早的发送时间
+    next_request: Mutex<Instant>,
+}
+
+impl NetworkLimiter {
+    pub fn new(policy: NetworkPolicy) -> Self {
+        Self {
+            accounts: policy
+                .max_concurrent_accounts
+                .map(|n| Semaphore::new(n.max(1))),
+            requests: policy
+                .max_concurrent_requests
+                .map(|n| Semaphore::new(n.max(1))),
+            next_request: Mutex::new(Instant::now()),
+            policy,
+        }
+    }
+
+    pub fn policy(&self) -> &NetworkPolicy {
+        &self.policy
+    }
+
+    // 等待开始运行一个账号, 返回的许可在账号运行结束前需保持
+    pub async fn account(&self) -> Option<SemaphorePermit<'_>> {
+        self.accounts.as_ref()?.acquire().await.ok()
+    }
+
+    // 等待发送一个请求, 返回的许可在请求结束前需保持
+    pub async fn request(&self) -> Option<SemaphorePermit<'_>> {
+        if let Some(rate) = self.policy.requests_per_second.filter(|r| *r > 0.0) {
+            let at = {
+                let mut next = self.next_request.lock().unwrap();
+                let at = (*next).max(Instant::now());
+                *next = at + Duration::from_secs_f64(1.0 / rate);
+                at
+            };
+            tokio::time::sleep_until(at).await;
+        }
+        self.requests.as_ref()?.acquire().await.ok()
+    }
+}
+
+impl Default for NetworkLimiter {
+    fn default() -> Self {
+        Self::new(NetworkPolicy::default())
+    }
+}
@@ -0,0 +1,112 @@
+use std::env;
+
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use reqwest::Client;
+use serde_json::{json, Value};
+
+use super::Notifier;
+use crate::redact::Secret;
+use crate::report::RunReport;
+
+// Bark(iOS)通知
+pub struct BarkNotifier {
+    client: Client,
+    server: String,
+    device_key: Secret,
+    sound: Option<String>,
+    group: String,
+}
+
+impl BarkNotifier {
+    pub fn new(server: &str, device_key: &str) -> Self {
+        Self {
+            client: Client::new(),
+            server: server.trim_end_matches('/').to_string(),
+            device_key: Secret::new(device_key),
+            sound: None,
+            group: t!("东东农场"),
+        }
+    }
+
+    // BARK_PUSH: 设备key, 或形如https://api.day.app/key的完整地址
+    // BARK_SOUND: 可选, 推送铃声
+    // BARK_GROUP: 可选, 推送分组
+    pub fn from_env() -> Option<Self> {
+        let push = env::var("BARK_PUSH").ok()?;
+        let push = push.trim_end_matches('/');
+        let mut notifier = match push.starts_with("http") {
+            true => {
+                let (server, device_key) = push.rsplit_once('/')?;
+                Self::new(server, device_key)
+            }
+            false => Self::new("https://api.day.app", push),
+        };
+        notifier.sound = env::var("BARK_SOUND").ok();
+        if let Ok(group) = env::var("BARK_GROUP") {
+            notifier.group = group;
+        }
+        Some(notifier)
+    }
+
+    // level: 时效性通知级别, timeSensitive可在专注模式下提醒
+    async fn push(&self, title: &str, content: &str, level: Option<&str>) -> Result<()> {
+        let mut body = json!({
+            "device_key": self.device_key.expose(),
+            "title": title,
+            "body": content,
+            "group": self.group,
+        });
+        if let Some(sound) = &self.sound {
+            body["sound"] = json!(sound);
+        }
+        if let Some(level) = level {
+            body["level"] = json!(level);
+        }
+
+        let res = self
+            .client
+            .post(format!("{}/push", self.server))
+            .json(&body)
+            .send()
+            .await?
+            .json::<Value>()
+            .await?;
+
+        match res["code"].as_i64().unwrap_or(-1) {
+            200 => Ok(()),
+            _ => Err(anyhow!("{}", res["message"])),
+        }
+    }
+}
+
+#[async_trait]
+impl Notifier for BarkNotifier {
+    fn name(&self) -> &str {
+        "Bark"
+    }
+
+    async fn send(&self, title: &str, content: &str) -> Result<()> {
+        self.push(title, content, None).await
+    }
+
+    async fn send_report(&self, report: &RunReport) -> Result<()> {
+        // 手机推送只列出需要处理的账号
+        let mut lines: Vec<String> = report
+            .accounts
+            .iter()
+            .filter(|a| !a.is_ok() || a.harvest_ready || !a.failed_tasks().is_empty())
+            .map(|a| a.to_text())
+            .collect();
+        lines.push(t!(
+            "共{}个账号, 获得水滴: {}g",
+            report.accounts.len(),
+            report.water_gained()
+        ));
+        self.send(&report.title(), &lines.join("\n")).await
+    }
+
+    async fn send_alert(&self, title: &str, content: &str) -> Result<()> {
+        self.push(title, content, Some("timeSensitive")).await
+    }
+}
@@ -0,0 +1,103 @@
+use std::env;
+
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use notify_rust::Notification;
+
+use super::Notifier;
+use crate::report::{RunReport, Severity};
+
+// 桌面通知中最多列出的出错账号数
+const MAX_FAILED_ACCOUNTS: usize = 5;
+
+// 桌面通知, 通过notify-rust在本机弹出系统原生通知, 适合在自己的电脑上运行时使用
+// Linux通过D-Bus发送给通知服务, macOS使用通知中心, Windows使用Toast通知
+#[derive(Default)]
+pub struct DesktopNotifier;
+
+impl DesktopNotifier {
+    pub fn new() -> Self {
+        Self
+    }
+
+    // DESKTOP_NOTIFY: 是否发送桌面通知, 默认false
+    pub fn from_env() -> Option<Self> {
+        matches!(
+            env::var("DESKTOP_NOTIFY").as_deref().map(str::trim),
+            Ok("true") | Ok("1")
+        )
+        .then(Self::new)
+    }
+
+    fn notification(title: &str, body: &str, severity: Severity) -> Notification {
+        let mut notification = Notification::new();
+        notification.appname("jd_farm").summary(title).body(body);
+        // 只有Linux等使用D-Bus的系统支持紧急程度, 其他系统忽略
+        #[cfg(all(unix, not(target_os = "macos")))]
+        notification.urgency(match severity {
+            Severity::Info => notify_rust::Urgency::Low,
+            Severity::Warning => notify_rust::Urgency::Normal,
+            Severity::Error => notify_rust::Urgency::Critical,
+        });
+        #[cfg(not(all(unix, not(target_os = "macos"))))]
+        let _ = severity;
+        notification
+    }
+
+    async fn show(&self, title: &str, body: &str, severity: Severity) -> Result<()> {
+        let notification = Self::notification(title, body, severity);
+        // 发送通知会阻塞等待系统响应, 在单独的线程中执行
+        tokio::task::spawn_blocking(move || {
+            notification
+                .show()
+                .map(|_| ())
+                .map_err(|e| anyhow!(t!("发送桌面通知失败, {}", e)))
+        })
+        .await?
+    }
+}
+
+// 运行结果的简短描述: 合计及出错的账号
+fn report_body(report: &RunReport) -> String {
+    let totals = report.totals();
+    let mut lines = vec![t!(
+        "共{}个账号, 获得水滴: {}g, cookie失效: {}个",
+        totals.accounts,
+        totals.water_gained,
+        totals.expired
+    )];
+    if totals.harvest_ready > 0 {
+        lines.push(t!("{}个账号果树已成熟, 请尽快领取!", totals.harvest_ready));
+    }
+    let failed: Vec<_> = report.accounts.iter().filter(|a| !a.is_ok()).collect();
+    lines.extend(
+        failed
+            .iter()
+            .take(MAX_FAILED_ACCOUNTS)
+            .map(|a| format!("【{}】{}", a.name, a.status())),
+    );
+    if failed.len() > MAX_FAILED_ACCOUNTS {
+        lines.push(t!("等{}个账号运行失败", failed.len()));
+    }
+    lines.join("\n")
+}
+
+#[async_trait]
+impl Notifier for DesktopNotifier {
+    fn name(&self) -> &str {
+        "Desktop"
+    }
+
+    async fn send(&self, title: &str, content: &str) -> Result<()> {
+        self.show(title, content, Severity::Info).await
+    }
+
+    async fn send_report(&self, report: &RunReport) -> Result<()> {
+        self.show(&report.title(), &report_body(report), report.severity())
+            .await
+    }
+
+    async fn send_alert(&self, title: &str, content: &str) -> Result<()> {
+        self.show(title, content, Severity::Error).await
+    }
+}
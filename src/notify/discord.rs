@@ -0,0 +1,114 @@
+use std::env;
+
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use reqwest::Client;
+use serde_json::{json, Value};
+
+use super::Notifier;
+use crate::redact::Secret;
+use crate::report::{progress_cell, AccountReport, RunReport, Severity};
+
+// 单条消息最多包含的embed数量
+const MAX_EMBEDS: usize = 10;
+
+// Discord webhook通知
+pub struct DiscordNotifier {
+    client: Client,
+    webhook_url: Secret,
+}
+
+impl DiscordNotifier {
+    pub fn new(webhook_url: &str) -> Self {
+        Self {
+            client: Client::new(),
+            webhook_url: Secret::new(webhook_url),
+        }
+    }
+
+    // DISCORD_WEBHOOK: webhook地址
+    pub fn from_env() -> Option<Self> {
+        let webhook_url = env::var("DISCORD_WEBHOOK").ok()?;
+        Some(Self::new(&webhook_url))
+    }
+
+    async fn post(&self, body: Value) -> Result<()> {
+        let res = self
+            .client
+            .post(self.webhook_url.expose())
+            .json(&body)
+            .send()
+            .await?;
+        match res.status().is_success() {
+            true => Ok(()),
+            false => Err(anyhow!("{}", res.text().await.unwrap_or_default())),
+        }
+    }
+}
+
+// 按严重程度选择embed颜色
+fn color(severity: Severity) -> u32 {
+    match severity {
+        Severity::Info => 0x2ecc71,
+        Severity::Warning => 0xf39c12,
+        Severity::Error => 0xe74c3c,
+    }
+}
+
+// 单个账号的embed
+fn account_embed(report: &RunReport, account: &AccountReport) -> Value {
+    let failed: Vec<&str> = account
+        .failed_tasks()
+        .iter()
+        .map(|t| crate::i18n::tr(&t.name))
+        .collect();
+    json!({
+        "title": account.name,
+        "color": color(account.severity()),
+        "fields": [
+            {"name": t!("获得水滴"), "value": format!("{}g", account.water_gained()), "inline": true},
+            {"name": t!("剩余水滴"), "value": format!("{}g", account.water_after), "inline": true},
+            {"name": t!("状态"), "value": account.status(), "inline": true},
+            {"name": t!("果树进度"), "value": report.progress_text(account).unwrap_or_else(|| progress_cell(account))},
+            {"name": t!("失败任务"), "value": if failed.is_empty() { t!("无") } else { failed.join(&t!("、")) }},
+        ],
+    })
+}
+
+#[async_trait]
+impl Notifier for DiscordNotifier {
+    fn name(&self) -> &str {
+        "Discord"
+    }
+
+    async fn send(&self, title: &str, content: &str) -> Result<()> {
+        self.post(json!({"content": format!("**{}**\n{}", title, content)}))
+            .await
+    }
+
+    async fn send_report(&self, report: &RunReport) -> Result<()> {
+        let embeds: Vec<Value> = report
+            .accounts
+            .iter()
+            .map(|a| account_embed(report, a))
+            .collect();
+        let summary = t!(
+            "**{}**\n共{}个账号, 获得水滴: {}g, cookie失效: {}个",
+            report.title(),
+            report.accounts.len(),
+            report.water_gained(),
+            report.expired_accounts().len()
+        );
+        if embeds.is_empty() {
+            return self.post(json!({ "content": summary })).await;
+        }
+        for (i, chunk) in embeds.chunks(MAX_EMBEDS).enumerate() {
+            let mut body = json!({ "embeds": chunk });
+            if i == 0 {
+                body["content"] = json!(summary);
+            }
+            self.post(body).await?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,140 @@
+use std::env;
+
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use lettre::{
+    message::{header::ContentType, Mailbox},
+    transport::smtp::authentication::Credentials,
+    AsyncSmtpTransport, AsyncTransport, Message, Tokio1Executor,
+};
+
+use super::Notifier;
+use crate::redact;
+use crate::report::RunReport;
+
+// 邮件HTML模板, {title}和{content}会被替换
+const HTML_TEMPLATE: &str = r#"<!DOCTYPE html>
+<html>
+<head><meta charset="utf-8"><title>{title}</title></head>
+<body style="font-family: sans-serif; font-size: 14px;">
+<h3>{title}</h3>
+{content}
+</body>
+</html>"#;
+
+// 加密方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SmtpTls {
+    // 直接使用TLS连接, 一般为465端口
+    Tls,
+    // 明文连接后升级为TLS, 一般为587端口
+    StartTls,
+    // 不加密
+    None,
+}
+
+// SMTP邮件通知
+pub struct EmailNotifier {
+    transport: AsyncSmtpTransport<Tokio1Executor>,
+    from: Mailbox,
+    to: Vec<Mailbox>,
+}
+
+impl EmailNotifier {
+    pub fn new(
+        host: &str,
+        port: Option<u16>,
+        tls: SmtpTls,
+        credentials: Option<Credentials>,
+        from: Mailbox,
+        to: Vec<Mailbox>,
+    ) -> Result<Self> {
+        let mut builder = match tls {
+            SmtpTls::Tls => AsyncSmtpTransport::<Tokio1Executor>::relay(host)?,
+            SmtpTls::StartTls => AsyncSmtpTransport::<Tokio1Executor>::starttls_relay(host)?,
+            SmtpTls::None => AsyncSmtpTransport::<Tokio1Executor>::builder_dangerous(host),
+        };
+        if let Some(port) = port {
+            builder = builder.port(port);
+        }
+        if let Some(credentials) = credentials {
+            builder = builder.credentials(credentials);
+        }
+        Ok(Self {
+            transport: builder.build(),
+            from,
+            to,
+        })
+    }
+
+    // SMTP_SERVER: 服务器地址
+    // SMTP_PORT: 可选, 端口, 默认根据加密方式选择
+    // SMTP_TLS: 可选, 加密方式tls/starttls/none, 默认tls
+    // SMTP_USER, SMTP_PASSWORD: 可选, 登录账号和密码(授权码)
+    // SMTP_FROM: 可选, 发件人, 默认为SMTP_USER
+    // SMTP_TO: 收件人, 多个用逗号分隔
+    pub fn from_env() -> Option<Self> {
+        let host = env::var("SMTP_SERVER").ok()?;
+        let to = env::var("SMTP_TO").ok()?;
+        let port = env::var("SMTP_PORT").ok().and_then(|p| p.parse().ok());
+        let tls = match env::var("SMTP_TLS")
+            .unwrap_or_default()
+            .to_lowercase()
+            .as_str()
+        {
+            "starttls" => SmtpTls::StartTls,
+            "none" => SmtpTls::None,
+            _ => SmtpTls::Tls,
+        };
+        let user = env::var("SMTP_USER").ok();
+        let credentials = match (&user, env::var("SMTP_PASSWORD")) {
+            (Some(user), Ok(password)) => {
+                redact::register(&password);
+                Some(Credentials::new(user.clone(), password))
+            }
+            _ => None,
+        };
+        let from = env::var("SMTP_FROM").ok().or(user)?.parse().ok()?;
+        let to = to
+            .split(',')
+            .filter_map(|addr| addr.trim().parse().ok())
+            .collect::<Vec<Mailbox>>();
+        if to.is_empty() {
+            return None;
+        }
+        Self::new(&host, port, tls, credentials, from, to).ok()
+    }
+
+    async fn send_email(&self, title: &str, body: String, content_type: ContentType) -> Result<()> {
+        let mut builder = Message::builder().from(self.from.clone()).subject(title);
+        for to in &self.to {
+            builder = builder.to(to.clone());
+        }
+        let message = builder.header(content_type).body(body)?;
+        self.transport
+            .send(message)
+            .await
+            .map_err(|e| anyhow!("{}", e))?;
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl Notifier for EmailNotifier {
+    fn name(&self) -> &str {
+        "邮件"
+    }
+
+    async fn send(&self, title: &str, content: &str) -> Result<()> {
+        self.send_email(title, content.to_string(), ContentType::TEXT_PLAIN)
+            .await
+    }
+
+    async fn send_report(&self, report: &RunReport) -> Result<()> {
+        let title = report.title();
+        let html = HTML_TEMPLATE
+            .replace("{title}", &title)
+            .replace("{content}", &report.to_html());
+        self.send_email(&title, html, ContentType::TEXT_HTML).await
+    }
+}
@@ -0,0 +1,99 @@
+use std::env;
+
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use reqwest::Client;
+use serde_json::{json, Value};
+
+use super::Notifier;
+use crate::redact::Secret;
+use crate::report::{RunReport, Severity};
+
+// Gotify通知
+pub struct GotifyNotifier {
+    client: Client,
+    server: String,
+    token: Secret,
+    // 各严重程度对应的优先级: [正常, 警告, 错误]
+    priorities: [u8; 3],
+}
+
+impl GotifyNotifier {
+    pub fn new(server: &str, token: &str) -> Self {
+        Self {
+            client: Client::new(),
+            server: server.trim_end_matches('/').to_string(),
+            token: Secret::new(token),
+            priorities: [2, 5, 8],
+        }
+    }
+
+    // GOTIFY_URL: 服务地址
+    // GOTIFY_TOKEN: 应用token
+    // GOTIFY_PRIORITY: 可选, 正常/警告/错误对应的优先级, 用逗号分隔, 默认2,5,8
+    pub fn from_env() -> Option<Self> {
+        let server = env::var("GOTIFY_URL").ok()?;
+        let token = env::var("GOTIFY_TOKEN").ok()?;
+        let mut notifier = Self::new(&server, &token);
+        if let Ok(priority) = env::var("GOTIFY_PRIORITY") {
+            let values: Vec<u8> = priority
+                .split(',')
+                .filter_map(|p| p.trim().parse().ok())
+                .collect();
+            if let Ok(priorities) = values.try_into() {
+                notifier.priorities = priorities;
+            }
+        }
+        Some(notifier)
+    }
+
+    fn priority(&self, severity: Severity) -> u8 {
+        match severity {
+            Severity::Info => self.priorities[0],
+            Severity::Warning => self.priorities[1],
+            Severity::Error => self.priorities[2],
+        }
+    }
+
+    async fn push(&self, title: &str, message: &str, severity: Severity) -> Result<()> {
+        let url = format!("{}/message?token={}", self.server, self.token.expose());
+        let res = self
+            .client
+            .post(url)
+            .json(&json!({
+                "title": title,
+                "message": message,
+                "priority": self.priority(severity),
+                "extras": {"client::display": {"contentType": "text/markdown"}},
+            }))
+            .send()
+            .await?
+            .json::<Value>()
+            .await?;
+
+        match res["id"].is_u64() {
+            true => Ok(()),
+            false => Err(anyhow!("{}", res["errorDescription"])),
+        }
+    }
+}
+
+#[async_trait]
+impl Notifier for GotifyNotifier {
+    fn name(&self) -> &str {
+        "Gotify"
+    }
+
+    async fn send(&self, title: &str, content: &str) -> Result<()> {
+        self.push(title, content, Severity::Info).await
+    }
+
+    async fn send_report(&self, report: &RunReport) -> Result<()> {
+        self.push(&report.title(), &report.to_markdown(), report.severity())
+            .await
+    }
+
+    async fn send_alert(&self, title: &str, content: &str) -> Result<()> {
+        self.push(title, content, Severity::Error).await
+    }
+}
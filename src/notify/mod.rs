@@ -0,0 +1,388 @@
+#[cfg(feature = "notify")]
+pub mod bark;
+#[cfg(feature = "notify")]
+pub mod desktop;
+#[cfg(feature = "notify")]
+pub mod ding_talk;
+#[cfg(feature = "notify")]
+pub mod discord;
+#[cfg(feature = "notify")]
+pub mod email;
+#[cfg(feature = "notify")]
+pub mod gotify;
+#[cfg(feature = "notify")]
+pub mod ntfy;
+#[cfg(feature = "notify")]
+pub mod push_plus;
+pub mod queue;
+#[cfg(feature = "notify")]
+pub mod server_chan;
+#[cfg(feature = "notify")]
+pub mod telegram;
+#[cfg(feature = "notify")]
+pub mod template;
+#[cfg(feature = "notify")]
+pub mod webhook;
+#[cfg(feature = "notify")]
+pub mod wecom;
+
+use std::env;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use anyhow::Result;
+use async_trait::async_trait;
+use tracing::info;
+
+use crate::report::{AccountReport, RunReport};
+#[cfg(feature = "notify")]
+use bark::BarkNotifier;
+#[cfg(feature = "notify")]
+use desktop::DesktopNotifier;
+#[cfg(feature = "notify")]
+use ding_talk::DingTalkNotifier;
+#[cfg(feature = "notify")]
+use discord::DiscordNotifier;
+#[cfg(feature = "notify")]
+use email::EmailNotifier;
+#[cfg(feature = "notify")]
+use gotify::GotifyNotifier;
+#[cfg(feature = "notify")]
+use ntfy::NtfyNotifier;
+#[cfg(feature = "notify")]
+use push_plus::PushPlusNotifier;
+use queue::{Message, NotifyQueue};
+#[cfg(feature = "notify")]
+use server_chan::ServerChanNotifier;
+#[cfg(feature = "notify")]
+use telegram::TelegramNotifier;
+#[cfg(feature = "notify")]
+use template::MessageTemplate;
+#[cfg(feature = "notify")]
+use webhook::WebhookNotifier;
+#[cfg(feature = "notify")]
+use wecom::{WeComAppNotifier, WeComBotNotifier};
+
+// 通知渠道
+#[async_trait]
+pub trait Notifier: Send + Sync {
+    // 渠道名称
+    fn name(&self) -> &str;
+
+    // 发送纯文本消息
+    async fn send(&self, title: &str, content: &str) -> Result<()>;
+
+    // 发送运行结果, 默认以纯文本发送, 支持富文本的渠道可自行实现
+    async fn send_report(&self, report: &RunReport) -> Result<()> {
+        self.send(&report.title(), &report.to_text()).await
+    }
+
+    // 发送需要立即处理的提醒, 默认以纯文本发送, 支持优先级的渠道以最高优先级发送
+    async fn send_alert(&self, title: &str, content: &str) -> Result<()> {
+        self.send(title, content).await
+    }
+}
+
+// 通知方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NotifyMode {
+    // 所有账号汇总为一条消息
+    Digest,
+    // 每个账号单独发送一条消息
+    Single,
+}
+
+impl NotifyMode {
+    fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_lowercase().as_str() {
+            "digest" => Some(Self::Digest),
+            "single" => Some(Self::Single),
+            _ => None,
+        }
+    }
+}
+
+// 通知级别, 只发送符合条件的账号
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NotifyLevel {
+    // 所有账号
+    All,
+    // 有任务失败、cookie失效或运行中断的账号
+    Failures,
+    // cookie失效的账号
+    Expired,
+    // 果树已成熟的账号
+    Harvest,
+}
+
+impl NotifyLevel {
+    fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_lowercase().as_str() {
+            "all" => Some(Self::All),
+            "failures" => Some(Self::Failures),
+            "expired" => Some(Self::Expired),
+            "harvest" => Some(Self::Harvest),
+            _ => None,
+        }
+    }
+
+    // 账号是否需要通知
+    pub fn matches(&self, account: &AccountReport) -> bool {
+        match self {
+            Self::All => true,
+            Self::Failures => !account.is_ok() || !account.failed_tasks().is_empty(),
+            Self::Expired => account.cookie_expired,
+            Self::Harvest => account.harvest_ready,
+        }
+    }
+}
+
+// 渠道配置
+#[derive(Debug, Clone)]
+pub struct ChannelOptions {
+    pub mode: NotifyMode,
+    pub level: NotifyLevel,
+}
+
+impl Default for ChannelOptions {
+    fn default() -> Self {
+        Self {
+            mode: NotifyMode::Digest,
+            level: NotifyLevel::All,
+        }
+    }
+}
+
+impl ChannelOptions {
+    // {prefix}_NOTIFY_MODE: 渠道的通知方式digest/single, 未设置时使用NOTIFY_MODE
+    // {prefix}_NOTIFY_LEVEL: 渠道的通知级别all/failures/expired/harvest, 未设置时使用NOTIFY_LEVEL
+    pub fn from_env(prefix: &str) -> Self {
+        let mut options = Self::default();
+        let var = |name: &str| {
+            env::var(format!("{}_{}", prefix, name))
+                .or_else(|_| env::var(name))
+                .ok()
+        };
+        if let Some(mode) = var("NOTIFY_MODE").and_then(|m| NotifyMode::parse(&m)) {
+            options.mode = mode;
+        }
+        if let Some(level) = var("NOTIFY_LEVEL").and_then(|l| NotifyLevel::parse(&l)) {
+            options.level = level;
+        }
+        options
+    }
+}
+
+// 已配置的渠道
+struct Channel {
+    notifier: Box<dyn Notifier>,
+    options: ChannelOptions,
+}
+
+// 发送失败后立即重试的等待时间
+const SEND_RETRY_DELAYS: [Duration; 2] = [Duration::from_secs(2), Duration::from_secs(5)];
+
+// 通知分发器, 将运行结果发送到所有已配置的渠道
+#[derive(Default)]
+pub struct Dispatcher {
+    channels: Vec<Channel>,
+    // 自定义消息模板, 配置后所有渠道均发送模板渲染的内容
+    #[cfg(feature = "notify")]
+    template: Option<MessageTemplate>,
+    // 离线消息队列, 重试后仍发送失败的消息保存到磁盘, 下次运行时补发
+    queue: Option<Mutex<NotifyQueue>>,
+}
+
+impl Dispatcher {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // 根据环境变量加载已配置的渠道, 未启用notify功能时只能通过add添加渠道
+    pub fn from_env() -> Self {
+        let mut dispatcher = Self::new();
+        #[cfg(feature = "notify")]
+        match MessageTemplate::from_env() {
+            Some(Ok(template)) => dispatcher.template = Some(template),
+            Some(Err(e)) => info!("{}", t!("加载通知模板失败, 使用默认格式, {}", e)),
+            None => {}
+        }
+        match NotifyQueue::from_env() {
+            Some(Ok(queue)) => dispatcher.queue = Some(Mutex::new(queue)),
+            Some(Err(e)) => info!("{}", t!("读取通知队列失败, {}", e)),
+            None => {}
+        }
+        #[cfg(feature = "notify")]
+        {
+            dispatcher.add_from_env("TG", TelegramNotifier::from_env());
+            dispatcher.add_from_env("PUSH_KEY", ServerChanNotifier::from_env());
+            dispatcher.add_from_env("PUSH_PLUS", PushPlusNotifier::from_env());
+            dispatcher.add_from_env("BARK", BarkNotifier::from_env());
+            dispatcher.add_from_env("DD_BOT", DingTalkNotifier::from_env());
+            dispatcher.add_from_env("QYWX_KEY", WeComBotNotifier::from_env());
+            dispatcher.add_from_env("QYWX_AM", WeComAppNotifier::from_env());
+            dispatcher.add_from_env("GOTIFY", GotifyNotifier::from_env());
+            dispatcher.add_from_env("NTFY", NtfyNotifier::from_env());
+            dispatcher.add_from_env("SMTP", EmailNotifier::from_env());
+            dispatcher.add_from_env("DISCORD", DiscordNotifier::from_env());
+            match WebhookNotifier::from_env() {
+                Some(Ok(notifier)) => dispatcher.add_from_env("WEBHOOK", Some(notifier)),
+                Some(Err(e)) => info!("{}", t!("加载Webhook通知失败, {}", e)),
+                None => {}
+            }
+            dispatcher.add_from_env("DESKTOP", DesktopNotifier::from_env());
+        }
+        dispatcher
+    }
+
+    // 添加已配置的渠道, 渠道配置从{prefix}_开头的环境变量读取
+    #[cfg(feature = "notify")]
+    fn add_from_env<N: Notifier + 'static>(&mut self, prefix: &str, notifier: Option<N>) {
+        if let Some(notifier) = notifier {
+            self.add(Box::new(notifier), ChannelOptions::from_env(prefix));
+        }
+    }
+
+    // 添加通知渠道
+    pub fn add(&mut self, notifier: Box<dyn Notifier>, options: ChannelOptions) {
+        self.channels.push(Channel { notifier, options });
+    }
+
+    // 设置消息模板
+    #[cfg(feature = "notify")]
+    pub fn set_template(&mut self, template: MessageTemplate) {
+        self.template = Some(template);
+    }
+
+    // 设置离线消息队列
+    pub fn set_queue(&mut self, queue: NotifyQueue) {
+        self.queue = Some(Mutex::new(queue));
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.channels.is_empty()
+    }
+
+    // 发送运行结果, 单个渠道失败不影响其他渠道
+    pub async fn dispatch(&self, report: &RunReport) {
+        self.flush_queue().await;
+        for channel in &self.channels {
+            let level = channel.options.level;
+            let report = report.filter(|a| level.matches(a));
+            if report.accounts.is_empty() {
+                continue;
+            }
+            match channel.options.mode {
+                NotifyMode::Digest => self.send(channel, &report).await,
+                NotifyMode::Single => {
+                    for account_report in report.split() {
+                        self.send(channel, &account_report).await;
+                    }
+                }
+            }
+        }
+        self.save_queue();
+    }
+
+    // 立即发送提醒到所有渠道, 不受通知方式及通知级别限制
+    pub async fn alert(&self, title: &str, content: &str) {
+        for channel in &self.channels {
+            let message = Message::Alert {
+                title: title.to_string(),
+                content: content.to_string(),
+            };
+            self.deliver(channel, message).await;
+        }
+        self.save_queue();
+    }
+
+    // 通过单个渠道发送
+    async fn send(&self, channel: &Channel, report: &RunReport) {
+        #[cfg(feature = "notify")]
+        let rendered = self.template.as_ref().map(|t| t.render(report));
+        #[cfg(not(feature = "notify"))]
+        let rendered: Option<Result<(String, String)>> = None;
+        let message = match rendered {
+            Some(Ok((title, content))) => Message::Text { title, content },
+            Some(Err(e)) => {
+                info!("{}", t!("渲染通知模板失败, 使用默认格式, {}", e));
+                Message::Report {
+                    report: report.clone(),
+                }
+            }
+            None => Message::Report {
+                report: report.clone(),
+            },
+        };
+        self.deliver(channel, message).await;
+    }
+
+    // 发送消息, 失败时稍后重试, 仍失败则加入离线消息队列
+    async fn deliver(&self, channel: &Channel, message: Message) {
+        let notifier = &channel.notifier;
+        let mut res = message.send(notifier.as_ref()).await;
+        for delay in SEND_RETRY_DELAYS {
+            if res.is_ok() {
+                break;
+            }
+            tokio::time::sleep(delay).await;
+            res = message.send(notifier.as_ref()).await;
+        }
+        match res {
+            Ok(_) => info!("{}", t!("通知渠道《{}》发送成功!", notifier.name())),
+            Err(e) => {
+                info!("{}", t!("通知渠道《{}》发送失败, {}", notifier.name(), e));
+                if let Some(queue) = &self.queue {
+                    queue.lock().unwrap().push(notifier.name(), message);
+                    info!(
+                        "{}",
+                        t!("通知渠道《{}》的消息已加入队列, 稍后重试", notifier.name())
+                    );
+                }
+            }
+        }
+    }
+
+    // 补发离线消息队列中已到重试时间的消息
+    async fn flush_queue(&self) {
+        let queue = match &self.queue {
+            Some(queue) => queue,
+            None => return,
+        };
+        for channel in &self.channels {
+            let notifier = &channel.notifier;
+            let (due, dropped) = queue.lock().unwrap().take_due(notifier.name());
+            if dropped > 0 {
+                info!(
+                    "{}",
+                    t!(
+                        "通知渠道《{}》有{}条消息重试次数过多或已过期, 已丢弃",
+                        notifier.name(),
+                        dropped
+                    )
+                );
+            }
+            for queued in due {
+                match queued.message.send(notifier.as_ref()).await {
+                    Ok(_) => info!("{}", t!("通知渠道《{}》补发消息成功!", notifier.name())),
+                    Err(e) => {
+                        info!(
+                            "{}",
+                            t!("通知渠道《{}》补发消息失败, {}", notifier.name(), e)
+                        );
+                        queue.lock().unwrap().retry_later(queued);
+                    }
+                }
+            }
+        }
+    }
+
+    // 保存离线消息队列
+    fn save_queue(&self) {
+        if let Some(queue) = &self.queue {
+            if let Err(e) = queue.lock().unwrap().save() {
+                info!("{}", t!("保存通知队列失败, {}", e));
+            }
+        }
+    }
+}
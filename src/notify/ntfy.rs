@@ -0,0 +1,108 @@
+use std::env;
+
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use reqwest::Client;
+use serde_json::{json, Value};
+
+use super::Notifier;
+use crate::redact::Secret;
+use crate::report::{RunReport, Severity};
+
+// ntfy通知
+pub struct NtfyNotifier {
+    client: Client,
+    server: String,
+    topic: String,
+    // 访问令牌, 自建服务开启认证时使用
+    token: Option<Secret>,
+    tags: Vec<String>,
+    // 固定优先级(1-5), 为空时按严重程度选择
+    priority: Option<u8>,
+}
+
+impl NtfyNotifier {
+    pub fn new(server: &str, topic: &str) -> Self {
+        Self {
+            client: Client::new(),
+            server: server.trim_end_matches('/').to_string(),
+            topic: topic.to_string(),
+            token: None,
+            tags: Vec::new(),
+            priority: None,
+        }
+    }
+
+    // NTFY_TOPIC: 主题
+    // NTFY_URL: 可选, 服务地址, 默认https://ntfy.sh
+    // NTFY_TOKEN: 可选, 访问令牌
+    // NTFY_TAGS: 可选, 标签, 用逗号分隔
+    // NTFY_PRIORITY: 可选, 固定优先级1-5
+    pub fn from_env() -> Option<Self> {
+        let topic = env::var("NTFY_TOPIC").ok()?;
+        let server = env::var("NTFY_URL").unwrap_or_else(|_| "https://ntfy.sh".to_string());
+        let mut notifier = Self::new(&server, &topic);
+        notifier.token = env::var("NTFY_TOKEN").ok().as_deref().map(Secret::new);
+        if let Ok(tags) = env::var("NTFY_TAGS") {
+            notifier.tags = tags.split(',').map(|t| t.trim().to_string()).collect();
+        }
+        notifier.priority = env::var("NTFY_PRIORITY")
+            .ok()
+            .and_then(|p| p.parse().ok())
+            .filter(|p| (1..=5).contains(p));
+        Some(notifier)
+    }
+
+    fn priority(&self, severity: Severity) -> u8 {
+        self.priority.unwrap_or(match severity {
+            Severity::Info => 3,
+            Severity::Warning => 4,
+            Severity::Error => 5,
+        })
+    }
+
+    async fn publish(&self, title: &str, message: &str, priority: u8) -> Result<()> {
+        let mut request = self.client.post(&self.server).json(&json!({
+            "topic": self.topic,
+            "title": title,
+            "message": message,
+            "tags": self.tags,
+            "priority": priority,
+        }));
+        if let Some(token) = &self.token {
+            request = request.bearer_auth(token.expose());
+        }
+
+        let res = request.send().await?.json::<Value>().await?;
+        match res["id"].is_string() {
+            true => Ok(()),
+            false => Err(anyhow!("{}", res["error"])),
+        }
+    }
+}
+
+#[async_trait]
+impl Notifier for NtfyNotifier {
+    fn name(&self) -> &str {
+        "ntfy"
+    }
+
+    async fn send(&self, title: &str, content: &str) -> Result<()> {
+        self.publish(title, content, self.priority(Severity::Info))
+            .await
+    }
+
+    async fn send_report(&self, report: &RunReport) -> Result<()> {
+        self.publish(
+            &report.title(),
+            &report.to_text(),
+            self.priority(report.severity()),
+        )
+        .await
+    }
+
+    // 提醒忽略固定优先级, 始终使用最高优先级
+    async fn send_alert(&self, title: &str, content: &str) -> Result<()> {
+        self.publish(title, content, 5).await
+    }
+}
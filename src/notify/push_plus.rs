@@ -0,0 +1,77 @@
+use std::env;
+
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use reqwest::Client;
+use serde_json::{json, Value};
+
+use super::Notifier;
+use crate::redact::Secret;
+use crate::report::RunReport;
+
+// PushPlus通知
+pub struct PushPlusNotifier {
+    client: Client,
+    token: Secret,
+    // 群组编码, 为空时只发给自己
+    topic: Option<String>,
+}
+
+impl PushPlusNotifier {
+    pub fn new(token: &str, topic: Option<String>) -> Self {
+        Self {
+            client: Client::new(),
+            token: Secret::new(token),
+            topic,
+        }
+    }
+
+    // PUSH_PLUS_TOKEN: 用户token
+    // PUSH_PLUS_USER: 可选, 一对多推送的群组编码
+    pub fn from_env() -> Option<Self> {
+        let token = env::var("PUSH_PLUS_TOKEN").ok()?;
+        let topic = env::var("PUSH_PLUS_USER").ok().filter(|t| !t.is_empty());
+        Some(Self::new(&token, topic))
+    }
+
+    async fn push(&self, title: &str, content: &str, template: &str) -> Result<()> {
+        let mut body = json!({
+            "token": self.token.expose(),
+            "title": title,
+            "content": content,
+            "template": template,
+        });
+        if let Some(topic) = &self.topic {
+            body["topic"] = json!(topic);
+        }
+
+        let res = self
+            .client
+            .post("https://www.pushplus.plus/send")
+            .json(&body)
+            .send()
+            .await?
+            .json::<Value>()
+            .await?;
+
+        match res["code"].as_i64().unwrap_or(-1) {
+            200 => Ok(()),
+            _ => Err(anyhow!("{}", res["msg"])),
+        }
+    }
+}
+
+#[async_trait]
+impl Notifier for PushPlusNotifier {
+    fn name(&self) -> &str {
+        "PushPlus"
+    }
+
+    async fn send(&self, title: &str, content: &str) -> Result<()> {
+        self.push(title, content, "txt").await
+    }
+
+    async fn send_report(&self, report: &RunReport) -> Result<()> {
+        self.push(&report.title(), &report.to_html(), "html").await
+    }
+}
@@ -0,0 +1,167 @@
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+
+use anyhow::Result;
+use chrono::{DateTime, Duration, Local};
+use serde::{Deserialize, Serialize};
+
+use super::Notifier;
+use crate::redact::scrub;
+use crate::report::RunReport;
+
+// 重试间隔初始值(分钟), 每次失败后翻倍
+const RETRY_BASE_MINUTES: i64 = 5;
+
+// 重试间隔上限(分钟)
+const RETRY_MAX_MINUTES: i64 = 6 * 60;
+
+// 最多重试次数, 超过后丢弃
+const MAX_ATTEMPTS: u32 = 10;
+
+// 消息最长保留时间(天), 超过后丢弃
+const MAX_AGE_DAYS: i64 = 3;
+
+// 待发送的消息
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Message {
+    // 纯文本消息
+    Text { title: String, content: String },
+    // 运行结果, 由渠道决定格式
+    Report { report: RunReport },
+    // 高优先级提醒
+    Alert { title: String, content: String },
+}
+
+impl Message {
+    // 通过指定渠道发送, 发送前隐藏敏感信息
+    pub async fn send(&self, notifier: &dyn Notifier) -> Result<()> {
+        match self {
+            Self::Text { title, content } => notifier.send(&scrub(title), &scrub(content)).await,
+            Self::Report { report } => notifier.send_report(&report.redacted()).await,
+            Self::Alert { title, content } => {
+                notifier.send_alert(&scrub(title), &scrub(content)).await
+            }
+        }
+    }
+}
+
+// 发送失败、等待重试的消息
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct QueuedMessage {
+    // 渠道名称
+    pub channel: String,
+    pub message: Message,
+    // 已重试次数
+    pub attempts: u32,
+    // 首次发送失败的时间
+    pub queued_at: DateTime<Local>,
+    // 下次重试时间
+    pub next_retry: DateTime<Local>,
+}
+
+impl QueuedMessage {
+    // 是否已超过最多重试次数或最长保留时间
+    fn expired(&self) -> bool {
+        self.attempts >= MAX_ATTEMPTS
+            || Local::now() - self.queued_at > Duration::days(MAX_AGE_DAYS)
+    }
+}
+
+// 离线消息队列, 渠道暂时无法访问时将消息保存到磁盘, 之后每次运行时按退避间隔重试
+pub struct NotifyQueue {
+    path: PathBuf,
+    messages: Vec<QueuedMessage>,
+}
+
+impl NotifyQueue {
+    // 读取队列文件, 文件不存在时为空队列
+    pub fn open<P: Into<PathBuf>>(path: P) -> Result<Self> {
+        let path = path.into();
+        let messages = match fs::read_to_string(&path) {
+            Ok(text) if !text.trim().is_empty() => serde_json::from_str(&text)?,
+            Ok(_) => Vec::new(),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Vec::new(),
+            Err(e) => return Err(e.into()),
+        };
+        Ok(Self { path, messages })
+    }
+
+    // NOTIFY_QUEUE: 队列文件路径, 未设置时发送失败的消息不会保存
+    pub fn from_env() -> Option<Result<Self>> {
+        let path = env::var("NOTIFY_QUEUE").ok().filter(|p| !p.is_empty())?;
+        Some(Self::open(path))
+    }
+
+    pub fn len(&self) -> usize {
+        self.messages.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.messages.is_empty()
+    }
+
+    // 加入发送失败的消息
+    pub fn push(&mut self, channel: &str, message: Message) {
+        let now = Local::now();
+        self.messages.push(QueuedMessage {
+            channel: channel.to_string(),
+            message,
+            attempts: 0,
+            queued_at: now,
+            next_retry: now + backoff(0),
+        });
+    }
+
+    // 取出指定渠道已到重试时间的消息, 同时丢弃过期消息, 返回(待重试消息, 丢弃数量)
+    pub fn take_due(&mut self, channel: &str) -> (Vec<QueuedMessage>, usize) {
+        let now = Local::now();
+        let (mine, rest): (Vec<_>, Vec<_>) =
+            self.messages.drain(..).partition(|m| m.channel == channel);
+        self.messages = rest;
+
+        let mut due = Vec::new();
+        let mut dropped = 0;
+        for message in mine {
+            if message.expired() {
+                dropped += 1;
+            } else if message.next_retry <= now {
+                due.push(message);
+            } else {
+                self.messages.push(message);
+            }
+        }
+        (due, dropped)
+    }
+
+    // 重试失败, 按退避间隔放回队列
+    pub fn retry_later(&mut self, mut message: QueuedMessage) {
+        message.attempts += 1;
+        message.next_retry = Local::now() + backoff(message.attempts);
+        self.messages.push(message);
+    }
+
+    // 写入队列文件, 队列为空时删除文件
+    pub fn save(&self) -> Result<()> {
+        if self.messages.is_empty() {
+            return match fs::remove_file(&self.path) {
+                Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e.into()),
+                _ => Ok(()),
+            };
+        }
+        if let Some(dir) = self.path.parent() {
+            if !dir.as_os_str().is_empty() {
+                fs::create_dir_all(dir)?;
+            }
+        }
+        fs::write(&self.path, serde_json::to_string(&self.messages)?)?;
+        Ok(())
+    }
+}
+
+// 第attempts次重试前的等待时间
+fn backoff(attempts: u32) -> Duration {
+    let minutes = RETRY_BASE_MINUTES.saturating_mul(1 << attempts.min(16));
+    Duration::minutes(minutes.min(RETRY_MAX_MINUTES))
+}
@@ -0,0 +1,61 @@
+use std::env;
+
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use reqwest::Client;
+use serde_json::Value;
+
+use super::Notifier;
+use crate::redact::Secret;
+use crate::report::RunReport;
+
+// Server酱Turbo版通知
+pub struct ServerChanNotifier {
+    client: Client,
+    send_key: Secret,
+}
+
+impl ServerChanNotifier {
+    pub fn new(send_key: &str) -> Self {
+        Self {
+            client: Client::new(),
+            send_key: Secret::new(send_key),
+        }
+    }
+
+    // PUSH_KEY: Server酱Turbo版SendKey
+    pub fn from_env() -> Option<Self> {
+        let send_key = env::var("PUSH_KEY").ok()?;
+        Some(Self::new(&send_key))
+    }
+}
+
+#[async_trait]
+impl Notifier for ServerChanNotifier {
+    fn name(&self) -> &str {
+        "Server酱"
+    }
+
+    async fn send(&self, title: &str, content: &str) -> Result<()> {
+        let url = format!("https://sctapi.ftqq.com/{}.send", self.send_key.expose());
+        let res = self
+            .client
+            .post(url)
+            .form(&[("title", title), ("desp", content)])
+            .send()
+            .await?
+            .json::<Value>()
+            .await?;
+
+        match res["code"].as_i64().unwrap_or(-1) {
+            0 => Ok(()),
+            _ => Err(anyhow!("{}", res["message"])),
+        }
+    }
+
+    async fn send_report(&self, report: &RunReport) -> Result<()> {
+        // desp支持markdown, 每个账号单独一段
+        let content = report.to_text().replace('\n', "\n\n");
+        self.send(&report.title(), &content).await
+    }
+}
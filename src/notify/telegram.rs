@@ -0,0 +1,136 @@
+use std::env;
+
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use reqwest::Client;
+use serde_json::{json, Value};
+
+use super::Notifier;
+use crate::redact::Secret;
+use crate::report::RunReport;
+
+// MarkdownV2中需要转义的字符
+const MARKDOWN_V2_SPECIAL_CHARS: &str = r"_*[]()~`>#+-=|{}.!\";
+
+// 转义MarkdownV2特殊字符
+fn escape_markdown(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        if MARKDOWN_V2_SPECIAL_CHARS.contains(c) {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+// Telegram机器人通知
+pub struct TelegramNotifier {
+    client: Client,
+    bot_token: Secret,
+    chat_id: String,
+    api_host: String,
+}
+
+impl TelegramNotifier {
+    pub fn new(bot_token: &str, chat_id: &str) -> Self {
+        Self {
+            client: Client::new(),
+            bot_token: Secret::new(bot_token),
+            chat_id: chat_id.to_string(),
+            api_host: "https://api.telegram.org".to_string(),
+        }
+    }
+
+    // TG_BOT_TOKEN: 机器人token
+    // TG_USER_ID: 接收消息的chat id
+    // TG_API_HOST: 可选, 自定义api地址
+    pub fn from_env() -> Option<Self> {
+        let bot_token = env::var("TG_BOT_TOKEN").ok()?;
+        let chat_id = env::var("TG_USER_ID").ok()?;
+        let mut notifier = Self::new(&bot_token, &chat_id);
+        if let Ok(api_host) = env::var("TG_API_HOST") {
+            notifier.api_host = api_host.trim_end_matches('/').to_string();
+        }
+        Some(notifier)
+    }
+
+    // 发送MarkdownV2格式的消息
+    async fn send_markdown(&self, text: &str) -> Result<()> {
+        let url = format!(
+            "{}/bot{}/sendMessage",
+            self.api_host,
+            self.bot_token.expose()
+        );
+        let res = self
+            .client
+            .post(url)
+            .json(&json!({
+                "chat_id": self.chat_id,
+                "text": text,
+                "parse_mode": "MarkdownV2",
+            }))
+            .send()
+            .await?
+            .json::<Value>()
+            .await?;
+
+        match res["ok"].as_bool().unwrap_or(false) {
+            true => Ok(()),
+            false => Err(anyhow!("{}", res["description"])),
+        }
+    }
+}
+
+#[async_trait]
+impl Notifier for TelegramNotifier {
+    fn name(&self) -> &str {
+        "Telegram"
+    }
+
+    async fn send(&self, title: &str, content: &str) -> Result<()> {
+        let text = format!(
+            "*{}*\n\n{}",
+            escape_markdown(title),
+            escape_markdown(content)
+        );
+        self.send_markdown(&text).await
+    }
+
+    async fn send_report(&self, report: &RunReport) -> Result<()> {
+        let mut lines = vec![format!("*{}*\n", escape_markdown(&report.title()))];
+        for account in &report.accounts {
+            let mut line = t!(
+                "*{}*: 获得水滴 `{}g`, 剩余水滴 `{}g`",
+                escape_markdown(&account.name),
+                account.water_gained(),
+                account.water_after
+            );
+            if account.cookie_expired {
+                line.push_str(&t!(", _cookie已失效_"));
+            }
+            if let Some(progress) = report.progress_text(account) {
+                line.push_str(&format!(", {}", escape_markdown(&progress)));
+            }
+            if let Some(e) = &account.error {
+                line.push_str(&t!(", 错误: {}", escape_markdown(e)));
+            }
+            lines.push(line);
+        }
+
+        let expired: Vec<String> = report
+            .expired_accounts()
+            .iter()
+            .map(|a| escape_markdown(&a.name))
+            .collect();
+        lines.push(t!(
+            "\n共{}个账号, 获得水滴 `{}g`",
+            report.accounts.len(),
+            report.water_gained()
+        ));
+        if !expired.is_empty() {
+            lines.push(t!("cookie已失效: {}", expired.join(", ")));
+        }
+        self.send_markdown(&lines.join("\n")).await
+    }
+}
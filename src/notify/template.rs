@@ -0,0 +1,78 @@
+use std::{env, fs};
+
+use anyhow::Result;
+use serde_json::{json, Value};
+use tera::{Context, Tera};
+
+use crate::report::RunReport;
+
+// 默认标题模板
+const DEFAULT_TITLE: &str = "{{ title }}";
+
+// 通知消息模板, 使用tera语法渲染运行结果
+pub struct MessageTemplate {
+    tera: Tera,
+}
+
+impl MessageTemplate {
+    pub fn new(title: &str, content: &str) -> Result<Self> {
+        let mut tera = Tera::default();
+        tera.add_raw_template("title", title)?;
+        tera.add_raw_template("content", content)?;
+        Ok(Self { tera })
+    }
+
+    // NOTIFY_TEMPLATE: 消息内容模板
+    // NOTIFY_TEMPLATE_FILE: 消息内容模板文件路径, 优先于NOTIFY_TEMPLATE
+    // NOTIFY_TITLE_TEMPLATE: 可选, 消息标题模板
+    pub fn from_env() -> Option<Result<Self>> {
+        let content = match env::var("NOTIFY_TEMPLATE_FILE") {
+            Ok(path) => match fs::read_to_string(path) {
+                Ok(content) => content,
+                Err(e) => return Some(Err(e.into())),
+            },
+            Err(_) => env::var("NOTIFY_TEMPLATE").ok()?,
+        };
+        let title = env::var("NOTIFY_TITLE_TEMPLATE").unwrap_or_else(|_| DEFAULT_TITLE.to_string());
+        Some(Self::new(&title, &content))
+    }
+
+    // 渲染标题和内容
+    pub fn render(&self, report: &RunReport) -> Result<(String, String)> {
+        let context = Context::from_value(context_value(report))?;
+        let title = self.tera.render("title", &context)?;
+        let content = self.tera.render("content", &context)?;
+        Ok((title, content))
+    }
+}
+
+// 模板可用的数据, 在RunReport字段基础上补充统计值
+fn context_value(report: &RunReport) -> Value {
+    let accounts: Vec<Value> = report
+        .accounts
+        .iter()
+        .map(|a| {
+            let mut value = json!(a);
+            value["water_gained"] = json!(a.water_gained());
+            value["status"] = json!(a.status());
+            value["severity"] = json!(a.severity());
+            value["failed_tasks"] = json!(a.failed_tasks());
+            value["tree_progress"] = json!((a.tree_progress() * 10.0).round() / 10.0);
+            value["tree_remaining"] = json!(a.tree_remaining());
+            value["harvest_eta_days"] = json!(report.harvest_eta_days(a).map(|d| d.ceil()));
+            value["share_link"] = json!(a.share_link());
+            value
+        })
+        .collect();
+    json!({
+        "title": report.title(),
+        "started_at": report.started_at.format("%Y-%m-%d %H:%M:%S").to_string(),
+        "finished_at": report.finished_at.format("%Y-%m-%d %H:%M:%S").to_string(),
+        "severity": report.severity(),
+        "water_gained": report.water_gained(),
+        "expired_count": report.expired_accounts().len(),
+        "totals": report.totals(),
+        "leaderboard": report.leaderboard(),
+        "accounts": accounts,
+    })
+}
@@ -0,0 +1,192 @@
+use std::env;
+
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use reqwest::{
+    header::{HeaderMap, HeaderName, HeaderValue},
+    Client,
+};
+use serde_json::{json, Value};
+
+use super::Notifier;
+use crate::redact::Secret;
+use crate::report::RunReport;
+
+// 默认请求体模板
+const DEFAULT_TEMPLATE: &str = r#"{"title":"{{title}}","content":"{{content}}"}"#;
+
+// 通用webhook通知, 按模板渲染JSON请求体
+pub struct WebhookNotifier {
+    client: Client,
+    url: Secret,
+    headers: HeaderMap,
+    // 请求体模板
+    template: Value,
+}
+
+impl WebhookNotifier {
+    pub fn new(url: &str, template: Value) -> Self {
+        Self {
+            client: Client::new(),
+            url: Secret::new(url),
+            headers: HeaderMap::new(),
+            template,
+        }
+    }
+
+    // WEBHOOK_URL: 请求地址
+    // WEBHOOK_BODY: 可选, JSON请求体模板, 字符串中的{{变量}}会被替换
+    // WEBHOOK_HEADERS: 可选, 额外请求头, 格式为`名称: 值`, 多个用换行分隔
+    pub fn from_env() -> Option<Result<Self>> {
+        let url = env::var("WEBHOOK_URL").ok()?;
+        let template = env::var("WEBHOOK_BODY").unwrap_or_else(|_| DEFAULT_TEMPLATE.to_string());
+        let template = match serde_json::from_str(&template) {
+            Ok(template) => template,
+            Err(e) => return Some(Err(anyhow!("{}", t!("WEBHOOK_BODY不是有效的JSON, {}", e)))),
+        };
+        let mut notifier = Self::new(&url, template);
+        if let Ok(headers) = env::var("WEBHOOK_HEADERS") {
+            for line in headers.lines() {
+                if let Some((name, value)) = line.split_once(':') {
+                    if let (Ok(name), Ok(value)) = (
+                        HeaderName::from_bytes(name.trim().as_bytes()),
+                        HeaderValue::from_str(value.trim()),
+                    ) {
+                        notifier.headers.append(name, value);
+                    }
+                }
+            }
+        }
+        Some(Ok(notifier))
+    }
+
+    async fn post(&self, variables: &Value) -> Result<()> {
+        let body = render(&self.template, variables);
+        let res = self
+            .client
+            .post(self.url.expose())
+            .headers(self.headers.clone())
+            .json(&body)
+            .send()
+            .await?;
+        match res.status().is_success() {
+            true => Ok(()),
+            false => Err(anyhow!("{}", res.status())),
+        }
+    }
+}
+
+// 渲染模板: 字符串恰好为{{变量}}时替换为变量的JSON值, 否则替换为变量的文本
+fn render(template: &Value, variables: &Value) -> Value {
+    match template {
+        Value::String(s) => {
+            let trimmed = s.trim();
+            if let Some(name) = trimmed
+                .strip_prefix("{{")
+                .and_then(|t| t.strip_suffix("}}"))
+            {
+                if let Some(value) = variables.get(name.trim()) {
+                    return value.clone();
+                }
+            }
+            let mut rendered = s.clone();
+            if let Some(map) = variables.as_object() {
+                for (name, value) in map {
+                    let text = match value {
+                        Value::String(v) => v.clone(),
+                        v => v.to_string(),
+                    };
+                    rendered = rendered.replace(&format!("{{{{{}}}}}", name), &text);
+                }
+            }
+            Value::String(rendered)
+        }
+        Value::Array(items) => Value::Array(items.iter().map(|v| render(v, variables)).collect()),
+        Value::Object(map) => Value::Object(
+            map.iter()
+                .map(|(k, v)| (k.clone(), render(v, variables)))
+                .collect(),
+        ),
+        v => v.clone(),
+    }
+}
+
+#[async_trait]
+impl Notifier for WebhookNotifier {
+    fn name(&self) -> &str {
+        "Webhook"
+    }
+
+    async fn send(&self, title: &str, content: &str) -> Result<()> {
+        self.post(&json!({"title": title, "content": content}))
+            .await
+    }
+
+    async fn send_report(&self, report: &RunReport) -> Result<()> {
+        let variables = json!({
+            "title": report.title(),
+            "content": report.to_text(),
+            "markdown": report.to_markdown(),
+            "html": report.to_html(),
+            "severity": report.severity(),
+            "water_gained": report.water_gained(),
+            "account_count": report.accounts.len(),
+            "expired_count": report.expired_accounts().len(),
+            "started_at": report.started_at.to_rfc3339(),
+            "finished_at": report.finished_at.to_rfc3339(),
+            "accounts": report.accounts,
+            "report": report,
+        });
+        self.post(&variables).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn renders_template() {
+        let template: Value = serde_json::from_str(
+            r#"{
+                "text": "*{{title}}*\n{{content}}",
+                "count": "{{account_count}}",
+                "accounts": "{{accounts}}",
+                "summary": "共{{account_count}}个账号, {{missing}}",
+                "tags": ["jd_farm", "{{severity}}"],
+                "retry": 3,
+                "silent": false
+            }"#,
+        )
+        .unwrap();
+        let variables = json!({
+            "title": "东东农场",
+            "content": "账号1: 成功",
+            "account_count": 2,
+            "severity": "info",
+            "accounts": [{"name": "账号1"}, {"name": "账号2"}],
+        });
+        assert_eq!(
+            render(&template, &variables),
+            json!({
+                "text": "*东东农场*\n账号1: 成功",
+                "count": 2,
+                "accounts": [{"name": "账号1"}, {"name": "账号2"}],
+                "summary": "共2个账号, {{missing}}",
+                "tags": ["jd_farm", "info"],
+                "retry": 3,
+                "silent": false
+            })
+        );
+    }
+
+    #[test]
+    fn renders_default_template() {
+        let template = serde_json::from_str(DEFAULT_TEMPLATE).unwrap();
+        let variables = json!({"title": "标题\"", "content": "第一行\n第二行"});
+        assert_eq!(
+            render(&template, &variables),
+            json!({"title": "标题\"", "content": "第一行\n第二行"})
+        );
+    }
+}
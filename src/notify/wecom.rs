@@ -0,0 +1,178 @@
+use std::env;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use reqwest::Client;
+use serde_json::{json, Value};
+
+use super::Notifier;
+use crate::redact::{self, Secret};
+use crate::report::RunReport;
+
+// 企业微信群机器人通知
+pub struct WeComBotNotifier {
+    client: Client,
+    key: Secret,
+}
+
+impl WeComBotNotifier {
+    pub fn new(key: &str) -> Self {
+        Self {
+            client: Client::new(),
+            key: Secret::new(key),
+        }
+    }
+
+    // QYWX_KEY: 群机器人webhook中的key
+    pub fn from_env() -> Option<Self> {
+        let key = env::var("QYWX_KEY").ok()?;
+        Some(Self::new(&key))
+    }
+
+    async fn post(&self, body: Value) -> Result<()> {
+        let url = format!(
+            "https://qyapi.weixin.qq.com/cgi-bin/webhook/send?key={}",
+            self.key.expose()
+        );
+        let res = self
+            .client
+            .post(url)
+            .json(&body)
+            .send()
+            .await?
+            .json::<Value>()
+            .await?;
+
+        match res["errcode"].as_i64().unwrap_or(-1) {
+            0 => Ok(()),
+            _ => Err(anyhow!("{}", res["errmsg"])),
+        }
+    }
+}
+
+#[async_trait]
+impl Notifier for WeComBotNotifier {
+    fn name(&self) -> &str {
+        "企业微信机器人"
+    }
+
+    async fn send(&self, title: &str, content: &str) -> Result<()> {
+        self.post(json!({
+            "msgtype": "text",
+            "text": {"content": format!("{}\n\n{}", title, content)},
+        }))
+        .await
+    }
+
+    async fn send_report(&self, report: &RunReport) -> Result<()> {
+        self.post(json!({
+            "msgtype": "markdown",
+            "markdown": {"content": report.to_markdown()},
+        }))
+        .await
+    }
+}
+
+// 企业微信应用消息通知
+pub struct WeComAppNotifier {
+    client: Client,
+    corp_id: String,
+    corp_secret: Secret,
+    to_user: String,
+    agent_id: String,
+    // 缓存的access_token及其过期时间
+    access_token: Mutex<Option<(String, Instant)>>,
+}
+
+impl WeComAppNotifier {
+    pub fn new(corp_id: &str, corp_secret: &str, to_user: &str, agent_id: &str) -> Self {
+        Self {
+            client: Client::new(),
+            corp_id: corp_id.to_string(),
+            corp_secret: Secret::new(corp_secret),
+            to_user: to_user.to_string(),
+            agent_id: agent_id.to_string(),
+            access_token: Mutex::new(None),
+        }
+    }
+
+    // QYWX_AM: 企业id,应用secret,接收成员(@all为全部),应用agentid, 用逗号分隔
+    pub fn from_env() -> Option<Self> {
+        let am = env::var("QYWX_AM").ok()?;
+        let parts: Vec<&str> = am.split(',').map(|s| s.trim()).collect();
+        if parts.len() < 4 {
+            return None;
+        }
+        Some(Self::new(parts[0], parts[1], parts[2], parts[3]))
+    }
+
+    // 获取access_token, 过期前复用缓存
+    async fn get_access_token(&self) -> Result<String> {
+        if let Some((token, expires_at)) = self.access_token.lock().unwrap().as_ref() {
+            if Instant::now() < *expires_at {
+                return Ok(token.clone());
+            }
+        }
+
+        let url = format!(
+            "https://qyapi.weixin.qq.com/cgi-bin/gettoken?corpid={}&corpsecret={}",
+            self.corp_id,
+            self.corp_secret.expose()
+        );
+        let res = self.client.get(url).send().await?.json::<Value>().await?;
+        let token = res["access_token"]
+            .as_str()
+            .ok_or_else(|| anyhow!("{}", res["errmsg"]))?
+            .to_string();
+        redact::register(&token);
+        // 提前一分钟过期, 避免临界时间使用失效的token
+        let expires_in = res["expires_in"]
+            .as_u64()
+            .unwrap_or(7200)
+            .saturating_sub(60);
+        *self.access_token.lock().unwrap() = Some((
+            token.clone(),
+            Instant::now() + Duration::from_secs(expires_in),
+        ));
+        Ok(token)
+    }
+
+    async fn post(&self, mut body: Value) -> Result<()> {
+        body["touser"] = json!(self.to_user);
+        body["agentid"] = json!(self.agent_id);
+        let url = format!(
+            "https://qyapi.weixin.qq.com/cgi-bin/message/send?access_token={}",
+            self.get_access_token().await?
+        );
+        let res = self
+            .client
+            .post(url)
+            .json(&body)
+            .send()
+            .await?
+            .json::<Value>()
+            .await?;
+
+        match res["errcode"].as_i64().unwrap_or(-1) {
+            0 => Ok(()),
+            _ => Err(anyhow!("{}", res["errmsg"])),
+        }
+    }
+}
+
+#[async_trait]
+impl Notifier for WeComAppNotifier {
+    fn name(&self) -> &str {
+        "企业微信应用"
+    }
+
+    async fn send(&self, title: &str, content: &str) -> Result<()> {
+        self.post(json!({
+            "msgtype": "text",
+            "text": {"content": format!("{}\n\n{}", title, content)},
+        }))
+        .await
+    }
+}
@@ -0,0 +1,188 @@
+use std::env;
+use std::time::Duration;
+
+use crate::config;
+
+// 连续操作之间的等待策略
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum DelayPolicy {
+    // 使用各任务默认的等待时间
+    #[default]
+    Default,
+    // 按比例缩放默认等待时间, 如2.0表示等待时间加倍
+    Scale(f64),
+    // 所有操作之间固定等待
+    Fixed(Duration),
+    // 默认等待时间限制在min~max之间
+    Range(Duration, Duration),
+}
+
+impl DelayPolicy {
+    // 根据任务默认的等待时间计算实际等待时间
+    pub fn apply(&self, default: Duration) -> Duration {
+        match self {
+            Self::Default => default,
+            Self::Scale(scale) => default.mul_f64(scale.max(0.0)),
+            Self::Fixed(delay) => *delay,
+            Self::Range(min, max) => default.max(*min).min(*max),
+        }
+    }
+
+    // 等待时间按比例放大后的策略, 如重试失败的账号时放慢操作
+    pub fn scaled(&self, scale: f64) -> Self {
+        let scale = scale.max(0.0);
+        match self {
+            Self::Default => Self::Scale(scale),
+            Self::Scale(s) => Self::Scale(s * scale),
+            Self::Fixed(delay) => Self::Fixed(delay.mul_f64(scale)),
+            Self::Range(min, max) => Self::Range(min.mul_f64(scale), max.mul_f64(scale)),
+        }
+    }
+}
+
+// 道具卡使用策略
+#[derive(Debug, Clone, PartialEq)]
+pub struct CardPolicy {
+    // 剩余水滴达到该值(g)时使用水滴翻倍卡, 为空时不使用
+    pub double_card_min_water: Option<u32>,
+    // 签到领水后是否使用加签卡
+    pub sign_card: bool,
+}
+
+impl Default for CardPolicy {
+    fn default() -> Self {
+        Self {
+            double_card_min_water: Some(100),
+            sign_card: true,
+        }
+    }
+}
+
+// 水滴雨提交的接到红包数范围, 各账号每次在范围内随机选取
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WaterRainRange {
+    pub min: u32,
+    pub max: u32,
+}
+
+impl Default for WaterRainRange {
+    fn default() -> Self {
+        Self { min: 50, max: 54 }
+    }
+}
+
+impl WaterRainRange {
+    // 解析`40-60`或单个数字, 最小值大于最大值时交换
+    pub fn parse(s: &str) -> Option<Self> {
+        let (min, max) = match s.split_once(['-', '~']) {
+            Some((min, max)) => (min.trim().parse().ok()?, max.trim().parse().ok()?),
+            None => {
+                let n = s.trim().parse().ok()?;
+                (n, n)
+            }
+        };
+        Some(Self {
+            min: u32::min(min, max),
+            max: u32::max(min, max),
+        })
+    }
+
+    // 根据账号名称及时间选取接到的红包数, 不同账号、不同轮次的结果不同
+    pub fn pick(&self, account: &str, time: u64) -> u32 {
+        let mut state = account.bytes().fold(0xcbf2_9ce4_8422_2325u64, |h, b| {
+            (h ^ b as u64).wrapping_mul(0x100_0000_01b3)
+        }) ^ time;
+        // xorshift64, 避免相近的时间得到相近的结果
+        for _ in 0..3 {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+        }
+        let span = (self.max - self.min) as u64 + 1;
+        self.min + (state % span) as u32
+    }
+}
+
+// 单个账号的运行选项
+#[derive(Debug, Clone, Default)]
+pub struct RunOptions {
+    // 试运行, 只查询农场及任务信息, 不执行任何任务
+    pub dry_run: bool,
+    // 只执行这些任务, 为空时执行所有任务
+    pub only_tasks: Vec<String>,
+    // 跳过这些任务
+    pub skip_tasks: Vec<String>,
+    // 单个账号运行的最长时间, 超时后中断该账号
+    pub account_timeout: Option<Duration>,
+    // 单个请求的最长时间
+    pub request_timeout: Option<Duration>,
+    // 连续操作之间的等待策略
+    pub delay: DelayPolicy,
+    // 道具卡使用策略
+    pub cards: CardPolicy,
+    // 水滴雨提交的接到红包数范围
+    pub water_rain: WaterRainRange,
+}
+
+impl RunOptions {
+    // DRY_RUN: 试运行, true/1
+    // ONLY_TASKS/SKIP_TASKS: 只执行/跳过的任务名称, 以逗号分隔, 如`点鸭子,定时领水`
+    // ACCOUNT_TIMEOUT/REQUEST_TIMEOUT: 单个账号/单个请求的超时时间(秒)
+    // TASK_DELAY: 连续操作之间固定等待的时间(秒), 未设置时使用各任务默认的等待时间
+    // DOUBLE_CARD_MIN_WATER: 剩余水滴达到该值(g)时使用水滴翻倍卡, 默认100, 设置为off不使用
+    // USE_SIGN_CARD: 签到领水后是否使用加签卡, 默认true
+    // WATER_RAIN_SCORE: 水滴雨提交的接到红包数范围, 如`40-60`, 默认50-54
+    // 之后应用配置文件及配置档案中的设置, 见config模块
+    pub fn from_env() -> Self {
+        let mut options = Self::default();
+        let flag = |name: &str| {
+            env::var(name)
+                .ok()
+                .map(|v| !matches!(v.trim(), "false" | "0" | ""))
+        };
+        let seconds = |name: &str| {
+            env::var(name)
+                .ok()
+                .and_then(|v| v.trim().parse::<f64>().ok())
+                .filter(|v| *v >= 0.0)
+                .map(Duration::from_secs_f64)
+        };
+        let list = |name: &str| -> Vec<String> {
+            env::var(name)
+                .unwrap_or_default()
+                .split(',')
+                .map(|t| t.trim().to_string())
+                .filter(|t| !t.is_empty())
+                .collect()
+        };
+
+        options.dry_run = flag("DRY_RUN").unwrap_or(false);
+        options.only_tasks = list("ONLY_TASKS");
+        options.skip_tasks = list("SKIP_TASKS");
+        options.account_timeout = seconds("ACCOUNT_TIMEOUT");
+        options.request_timeout = seconds("REQUEST_TIMEOUT");
+        if let Some(delay) = seconds("TASK_DELAY") {
+            options.delay = DelayPolicy::Fixed(delay);
+        }
+        if let Ok(water) = env::var("DOUBLE_CARD_MIN_WATER") {
+            options.cards.double_card_min_water = water.trim().parse().ok();
+        }
+        if let Some(sign_card) = flag("USE_SIGN_CARD") {
+            options.cards.sign_card = sign_card;
+        }
+        if let Some(range) = env::var("WATER_RAIN_SCORE")
+            .ok()
+            .and_then(|v| WaterRainRange::parse(&v))
+        {
+            options.water_rain = range;
+        }
+        config::profile().apply(&mut options);
+        options
+    }
+
+    // 是否需要执行该任务, 不考虑试运行
+    pub fn wants(&self, task: &str) -> bool {
+        (self.only_tasks.is_empty() || self.only_tasks.iter().any(|t| t == task))
+            && !self.skip_tasks.iter().any(|t| t == task)
+    }
+}
@@ -0,0 +1,44 @@
+mod models;
+mod tasks;
+
+use std::env;
+use std::sync::Arc;
+
+use anyhow::Result;
+use async_trait::async_trait;
+
+use crate::plugin::{FarmTask, FarmTasks};
+use crate::JClient;
+
+pub use self::models::{
+    EnergyCollect, FeedReachTask, PetGoods, PetInfo, PetReward, PetTaskInfo, PetTaskState,
+};
+
+// 任务名称, 可通过ONLY_TASKS/SKIP_TASKS过滤
+pub const PET_TASK: &str = "东东萌宠";
+
+// 东东萌宠, 与农场共用账号、签名及网络限制, 在农场任务之后执行
+pub struct PetTask;
+
+#[async_trait]
+impl FarmTask for PetTask {
+    fn name(&self) -> &str {
+        PET_TASK
+    }
+
+    async fn run(&self, client: &JClient) -> Result<()> {
+        client.run_pet().await
+    }
+}
+
+// PET: 是否同时执行东东萌宠任务, 默认false
+pub fn from_env() -> FarmTasks {
+    let enabled = matches!(
+        env::var("PET").as_deref().map(str::trim),
+        Ok("true") | Ok("1")
+    );
+    match enabled {
+        true => vec![Arc::new(PetTask)],
+        false => Vec::new(),
+    }
+}
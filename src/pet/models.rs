@@ -0,0 +1,89 @@
+use serde::{Deserialize, Serialize};
+
+// 萌宠信息(initPetTown)
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(default, rename_all = "camelCase")]
+pub struct PetInfo {
+    // 0: 未开通萌宠
+    pub user_status: u8,
+
+    // 5: 已集齐勋章可兑换奖品, 6: 未选择奖品
+    pub pet_status: u8,
+
+    // 奖品信息, 未选择奖品时为空
+    pub goods_info: Option<PetGoods>,
+
+    // 已获得的勋章数
+    pub medal_num: u32,
+
+    // 当前勋章进度
+    pub medal_percent: f64,
+
+    // 剩余狗粮(g)
+    pub food_amount: u32,
+
+    // 助力码
+    pub share_code: String,
+}
+
+// 萌宠奖品
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(default, rename_all = "camelCase")]
+pub struct PetGoods {
+    // 奖品名称
+    pub goods_name: String,
+    // 兑换需要的勋章数
+    pub exchange_medal_num: u32,
+}
+
+// 是否已完成
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(default, rename_all = "camelCase")]
+pub struct PetTaskState {
+    pub finished: bool,
+}
+
+// 每日喂食任务
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(default, rename_all = "camelCase")]
+pub struct FeedReachTask {
+    // 是否已完成
+    pub finished: bool,
+    // 需要喂食的狗粮(g)
+    pub feed_reach_amount: u32,
+    // 已喂食的狗粮(g)
+    pub had_feed_amount: u32,
+}
+
+// 萌宠任务信息(taskInit), 缺少某项任务的数据时只跳过该任务
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(default, rename_all = "camelCase")]
+pub struct PetTaskInfo {
+    // 每日签到
+    pub sign_init: Option<PetTaskState>,
+    // 三餐定时领狗粮
+    pub three_meal_init: Option<PetTaskState>,
+    // 每日首次喂食
+    pub first_feed_init: Option<PetTaskState>,
+    // 每日累计喂食
+    pub feed_reach_init: Option<FeedReachTask>,
+}
+
+// 领取奖励(getSignReward、getThreeMealReward、getFirstFeedReward、getSportReward)
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(default, rename_all = "camelCase")]
+pub struct PetReward {
+    // 获得的狗粮(g)
+    #[serde(alias = "signReward", alias = "threeMealReward", alias = "foodReward")]
+    pub reward: u32,
+}
+
+// 收集好感度(energyCollect)
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(default, rename_all = "camelCase")]
+pub struct EnergyCollect {
+    // 已获得的勋章数
+    pub medal_num: u32,
+    // 当前勋章进度
+    pub medal_percent: f64,
+}
@@ -0,0 +1,203 @@
+use std::time::Duration;
+
+use anyhow::{anyhow, Result};
+use serde::{de::DeserializeOwned, Serialize};
+use serde_json::{json, Value};
+use tracing::info;
+
+use super::models::{EnergyCollect, PetInfo, PetReward, PetTaskInfo};
+use super::PET_TASK;
+use crate::models::Response;
+use crate::{i18n, shutdown, JClient};
+
+// 每次喂食的狗粮(g)
+const FEED_AMOUNT: u32 = 10;
+
+// 每日遛狗次数上限
+const SPORT_TIMES: u8 = 10;
+
+impl JClient {
+    // 请求萌宠接口, code及resultCode都为0时表示操作成功, 业务数据在result中
+    async fn pet_call<T: DeserializeOwned + Serialize + Default>(
+        &self,
+        function_id: &str,
+        body: Value,
+    ) -> Result<Response<T>> {
+        self.activity_call(function_id, body, Some("resultCode"), "result")
+            .await
+    }
+
+    // 执行东东萌宠任务, 领取的狗粮不计入水滴收益
+    pub async fn run_pet(&self) -> Result<()> {
+        let res: Response<PetInfo> = self
+            .pet_call("initPetTown", json!({"version": 2, "channel": "app"}))
+            .await?;
+        if let Some(e) = res.error() {
+            return Err(anyhow!(e));
+        }
+        let mut pet = res.data;
+
+        if pet.user_status == 0 {
+            info!("{}", t!("{}, 未开通东东萌宠!", self.account.name()));
+            return Ok(());
+        }
+        match pet.pet_status {
+            5 => {
+                let name = pet
+                    .goods_info
+                    .map(|goods| goods.goods_name)
+                    .unwrap_or_default();
+                self.record_prize(PET_TASK, &name, None, None);
+                info!(
+                    "{}",
+                    t!("{}, 萌宠已集齐勋章, 请及时兑换奖品!", self.account.name())
+                );
+                return Ok(());
+            }
+            6 => {
+                info!("{}", t!("{}, 萌宠未选择奖品!", self.account.name()));
+                return Ok(());
+            }
+            _ => {}
+        }
+
+        let res: Response<PetTaskInfo> = self.pet_call("taskInit", json!({"version": 1})).await?;
+        if let Some(e) = res.error() {
+            return Err(anyhow!(e));
+        }
+        let tasks = res.data;
+
+        if tasks.sign_init.is_some_and(|task| !task.finished) {
+            pet.food_amount += self.pet_reward("东东萌宠->签到", "getSignReward").await?;
+        }
+
+        if tasks.three_meal_init.is_some_and(|task| !task.finished) {
+            pet.food_amount += self
+                .pet_reward("东东萌宠->定时领狗粮", "getThreeMealReward")
+                .await?;
+        }
+
+        // 本次运行已喂食的狗粮
+        let mut fed = 0;
+        if tasks.first_feed_init.is_some_and(|task| !task.finished)
+            && pet.food_amount >= FEED_AMOUNT
+            && self.feed_pet().await?
+        {
+            fed += FEED_AMOUNT;
+            self.pause(Duration::from_secs(1)).await;
+            pet.food_amount += self
+                .pet_reward("东东萌宠->首次喂食", "getFirstFeedReward")
+                .await?;
+        }
+
+        if let Some(task) = tasks.feed_reach_init.filter(|task| !task.finished) {
+            while task.had_feed_amount + fed < task.feed_reach_amount
+                && pet.food_amount >= fed + FEED_AMOUNT
+                && !shutdown::is_requested()
+            {
+                if !self.feed_pet().await? {
+                    break;
+                }
+                fed += FEED_AMOUNT;
+                self.pause(Duration::from_secs(1)).await;
+            }
+            let success = task.had_feed_amount + fed >= task.feed_reach_amount;
+            info!(
+                "{}",
+                t!(
+                    "{}, 今日已喂食{}g, 目标{}g",
+                    self.account.name(),
+                    task.had_feed_amount + fed,
+                    task.feed_reach_amount
+                )
+            );
+            self.record_task("东东萌宠->每日喂食", success, 0);
+        }
+        pet.food_amount = pet.food_amount.saturating_sub(fed);
+
+        pet.food_amount += self.pet_sport().await?;
+
+        let res: Response<EnergyCollect> = self.pet_call("energyCollect", json!({})).await?;
+        if res.is_success() {
+            pet.medal_num = res.data.medal_num;
+            pet.medal_percent = res.data.medal_percent;
+        }
+
+        let (name, exchange) = pet
+            .goods_info
+            .map(|goods| (goods.goods_name, goods.exchange_medal_num))
+            .unwrap_or_default();
+        info!(
+            "{}",
+            t!(
+                "{}: 萌宠信息:\n\t奖品名称: {}\n\t已获得勋章: {}/{}\n\t勋章进度(%): {}\n\t剩余狗粮(g): {}",
+                self.account.name(),
+                name,
+                pet.medal_num,
+                exchange,
+                pet.medal_percent,
+                pet.food_amount
+            )
+        );
+        Ok(())
+    }
+
+    // 喂食一次, 狗粮不足等情况时返回false
+    async fn feed_pet(&self) -> Result<bool> {
+        let res: Response<Value> = self.pet_call("feedPets", json!({})).await?;
+        if !res.is_success() {
+            info!("{}", t!("{}, 喂食萌宠失败, {}", self.account.name(), res));
+        }
+        Ok(res.is_success())
+    }
+
+    // 遛狗并领取狗粮, 达到每日次数上限时结束, 返回获得的狗粮
+    async fn pet_sport(&self) -> Result<u32> {
+        let mut food = 0;
+        for _ in 0..SPORT_TIMES {
+            if shutdown::is_requested() {
+                break;
+            }
+            let res: Response<Value> = self.pet_call("petSport", json!({})).await?;
+            if !res.is_success() {
+                break;
+            }
+            self.pause(Duration::from_secs(1)).await;
+            food += self.pet_reward("东东萌宠->遛狗", "getSportReward").await?;
+        }
+        Ok(food)
+    }
+
+    // 领取任务奖励, 返回获得的狗粮
+    async fn pet_reward(&self, task: &str, function_id: &str) -> Result<u32> {
+        let res: Response<PetReward> = self.pet_call(function_id, json!({})).await?;
+        match res.is_success() {
+            true => {
+                info!(
+                    "{}",
+                    t!(
+                        "{}, 完成任务《{}》, 获得狗粮:{}g!",
+                        self.account.name(),
+                        i18n::tr(task),
+                        res.data.reward
+                    )
+                );
+                self.record_task(task, true, 0);
+                Ok(res.data.reward)
+            }
+            false => {
+                info!(
+                    "{}",
+                    t!(
+                        "{}, 无法完成任务《{}》, {}",
+                        self.account.name(),
+                        i18n::tr(task),
+                        res
+                    )
+                );
+                self.record_task(task, false, 0);
+                Ok(0)
+            }
+        }
+    }
+}
@@ -0,0 +1,82 @@
+use serde::{Deserialize, Serialize};
+
+use crate::i18n::tr;
+use crate::report::AccountReport;
+
+// 每次浇水消耗的水滴(g)
+pub const WATER_PER_WATERING: u32 = 10;
+
+// 运行前根据农场当前状态预计的运行结果, 运行结束后与实际结果对比, 找出未报错但实际未生效的操作
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+#[serde(default)]
+pub struct RunPlan {
+    // 运行前剩余水滴(g)
+    pub water_before: u32,
+    // 预计执行的任务, 今日未完成且需要执行
+    pub tasks: Vec<String>,
+    // 预计浇水次数
+    pub waterings: u32,
+    // 预计使用水滴翻倍卡
+    pub double_card: bool,
+}
+
+impl RunPlan {
+    // 纯文本描述, 如`执行任务: 定时领水、十次浇水; 浇水10次(消耗100g水滴); 使用水滴翻倍卡`
+    pub fn to_text(&self) -> String {
+        let mut parts = Vec::new();
+        if !self.tasks.is_empty() {
+            let names: Vec<&str> = self.tasks.iter().map(|t| tr(t)).collect();
+            parts.push(t!("执行任务: {}", names.join(&t!("、"))));
+        }
+        if self.waterings > 0 {
+            parts.push(t!(
+                "浇水{}次(剩余水滴{}g, 消耗{}g)",
+                self.waterings,
+                self.water_before,
+                self.waterings * WATER_PER_WATERING
+            ));
+        }
+        if self.double_card {
+            parts.push(t!("使用水滴翻倍卡"));
+        }
+        match parts.is_empty() {
+            true => t!("今日任务均已完成, 无需执行"),
+            false => parts.join("; "),
+        }
+    }
+
+    // 与实际运行结果对比, 返回与预计不符之处, 已记录失败或出错的任务不再重复列出
+    pub fn verify(&self, report: &AccountReport) -> Vec<String> {
+        let recorded = |task: &str| {
+            report.tasks.iter().any(|t| t.name == task)
+                || report.task_errors.iter().any(|e| e.task == task)
+        };
+        let mut mismatches: Vec<String> = self
+            .tasks
+            .iter()
+            .filter(|task| !recorded(task))
+            .map(|task| t!("任务《{}》没有执行结果", tr(task)))
+            .collect();
+
+        let watered = report
+            .endpoints
+            .get("waterGoodForFarm")
+            .map(|s| s.success)
+            .unwrap_or_default();
+        let water_errors = ["首次浇水", "十次浇水"]
+            .iter()
+            .any(|task| report.task_errors.iter().any(|e| e.task == *task));
+        if watered < self.waterings && !water_errors {
+            mismatches.push(t!("预计浇水{}次, 实际成功{}次", self.waterings, watered));
+        }
+
+        let used_card = report
+            .endpoints
+            .get("userMyCardForFarm")
+            .is_some_and(|s| s.success > 0);
+        if self.double_card && !used_card {
+            mismatches.push(t!("预计使用水滴翻倍卡, 实际未使用"));
+        }
+        mismatches
+    }
+}
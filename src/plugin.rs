@@ -0,0 +1,27 @@
+use std::sync::Arc;
+
+use anyhow::Result;
+use async_trait::async_trait;
+
+use crate::{bean, pet, JClient};
+
+// 外部任务, 其他crate可实现该trait为农场增加新的任务(如季节性活动), 无需修改本项目
+// 任务内可通过JClient::request/call请求接口, 通过JClient::record_task记录执行结果
+#[async_trait]
+pub trait FarmTask: Send + Sync {
+    // 任务名称, 用于日志、运行结果及ONLY_TASKS/SKIP_TASKS过滤
+    fn name(&self) -> &str;
+
+    // 执行任务, 返回的错误按账号的错误处理策略处理
+    async fn run(&self, client: &JClient) -> Result<()>;
+}
+
+// 已注册的外部任务
+pub type FarmTasks = Vec<Arc<dyn FarmTask>>;
+
+// 通过环境变量启用的内置活动, 如东东萌宠、种豆得豆, 依次在农场任务之后执行
+pub fn builtin_from_env() -> FarmTasks {
+    let mut tasks = pet::from_env();
+    tasks.extend(bean::from_env());
+    tasks
+}
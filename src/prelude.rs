@@ -0,0 +1,30 @@
+// 常用类型, 通过`use jd_farm::prelude::*;`一次导入
+pub use jd_com::account::{get_accounts, JAccount};
+
+pub use crate::accounts::AccountPriority;
+pub use crate::api::JClient;
+pub use crate::bean::BeanTask;
+pub use crate::daemon::Daemon;
+pub use crate::device::Device;
+pub use crate::doctor::{Doctor, DoctorReport};
+pub use crate::error::{ErrorPolicy, JError};
+pub use crate::event::RunEvent;
+pub use crate::health::{AccountHealth, AccountStatus};
+#[cfg(feature = "history")]
+pub use crate::history::History;
+pub use crate::i18n::Lang;
+pub use crate::models::{Card, CardInfo, FriendInfo, JdFarmInfo, TaskInfo, WaterRecord};
+pub use crate::network::{AccountRetry, Backoff, NetworkPolicy};
+pub use crate::notify::{ChannelOptions, Dispatcher, Notifier, NotifyLevel, NotifyMode};
+pub use crate::options::{CardPolicy, DelayPolicy, RunOptions, WaterRainRange};
+pub use crate::pet::PetTask;
+pub use crate::plan::RunPlan;
+pub use crate::plugin::FarmTask;
+pub use crate::report::{
+    AccountReport, FleetTotals, FriendWatering, Leaderboard, PrizeReport, RunReport, Severity,
+    TaskReport,
+};
+pub use crate::runner::Runner;
+pub use crate::share::ShareCode;
+pub use crate::state::StateStore;
+pub use crate::version::ApiVersion;
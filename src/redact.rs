@@ -0,0 +1,137 @@
+use std::borrow::Cow;
+use std::fmt;
+use std::io::{self, Write};
+use std::sync::RwLock;
+
+use tracing_subscriber::fmt::MakeWriter;
+
+// 替换敏感信息的文本
+const MASK: &str = "******";
+
+// cookie中需要隐藏值的字段
+const COOKIE_SECRET_KEYS: [&str; 3] = ["pt_key=", "wskey=", "pt_token="];
+
+// 过短的值容易误伤正常文本, 不做替换
+const MIN_SECRET_LEN: usize = 6;
+
+// 已登记的敏感信息
+static SECRETS: RwLock<Vec<String>> = RwLock::new(Vec::new());
+
+// 登记敏感信息, 之后所有日志输出中都会被替换
+pub fn register(secret: &str) {
+    if secret.len() < MIN_SECRET_LEN {
+        return;
+    }
+    let mut secrets = SECRETS.write().unwrap();
+    if !secrets.iter().any(|s| s == secret) {
+        secrets.push(secret.to_string());
+    }
+}
+
+// 登记cookie中的pt_key、wskey等字段
+pub fn register_cookie(cookie: &str) {
+    for item in cookie.split(';') {
+        let item = item.trim();
+        for key in COOKIE_SECRET_KEYS {
+            if let Some(value) = item.strip_prefix(key) {
+                register(value);
+            }
+        }
+    }
+}
+
+// 隐藏文本中的敏感信息
+pub fn redact(text: &str) -> Cow<'_, str> {
+    let mut result = Cow::Borrowed(text);
+    for secret in SECRETS.read().unwrap().iter() {
+        if result.contains(secret.as_str()) {
+            result = Cow::Owned(result.replace(secret.as_str(), MASK));
+        }
+    }
+    for key in COOKIE_SECRET_KEYS {
+        if result.contains(key) {
+            result = Cow::Owned(mask_after(&result, key));
+        }
+    }
+    result
+}
+
+// 隐藏敏感信息后的文本, 错误信息、通知等离开进程的文本都需经过该函数
+// 接口返回的错误信息可能原样带回请求中的cookie或token
+pub fn scrub(text: &str) -> String {
+    redact(text).into_owned()
+}
+
+// 隐藏key=之后直到分隔符的内容
+fn mask_after(text: &str, key: &str) -> String {
+    let mut output = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(pos) = rest.find(key) {
+        let value_start = pos + key.len();
+        output.push_str(&rest[..value_start]);
+        let value = &rest[value_start..];
+        let end = value
+            .find(|c: char| c == ';' || c == '&' || c == '"' || c == '\'' || c.is_whitespace())
+            .unwrap_or(value.len());
+        if end > 0 {
+            output.push_str(MASK);
+        }
+        rest = &value[end..];
+    }
+    output.push_str(rest);
+    output
+}
+
+// 敏感信息包装类型, Debug和Display均不输出原文
+#[derive(Clone, PartialEq, Eq)]
+pub struct Secret(String);
+
+impl Secret {
+    pub fn new(value: &str) -> Self {
+        register(value);
+        Self(value.to_string())
+    }
+
+    // 获取原文, 仅在发送请求时使用
+    pub fn expose(&self) -> &str {
+        &self.0
+    }
+}
+
+impl fmt::Debug for Secret {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(MASK)
+    }
+}
+
+impl fmt::Display for Secret {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(MASK)
+    }
+}
+
+// 写入前隐藏敏感信息的writer
+pub struct RedactingWriter<W: Write>(W);
+
+impl<W: Write> Write for RedactingWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let text = String::from_utf8_lossy(buf);
+        self.0.write_all(redact(&text).as_bytes())?;
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.0.flush()
+    }
+}
+
+// 为日志layer提供RedactingWriter
+pub struct RedactingMakeWriter<M>(pub M);
+
+impl<'a, M: MakeWriter<'a>> MakeWriter<'a> for RedactingMakeWriter<M> {
+    type Writer = RedactingWriter<M::Writer>;
+
+    fn make_writer(&'a self) -> Self::Writer {
+        RedactingWriter(self.0.make_writer())
+    }
+}
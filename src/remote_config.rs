@@ -0,0 +1,325 @@
+use std::env;
+use std::future::{pending, Future};
+use std::time::Duration;
+
+use anyhow::{anyhow, Result};
+use reqwest::{redirect, Client};
+use std::num::NonZeroU32;
+
+use ring::aead::{Aad, LessSafeKey, Nonce, UnboundKey, AES_256_GCM, NONCE_LEN};
+use ring::pbkdf2::{self, PBKDF2_HMAC_SHA256};
+use ring::rand::{SecureRandom, SystemRandom};
+use toml::value::Table;
+use tracing::info;
+
+use crate::config;
+use crate::redact::Secret;
+use crate::shutdown;
+
+// 从HTTPS地址获取集中管理的配置, 多台设备启动时及定时拉取同一份配置
+// 远程配置覆盖本地配置文件的设置, 格式与配置文件相同
+#[derive(Debug, Clone)]
+pub struct RemoteConfig {
+    url: Secret,
+    // 解密密钥, 设置后远程配置须为encrypt-config命令加密的内容
+    key: Option<Secret>,
+    // 刷新间隔, 为空时只在启动时获取一次
+    refresh: Option<Duration>,
+    timeout: Duration,
+}
+
+impl RemoteConfig {
+    pub fn new(url: &str) -> Self {
+        Self {
+            url: Secret::new(url),
+            key: None,
+            refresh: None,
+            timeout: Duration::from_secs(10),
+        }
+    }
+
+    // 设置解密密钥
+    pub fn key(mut self, key: &str) -> Self {
+        self.key = Some(Secret::new(key));
+        self
+    }
+
+    // 设置刷新间隔
+    pub fn refresh(mut self, interval: Duration) -> Self {
+        self.refresh = Some(interval);
+        self
+    }
+
+    // JD_FARM_CONFIG_URL: 远程配置地址, 须为https
+    // JD_FARM_CONFIG_KEY: 可选, 解密密钥
+    // JD_FARM_CONFIG_REFRESH: 可选, 刷新间隔(秒), 默认只在启动时获取
+    pub fn from_env() -> Option<Self> {
+        let url = env::var("JD_FARM_CONFIG_URL")
+            .ok()
+            .filter(|u| !u.trim().is_empty())?;
+        let mut remote = Self::new(url.trim());
+        if let Some(key) = env::var("JD_FARM_CONFIG_KEY")
+            .ok()
+            .filter(|k| !k.is_empty())
+        {
+            remote = remote.key(&key);
+        }
+        if let Some(refresh) = env::var("JD_FARM_CONFIG_REFRESH")
+            .ok()
+            .and_then(|v| v.trim().parse::<f64>().ok())
+            .filter(|v| *v > 0.0)
+        {
+            remote = remote.refresh(Duration::from_secs_f64(refresh));
+        }
+        Some(remote)
+    }
+
+    // 获取并解析远程配置
+    pub async fn fetch(&self) -> Result<Table> {
+        let url = self.url.expose();
+        // 配置中可能包含cookie等敏感信息, 只允许通过https获取
+        if !url.starts_with("https://") {
+            return Err(anyhow!(t!("远程配置地址必须以https://开头")));
+        }
+        // 重定向到非https地址时失败, 避免配置经明文传输
+        let policy = redirect::Policy::custom(|attempt| {
+            if attempt.url().scheme() != "https" {
+                attempt.error(t!("远程配置地址必须以https://开头"))
+            } else if attempt.previous().len() >= 10 {
+                attempt.stop()
+            } else {
+                attempt.follow()
+            }
+        });
+        let res = Client::builder()
+            .timeout(self.timeout)
+            .redirect(policy)
+            .build()?
+            .get(url)
+            .send()
+            .await?
+            .error_for_status()?;
+        let body = res.text().await?;
+        let text = match &self.key {
+            Some(key) => decrypt(body.trim(), key.expose())?,
+            None => body,
+        };
+        Ok(toml::from_str(&text)?)
+    }
+
+    // 获取远程配置并替换当前生效的远程配置, 失败时保留之前的配置
+    pub async fn load(&self) -> bool {
+        match self.fetch().await {
+            Ok(table) => {
+                config::set_remote(Some(table));
+                info!("{}", t!("已加载远程配置"));
+                true
+            }
+            Err(e) => {
+                info!("{}", t!("获取远程配置失败, {}", e));
+                false
+            }
+        }
+    }
+
+    // 按刷新间隔重新获取, 之后的运行使用新的配置, 收到退出信号后不再获取
+    // 不会结束, 也不会创建后台任务, 由调用方与常驻运行的future一起等待
+    pub async fn watch(&self) {
+        if let Some(interval) = self.refresh {
+            loop {
+                tokio::select! {
+                    _ = tokio::time::sleep(interval) => {}
+                    _ = shutdown::requested() => break,
+                }
+                self.load().await;
+            }
+        }
+        pending::<()>().await
+    }
+}
+
+// 运行future期间按刷新间隔更新远程配置, future结束时停止刷新, 用于常驻运行、定时运行及服务模式
+pub async fn refreshing<F: Future>(remote: Option<&RemoteConfig>, future: F) -> F::Output {
+    let refresh = async {
+        match remote {
+            Some(remote) => remote.watch().await,
+            None => pending().await,
+        }
+    };
+    tokio::select! {
+        output = future => output,
+        _ = refresh => unreachable!(),
+    }
+}
+
+// 加密格式的版本, 加密结果为`v1:迭代次数:base64(盐):base64(随机数及密文)`
+const FORMAT_VERSION: &str = "v1";
+
+// PBKDF2迭代次数, 解密时使用加密结果中记录的次数, 超过上限的视为无效内容
+const PBKDF2_ITERATIONS: u32 = 600_000;
+const MAX_PBKDF2_ITERATIONS: u32 = 10_000_000;
+
+// 盐的长度(字节)
+const SALT_LEN: usize = 16;
+
+// 由密钥及盐通过PBKDF2-HMAC-SHA256生成AES-256-GCM密钥
+fn cipher(key: &str, salt: &[u8], iterations: NonZeroU32) -> Result<LessSafeKey> {
+    let mut derived = [0u8; 32];
+    pbkdf2::derive(
+        PBKDF2_HMAC_SHA256,
+        iterations,
+        salt,
+        key.as_bytes(),
+        &mut derived,
+    );
+    let key = UnboundKey::new(&AES_256_GCM, &derived).map_err(|_| anyhow!(t!("无效的密钥")))?;
+    Ok(LessSafeKey::new(key))
+}
+
+// 加密配置内容, 每次使用随机的盐及随机数
+pub fn encrypt(text: &str, key: &str) -> Result<String> {
+    encrypt_with(text, key, NonZeroU32::new(PBKDF2_ITERATIONS).unwrap())
+}
+
+fn encrypt_with(text: &str, key: &str, iterations: NonZeroU32) -> Result<String> {
+    let rng = SystemRandom::new();
+    let mut salt = [0u8; SALT_LEN];
+    let mut nonce = [0u8; NONCE_LEN];
+    rng.fill(&mut salt)
+        .and_then(|_| rng.fill(&mut nonce))
+        .map_err(|_| anyhow!(t!("生成随机数失败")))?;
+    let mut data = text.as_bytes().to_vec();
+    cipher(key, &salt, iterations)?
+        .seal_in_place_append_tag(Nonce::assume_unique_for_key(nonce), Aad::empty(), &mut data)
+        .map_err(|_| anyhow!(t!("加密失败")))?;
+    let mut out = nonce.to_vec();
+    out.extend(data);
+    Ok(format!(
+        "{}:{}:{}:{}",
+        FORMAT_VERSION,
+        iterations,
+        base64::encode(salt),
+        base64::encode(out)
+    ))
+}
+
+// 解密encrypt加密的内容, 密钥错误或内容被修改时失败
+pub fn decrypt(encoded: &str, key: &str) -> Result<String> {
+    let parts: Vec<&str> = encoded.split(':').collect();
+    let [version, iterations, salt, data] = parts[..] else {
+        return Err(anyhow!(t!(
+            "远程配置不是加密内容, 请使用encrypt-config重新加密"
+        )));
+    };
+    if version != FORMAT_VERSION {
+        return Err(anyhow!(t!("不支持的加密格式版本: {}", version)));
+    }
+    let iterations = iterations
+        .parse::<u32>()
+        .ok()
+        .filter(|i| *i <= MAX_PBKDF2_ITERATIONS)
+        .and_then(NonZeroU32::new)
+        .ok_or_else(|| anyhow!(t!("远程配置不是加密内容, 请使用encrypt-config重新加密")))?;
+    let salt = base64::decode(salt).map_err(|e| anyhow!(t!("远程配置不是加密内容, {}", e)))?;
+    let data = base64::decode(data).map_err(|e| anyhow!(t!("远程配置不是加密内容, {}", e)))?;
+    if data.len() < NONCE_LEN {
+        return Err(anyhow!(t!("远程配置内容过短, 不是加密内容")));
+    }
+    let (nonce, sealed) = data.split_at(NONCE_LEN);
+    let nonce = Nonce::try_assume_unique_for_key(nonce).map_err(|_| anyhow!(t!("解密失败")))?;
+    let mut sealed = sealed.to_vec();
+    let plain = cipher(key, &salt, iterations)?
+        .open_in_place(nonce, Aad::empty(), &mut sealed)
+        .map_err(|_| anyhow!(t!("解密失败, 请检查JD_FARM_CONFIG_KEY")))?;
+    Ok(String::from_utf8(plain.to_vec())?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TEXT: &str = "[options]\nwater_ratio = 0.8\n";
+
+    // 测试中使用较少的迭代次数, 避免过慢
+    fn encrypt_fast(text: &str, key: &str) -> String {
+        encrypt_with(text, key, NonZeroU32::new(1000).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn round_trip() {
+        let encoded = encrypt(TEXT, "secret").unwrap();
+        assert!(encoded.starts_with(&format!("v1:{}:", PBKDF2_ITERATIONS)));
+        assert!(!encoded.contains("water_ratio"));
+        assert_eq!(decrypt(&encoded, "secret").unwrap(), TEXT);
+        // 每次加密使用随机的盐及随机数
+        assert_ne!(encrypt_fast(TEXT, "secret"), encrypt_fast(TEXT, "secret"));
+    }
+
+    #[test]
+    fn wrong_key_fails() {
+        let encoded = encrypt_fast(TEXT, "secret");
+        assert!(decrypt(&encoded, "Secret").is_err());
+        assert!(decrypt(&encoded, "").is_err());
+    }
+
+    #[test]
+    fn tampered_ciphertext_fails() {
+        let encoded = encrypt_fast(TEXT, "secret");
+        let (head, data) = encoded.rsplit_once(':').unwrap();
+        let mut data = base64::decode(data).unwrap();
+        // 分别修改随机数、密文及认证标签
+        for i in [0, NONCE_LEN, data.len() - 1] {
+            data[i] ^= 1;
+            let tampered = format!("{}:{}", head, base64::encode(&data));
+            assert!(decrypt(&tampered, "secret").is_err());
+            data[i] ^= 1;
+        }
+        let truncated = format!("{}:{}", head, base64::encode(&data[..data.len() - 1]));
+        assert!(decrypt(&truncated, "secret").is_err());
+        assert_eq!(
+            decrypt(&format!("{}:{}", head, base64::encode(&data)), "secret").unwrap(),
+            TEXT
+        );
+
+        // 修改盐
+        let parts: Vec<&str> = encoded.split(':').collect();
+        let mut salt = base64::decode(parts[2]).unwrap();
+        salt[0] ^= 1;
+        let tampered = format!(
+            "{}:{}:{}:{}",
+            parts[0],
+            parts[1],
+            base64::encode(salt),
+            parts[3]
+        );
+        assert!(decrypt(&tampered, "secret").is_err());
+    }
+
+    #[test]
+    fn iterations_are_capped() {
+        let encoded = encrypt_fast(TEXT, "secret");
+        let rest = encoded.strip_prefix("v1:1000:").unwrap();
+        for iterations in [
+            (MAX_PBKDF2_ITERATIONS + 1).to_string(),
+            u32::MAX.to_string(),
+            "0".to_string(),
+            "-1".to_string(),
+            "abc".to_string(),
+        ] {
+            let encoded = format!("v1:{}:{}", iterations, rest);
+            assert!(decrypt(&encoded, "secret").is_err(), "{}", iterations);
+        }
+        // 记录的迭代次数与加密时不同时无法解密
+        assert!(decrypt(&format!("v1:1001:{}", rest), "secret").is_err());
+    }
+
+    #[test]
+    fn rejects_other_formats() {
+        let encoded = encrypt_fast(TEXT, "secret");
+        let v2 = encoded.replacen("v1:", "v2:", 1);
+        assert!(decrypt(&v2, "secret").is_err());
+        assert!(decrypt(TEXT, "secret").is_err());
+        assert!(decrypt("v1:1000:AAAA", "secret").is_err());
+        assert!(decrypt("v1:1000:AAAA:AAAA", "secret").is_err());
+    }
+}
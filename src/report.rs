@@ -0,0 +1,957 @@
+use std::collections::BTreeMap;
+
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+use unicode_width::UnicodeWidthStr;
+
+use crate::i18n::tr;
+use crate::plan::RunPlan;
+use crate::redact;
+use crate::share;
+use crate::trend::Trends;
+
+// 运行结果的严重程度
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Severity {
+    // 全部正常
+    Info,
+    // 有任务失败或果树已成熟, 需要关注
+    Warning,
+    // cookie失效或运行中断, 需要处理
+    Error,
+}
+
+// 单个任务执行结果
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct TaskReport {
+    // 任务名称
+    pub name: String,
+    // 是否成功
+    pub success: bool,
+    // 获得水滴(g)
+    pub gain: u64,
+}
+
+// 执行任务时出现的错误
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct TaskError {
+    // 任务名称
+    pub task: String,
+    // 错误描述
+    pub message: String,
+    // 建议的处理方式, 见error_codes
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub advice: Option<String>,
+}
+
+// 成功率低于该值的接口视为异常
+const ENDPOINT_FAILURE_RATE: f64 = 0.5;
+
+// 汇总中最多列出的异常接口数量
+const MAX_WORST_ENDPOINTS: usize = 3;
+
+// 排行榜每项最多列出的账号数量
+const LEADERBOARD_SIZE: usize = 3;
+
+// 所有账号的合计
+#[derive(Serialize, Debug, Clone, Default, PartialEq)]
+pub struct FleetTotals {
+    // 账号数
+    pub accounts: usize,
+    // 运行成功的账号数
+    pub succeeded: usize,
+    // 获得水滴(g)
+    pub water_gained: u64,
+    // 剩余水滴净增(g), 浇水消耗后可能为负
+    pub net_water: i64,
+    // 失败任务数
+    pub failed_tasks: usize,
+    // 出错任务数
+    pub task_errors: usize,
+    // cookie已失效的账号数
+    pub expired: usize,
+    // 果树已成熟的账号数
+    pub harvest_ready: usize,
+    // 获得的奖品数
+    pub prizes: usize,
+    // 触发风控的请求数
+    pub risk_control: u32,
+}
+
+// 排行榜中的一个账号
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct Ranked<T> {
+    // 账号名称
+    pub name: String,
+    pub value: T,
+}
+
+// 账号排行榜, 账号较多时快速找出表现突出或异常的账号
+#[derive(Serialize, Debug, Clone, Default, PartialEq)]
+pub struct Leaderboard {
+    // 获得水滴最多(g)
+    pub most_water: Vec<Ranked<u64>>,
+    // 最接近成熟, 果树进度(%), 不含已成熟的账号
+    pub closest_to_harvest: Vec<Ranked<f64>>,
+    // 失败最多, 失败及出错的任务数, 运行中断时计1
+    pub most_failures: Vec<Ranked<usize>>,
+}
+
+impl Leaderboard {
+    // 纯文本, 每项一行, 没有账号的项不输出
+    pub fn to_lines(&self) -> Vec<String> {
+        fn join<T>(items: &[Ranked<T>], f: impl Fn(&Ranked<T>) -> String) -> String {
+            items.iter().map(f).collect::<Vec<_>>().join(&t!("、"))
+        }
+        let mut lines = Vec::new();
+        if !self.most_water.is_empty() {
+            let items = join(&self.most_water, |r| format!("{}({}g)", r.name, r.value));
+            lines.push(t!("水滴榜: {}", items));
+        }
+        if !self.closest_to_harvest.is_empty() {
+            let items = join(&self.closest_to_harvest, |r| {
+                format!("{}({:.1}%)", r.name, r.value)
+            });
+            lines.push(t!("成熟榜: {}", items));
+        }
+        if !self.most_failures.is_empty() {
+            let items = join(&self.most_failures, |r| format!("{}({})", r.name, r.value));
+            lines.push(t!("失败榜: {}", items));
+        }
+        lines
+    }
+}
+
+// 单个接口的请求结果统计
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default)]
+pub struct EndpointStats {
+    // 成功次数
+    pub success: u32,
+    // 失败次数
+    pub failure: u32,
+}
+
+impl EndpointStats {
+    pub fn total(&self) -> u32 {
+        self.success + self.failure
+    }
+
+    // 成功率
+    pub fn success_rate(&self) -> f64 {
+        if self.total() == 0 {
+            return 1.0;
+        }
+        self.success as f64 / self.total() as f64
+    }
+}
+
+// 获得的奖品
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct PrizeReport {
+    // 来源, 如果树成熟、点鸭子
+    pub source: String,
+    // 奖品名称
+    pub name: String,
+    // 奖品等级
+    pub level: Option<u8>,
+    // 优惠券码
+    pub code: Option<String>,
+}
+
+// 为我浇水的好友及次数
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct FriendWatering {
+    // 好友昵称
+    pub name: String,
+    // 好友助力码
+    pub share_code: String,
+    // 今日浇水次数
+    pub today: u32,
+    // 本周(周一起)浇水次数
+    pub week: u32,
+}
+
+// 单个账号运行结果
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct AccountReport {
+    // 账号名称
+    pub name: String,
+    // 运行前剩余水滴(g)
+    pub water_before: u32,
+    // 运行后剩余水滴(g)
+    pub water_after: u32,
+    // 任务执行结果
+    pub tasks: Vec<TaskReport>,
+    // cookie是否已失效
+    pub cookie_expired: bool,
+    // 果树是否已成熟可领取
+    pub harvest_ready: bool,
+    // 果树已浇水滴(g)
+    #[serde(default)]
+    pub tree_energy: u32,
+    // 果树成熟需要的水滴(g)
+    #[serde(default)]
+    pub tree_total_energy: u32,
+    // 导致运行中断的错误
+    pub error: Option<String>,
+    // 未中断运行的任务错误
+    #[serde(default)]
+    pub task_errors: Vec<TaskError>,
+    // 获得的奖品
+    #[serde(default)]
+    pub prizes: Vec<PrizeReport>,
+    // 各接口(function_id)请求结果
+    #[serde(default)]
+    pub endpoints: BTreeMap<String, EndpointStats>,
+    // 下一轮水滴雨开始时间, 今日已全部完成时为空
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub next_water_rain: Option<DateTime<Local>>,
+    // 本次水滴雨提交的接到红包数, 未执行水滴雨时为空
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub water_rain_score: Option<u32>,
+    // 本周为我浇水的好友, 按浇水次数从多到少排列
+    #[serde(default)]
+    pub watered_by: Vec<FriendWatering>,
+    // 本周未为我浇水的好友昵称, 可据此清理不活跃的好友
+    #[serde(default)]
+    pub idle_friends: Vec<String>,
+    // 触发风控的请求次数
+    #[serde(default)]
+    pub risk_control: u32,
+    // 助力码, 未获取到果树信息时为空
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub share_code: Option<String>,
+    // 导致运行中断的错误是否为网络错误、超时等临时错误, 可稍后重试
+    #[serde(default)]
+    pub transient: bool,
+    // 是否在运行结束时重试过
+    #[serde(default)]
+    pub retried: bool,
+    // 运行前预计的运行结果, 未获取到任务列表时为空
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub plan: Option<RunPlan>,
+    // 实际运行结果与预计不符之处, 往往说明有操作未报错但实际未生效
+    #[serde(default)]
+    pub plan_mismatches: Vec<String>,
+    // 接口返回数据的变化(新增或缺少字段), 需设置SCHEMA_DRIFT=true
+    #[serde(default)]
+    pub schema_drift: Vec<String>,
+    // 导致运行中断的错误的处理建议
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub advice: Option<String>,
+    // 需要完成安全验证时的验证页面地址, 未返回地址时为空字符串
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub verification_url: Option<String>,
+    // 使用的cookie在该账号cookie池中的序号, 0为主cookie, 主cookie失效后改用备用cookie时大于0
+    #[serde(default)]
+    pub cookie_index: usize,
+    // 之前触发风控而暂停运行时的恢复时间, 本次未运行该账号
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub paused_until: Option<DateTime<Local>>,
+    // 账号正在被另一个实例运行, 本次未运行该账号
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub running_elsewhere: bool,
+}
+
+impl AccountReport {
+    pub fn new(name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            ..Default::default()
+        }
+    }
+
+    // 本次运行获得的水滴(g)
+    pub fn water_gained(&self) -> u64 {
+        self.tasks.iter().map(|t| t.gain).sum()
+    }
+
+    // 执行失败的任务
+    pub fn failed_tasks(&self) -> Vec<&TaskReport> {
+        self.tasks.iter().filter(|t| !t.success).collect()
+    }
+
+    // 失败及出错的任务数, 运行中断时计1
+    pub fn failures(&self) -> usize {
+        self.failed_tasks().len() + self.task_errors.len() + usize::from(self.error.is_some())
+    }
+
+    // 果树成熟进度(%)
+    pub fn tree_progress(&self) -> f64 {
+        if self.tree_total_energy == 0 {
+            return 0.0;
+        }
+        (self.tree_energy as f64 / self.tree_total_energy as f64 * 100.0).min(100.0)
+    }
+
+    // 果树成熟还需浇水(g)
+    pub fn tree_remaining(&self) -> u32 {
+        self.tree_total_energy.saturating_sub(self.tree_energy)
+    }
+
+    // 果树进度描述, 未获取到果树信息或已成熟时为空
+    pub fn progress_text(&self, daily_gain: f64) -> Option<String> {
+        if self.tree_total_energy == 0 || self.harvest_ready {
+            return None;
+        }
+        let mut text = t!(
+            "果树进度: {}%, 还需浇水: {}g",
+            format!("{:.1}", self.tree_progress()),
+            self.tree_remaining()
+        );
+        if let Some(days) = self.harvest_eta_days(daily_gain) {
+            text.push_str(&t!(", 预计{}天后成熟", format!("{:.0}", days.ceil())));
+        }
+        Some(text)
+    }
+
+    // 按每日获得水滴(g)估算果树成熟还需的天数, 剩余水滴也会用于浇水
+    pub fn harvest_eta_days(&self, daily_gain: f64) -> Option<f64> {
+        if self.harvest_ready {
+            return Some(0.0);
+        }
+        if self.tree_total_energy == 0 {
+            return None;
+        }
+        let need = self
+            .tree_total_energy
+            .saturating_sub(self.tree_energy)
+            .saturating_sub(self.water_after);
+        if need == 0 {
+            return Some(0.0);
+        }
+        if daily_gain <= 0.0 {
+            return None;
+        }
+        Some(need as f64 / daily_gain)
+    }
+
+    // 果树成熟提醒: (标题, 内容)
+    pub fn harvest_alert(&self) -> (String, String) {
+        let prize = self.prizes.iter().find(|p| p.source == "果树成熟");
+        let content = match prize {
+            Some(p) => t!("【{}】果树已成熟, 奖品: {}, 请尽快领取!", self.name, p.name),
+            None => t!("【{}】果树已成熟, 请尽快领取!", self.name),
+        };
+        (t!("东东农场: 果树已成熟"), content)
+    }
+
+    // 需要完成安全验证的提醒标题及内容
+    pub fn verification_alert(&self) -> (String, String) {
+        let content = match self.verification_url.as_deref() {
+            Some(url) if !url.is_empty() => t!(
+                "【{}】需要完成安全验证, 已停止运行该账号, 请在京东App中打开链接完成验证: {}",
+                self.name,
+                url
+            ),
+            _ => t!(
+                "【{}】需要完成安全验证, 已停止运行该账号, 请打开京东App完成验证",
+                self.name
+            ),
+        };
+        (t!("东东农场: 需要安全验证"), content)
+    }
+
+    // 分享链接, 好友打开后为该账号助力
+    pub fn share_link(&self) -> Option<String> {
+        self.share_code.as_deref().map(share::link)
+    }
+
+    // 合并首次运行的结果, 重试时首次运行获得的水滴及奖品仍计入本次运行
+    pub fn merge_retry(&mut self, first: AccountReport) {
+        if first.share_code.is_some() {
+            self.water_before = first.water_before;
+        }
+        let mut tasks = first.tasks;
+        tasks.append(&mut self.tasks);
+        self.tasks = tasks;
+        let mut prizes = first.prizes;
+        prizes.append(&mut self.prizes);
+        self.prizes = prizes;
+        self.risk_control += first.risk_control;
+        self.water_rain_score = self.water_rain_score.or(first.water_rain_score);
+        if self.watered_by.is_empty() && self.idle_friends.is_empty() {
+            self.watered_by = first.watered_by;
+            self.idle_friends = first.idle_friends;
+        }
+        for drift in first.schema_drift {
+            if !self.schema_drift.contains(&drift) {
+                self.schema_drift.push(drift);
+            }
+        }
+        self.retried = true;
+    }
+
+    // 本次是否跳过了该账号(因风控暂停或正在被另一个实例运行)
+    pub fn skipped(&self) -> bool {
+        self.paused_until.is_some() || self.running_elsewhere
+    }
+
+    // 隐藏错误信息及验证页面地址中的敏感信息
+    pub fn redact(&mut self) {
+        if let Some(error) = &mut self.error {
+            *error = redact::scrub(error);
+        }
+        for e in &mut self.task_errors {
+            e.message = redact::scrub(&e.message);
+        }
+        if let Some(url) = &mut self.verification_url {
+            *url = redact::scrub(url);
+        }
+    }
+
+    // 是否运行成功
+    pub fn is_ok(&self) -> bool {
+        !self.cookie_expired && self.error.is_none()
+    }
+
+    // 严重程度
+    pub fn severity(&self) -> Severity {
+        if !self.is_ok() {
+            Severity::Error
+        } else if self.harvest_ready
+            || self.skipped()
+            || self.cookie_index > 0
+            || !self.failed_tasks().is_empty()
+            || !self.task_errors.is_empty()
+            || !self.plan_mismatches.is_empty()
+            || !self.schema_drift.is_empty()
+        {
+            Severity::Warning
+        } else {
+            Severity::Info
+        }
+    }
+
+    // 状态描述
+    pub fn status(&self) -> String {
+        match (self.cookie_expired, &self.error) {
+            (true, _) => t!("cookie已失效"),
+            (false, Some(e)) => e.clone(),
+            (false, None) if self.paused_until.is_some() => t!("触发风控, 暂停运行"),
+            (false, None) if self.running_elsewhere => t!("正在被另一个实例运行"),
+            (false, None) if self.harvest_ready => t!("果树已成熟"),
+            (false, None) => t!("正常"),
+        }
+    }
+
+    // 纯文本摘要, 按本次获得水滴估算成熟时间
+    pub fn to_text(&self) -> String {
+        self.summary(self.water_gained() as f64)
+    }
+
+    // 纯文本摘要, daily_gain为估算成熟时间使用的每日获得水滴(g)
+    pub fn summary(&self, daily_gain: f64) -> String {
+        if let Some(until) = self.paused_until {
+            return t!(
+                "【{}】触发风控, 暂停运行至{}, 本次已跳过",
+                self.name,
+                until.format("%m-%d %H:%M")
+            );
+        }
+        if self.running_elsewhere {
+            return t!("【{}】正在被另一个实例运行, 本次已跳过", self.name);
+        }
+        let mut text = t!(
+            "【{}】获得水滴: {}g, 剩余水滴: {}g",
+            self.name,
+            self.water_gained(),
+            self.water_after
+        );
+        if self.cookie_expired {
+            text.push_str(&t!(", cookie已失效"));
+        }
+        if self.cookie_expired && self.cookie_index > 0 {
+            text.push_str(&t!(", {}个备用cookie也已失效", self.cookie_index));
+        } else if self.cookie_index > 0 {
+            text.push_str(&t!(
+                ", 主cookie已失效, 已改用第{}个备用cookie",
+                self.cookie_index
+            ));
+        }
+        if self.harvest_ready {
+            text.push_str(&t!(", 果树已成熟, 请及时领取"));
+        }
+        if let Some(progress) = self.progress_text(daily_gain) {
+            text.push_str(&format!(", {}", progress));
+        }
+        let failed = self.failed_tasks();
+        if !failed.is_empty() {
+            let names: Vec<&str> = failed.iter().map(|t| tr(&t.name)).collect();
+            text.push_str(&t!(", 失败任务: {}", names.join(&t!("、"))));
+        }
+        if !self.prizes.is_empty() {
+            let names: Vec<&str> = self.prizes.iter().map(|p| p.name.as_str()).collect();
+            text.push_str(&t!(", 获得奖品: {}", names.join(&t!("、"))));
+        }
+        if !self.watered_by.is_empty() {
+            let names: Vec<String> = self
+                .watered_by
+                .iter()
+                .map(|f| t!("{}(今日{}次/本周{}次)", f.name, f.today, f.week))
+                .collect();
+            text.push_str(&t!(", 为你浇水的好友: {}", names.join(&t!("、"))));
+        }
+        if !self.idle_friends.is_empty() {
+            text.push_str(&t!(", 本周未为你浇水的好友: {}位", self.idle_friends.len()));
+        }
+        if !self.task_errors.is_empty() {
+            let names: Vec<&str> = self.task_errors.iter().map(|e| tr(&e.task)).collect();
+            text.push_str(&t!(", 出错任务: {}", names.join(&t!("、"))));
+        }
+        if !self.plan_mismatches.is_empty() {
+            text.push_str(&t!(", 与预计不符: {}", self.plan_mismatches.join("; ")));
+        }
+        if !self.schema_drift.is_empty() {
+            text.push_str(&t!(", 接口变化: {}", self.schema_drift.join("; ")));
+        }
+        if let Some(e) = &self.error {
+            text.push_str(&t!(", 错误: {}", e));
+            if let Some(advice) = &self.advice {
+                text.push_str(&t!("(建议: {})", advice));
+            }
+        }
+        if let Some(link) = self.share_link().filter(|_| share::in_notify()) {
+            text.push_str(&t!(", 分享链接: {}", link));
+        }
+        text
+    }
+}
+
+// 一次运行的结果
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct RunReport {
+    // 开始时间
+    pub started_at: DateTime<Local>,
+    // 结束时间
+    pub finished_at: DateTime<Local>,
+    // 各账号运行结果
+    pub accounts: Vec<AccountReport>,
+    // 与历史运行结果对比的收益趋势, 未保存运行历史时为空
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub trends: Option<Trends>,
+    // 运行期间收到退出信号, 部分任务未执行
+    #[serde(default)]
+    pub interrupted: bool,
+    // 模拟运行, 数据均为虚构
+    #[serde(default)]
+    pub simulated: bool,
+}
+
+impl RunReport {
+    pub fn new(started_at: DateTime<Local>, accounts: Vec<AccountReport>) -> Self {
+        Self {
+            started_at,
+            finished_at: Local::now(),
+            accounts,
+            trends: None,
+            interrupted: false,
+            simulated: false,
+        }
+    }
+
+    // 隐藏各账号错误信息中的敏感信息后的副本
+    pub fn redacted(&self) -> RunReport {
+        let mut report = self.clone();
+        report.accounts.iter_mut().for_each(AccountReport::redact);
+        report
+    }
+
+    // 只保留符合条件的账号
+    pub fn filter<F: Fn(&AccountReport) -> bool>(&self, f: F) -> RunReport {
+        let accounts: Vec<AccountReport> = self.accounts.iter().filter(|a| f(a)).cloned().collect();
+        let names: Vec<&str> = accounts.iter().map(|a| a.name.as_str()).collect();
+        RunReport {
+            started_at: self.started_at,
+            finished_at: self.finished_at,
+            trends: self.trends.as_ref().map(|t| t.filter(&names)),
+            accounts,
+            interrupted: self.interrupted,
+            simulated: self.simulated,
+        }
+    }
+
+    // 拆分为每个账号单独的运行结果
+    pub fn split(&self) -> Vec<RunReport> {
+        self.accounts
+            .iter()
+            .map(|a| RunReport {
+                started_at: self.started_at,
+                finished_at: self.finished_at,
+                accounts: vec![a.clone()],
+                trends: self.trends.as_ref().map(|t| t.filter(&[a.name.as_str()])),
+                interrupted: self.interrupted,
+                simulated: self.simulated,
+            })
+            .collect()
+    }
+
+    // 估算成熟时间使用的每日获得水滴(g), 有历史数据时使用近7日平均值, 否则使用本次获得水滴
+    pub fn daily_gain(&self, account: &AccountReport) -> f64 {
+        self.trends
+            .as_ref()
+            .and_then(|t| t.accounts.iter().find(|a| a.name == account.name))
+            .map(|a| a.daily_average)
+            .filter(|d| *d > 0.0)
+            .unwrap_or(account.water_gained() as f64)
+    }
+
+    // 账号果树预计成熟天数
+    pub fn harvest_eta_days(&self, account: &AccountReport) -> Option<f64> {
+        account.harvest_eta_days(self.daily_gain(account))
+    }
+
+    // 账号果树进度描述
+    pub fn progress_text(&self, account: &AccountReport) -> Option<String> {
+        account.progress_text(self.daily_gain(account))
+    }
+
+    // 所有账号获得的水滴(g)
+    pub fn water_gained(&self) -> u64 {
+        self.accounts.iter().map(|a| a.water_gained()).sum()
+    }
+
+    // 所有账号各接口请求结果
+    pub fn endpoint_stats(&self) -> BTreeMap<String, EndpointStats> {
+        let mut stats: BTreeMap<String, EndpointStats> = BTreeMap::new();
+        for account in &self.accounts {
+            for (function_id, s) in &account.endpoints {
+                let entry = stats.entry(function_id.clone()).or_default();
+                entry.success += s.success;
+                entry.failure += s.failure;
+            }
+        }
+        stats
+    }
+
+    // 成功率最低的异常接口, 往往是京东修改或下线了该接口
+    pub fn worst_endpoints(&self) -> Vec<(String, EndpointStats)> {
+        let mut worst: Vec<(String, EndpointStats)> = self
+            .endpoint_stats()
+            .into_iter()
+            .filter(|(_, s)| s.success_rate() < ENDPOINT_FAILURE_RATE)
+            .collect();
+        worst.sort_by(|a, b| {
+            a.1.success_rate()
+                .partial_cmp(&b.1.success_rate())
+                .unwrap_or(std::cmp::Ordering::Equal)
+                .then(b.1.failure.cmp(&a.1.failure))
+        });
+        worst.truncate(MAX_WORST_ENDPOINTS);
+        worst
+    }
+
+    // 所有账号的合计
+    pub fn totals(&self) -> FleetTotals {
+        let accounts = &self.accounts;
+        FleetTotals {
+            accounts: accounts.len(),
+            succeeded: accounts.iter().filter(|a| a.is_ok()).count(),
+            water_gained: self.water_gained(),
+            net_water: accounts
+                .iter()
+                .map(|a| a.water_after as i64 - a.water_before as i64)
+                .sum(),
+            failed_tasks: accounts.iter().map(|a| a.failed_tasks().len()).sum(),
+            task_errors: accounts.iter().map(|a| a.task_errors.len()).sum(),
+            expired: self.expired_accounts().len(),
+            harvest_ready: accounts.iter().filter(|a| a.harvest_ready).count(),
+            prizes: accounts.iter().map(|a| a.prizes.len()).sum(),
+            risk_control: accounts.iter().map(|a| a.risk_control).sum(),
+        }
+    }
+
+    // 账号排行榜, 每项最多列出LEADERBOARD_SIZE个账号, 数值为0的账号不上榜
+    pub fn leaderboard(&self) -> Leaderboard {
+        fn top<T: PartialOrd>(mut items: Vec<Ranked<T>>) -> Vec<Ranked<T>> {
+            // 稳定排序, 数值相同时按账号顺序
+            items.sort_by(|a, b| {
+                b.value
+                    .partial_cmp(&a.value)
+                    .unwrap_or(std::cmp::Ordering::Equal)
+            });
+            items.truncate(LEADERBOARD_SIZE);
+            items
+        }
+        Leaderboard {
+            most_water: top(self
+                .accounts
+                .iter()
+                .filter(|a| a.water_gained() > 0)
+                .map(|a| Ranked {
+                    name: a.name.clone(),
+                    value: a.water_gained(),
+                })
+                .collect()),
+            closest_to_harvest: top(self
+                .accounts
+                .iter()
+                .filter(|a| !a.harvest_ready && a.tree_total_energy > 0)
+                .map(|a| Ranked {
+                    name: a.name.clone(),
+                    value: (a.tree_progress() * 10.0).round() / 10.0,
+                })
+                .collect()),
+            most_failures: top(self
+                .accounts
+                .iter()
+                .filter(|a| a.failures() > 0)
+                .map(|a| Ranked {
+                    name: a.name.clone(),
+                    value: a.failures(),
+                })
+                .collect()),
+        }
+    }
+
+    // 多账号时的合计及排行榜, 单个账号时为空
+    fn fleet_lines(&self) -> Vec<String> {
+        if self.accounts.len() <= 1 {
+            return Vec::new();
+        }
+        let totals = self.totals();
+        let mut lines = vec![t!(
+            "合计: 运行成功{}个, 剩余水滴净增{}g, 失败任务{}个, 出错任务{}个, 果树成熟{}个, 奖品{}个, 触发风控{}次",
+            totals.succeeded,
+            totals.net_water,
+            totals.failed_tasks,
+            totals.task_errors,
+            totals.harvest_ready,
+            totals.prizes,
+            totals.risk_control
+        )];
+        lines.extend(self.leaderboard().to_lines());
+        lines
+    }
+
+    // cookie已失效的账号
+    pub fn expired_accounts(&self) -> Vec<&AccountReport> {
+        self.accounts.iter().filter(|a| a.cookie_expired).collect()
+    }
+
+    // 所有账号中最高的严重程度
+    pub fn severity(&self) -> Severity {
+        self.accounts
+            .iter()
+            .map(|a| a.severity())
+            .max()
+            .unwrap_or(Severity::Info)
+    }
+
+    // 通知标题
+    pub fn title(&self) -> String {
+        t!("东东农场")
+    }
+
+    // 纯文本摘要
+    pub fn to_text(&self) -> String {
+        let mut lines: Vec<String> = self
+            .accounts
+            .iter()
+            .map(|a| a.summary(self.daily_gain(a)))
+            .collect();
+        lines.push(t!(
+            "共{}个账号, 获得水滴: {}g, cookie失效: {}个",
+            self.accounts.len(),
+            self.water_gained(),
+            self.expired_accounts().len()
+        ));
+        lines.extend(self.fleet_lines());
+        if self.interrupted {
+            lines.push(t!("运行被中断, 部分任务未执行"));
+        }
+        if self.simulated {
+            lines.push(t!("模拟运行, 以上数据均为虚构"));
+        }
+        if self.accounts.len() > 1 {
+            let failed: Vec<&str> = self
+                .accounts
+                .iter()
+                .filter(|a| a.severity() != Severity::Info)
+                .map(|a| a.name.as_str())
+                .collect();
+            if !failed.is_empty() {
+                lines.push(t!("需关注账号: {}", failed.join(&t!("、"))));
+            }
+            let expired: Vec<&str> = self
+                .expired_accounts()
+                .iter()
+                .map(|a| a.name.as_str())
+                .collect();
+            if !expired.is_empty() {
+                lines.push(t!("cookie失效账号: {}", expired.join(&t!("、"))));
+            }
+        }
+        let worst: Vec<String> = self
+            .worst_endpoints()
+            .iter()
+            .map(|(f, s)| t!("{}({}/{}成功)", f, s.success, s.total()))
+            .collect();
+        if !worst.is_empty() {
+            lines.push(t!("异常接口: {}", worst.join(&t!("、"))));
+        }
+        if let Some(trends) = &self.trends {
+            lines.push(trends.to_text());
+        }
+        lines.join("\n")
+    }
+
+    // 终端表格, 各列按显示宽度对齐
+    pub fn to_table(&self) -> String {
+        let header: Vec<String> = [
+            "账号",
+            "运行前(g)",
+            "运行后(g)",
+            "净增(g)",
+            "获得(g)",
+            "果树进度",
+            "成熟(天)",
+            "失败任务",
+            "状态",
+        ]
+        .iter()
+        .map(|h| t!(h))
+        .collect();
+        let mut rows = vec![header];
+        for account in &self.accounts {
+            rows.push(vec![
+                account.name.clone(),
+                account.water_before.to_string(),
+                account.water_after.to_string(),
+                (account.water_after as i64 - account.water_before as i64).to_string(),
+                account.water_gained().to_string(),
+                progress_cell(account),
+                self.harvest_eta_days(account)
+                    .map(|d| format!("{:.0}", d.ceil()))
+                    .unwrap_or_else(|| "-".to_string()),
+                account.failed_tasks().len().to_string(),
+                account.status(),
+            ]);
+        }
+
+        let columns = rows[0].len();
+        let widths: Vec<usize> = (0..columns)
+            .map(|i| rows.iter().map(|r| r[i].width()).max().unwrap_or(0))
+            .collect();
+        let separator: String = widths
+            .iter()
+            .map(|w| "-".repeat(w + 2))
+            .collect::<Vec<_>>()
+            .join("+");
+
+        let mut lines = Vec::new();
+        for (index, row) in rows.iter().enumerate() {
+            let cells: Vec<String> = row
+                .iter()
+                .zip(&widths)
+                .enumerate()
+                .map(|(i, (cell, width))| {
+                    let padding = " ".repeat(width - cell.width());
+                    // 账号和状态左对齐, 数字右对齐
+                    match i == 0 || i == columns - 1 || index == 0 {
+                        true => format!(" {}{} ", cell, padding),
+                        false => format!(" {}{} ", padding, cell),
+                    }
+                })
+                .collect();
+            lines.push(cells.join("|").trim_end().to_string());
+            if index == 0 {
+                lines.push(separator.clone());
+            }
+        }
+        lines.push(separator);
+        lines.push(t!(
+            "共{}个账号, 获得水滴: {}g, cookie失效: {}个",
+            self.accounts.len(),
+            self.water_gained(),
+            self.expired_accounts().len()
+        ));
+        lines.extend(self.fleet_lines());
+        lines.join("\n")
+    }
+
+    // Markdown格式摘要, 每个账号一行
+    pub fn to_markdown(&self) -> String {
+        let mut lines = vec![format!("### {}", self.title())];
+        for account in &self.accounts {
+            lines.push(format!("- {}", account.summary(self.daily_gain(account))));
+        }
+        lines.push(t!(
+            "\n**共{}个账号, 获得水滴: {}g, cookie失效: {}个**",
+            self.accounts.len(),
+            self.water_gained(),
+            self.expired_accounts().len()
+        ));
+        for line in self.fleet_lines() {
+            lines.push(format!("- {}", line));
+        }
+        let worst: Vec<String> = self
+            .worst_endpoints()
+            .iter()
+            .map(|(f, s)| t!("`{}`({}/{}成功)", f, s.success, s.total()))
+            .collect();
+        if !worst.is_empty() {
+            lines.push(t!("\n异常接口: {}", worst.join(&t!("、"))));
+        }
+        if let Some(trends) = &self.trends {
+            lines.push(trends.to_text());
+        }
+        lines.join("\n")
+    }
+
+    // HTML格式摘要, 每个账号一行
+    pub fn to_html(&self) -> String {
+        let mut html = String::from(
+            tr("<table border=\"1\" cellspacing=\"0\" cellpadding=\"4\"><tr><th>账号</th><th>获得水滴(g)</th><th>剩余水滴(g)</th><th>果树进度</th><th>失败任务</th><th>状态</th></tr>"),
+        );
+        for account in &self.accounts {
+            let failed: Vec<&str> = account.failed_tasks().iter().map(|t| tr(&t.name)).collect();
+            html.push_str(&format!(
+                "<tr><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td></tr>",
+                escape_html(&account.name),
+                account.water_gained(),
+                account.water_after,
+                escape_html(&progress_cell(account)),
+                escape_html(&failed.join(&t!("、"))),
+                escape_html(&account.status())
+            ));
+        }
+        html.push_str("</table>");
+        html.push_str(&t!(
+            "<p>共{}个账号, 获得水滴: {}g, cookie失效: {}个</p>",
+            self.accounts.len(),
+            self.water_gained(),
+            self.expired_accounts().len()
+        ));
+        if let Some(trends) = &self.trends {
+            html.push_str(&format!(
+                "<p>{}</p>",
+                escape_html(&trends.to_text()).replace('\n', "<br>")
+            ));
+        }
+        html
+    }
+}
+
+// 表格中的果树进度, 如85.3%(还需1200g)
+pub(crate) fn progress_cell(account: &AccountReport) -> String {
+    if account.tree_total_energy == 0 {
+        return "-".to_string();
+    }
+    if account.harvest_ready {
+        return t!("已成熟");
+    }
+    t!(
+        "{}%(还需{}g)",
+        format!("{:.1}", account.tree_progress()),
+        account.tree_remaining()
+    )
+}
+
+// 转义HTML特殊字符
+pub(crate) fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
@@ -0,0 +1,303 @@
+use std::collections::BTreeMap;
+use std::env;
+use std::net::SocketAddr;
+use std::sync::Arc;
+
+use anyhow::{anyhow, Result};
+use axum::extract::ws::{Message, WebSocket, WebSocketUpgrade};
+use axum::extract::{Path, Query, State};
+use axum::http::{header::AUTHORIZATION, Request, StatusCode};
+use axum::middleware::{self, Next};
+use axum::response::{IntoResponse, Response};
+use axum::routing::{delete, get, post};
+use axum::{Json, Router};
+use serde::Deserialize;
+use serde_json::json;
+use tokio::sync::broadcast::{self, error::RecvError};
+use tracing::info;
+
+use crate::control::Controller;
+use crate::event::RunEvent;
+use crate::logging;
+use crate::options::RunOptions;
+use crate::redact::Secret;
+
+// HTTP接口服务, 提供触发运行、查询结果、管理账号及查询运行历史的接口, 可作为Web界面的后端
+pub struct RestServer {
+    addr: SocketAddr,
+    token: Option<Secret>,
+}
+
+#[derive(Clone)]
+struct AppState {
+    controller: Arc<Controller>,
+    token: Option<Arc<Secret>>,
+}
+
+impl RestServer {
+    pub fn new(addr: SocketAddr) -> Self {
+        Self { addr, token: None }
+    }
+
+    // 设置访问令牌, 请求需携带`Authorization: Bearer <token>`
+    pub fn token(mut self, token: &str) -> Self {
+        self.token = Some(Secret::new(token));
+        self
+    }
+
+    // API_TOKEN: 访问令牌, 只监听本机地址时可不设置
+    pub fn with_env(mut self) -> Self {
+        if let Some(token) = env::var("API_TOKEN").ok().filter(|t| !t.is_empty()) {
+            self = self.token(&token);
+        }
+        self
+    }
+
+    // 启动服务, 直到出错才返回, 未设置访问令牌时只允许监听本机地址
+    pub async fn serve(self, controller: Arc<Controller>) -> Result<()> {
+        if self.token.is_none() && !self.addr.ip().is_loopback() {
+            return Err(anyhow!(t!(
+                "监听非本机地址{}时必须设置API_TOKEN",
+                self.addr
+            )));
+        }
+        let state = AppState {
+            controller,
+            token: self.token.map(Arc::new),
+        };
+        let app = Router::new()
+            .route("/api/runs", post(trigger_run))
+            .route("/api/status", get(status))
+            .route("/api/reports/latest", get(latest_report))
+            .route("/api/accounts", get(list_accounts).post(add_account))
+            .route("/api/accounts/:name", delete(remove_account))
+            .route("/api/history", get(history))
+            .route("/api/health/accounts", get(account_health))
+            .route("/api/events", get(events))
+            .route("/api/log-levels", get(log_levels).put(set_log_levels))
+            .layer(middleware::from_fn_with_state(state.clone(), auth))
+            .with_state(state);
+        info!("{}", t!("HTTP接口已启动: http://{}", self.addr));
+        axum::Server::bind(&self.addr)
+            .serve(app.into_make_service())
+            .await?;
+        Ok(())
+    }
+}
+
+// 校验访问令牌, 浏览器无法为WebSocket设置请求头, 也可通过`?token=<token>`传入
+async fn auth<B>(State(state): State<AppState>, request: Request<B>, next: Next<B>) -> Response {
+    if let Some(token) = &state.token {
+        let header = request
+            .headers()
+            .get(AUTHORIZATION)
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.strip_prefix("Bearer "));
+        let query = request
+            .uri()
+            .query()
+            .unwrap_or_default()
+            .split('&')
+            .find_map(|p| p.strip_prefix("token="))
+            .and_then(|t| urlencoding::decode(t).ok());
+        let valid = |t: &str| constant_time_eq(t.as_bytes(), token.expose().as_bytes());
+        if !header.is_some_and(valid) && !query.is_some_and(|t| valid(&t)) {
+            return (StatusCode::UNAUTHORIZED, t!("访问令牌无效")).into_response();
+        }
+    }
+    next.run(request).await
+}
+
+// 比较令牌, 耗时与内容无关, 避免通过响应时间逐字节猜测令牌
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0, |diff, (x, y)| diff | (x ^ y)) == 0
+}
+
+#[derive(Deserialize, Default)]
+#[serde(default)]
+struct TriggerRun {
+    // 只运行这些账号, 为空时运行所有账号
+    accounts: Vec<String>,
+    // 试运行
+    dry_run: bool,
+}
+
+// POST /api/runs: 触发一次运行
+async fn trigger_run(
+    State(state): State<AppState>,
+    body: Option<Json<TriggerRun>>,
+) -> impl IntoResponse {
+    let body = body.map(|Json(b)| b).unwrap_or_default();
+    let mut options = RunOptions::from_env();
+    options.dry_run = options.dry_run || body.dry_run;
+    match state.controller.trigger(&body.accounts, options) {
+        true => (
+            StatusCode::ACCEPTED,
+            Json(json!({ "started": true, "message": t!("已开始运行") })),
+        ),
+        false => (
+            StatusCode::CONFLICT,
+            Json(json!({ "started": false, "message": t!("上一次运行尚未结束") })),
+        ),
+    }
+}
+
+// GET /api/status: 是否正在运行及最近一次运行结果
+async fn status(State(state): State<AppState>) -> impl IntoResponse {
+    Json(json!({
+        "running": state.controller.is_running(),
+        "report": state.controller.last_report(),
+    }))
+}
+
+// GET /api/reports/latest: 最近一次运行结果, 尚未运行时返回404
+async fn latest_report(State(state): State<AppState>) -> Response {
+    match state.controller.last_report() {
+        Some(report) => Json(report).into_response(),
+        None => StatusCode::NOT_FOUND.into_response(),
+    }
+}
+
+// GET /api/accounts: 已配置的账号
+async fn list_accounts(State(state): State<AppState>) -> impl IntoResponse {
+    let names: Vec<String> = state
+        .controller
+        .accounts()
+        .iter()
+        .map(|a| a.name())
+        .collect();
+    Json(names)
+}
+
+#[derive(Deserialize)]
+struct NewAccount {
+    cookie: String,
+}
+
+// POST /api/accounts: 添加账号, 只在本次运行期间有效
+async fn add_account(State(state): State<AppState>, Json(account): Json<NewAccount>) -> Response {
+    match state.controller.add_account(&account.cookie) {
+        Some(name) => (StatusCode::CREATED, Json(json!({ "name": name }))).into_response(),
+        None => (StatusCode::CONFLICT, t!("cookie无效或账号已存在")).into_response(),
+    }
+}
+
+// DELETE /api/accounts/:name: 删除账号
+async fn remove_account(State(state): State<AppState>, Path(name): Path<String>) -> StatusCode {
+    match state.controller.remove_account(&name) {
+        true => StatusCode::NO_CONTENT,
+        false => StatusCode::NOT_FOUND,
+    }
+}
+
+// GET /api/log-levels: 各子系统单独设置的日志级别
+async fn log_levels() -> impl IntoResponse {
+    Json(logging::levels())
+}
+
+// PUT /api/log-levels: 修改子系统的日志级别, 如`{"transport": "debug"}`, 级别为default时恢复使用RUST_LOG
+async fn set_log_levels(Json(levels): Json<BTreeMap<String, String>>) -> Response {
+    for (subsystem, level) in &levels {
+        if let Err(e) = logging::set_level(subsystem, level) {
+            return (StatusCode::BAD_REQUEST, e.to_string()).into_response();
+        }
+    }
+    Json(logging::levels()).into_response()
+}
+
+// GET /api/events: 通过WebSocket推送运行事件(JSON), 供浏览器实时展示各账号的任务进度
+async fn events(State(state): State<AppState>, ws: WebSocketUpgrade) -> Response {
+    let receiver = state.controller.subscribe();
+    ws.on_upgrade(move |socket| forward_events(socket, receiver))
+}
+
+// 转发运行事件, 客户端断开后结束
+async fn forward_events(mut socket: WebSocket, mut events: broadcast::Receiver<RunEvent>) {
+    loop {
+        tokio::select! {
+            event = events.recv() => {
+                let event = match event {
+                    Ok(event) => event,
+                    Err(RecvError::Lagged(_)) => continue,
+                    Err(RecvError::Closed) => break,
+                };
+                let Ok(text) = serde_json::to_string(&event) else {
+                    continue;
+                };
+                if socket.send(Message::Text(text)).await.is_err() {
+                    break;
+                }
+            }
+            message = socket.recv() => match message {
+                Some(Ok(Message::Close(_))) | Some(Err(_)) | None => break,
+                Some(Ok(_)) => continue,
+            },
+        }
+    }
+}
+
+#[derive(Deserialize)]
+#[cfg_attr(not(feature = "history"), allow(dead_code))]
+struct HistoryQuery {
+    // 查询最近几天, 默认7天
+    days: Option<i64>,
+}
+
+// GET /api/history?days=7: 最近几天的运行结果, 需启用history功能并设置HISTORY_DB
+#[cfg(feature = "history")]
+async fn history(Query(query): Query<HistoryQuery>) -> Response {
+    use chrono::{Duration, Local};
+
+    use crate::history::History;
+
+    let history = match History::from_env() {
+        Some(Ok(history)) => history,
+        Some(Err(e)) => return (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()).into_response(),
+        None => {
+            return (StatusCode::NOT_IMPLEMENTED, t!("未设置环境变量HISTORY_DB.")).into_response()
+        }
+    };
+    // 天数过大时计算时间会溢出, 限制在约100年内
+    let days = query.days.unwrap_or(7).clamp(0, 36500);
+    let since = Local::now() - Duration::days(days);
+    match history.runs_since(since) {
+        Ok(runs) => Json(runs).into_response(),
+        Err(e) => (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()).into_response(),
+    }
+}
+
+// GET /api/health/accounts: 各账号的健康状态, 需启用history功能并设置HISTORY_DB
+#[cfg(feature = "history")]
+async fn account_health() -> Response {
+    use crate::history::History;
+
+    let history = match History::from_env() {
+        Some(Ok(history)) => history,
+        Some(Err(e)) => return (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()).into_response(),
+        None => {
+            return (StatusCode::NOT_IMPLEMENTED, t!("未设置环境变量HISTORY_DB.")).into_response()
+        }
+    };
+    match history.health() {
+        Ok(accounts) => Json(accounts).into_response(),
+        Err(e) => (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()).into_response(),
+    }
+}
+
+#[cfg(not(feature = "history"))]
+async fn account_health() -> Response {
+    (
+        StatusCode::NOT_IMPLEMENTED,
+        t!("未启用history功能, 请使用--features history重新编译."),
+    )
+        .into_response()
+}
+
+#[cfg(not(feature = "history"))]
+async fn history(Query(_query): Query<HistoryQuery>) -> Response {
+    (
+        StatusCode::NOT_IMPLEMENTED,
+        t!("未启用history功能, 请使用--features history重新编译."),
+    )
+        .into_response()
+}
@@ -0,0 +1,722 @@
+use std::any::Any;
+use std::collections::{HashMap, HashSet};
+use std::env;
+use std::panic::AssertUnwindSafe;
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use chrono::Local;
+use futures::future::OptionFuture;
+use futures::FutureExt;
+use jd_com::account::JAccount;
+use tokio::sync::broadcast;
+use tokio::task::JoinSet;
+use tracing::instrument::WithSubscriber;
+use tracing::subscriber::NoSubscriber;
+use tracing::{info, info_span, Instrument};
+
+use crate::accounts::{self, AccountPriority, RiskCooldown};
+use crate::assist::{self, AssistStore};
+use crate::device::Device;
+use crate::error::ErrorPolicy;
+use crate::error_codes;
+use crate::event::{self, RunEvent};
+use crate::export::{CsvExporter, IcsExporter, ReportFile};
+use crate::health;
+#[cfg(feature = "history")]
+use crate::history::History;
+use crate::hooks::{Hooks, PostRunCommand};
+use crate::lock::RunLock;
+#[cfg(feature = "metrics")]
+use crate::metrics;
+#[cfg(feature = "mqtt")]
+use crate::mqtt::MqttPublisher;
+use crate::network::{AccountRetry, NetworkLimiter, NetworkPolicy};
+use crate::notify::Dispatcher;
+use crate::options::RunOptions;
+use crate::plugin::{self, FarmTask, FarmTasks};
+use crate::redact;
+use crate::report::{AccountReport, RunReport, TaskReport};
+use crate::shutdown;
+use crate::simulate::Simulator;
+use crate::state::{self, StateStore};
+#[cfg(feature = "history")]
+use crate::trend::Trends;
+use crate::version;
+use crate::JClient;
+
+// 运行事件通道容量, 订阅者处理过慢时会丢失最早的事件
+const EVENT_CAPACITY: usize = 1024;
+
+// 多账号运行器, 运行结束后自动发送通知
+pub struct Runner {
+    accounts: Vec<JAccount>,
+    // 各账号的备用cookie, 主cookie失效时依次改用
+    backups: HashMap<String, Vec<JAccount>>,
+    dispatcher: Arc<Dispatcher>,
+    // 果树成熟时是否立即发送提醒
+    harvest_alert: bool,
+    // 任务出错时的默认处理策略
+    error_policy: ErrorPolicy,
+    // 单独设置处理策略的账号
+    account_policies: HashMap<String, ErrorPolicy>,
+    // 请求模拟的默认设备
+    device: Device,
+    // 单独设置设备的账号
+    account_devices: HashMap<String, Device>,
+    // 各账号的运行选项
+    options: RunOptions,
+    // 外部任务
+    tasks: FarmTasks,
+    // 助力码池
+    assist: Option<Arc<dyn AssistStore>>,
+    // 请求频率、并发数限制及网络错误重试
+    network: NetworkPolicy,
+    // 运行结束时重试因临时错误失败的账号, 为空时不重试
+    retry: Option<AccountRetry>,
+    // 账号的运行优先级
+    priority: AccountPriority,
+    // 触发风控的账号暂停运行, 为空时不暂停
+    cooldown: Option<RiskCooldown>,
+    // 保存风控暂停状态的存储, 为空时运行时使用accounts::cooldown_store
+    cooldown_state: Option<Arc<dyn StateStore>>,
+    // 各账号在该时间窗口内分散开始运行, 为空时同时开始
+    spread: Option<Duration>,
+    // 运行状态存储
+    state: Arc<dyn StateStore>,
+    // 模拟的接口, 设置后不发送任何网络请求
+    simulator: Option<Arc<Simulator>>,
+    events: broadcast::Sender<RunEvent>,
+    hooks: Hooks,
+    // 静默模式, 不输出任何日志
+    quiet: bool,
+}
+
+impl Runner {
+    pub fn new(accounts: Vec<JAccount>) -> Self {
+        Self {
+            accounts,
+            backups: HashMap::new(),
+            dispatcher: Arc::new(Dispatcher::from_env()),
+            harvest_alert: harvest_alert_from_env(),
+            error_policy: ErrorPolicy::from_env(),
+            account_policies: ErrorPolicy::accounts_from_env(),
+            device: Device::from_env(),
+            account_devices: Device::accounts_from_env(),
+            options: RunOptions::from_env(),
+            tasks: plugin::builtin_from_env(),
+            assist: assist::from_env(),
+            network: NetworkPolicy::from_env(),
+            retry: AccountRetry::from_env(),
+            priority: AccountPriority::from_env(),
+            cooldown: RiskCooldown::from_env(),
+            cooldown_state: None,
+            spread: None,
+            state: state::from_env_or_memory(),
+            simulator: Simulator::from_env().map(Arc::new),
+            events: broadcast::channel(EVENT_CAPACITY).0,
+            hooks: Hooks::default(),
+            quiet: false,
+        }
+    }
+
+    // 设置各账号的备用cookie(accounts::backups), 运行中主cookie失效时依次改用, 继续执行剩余的任务
+    pub fn cookie_pool(mut self, backups: HashMap<String, Vec<JAccount>>) -> Self {
+        self.backups = backups;
+        self
+    }
+
+    // 订阅运行事件, 需在run之前调用
+    pub fn subscribe(&self) -> broadcast::Receiver<RunEvent> {
+        self.events.subscribe()
+    }
+
+    // 开始执行任务时调用, 参数为账号名称、任务名称
+    pub fn on_task_start<F: Fn(&str, &str) + Send + Sync + 'static>(mut self, f: F) -> Self {
+        self.hooks.on_task_start(f);
+        self
+    }
+
+    // 任务执行结束(成功或失败)时调用, 参数为账号名称、任务结果
+    pub fn on_task_complete<F: Fn(&str, &TaskReport) + Send + Sync + 'static>(
+        mut self,
+        f: F,
+    ) -> Self {
+        self.hooks.on_task_complete(f);
+        self
+    }
+
+    // 账号运行中断或cookie失效时调用, 参数为账号名称、错误描述
+    pub fn on_error<F: Fn(&str, &str) + Send + Sync + 'static>(mut self, f: F) -> Self {
+        self.hooks.on_error(f);
+        self
+    }
+
+    // 静默模式下不输出任何日志, 只通过返回的运行结果及事件通信, 适用于自行展示结果的机器人、Web服务等
+    pub fn quiet(mut self, quiet: bool) -> Self {
+        self.quiet = quiet;
+        self
+    }
+
+    // 替换通知分发器
+    pub fn with_dispatcher(mut self, dispatcher: Dispatcher) -> Self {
+        self.dispatcher = Arc::new(dispatcher);
+        self
+    }
+
+    // 果树成熟时是否立即发送提醒, 默认读取HARVEST_ALERT
+    pub fn harvest_alert(mut self, enabled: bool) -> Self {
+        self.harvest_alert = enabled;
+        self
+    }
+
+    // 任务出错时的默认处理策略, 默认读取ERROR_POLICY
+    pub fn error_policy(mut self, policy: ErrorPolicy) -> Self {
+        self.error_policy = policy;
+        self
+    }
+
+    // 单独设置指定账号的处理策略, 默认读取ERROR_POLICY_ACCOUNTS
+    pub fn account_error_policy(mut self, name: &str, policy: ErrorPolicy) -> Self {
+        self.account_policies.insert(name.to_string(), policy);
+        self
+    }
+
+    // 请求模拟的默认设备, 默认读取DEVICE
+    pub fn device(mut self, device: Device) -> Self {
+        self.device = device;
+        self
+    }
+
+    // 单独设置指定账号模拟的设备, 默认读取DEVICE_ACCOUNTS
+    pub fn account_device(mut self, name: &str, device: Device) -> Self {
+        self.account_devices.insert(name.to_string(), device);
+        self
+    }
+
+    // 各账号的运行选项, 默认从环境变量读取
+    pub fn options(mut self, options: RunOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    // 注册外部任务, 在内置任务之后按注册顺序执行
+    pub fn register_task<T: FarmTask + 'static>(mut self, task: T) -> Self {
+        self.tasks.push(Arc::new(task));
+        self
+    }
+
+    // 设置外部任务, 替换已注册的任务
+    pub fn tasks(mut self, tasks: FarmTasks) -> Self {
+        self.tasks = tasks;
+        self
+    }
+
+    // 设置助力码池, 各账号互相助力, 默认在设置REDIS_URL时使用Redis
+    pub fn assist(mut self, store: Arc<dyn AssistStore>) -> Self {
+        self.assist = Some(store);
+        self
+    }
+
+    // 网络策略, 所有账号共享请求频率及并发数限制, 默认从环境变量读取
+    pub fn network(mut self, policy: NetworkPolicy) -> Self {
+        self.network = policy;
+        self
+    }
+
+    // 运行结束时重试因网络错误、超时等临时错误失败的账号, 为空时不重试, 默认读取RETRY_FAILED_ACCOUNTS
+    pub fn retry_failed(mut self, retry: Option<AccountRetry>) -> Self {
+        self.retry = retry;
+        self
+    }
+
+    // 账号的运行优先级, 优先级高的账号先开始运行并优先重试, 默认读取ACCOUNT_PRIORITY
+    pub fn priority(mut self, priority: AccountPriority) -> Self {
+        self.priority = priority;
+        self
+    }
+
+    // 触发风控或需要安全验证的账号暂停运行一段时间, 为空时不暂停, 默认读取RISK_COOLDOWN
+    pub fn risk_cooldown(mut self, cooldown: Option<RiskCooldown>) -> Self {
+        self.cooldown = cooldown;
+        self
+    }
+
+    // 各账号在时间窗口内均匀分散开始运行并加入随机抖动, 避免所有账号同时请求
+    pub fn spread(mut self, window: Duration) -> Self {
+        self.spread = Some(window);
+        self
+    }
+
+    // 运行状态存储, 保存检查点、已浇水的好友等, 默认读取STATE_STORE, 未设置时保存在内存中
+    // 设置后风控暂停状态也保存在该存储中
+    pub fn state(mut self, store: Arc<dyn StateStore>) -> Self {
+        self.cooldown_state = Some(store.clone());
+        self.state = store;
+        self
+    }
+
+    // 模拟运行, 使用模拟的接口执行所有任务, 不发送任何网络请求, 默认读取SIMULATE
+    pub fn simulate(mut self, simulator: Simulator) -> Self {
+        self.simulator = Some(Arc::new(simulator));
+        self
+    }
+
+    // 按运行器的设置为单个账号创建客户端, 与运行时该账号使用的客户端相同, 如常驻运行时单独执行水滴雨任务
+    pub(crate) fn client(mut self, account: JAccount) -> JClient {
+        let network = Arc::new(NetworkLimiter::new(self.network.clone()));
+        let options = self.options.clone();
+        let client = self.client_factory(network).build(account);
+        *client.options.lock().unwrap() = options;
+        client
+    }
+
+    // 取出创建客户端使用的设置
+    fn client_factory(&mut self, network: Arc<NetworkLimiter>) -> ClientFactory {
+        ClientFactory {
+            error_policy: self.error_policy,
+            account_policies: std::mem::take(&mut self.account_policies),
+            device: self.device,
+            account_devices: std::mem::take(&mut self.account_devices),
+            tasks: std::mem::take(&mut self.tasks),
+            assist: self.assist.clone(),
+            backups: std::mem::take(&mut self.backups),
+            network,
+            state: self.state.clone(),
+            simulator: self.simulator.clone(),
+        }
+    }
+
+    // 运行所有账号
+    pub async fn run(self) -> RunReport {
+        // 运行期间持续发送运行器心跳, 耗时较长的运行不会被systemd看门狗误判为卡死
+        match self.quiet {
+            true => {
+                health::beating(self.run_all())
+                    .with_subscriber(NoSubscriber::default())
+                    .await
+            }
+            false => health::beating(self.run_all()).await,
+        }
+    }
+
+    async fn run_all(mut self) -> RunReport {
+        let started_at = Local::now();
+        let network = Arc::new(NetworkLimiter::new(self.network.clone()));
+        version::restore(self.state.as_ref()).await;
+        let names: Vec<String> = self.accounts.iter().map(|a| a.name()).collect();
+        let progress = match self.priority.near_harvest {
+            true => accounts::load_progress(self.state.as_ref(), &names).await,
+            false => HashMap::new(),
+        };
+        // 因触发风控暂停中的账号本次跳过, 到期后自动恢复运行
+        let cooldown_state = self.cooldown.map(|_| {
+            self.cooldown_state
+                .take()
+                .unwrap_or_else(accounts::cooldown_store)
+        });
+        let paused = match (&self.cooldown, &cooldown_state) {
+            (Some(cooldown), Some(store)) => cooldown.paused(store.as_ref(), &names).await,
+            _ => HashMap::new(),
+        };
+        // 按优先级依次开始运行, 结果仍按账号顺序输出
+        let order: Vec<usize> = self
+            .priority
+            .order(&names, &progress)
+            .into_iter()
+            .filter(|index| !paused.contains_key(&names[*index]))
+            .collect();
+        let paused_reports: Vec<(usize, AccountReport)> = names
+            .iter()
+            .enumerate()
+            .filter_map(|(index, name)| {
+                let until = paused.get(name)?;
+                info!(
+                    "{}",
+                    t!(
+                        "{}, 触发风控, 暂停运行至{}, 本次跳过",
+                        name,
+                        until.format("%m-%d %H:%M")
+                    )
+                );
+                let mut report = AccountReport::new(name);
+                report.paused_until = Some(*until);
+                Some((index, report))
+            })
+            .collect();
+        let simulated = self.simulator.is_some();
+        // 在时间窗口内按运行顺序分散开始, 未设置时同时开始
+        let starts = match self.spread.filter(|_| order.len() > 1) {
+            Some(window) => {
+                info!(
+                    "{}",
+                    t!(
+                        "{}个账号在{}分钟内分散运行",
+                        order.len(),
+                        window.as_secs() / 60
+                    )
+                );
+                spread_starts(window, order.len())
+            }
+            None => vec![Duration::ZERO; order.len()],
+        };
+        let hooks = match self.hooks.is_empty() {
+            true => None,
+            false => Some(self.hooks.clone().consume(self.events.subscribe())),
+        };
+        let ndjson = event::is_ndjson().then(|| event::write_ndjson(self.events.subscribe()));
+
+        let harvest_alert = self.harvest_alert;
+        let dispatcher = self.dispatcher.clone();
+        let factory = Arc::new(self.client_factory(network));
+        let progress_state = self.state.clone();
+        let events = self.events;
+        let spawn_events = events.clone();
+        let spawn = move |set: &mut JoinSet<(usize, AccountReport)>,
+                          index: usize,
+                          account: JAccount,
+                          options: RunOptions,
+                          start: Duration| {
+            let name = account.name().to_string();
+            let span = info_span!("account", account = %name);
+            let events = spawn_events.clone();
+            let dispatcher = dispatcher.clone();
+            let factory = factory.clone();
+            let account_name = name.clone();
+            let panic_events = spawn_events.clone();
+            let future = async move {
+                // 按分散运行的时间窗口等待开始, 模拟运行时不等待
+                if !start.is_zero() && !simulated {
+                    tokio::select! {
+                        _ = tokio::time::sleep(start) => {}
+                        _ = shutdown::requested() => {}
+                    }
+                }
+                // 账号正在被另一个实例运行时本次跳过, 锁在该账号运行结束后释放, 模拟运行不加锁
+                let _lock = match RunLock::for_account(&name).filter(|_| !simulated) {
+                    Some(Err(e)) => {
+                        info!("{}", e);
+                        let mut report = AccountReport::new(&name);
+                        report.running_elsewhere = true;
+                        let _ = events.send(RunEvent::AccountFinished {
+                            report: Box::new(report.clone()),
+                        });
+                        return report;
+                    }
+                    lock => lock,
+                };
+                // 超出同时运行的账号数上限时等待其他账号运行结束
+                let _permit = factory.network.account().await;
+                let client = factory.build(account).with_events(events);
+                let report = match client.run_with(options).await {
+                    Ok(report) => report,
+                    Err(e) => {
+                        let mut report = AccountReport::new(&name);
+                        report.error = Some(redact::scrub(&e.to_string()));
+                        report.advice = error_codes::advice(&e);
+                        report
+                    }
+                };
+                // 果树成熟不等待其他账号运行结束, 立即提醒, 没有通知渠道时不记录提醒状态
+                if harvest_alert && !dispatcher.is_empty() && should_alert(&report) {
+                    let (title, content) = report.harvest_alert();
+                    dispatcher.alert(&title, &content).await;
+                }
+                // 需要安全验证时立即提醒, 附上验证页面地址, 不受HARVEST_ALERT影响
+                if report.verification_url.is_some() && !dispatcher.is_empty() {
+                    let (title, content) = report.verification_alert();
+                    dispatcher.alert(&title, &content).await;
+                }
+                report
+            }
+            .instrument(span);
+            // 单个账号panic时记录到该账号的运行结果, 不影响其他账号
+            let future = AssertUnwindSafe(future).catch_unwind().map(move |res| {
+                let report = res.unwrap_or_else(|panic| {
+                    let report = panic_report(&account_name, panic);
+                    info!(
+                        "{}, {}",
+                        account_name,
+                        report.error.as_deref().unwrap_or_default()
+                    );
+                    let _ = panic_events.send(RunEvent::AccountFinished {
+                        report: Box::new(report.clone()),
+                    });
+                    report
+                });
+                (index, report)
+            });
+            // 各账号在独立的任务中并行执行, 同时运行的账号数受max_concurrent_accounts限制
+            // 运行器被丢弃时JoinSet会取消所有账号的任务, 静默模式的日志设置也会传递给各任务
+            set.spawn(future.with_current_subscriber());
+        };
+
+        let accounts = self.accounts;
+        let options = self.options;
+        let retry = self.retry;
+        let run_accounts = async move {
+            let mut set = JoinSet::new();
+            for (index, start) in order.iter().copied().zip(starts) {
+                spawn(
+                    &mut set,
+                    index,
+                    accounts[index].clone(),
+                    options.clone(),
+                    start,
+                );
+            }
+            let mut reports = join_all(&mut set).await;
+            for (index, report) in paused_reports {
+                let _ = events.send(RunEvent::AccountFinished {
+                    report: Box::new(report.clone()),
+                });
+                reports.push((index, report));
+            }
+            reports.sort_by_key(|(index, _)| *index);
+
+            // 因临时错误失败的账号在所有账号运行结束后按优先级重试一次, 放慢操作降低再次失败的概率
+            let failed: Vec<usize> = order
+                .iter()
+                .copied()
+                .filter(|index| {
+                    reports
+                        .iter()
+                        .any(|(i, report)| i == index && report.transient)
+                })
+                .collect();
+            if let Some(retry) = retry.filter(|_| !failed.is_empty() && !shutdown::is_requested()) {
+                info!(
+                    "{}",
+                    t!(
+                        "{}个账号因临时错误运行失败, {}秒后重试",
+                        failed.len(),
+                        retry.wait.as_secs_f64()
+                    )
+                );
+                // 模拟运行时不等待
+                if !simulated {
+                    tokio::select! {
+                        _ = tokio::time::sleep(retry.wait) => {}
+                        _ = shutdown::requested() => {}
+                    }
+                }
+                if !shutdown::is_requested() {
+                    let mut options = options.clone();
+                    options.delay = options.delay.scaled(retry.delay_scale);
+                    for index in failed {
+                        spawn(
+                            &mut set,
+                            index,
+                            accounts[index].clone(),
+                            options.clone(),
+                            Duration::ZERO,
+                        );
+                    }
+                    for (index, mut report) in join_all(&mut set).await {
+                        if let Some((_, first)) = reports.iter_mut().find(|(i, _)| *i == index) {
+                            report.merge_retry(std::mem::take(first));
+                            *first = report;
+                        }
+                    }
+                }
+            }
+            // 关闭事件通道, 回调处理完剩余事件后结束
+            drop(spawn);
+            drop(events);
+            reports
+                .into_iter()
+                .map(|(_, report)| report)
+                .collect::<Vec<AccountReport>>()
+        };
+        let accounts = tokio::join!(
+            run_accounts,
+            OptionFuture::from(hooks),
+            OptionFuture::from(ndjson)
+        )
+        .0;
+
+        let mut report = RunReport::new(started_at, accounts);
+        report.interrupted = shutdown::is_requested();
+        report.simulated = simulated;
+        // 保存果树进度, 下次运行时优先运行接近成熟的账号, 模拟运行的结果不保存
+        if !simulated {
+            accounts::save_progress(progress_state.as_ref(), &report.accounts).await;
+            if let (Some(cooldown), Some(store)) = (&self.cooldown, &cooldown_state) {
+                cooldown.record(store.as_ref(), &report.accounts).await;
+            }
+        }
+        // 模拟运行的结果不保存到运行历史
+        #[cfg(feature = "history")]
+        if let Some(history) = History::from_env().filter(|_| !report.simulated) {
+            match history {
+                Ok(mut history) => {
+                    match Trends::from_history(&history, &report) {
+                        Ok(trends) => report.trends = Some(trends),
+                        Err(e) => info!("{}", t!("计算收益趋势失败, {}", e)),
+                    }
+                    if let Err(e) = history.save(&report) {
+                        info!("{}", t!("保存运行历史失败, {}", e));
+                    }
+                }
+                Err(e) => info!("{}", t!("打开运行历史失败, {}", e)),
+            }
+        }
+        info!("{}", t!("运行结束:\n{}", report.to_text()));
+        #[cfg(feature = "metrics")]
+        metrics::record_report(&report);
+        #[cfg(feature = "sentry")]
+        crate::error_report::capture_report(&report);
+        #[cfg(feature = "metrics")]
+        if let Some(Err(e)) = metrics::write_textfile_from_env() {
+            info!("{}", t!("写入指标文件失败, {}", e));
+        }
+        if let Some(exporter) = CsvExporter::from_env() {
+            if let Err(e) = exporter.export(&report) {
+                info!("{}", t!("导出CSV失败, {}", e));
+            }
+        }
+        if let Some(exporter) = IcsExporter::from_env() {
+            if let Err(e) = exporter.export(&report) {
+                info!("{}", t!("导出日历失败, {}", e));
+            }
+        }
+        if let Some(file) = ReportFile::from_env() {
+            match file.write(&report) {
+                Ok(path) => info!("{}", t!("运行报告已写入: {}", path.display())),
+                Err(e) => info!("{}", t!("写入运行报告失败, {}", e)),
+            }
+        }
+
+        #[cfg(feature = "mqtt")]
+        if let Some(publisher) = MqttPublisher::from_env() {
+            if let Err(e) = publisher.publish(&report).await {
+                info!("{}", t!("发布MQTT消息失败, {}", e));
+            }
+        }
+
+        if !self.dispatcher.is_empty() {
+            self.dispatcher.dispatch(&report).await;
+        }
+        if let Some(command) = PostRunCommand::from_env() {
+            if let Err(e) = command.run(&report).await {
+                info!("{}", t!("执行运行后命令失败, {}", e));
+            }
+        }
+        report
+    }
+}
+
+// 为各账号创建客户端时使用的设置
+struct ClientFactory {
+    error_policy: ErrorPolicy,
+    account_policies: HashMap<String, ErrorPolicy>,
+    device: Device,
+    account_devices: HashMap<String, Device>,
+    tasks: FarmTasks,
+    assist: Option<Arc<dyn AssistStore>>,
+    backups: HashMap<String, Vec<JAccount>>,
+    network: Arc<NetworkLimiter>,
+    state: Arc<dyn StateStore>,
+    simulator: Option<Arc<Simulator>>,
+}
+
+impl ClientFactory {
+    fn build(&self, account: JAccount) -> JClient {
+        let name = account.name();
+        let mut client = JClient::new(account)
+            .with_error_policy(
+                self.account_policies
+                    .get(&name)
+                    .copied()
+                    .unwrap_or(self.error_policy),
+            )
+            .with_device(
+                self.account_devices
+                    .get(&name)
+                    .copied()
+                    .unwrap_or(self.device),
+            )
+            .with_tasks(self.tasks.clone())
+            .with_network(self.network.clone())
+            .with_state(self.state.clone());
+        if let Some(assist) = &self.assist {
+            client = client.with_assist(assist.clone());
+        }
+        if let Some(simulator) = &self.simulator {
+            client = client.with_simulator(simulator.clone());
+        }
+        if let Some(backups) = self.backups.get(&name) {
+            client = client.with_backup_cookies(backups.clone());
+        }
+        client
+    }
+}
+
+// 各账号在时间窗口内的开始时间, 窗口均分为n段, 每个账号在各自的一段内随机开始
+fn spread_starts(window: Duration, n: usize) -> Vec<Duration> {
+    let slot = window / n.max(1) as u32;
+    let mut seed = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_nanos() as u64)
+        .unwrap_or_default()
+        | 1;
+    (0..n)
+        .map(|i| {
+            // xorshift64
+            seed ^= seed << 13;
+            seed ^= seed >> 7;
+            seed ^= seed << 17;
+            let jitter = (seed >> 11) as f64 / (1u64 << 53) as f64;
+            slot * i as u32 + slot.mul_f64(jitter)
+        })
+        .collect()
+}
+
+// 等待所有账号运行结束, 按账号顺序返回结果
+async fn join_all(set: &mut JoinSet<(usize, AccountReport)>) -> Vec<(usize, AccountReport)> {
+    let mut reports = Vec::new();
+    while let Some(res) = set.join_next().await {
+        // 账号的任务已捕获panic, 只有被取消时没有结果
+        if let Ok(report) = res {
+            reports.push(report);
+        }
+    }
+    reports.sort_by_key(|(index, _)| *index);
+    reports
+}
+
+// panic的账号的运行结果
+fn panic_report(name: &str, panic: Box<dyn Any + Send>) -> AccountReport {
+    let message = panic
+        .downcast_ref::<&str>()
+        .map(|s| s.to_string())
+        .or_else(|| panic.downcast_ref::<String>().cloned())
+        .unwrap_or_default();
+    let mut report = AccountReport::new(name);
+    report.error = Some(redact::scrub(&t!("运行时出现异常(panic), {}", message)));
+    report
+}
+
+// HARVEST_ALERT: 果树成熟时是否立即发送提醒, 默认开启, 设置为false关闭
+fn harvest_alert_from_env() -> bool {
+    !matches!(
+        env::var("HARVEST_ALERT").as_deref().map(str::trim),
+        Ok("false") | Ok("0")
+    )
+}
+
+// 已发送成熟提醒的账号, 常驻运行时每次成熟只提醒一次, 领取后重新计算
+fn should_alert(report: &AccountReport) -> bool {
+    static ALERTED: OnceLock<Mutex<HashSet<String>>> = OnceLock::new();
+    let mut alerted = ALERTED.get_or_init(Default::default).lock().unwrap();
+    match report.harvest_ready {
+        true => alerted.insert(report.name.clone()),
+        false => {
+            // 运行失败时无法判断果树状态, 保留提醒记录
+            if report.is_ok() {
+                alerted.remove(&report.name);
+            }
+            false
+        }
+    }
+}
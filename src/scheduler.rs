@@ -0,0 +1,374 @@
+use std::env;
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, Local};
+use cron::Schedule;
+use tracing::info;
+
+use crate::accounts;
+use crate::health;
+use crate::options::RunOptions;
+use crate::plugin::{self, FarmTask, FarmTasks};
+use crate::runner::Runner;
+use crate::shutdown;
+
+// 一条定时规则
+#[derive(Debug, Clone)]
+pub struct ScheduleEntry {
+    // 原始cron表达式
+    pub expr: String,
+    schedule: Schedule,
+    // 只执行这些任务, 为空时执行所有任务
+    pub tasks: Vec<String>,
+    // 只运行这些账号, 为空时运行所有账号
+    pub accounts: Vec<String>,
+    // 各账号在触发后的该时间窗口内分散运行, 为空时使用调度器的设置
+    pub spread: Option<Duration>,
+}
+
+impl ScheduleEntry {
+    // expr为5段的标准cron表达式(分 时 日 月 星期), 按本地时间计算
+    pub fn new(expr: &str, tasks: &[&str], accounts: &[&str]) -> Result<Self> {
+        let expr = expr.split_whitespace().collect::<Vec<_>>().join(" ");
+        if expr.split(' ').count() != 5 {
+            return Err(anyhow!(
+                "{}",
+                t!("cron表达式需为5段(分 时 日 月 星期): {}", expr)
+            ));
+        }
+        // cron库的表达式以秒开始
+        let schedule = Schedule::from_str(&format!("0 {}", expr))
+            .map_err(|e| anyhow!("{}", t!("无效的cron表达式{}, {}", expr, e)))?;
+        Ok(Self {
+            expr,
+            schedule,
+            tasks: tasks.iter().map(|t| t.to_string()).collect(),
+            accounts: accounts.iter().map(|a| a.to_string()).collect(),
+            spread: None,
+        })
+    }
+
+    // 各账号在触发后的时间窗口内分散运行
+    pub fn spread(mut self, window: Duration) -> Self {
+        self.spread = Some(window);
+        self
+    }
+
+    // 解析`分 时 日 月 星期 [任务1,任务2] [@账号1,账号2] [~时间窗口]`
+    pub fn parse(s: &str) -> Result<Self> {
+        let parts: Vec<&str> = s.split_whitespace().collect();
+        if parts.len() < 5 {
+            return Err(anyhow!(
+                "{}",
+                t!("cron表达式需为5段(分 时 日 月 星期): {}", s.trim())
+            ));
+        }
+        let mut tasks = Vec::new();
+        let mut accounts = Vec::new();
+        let mut spread = None;
+        for part in &parts[5..] {
+            if let Some(window) = part.strip_prefix('~') {
+                spread = Some(
+                    parse_window(window)
+                        .ok_or_else(|| anyhow!("{}", t!("无效的时间窗口: {}", part)))?,
+                );
+                continue;
+            }
+            let list: Vec<&str> = part
+                .trim_start_matches('@')
+                .split(',')
+                .map(str::trim)
+                .filter(|v| !v.is_empty())
+                .collect();
+            match part.starts_with('@') {
+                true => accounts.extend(list),
+                false => tasks.extend(list),
+            }
+        }
+        let mut entry = Self::new(&parts[..5].join(" "), &tasks, &accounts)?;
+        entry.spread = spread;
+        Ok(entry)
+    }
+}
+
+// 解析时间窗口, 如`2h`、`90m`、`30s`, 不带单位时为分钟
+fn parse_window(s: &str) -> Option<Duration> {
+    let s = s.trim();
+    let (value, unit) = match s.char_indices().last()? {
+        (i, c @ ('h' | 'm' | 's')) => (&s[..i], c),
+        _ => (s, 'm'),
+    };
+    let value: f64 = value.parse().ok().filter(|v: &f64| *v >= 0.0)?;
+    let secs = match unit {
+        'h' => value * 3600.0,
+        'm' => value * 60.0,
+        _ => value,
+    };
+    Some(Duration::from_secs_f64(secs))
+}
+
+// 按cron表达式定时运行, 可为不同账号、不同任务设置不同的运行时间
+pub struct Scheduler {
+    entries: Vec<ScheduleEntry>,
+    // 外部任务
+    tasks: FarmTasks,
+    // 未单独设置时间窗口的规则, 各账号在触发后的该时间窗口内分散运行
+    spread: Option<Duration>,
+}
+
+impl Scheduler {
+    pub fn new() -> Self {
+        Self {
+            entries: Vec::new(),
+            tasks: plugin::builtin_from_env(),
+            spread: None,
+        }
+    }
+
+    // 各账号在触发后的时间窗口内均匀分散运行并加入随机抖动, 规则中的`~时间窗口`优先
+    pub fn spread(mut self, window: Duration) -> Self {
+        self.spread = Some(window);
+        self
+    }
+
+    // 添加定时规则, tasks/accounts为空时运行所有任务/账号
+    pub fn add(mut self, expr: &str, tasks: &[&str], accounts: &[&str]) -> Result<Self> {
+        self.entries
+            .push(ScheduleEntry::new(expr, tasks, accounts)?);
+        Ok(self)
+    }
+
+    // 注册外部任务, 每次运行时在内置任务之后执行
+    pub fn register_task<T: FarmTask + 'static>(mut self, task: T) -> Self {
+        self.tasks.push(Arc::new(task));
+        self
+    }
+
+    // SCHEDULE: 定时规则, 以分号或换行分隔, 每条为`分 时 日 月 星期 [任务1,任务2] [@账号1,账号2]`
+    // 如`10 7 * * *; 0 */3 * * * 收集水滴雨; 0 7,12,18 * * * 定时领水 @账号1`
+    // 规则末尾加上`~2h`时各账号在触发后2小时内分散运行, 如`0 7 * * * ~2h`在7:00~9:00之间运行各账号
+    // SCHEDULE_SPREAD: 未设置时间窗口的规则使用的时间窗口, 如`30m`, 默认所有账号同时运行
+    pub fn from_env() -> Option<Result<Self>> {
+        let value = env::var("SCHEDULE").ok().filter(|v| !v.trim().is_empty())?;
+        let entries = value
+            .split([';', '\n'])
+            .map(str::trim)
+            .filter(|s| !s.is_empty())
+            .map(ScheduleEntry::parse)
+            .collect::<Result<Vec<_>>>();
+        let spread = match env::var("SCHEDULE_SPREAD") {
+            Ok(window) if !window.trim().is_empty() => match parse_window(&window) {
+                Some(window) => Some(window),
+                None => return Some(Err(anyhow!("{}", t!("无效的时间窗口: {}", window.trim())))),
+            },
+            _ => None,
+        };
+        Some(entries.map(|entries| Self {
+            entries,
+            tasks: plugin::builtin_from_env(),
+            spread,
+        }))
+    }
+
+    // 按定时规则循环运行, 同一时间触发的多条规则依次运行, 运行期间错过的触发时间会被跳过
+    pub async fn run(self, jd_cookie: String) -> Result<()> {
+        if self.entries.is_empty() {
+            return Err(anyhow!("{}", t!("未设置定时规则")));
+        }
+        loop {
+            let now = Local::now();
+            let Some((at, entries)) = self.next_run(&now) else {
+                return Err(anyhow!("{}", t!("定时规则没有下次运行时间")));
+            };
+            let wait = (at - now).to_std().unwrap_or_default();
+            info!("{}", t!("下次运行时间: {}", at.format("%Y-%m-%d %H:%M")));
+            health::set_interval(wait);
+            health::beat();
+            tokio::select! {
+                _ = tokio::time::sleep(wait) => {},
+                _ = shutdown::requested() => return Ok(()),
+            }
+
+            for entry in entries {
+                let spread = self.spread_for(entry, &at);
+                self.run_entry(&jd_cookie, entry, spread).await;
+                if shutdown::is_requested() {
+                    return Ok(());
+                }
+            }
+        }
+    }
+
+    // now之后最近的触发时间及该时间触发的所有规则
+    fn next_run(&self, now: &DateTime<Local>) -> Option<(DateTime<Local>, Vec<&ScheduleEntry>)> {
+        let next = self
+            .entries
+            .iter()
+            .filter_map(|e| e.schedule.after(now).next().map(|t| (t, e)))
+            .collect::<Vec<_>>();
+        let at = next.iter().map(|(t, _)| *t).min()?;
+        let entries = next
+            .into_iter()
+            .filter(|(t, _)| *t == at)
+            .map(|(_, e)| e)
+            .collect();
+        Some((at, entries))
+    }
+
+    // 时间窗口不超过该规则的下次触发时间, 避免与下次运行重叠
+    fn spread_for(&self, entry: &ScheduleEntry, at: &DateTime<Local>) -> Option<Duration> {
+        entry
+            .spread
+            .or(self.spread)
+            .map(|window| match entry.schedule.after(at).next() {
+                Some(next) => window.min((next - *at).to_std().unwrap_or_default()),
+                None => window,
+            })
+    }
+
+    async fn run_entry(&self, jd_cookie: &str, entry: &ScheduleEntry, spread: Option<Duration>) {
+        info!("{}", t!("执行定时规则: {}", entry.expr));
+        health::beat();
+        let accounts = accounts::load(jd_cookie)
+            .into_iter()
+            .filter(|a| entry.accounts.is_empty() || entry.accounts.contains(&a.name()))
+            .collect();
+        let mut options = RunOptions::from_env();
+        if !entry.tasks.is_empty() {
+            options.only_tasks = entry.tasks.clone();
+        }
+        let mut runner = Runner::new(accounts)
+            .cookie_pool(accounts::backups(jd_cookie))
+            .options(options)
+            .tasks(self.tasks.clone());
+        if let Some(window) = spread {
+            // 分散运行期间调度器不发送心跳, 避免被误判为已卡死
+            health::set_interval(window);
+            runner = runner.spread(window);
+        }
+        let report = runner.run().await;
+        health::record_run(&report).await;
+        println!("\n{}", report.to_table());
+    }
+}
+
+impl Default for Scheduler {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn at(hour: u32, minute: u32) -> DateTime<Local> {
+        Local.ymd(2024, 1, 1).and_hms(hour, minute, 0)
+    }
+
+    #[test]
+    fn parses_valid_expressions() {
+        let entry = ScheduleEntry::new("10  7 * * *", &[], &[]).unwrap();
+        assert_eq!(entry.expr, "10 7 * * *");
+        assert!(entry.tasks.is_empty() && entry.accounts.is_empty());
+        assert!(entry.spread.is_none());
+
+        let entry =
+            ScheduleEntry::parse("0 7,12,18 * * * 定时领水,收集水滴雨 @账号1,账号2 ~2h").unwrap();
+        assert_eq!(entry.expr, "0 7,12,18 * * *");
+        assert_eq!(entry.tasks, ["定时领水", "收集水滴雨"]);
+        assert_eq!(entry.accounts, ["账号1", "账号2"]);
+        assert_eq!(entry.spread, Some(Duration::from_secs(7200)));
+    }
+
+    #[test]
+    fn rejects_invalid_expressions() {
+        assert!(ScheduleEntry::new("7 * * *", &[], &[]).is_err());
+        assert!(ScheduleEntry::new("0 10 7 * * *", &[], &[]).is_err());
+        assert!(ScheduleEntry::new("61 7 * * *", &[], &[]).is_err());
+        assert!(ScheduleEntry::new("0 25 * * *", &[], &[]).is_err());
+        assert!(ScheduleEntry::parse("0 7 * *").is_err());
+        assert!(ScheduleEntry::parse("0 7 * * * ~abc").is_err());
+        assert!(ScheduleEntry::parse("0 7 * * * ~-1h").is_err());
+    }
+
+    #[test]
+    fn parses_windows() {
+        assert_eq!(parse_window("2h"), Some(Duration::from_secs(7200)));
+        assert_eq!(parse_window("90m"), Some(Duration::from_secs(5400)));
+        assert_eq!(parse_window("30s"), Some(Duration::from_secs(30)));
+        assert_eq!(parse_window("15"), Some(Duration::from_secs(900)));
+        assert_eq!(parse_window("0.5h"), Some(Duration::from_secs(1800)));
+        assert_eq!(parse_window("-1"), None);
+        assert_eq!(parse_window("h"), None);
+        assert_eq!(parse_window(""), None);
+    }
+
+    #[test]
+    fn finds_next_trigger() {
+        let scheduler = Scheduler::new()
+            .add("0 7 * * *", &["定时领水"], &[])
+            .unwrap()
+            .add("0 */3 * * *", &[], &[])
+            .unwrap()
+            .add("10 7 * * *", &[], &[])
+            .unwrap();
+
+        let (next, entries) = scheduler.next_run(&at(6, 30)).unwrap();
+        assert_eq!(next, at(7, 0));
+        let exprs: Vec<_> = entries.iter().map(|e| e.expr.as_str()).collect();
+        assert_eq!(exprs, ["0 7 * * *"]);
+
+        // 触发时间本身不算作下次运行
+        let (next, entries) = scheduler.next_run(&at(7, 0)).unwrap();
+        assert_eq!(next, at(7, 10));
+        assert_eq!(entries.len(), 1);
+
+        let (next, entries) = scheduler.next_run(&at(8, 0)).unwrap();
+        assert_eq!(next, at(9, 0));
+        assert_eq!(entries.len(), 1);
+        let (next, entries) = scheduler.next_run(&at(23, 30)).unwrap();
+        assert_eq!(next, Local.ymd(2024, 1, 2).and_hms(0, 0, 0));
+        assert_eq!(entries.len(), 1);
+
+        // 同一时间触发的规则一起返回
+        let scheduler = Scheduler::new()
+            .add("0 12 * * *", &[], &[])
+            .unwrap()
+            .add("0 */6 * * *", &[], &[])
+            .unwrap();
+        let (next, entries) = scheduler.next_run(&at(11, 0)).unwrap();
+        assert_eq!(next, at(12, 0));
+        assert_eq!(entries.len(), 2);
+
+        assert!(Scheduler::new().next_run(&at(0, 0)).is_none());
+    }
+
+    #[test]
+    fn spread_is_capped_by_next_trigger() {
+        let scheduler = Scheduler::new().spread(Duration::from_secs(3600));
+        let hourly = ScheduleEntry::parse("0 */3 * * * ~4h").unwrap();
+        assert_eq!(
+            scheduler.spread_for(&hourly, &at(9, 0)),
+            Some(Duration::from_secs(3 * 3600))
+        );
+        let daily = ScheduleEntry::parse("0 7 * * *").unwrap();
+        assert_eq!(
+            scheduler.spread_for(&daily, &at(7, 0)),
+            Some(Duration::from_secs(3600))
+        );
+        let daily = daily.spread(Duration::from_secs(600));
+        assert_eq!(
+            scheduler.spread_for(&daily, &at(7, 0)),
+            Some(Duration::from_secs(600))
+        );
+        assert_eq!(
+            Scheduler::new().spread_for(&ScheduleEntry::parse("0 7 * * *").unwrap(), &at(7, 0)),
+            None
+        );
+    }
+}
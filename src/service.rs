@@ -0,0 +1,190 @@
+use std::future::Future;
+#[cfg(all(feature = "service", unix))]
+use std::time::Duration;
+
+use anyhow::Result;
+
+#[cfg(all(feature = "service", unix))]
+use crate::health;
+
+// 以系统服务方式常驻运行: 启动后通知systemd已就绪, 运行期间按WatchdogSec发送心跳, 退出前通知正在停止
+// 未启用service功能或不是由systemd启动时直接运行
+pub async fn supervise<F: Future<Output = Result<()>>>(future: F) -> Result<()> {
+    notify_ready();
+    let res = tokio::select! {
+        res = future => res,
+        _ = watchdog() => Ok(()),
+    };
+    notify_stopping();
+    res
+}
+
+// 通知systemd服务已就绪(Type=notify)
+pub fn notify_ready() {
+    #[cfg(all(feature = "service", unix))]
+    let _ = sd_notify::notify(false, &[sd_notify::NotifyState::Ready]);
+}
+
+// 通知systemd服务正在停止
+pub fn notify_stopping() {
+    #[cfg(all(feature = "service", unix))]
+    let _ = sd_notify::notify(false, &[sd_notify::NotifyState::Stopping]);
+}
+
+// 按WatchdogSec的一半间隔发送心跳, 调度器存活或运行进行中时发送
+// 调度器卡死(超过两个运行间隔没有心跳)且没有进行中的运行(或运行卡死)时停止发送, 由systemd重启服务
+async fn watchdog() {
+    #[cfg(all(feature = "service", unix))]
+    {
+        let mut usec = 0;
+        if sd_notify::watchdog_enabled(false, &mut usec) {
+            let interval = Duration::from_micros(usec / 2);
+            loop {
+                tokio::time::sleep(interval).await;
+                if health::current().is_alive() || health::is_running() {
+                    let _ = sd_notify::notify(false, &[sd_notify::NotifyState::Watchdog]);
+                }
+            }
+        }
+    }
+    std::future::pending::<()>().await
+}
+
+#[cfg(all(feature = "service", windows))]
+pub use self::windows::{enter_exe_dir, run_windows_service};
+
+#[cfg(all(feature = "service", windows))]
+mod windows {
+    use std::env;
+    use std::ffi::OsString;
+    use std::time::Duration;
+
+    use anyhow::{anyhow, Result};
+    use tracing::info;
+    use windows_service::service::{
+        ServiceControl, ServiceControlAccept, ServiceExitCode, ServiceState, ServiceStatus,
+        ServiceType,
+    };
+    use windows_service::service_control_handler::{self, ServiceControlHandlerResult};
+    use windows_service::{define_windows_service, service_dispatcher};
+
+    use crate::daemon::Daemon;
+    use crate::remote_config::{refreshing, RemoteConfig};
+    use crate::shutdown;
+
+    // 服务名称, 需与sc create时的名称一致
+    const SERVICE_NAME: &str = "jd_farm";
+
+    // 停止服务时等待进行中的运行结束的最长时间, 超过后直接退出
+    const STOP_TIMEOUT: Duration = Duration::from_secs(110);
+
+    // 停止服务时告知服务管理器的等待时间, 略长于STOP_TIMEOUT
+    const STOP_WAIT_HINT: Duration = Duration::from_secs(120);
+
+    define_windows_service!(ffi_service_main, service_main);
+
+    // 服务管理器启动服务时工作目录为系统目录, 切换到程序所在目录以读取.env及相对路径的配置
+    pub fn enter_exe_dir() -> Result<()> {
+        let exe = env::current_exe()?;
+        if let Some(dir) = exe.parent() {
+            env::set_current_dir(dir)?;
+        }
+        Ok(())
+    }
+
+    // 作为Windows服务运行, 阻塞直到服务停止
+    pub fn run_windows_service() -> Result<()> {
+        service_dispatcher::start(SERVICE_NAME, ffi_service_main)?;
+        Ok(())
+    }
+
+    fn service_main(_arguments: Vec<OsString>) {
+        if let Err(e) = run_service() {
+            info!("{}", t!("Windows服务运行失败, {}", e));
+        }
+    }
+
+    fn status(
+        state: ServiceState,
+        controls_accepted: ServiceControlAccept,
+        wait_hint: Duration,
+    ) -> ServiceStatus {
+        ServiceStatus {
+            service_type: ServiceType::OWN_PROCESS,
+            current_state: state,
+            controls_accepted,
+            exit_code: ServiceExitCode::Win32(0),
+            checkpoint: 0,
+            wait_hint,
+            process_id: None,
+        }
+    }
+
+    fn run_service() -> Result<()> {
+        // 停止服务与收到Ctrl+C相同: 不再开始新的任务, 进行中的任务完成后照常保存检查点及输出汇总
+        let handle =
+            service_control_handler::register(SERVICE_NAME, move |control| match control {
+                ServiceControl::Stop | ServiceControl::Shutdown => {
+                    shutdown::request();
+                    ServiceControlHandlerResult::NoError
+                }
+                ServiceControl::Interrogate => ServiceControlHandlerResult::NoError,
+                _ => ServiceControlHandlerResult::NotImplemented,
+            })?;
+        handle.set_service_status(status(
+            ServiceState::Running,
+            ServiceControlAccept::STOP | ServiceControlAccept::SHUTDOWN,
+            Duration::default(),
+        ))?;
+
+        let runtime = tokio::runtime::Builder::new_multi_thread()
+            .enable_all()
+            .build()?;
+        let res = runtime.block_on(async {
+            let run = run_daemon();
+            tokio::pin!(run);
+            tokio::select! {
+                res = &mut run => return res,
+                _ = shutdown::requested() => {}
+            }
+            handle.set_service_status(status(
+                ServiceState::StopPending,
+                ServiceControlAccept::empty(),
+                STOP_WAIT_HINT,
+            ))?;
+            match tokio::time::timeout(STOP_TIMEOUT, run).await {
+                Ok(res) => res,
+                Err(_) => {
+                    info!("{}", t!("等待进行中的运行结束超时, 停止服务"));
+                    Ok(())
+                }
+            }
+        });
+
+        handle.set_service_status(status(
+            ServiceState::Stopped,
+            ServiceControlAccept::empty(),
+            Duration::default(),
+        ))?;
+        res
+    }
+
+    // 服务模式下常驻运行, SCHEDULE优先, 其次DAEMON_INTERVAL
+    async fn run_daemon() -> Result<()> {
+        let jd_cookie =
+            env::var("JD_COOKIE").map_err(|_| anyhow!("{}", t!("未找到环境变量JD_COOKIE.")))?;
+        // 远程配置已在启动时获取, 运行期间按刷新间隔更新
+        let remote = RemoteConfig::from_env();
+        #[cfg(feature = "scheduler")]
+        if let Some(scheduler) = crate::scheduler::Scheduler::from_env() {
+            return refreshing(remote.as_ref(), scheduler?.run(jd_cookie)).await;
+        }
+        match Daemon::from_env() {
+            Some(daemon) => refreshing(remote.as_ref(), daemon.run(jd_cookie)).await,
+            None => Err(anyhow!(
+                "{}",
+                t!("以服务方式运行时需设置DAEMON_INTERVAL或SCHEDULE")
+            )),
+        }
+    }
+}
@@ -0,0 +1,61 @@
+use std::env;
+
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::{JClient, JError};
+
+// 默认的农场分享链接, 好友打开后即为该账号助力
+const DEFAULT_LINK_TEMPLATE: &str = "https://h5.m.jd.com/babelDiy/Zeus/3KSjXqQabiTuD1cJ28QskrpWoBKT/index.html?babelChannel=121&lng=&lat=&sid=&un_area=&shareCode={share_code}&inviteCode={share_code}&channel=1";
+
+// SHARE_LINK_TEMPLATE: 分享链接模板, {share_code}替换为助力码, 默认为农场活动页
+pub fn link(share_code: &str) -> String {
+    let template = env::var("SHARE_LINK_TEMPLATE")
+        .ok()
+        .filter(|t| t.contains("{share_code}"))
+        .unwrap_or_else(|| DEFAULT_LINK_TEMPLATE.to_string());
+    template.replace("{share_code}", share_code)
+}
+
+// NOTIFY_SHARE_LINK: 是否在通知中附上各账号的分享链接, 默认false
+pub fn in_notify() -> bool {
+    matches!(
+        env::var("NOTIFY_SHARE_LINK").as_deref().map(str::trim),
+        Ok("true") | Ok("1")
+    )
+}
+
+// 账号的助力码及分享链接
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct ShareCode {
+    // 账号名称
+    pub account: String,
+    // 助力码
+    pub share_code: String,
+    // 分享链接
+    pub link: String,
+}
+
+impl ShareCode {
+    pub fn new(account: &str, share_code: &str) -> Self {
+        Self {
+            account: account.to_string(),
+            share_code: share_code.to_string(),
+            link: link(share_code),
+        }
+    }
+}
+
+impl JClient {
+    // 查询账号的助力码并生成分享链接, 只读取数据, 不执行任何任务
+    pub async fn share_code(&self) -> Result<ShareCode> {
+        let farm_info = self.get_farm_info().await?;
+        if farm_info.share_code.is_empty() {
+            return Err(anyhow!(JError::parse(
+                "initForFarm",
+                t!("缺少字段{}", "shareCode")
+            )));
+        }
+        Ok(ShareCode::new(&self.account.name(), &farm_info.share_code))
+    }
+}
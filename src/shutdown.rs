@@ -0,0 +1,71 @@
+use std::future::Future;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::OnceLock;
+
+use tokio::sync::Notify;
+use tracing::info;
+
+static REQUESTED: AtomicBool = AtomicBool::new(false);
+
+fn notify() -> &'static Notify {
+    static NOTIFY: OnceLock<Notify> = OnceLock::new();
+    NOTIFY.get_or_init(Notify::new)
+}
+
+// 请求退出, 之后不再开始新的任务, 进行中的任务完成后照常保存运行结果、发送通知
+pub fn request() {
+    REQUESTED.store(true, Ordering::SeqCst);
+    notify().notify_waiters();
+}
+
+// 是否已请求退出
+pub fn is_requested() -> bool {
+    REQUESTED.load(Ordering::SeqCst)
+}
+
+// 等待退出请求
+pub async fn requested() {
+    loop {
+        let notified = notify().notified();
+        if is_requested() {
+            return;
+        }
+        notified.await;
+    }
+}
+
+// 等待SIGINT(Ctrl+C)或SIGTERM
+async fn signal() {
+    #[cfg(unix)]
+    {
+        use tokio::signal::unix::{signal, SignalKind};
+        if let Ok(mut terminate) = signal(SignalKind::terminate()) {
+            tokio::select! {
+                _ = tokio::signal::ctrl_c() => {},
+                _ = terminate.recv() => {},
+            }
+            return;
+        }
+    }
+    let _ = tokio::signal::ctrl_c().await;
+}
+
+// 监听退出信号, 第一次收到时请求退出, 再次收到时立即退出
+async fn listen() {
+    signal().await;
+    info!(
+        "{}",
+        t!("收到退出信号, 等待进行中的任务完成后退出, 再次按Ctrl+C立即退出")
+    );
+    request();
+    signal().await;
+    std::process::exit(130);
+}
+
+// 运行期间监听退出信号
+pub async fn with_signals<F: Future>(future: F) -> F::Output {
+    tokio::select! {
+        output = future => output,
+        _ = listen() => unreachable!(),
+    }
+}
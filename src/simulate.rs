@@ -0,0 +1,614 @@
+use std::collections::HashMap;
+use std::env;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+
+use serde_json::{json, Value};
+
+use crate::error::JError;
+
+// 模拟的好友数量
+const FRIENDS: usize = 3;
+
+// 每日点鸭子次数上限
+const DUCK_LIMIT: u32 = 3;
+
+// 默认的随机失败概率
+const DEFAULT_FAILURE_RATE: f64 = 0.05;
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+
+// 开启模拟运行, 之后创建的Runner使用模拟的接口
+pub fn enable() {
+    ENABLED.store(true, Ordering::SeqCst);
+}
+
+// SIMULATE: 模拟运行, true/1, 也可通过enable开启
+pub fn is_enabled() -> bool {
+    ENABLED.load(Ordering::SeqCst)
+        || matches!(
+            env::var("SIMULATE").as_deref().map(str::trim),
+            Ok("true") | Ok("1")
+        )
+}
+
+// 未设置JD_COOKIE时使用的模拟账号, 其中一个账号的cookie已失效
+pub fn cookies() -> String {
+    (1..=3)
+        .map(|i| {
+            format!(
+                "pt_key=simulate;pt_pin=simulate_{};remark=模拟账号{};",
+                i, i
+            )
+        })
+        .chain(["pt_key=simulate;pt_pin=simulate_expired;remark=模拟账号(expired);".to_string()])
+        .collect::<Vec<_>>()
+        .join("&")
+}
+
+// 模拟的单个账号的农场状态
+struct FakeFarm {
+    total_energy: u32,
+    tree_energy: u32,
+    tree_total_energy: u32,
+    tree_state: u8,
+    share_code: String,
+    can_pop: bool,
+    water_times: u16,
+    first_water_claimed: bool,
+    total_water_claimed: bool,
+    three_meal: bool,
+    treasure_box: bool,
+    // 各浏览任务是否已浏览、已领取
+    browse: Vec<(bool, bool)>,
+    rain_times: u8,
+    rain_last_time: u64,
+    friend_watered: u8,
+    friend_award: bool,
+    clock_in: bool,
+    // 各关注任务是否已关注、已领取
+    follow: Vec<(bool, bool)>,
+    duck_times: u32,
+    cards: HashMap<&'static str, u32>,
+    pet: FakePet,
+    bean: FakeBean,
+}
+
+// 模拟的种豆得豆状态
+struct FakeBean {
+    award_state: &'static str,
+    award_beans: u32,
+    time_nutrients: bool,
+    signed: bool,
+    // 待培养的营养液数量
+    bubbles: u32,
+    nutrients: u32,
+}
+
+impl FakeBean {
+    fn new(seed: u64) -> Self {
+        Self {
+            award_state: "5",
+            award_beans: 5 + (seed % 20) as u32,
+            time_nutrients: true,
+            signed: false,
+            bubbles: 0,
+            nutrients: (seed % 10) as u32,
+        }
+    }
+
+    fn respond(&mut self, function_id: &str) -> Value {
+        let success = |data: Value| json!({"code": "0", "data": data});
+        let failed = |code: &str, message: &str| json!({"code": code, "errorMessage": message});
+        match function_id {
+            "plantBeanIndex" => {
+                let bubbles: Vec<Value> = (0..self.bubbles)
+                    .map(|i| json!({"name": format!("模拟营养液{}", i + 1), "nutrientsType": "1"}))
+                    .collect();
+                success(json!({
+                    "roundList": [
+                        {"roundId": "simulate_last", "awardState": self.award_state, "awardBeans": self.award_beans.to_string()},
+                        {"roundId": "simulate_current", "nutrients": self.nutrients.to_string(), "growth": (self.nutrients * 10).to_string(), "bubbleInfos": bubbles},
+                        {"roundId": "simulate_next"},
+                    ],
+                    "taskList": [
+                        {"taskName": "每日签到", "isFinished": self.signed as u8, "awardType": "1"},
+                        {"taskName": "浏览店铺", "isFinished": 0, "awardType": "3"},
+                    ],
+                    "timeNutrientsRes": {"state": if self.time_nutrients { "1" } else { "2" }, "nutrCount": "2"},
+                }))
+            }
+            "receivedBean" if self.award_state == "5" => {
+                self.award_state = "6";
+                success(json!({"awardBean": self.award_beans.to_string()}))
+            }
+            "receiveNutrients" if self.time_nutrients => {
+                self.time_nutrients = false;
+                self.bubbles += 1;
+                success(json!({"nutrients": "2"}))
+            }
+            "receiveNutrientsTask" if !self.signed => {
+                self.signed = true;
+                self.bubbles += 1;
+                success(json!({"nutrNum": 1}))
+            }
+            "cultureBean" if self.bubbles > 0 => {
+                self.bubbles -= 1;
+                self.nutrients += 1;
+                success(json!({}))
+            }
+            _ => failed("7", "任务已完成或不满足条件"),
+        }
+    }
+}
+
+// 模拟的萌宠状态
+struct FakePet {
+    food_amount: u32,
+    fed: u32,
+    signed: bool,
+    three_meal: bool,
+    first_feed: bool,
+    sport_times: u8,
+    medal_percent: f64,
+}
+
+impl FakePet {
+    fn new(seed: u64) -> Self {
+        Self {
+            food_amount: 100 + (seed % 100) as u32,
+            fed: 0,
+            signed: false,
+            three_meal: false,
+            first_feed: false,
+            sport_times: 0,
+            medal_percent: (seed % 80) as f64,
+        }
+    }
+
+    // 萌宠接口的返回码为code及resultCode
+    fn respond(&mut self, function_id: &str) -> Value {
+        let success = |result: Value| json!({"code": "0", "resultCode": "0", "result": result});
+        let failed = |code: &str, message: &str| json!({"code": "0", "resultCode": code, "message": message});
+        match function_id {
+            "initPetTown" => success(json!({
+                "userStatus": 1,
+                "petStatus": 4,
+                "goodsInfo": {"goodsName": "模拟萌宠奖品", "exchangeMedalNum": 8},
+                "medalNum": 3,
+                "medalPercent": self.medal_percent,
+                "foodAmount": self.food_amount,
+                "shareCode": "simulate_pet",
+            })),
+            "taskInit" => success(json!({
+                "signInit": {"finished": self.signed},
+                "threeMealInit": {"finished": self.three_meal},
+                "firstFeedInit": {"finished": self.first_feed},
+                "feedReachInit": {
+                    "finished": self.fed >= 80,
+                    "feedReachAmount": 80,
+                    "hadFeedAmount": self.fed,
+                },
+            })),
+            "getSignReward" if !self.signed => {
+                self.signed = true;
+                self.food_amount += 20;
+                success(json!({"signReward": 20}))
+            }
+            "getThreeMealReward" if !self.three_meal => {
+                self.three_meal = true;
+                self.food_amount += 20;
+                success(json!({"threeMealReward": 20}))
+            }
+            "feedPets" if self.food_amount >= 10 => {
+                self.food_amount -= 10;
+                self.fed += 10;
+                self.medal_percent = (self.medal_percent + 1.0).min(100.0);
+                success(json!({"foodAmount": self.food_amount}))
+            }
+            "feedPets" => failed("3003", "剩余狗粮不足"),
+            "getFirstFeedReward" if self.fed > 0 && !self.first_feed => {
+                self.first_feed = true;
+                self.food_amount += 10;
+                success(json!({"reward": 10}))
+            }
+            "petSport" if self.sport_times < 10 => {
+                self.sport_times += 1;
+                success(json!({"petSportStatus": 1}))
+            }
+            "petSport" => failed("1013", "今日遛狗次数已达上限"),
+            "getSportReward" => {
+                self.food_amount += 10;
+                success(json!({"foodReward": 10}))
+            }
+            "energyCollect" => success(json!({
+                "medalNum": 3,
+                "medalPercent": self.medal_percent,
+            })),
+            _ => failed("7", "任务已完成或不满足条件"),
+        }
+    }
+}
+
+impl FakeFarm {
+    fn new(seed: u64) -> Self {
+        Self {
+            total_energy: 200 + (seed % 300) as u32,
+            tree_energy: 1000 + (seed % 300) as u32 * 10,
+            tree_total_energy: 5000,
+            tree_state: 1,
+            share_code: format!("{:016x}", seed),
+            can_pop: true,
+            water_times: 0,
+            first_water_claimed: false,
+            total_water_claimed: false,
+            three_meal: false,
+            treasure_box: false,
+            browse: vec![(false, false); 3],
+            rain_times: 0,
+            rain_last_time: 0,
+            friend_watered: 0,
+            friend_award: false,
+            clock_in: false,
+            follow: vec![(false, false); 2],
+            duck_times: 0,
+            cards: HashMap::from([
+                ("doubleCard", 1),
+                ("fastCard", 0),
+                ("signCard", 1),
+                ("beanCard", 0),
+            ]),
+            pet: FakePet::new(seed),
+            bean: FakeBean::new(seed),
+        }
+    }
+
+    // 增加水滴, 返回增加的数量
+    fn gain(&mut self, amount: u32) -> u32 {
+        self.total_energy += amount;
+        amount
+    }
+
+    fn farm_info(&self) -> Value {
+        json!({
+            "code": "0",
+            "farmUserPro": {
+                "totalEnergy": self.total_energy,
+                "treeState": self.tree_state,
+                "treeEnergy": self.tree_energy,
+                "treeTotalEnergy": self.tree_total_energy,
+                "shareCode": self.share_code,
+                "nickName": "模拟用户",
+                "name": "模拟奖品",
+                "prizeLevel": 2,
+            },
+            "todayGotWaterGoalTask": {"canPop": self.can_pop},
+        })
+    }
+
+    fn task_info(&self) -> Value {
+        let ads: Vec<Value> = self
+            .browse
+            .iter()
+            .enumerate()
+            .map(|(i, (finished, got))| {
+                json!({
+                    "advertId": format!("simulate_ad_{}", i),
+                    "mainTitle": format!("浏览模拟商品{}", i + 1),
+                    "limit": 1,
+                    "hadFinishedTimes": *finished as u8,
+                    "time": 1,
+                    "hadGotTimes": *got as u8,
+                })
+            })
+            .collect();
+        json!({
+            "code": "0",
+            "signInit": {"f": true},
+            "firstWaterInit": {"f": self.first_water_claimed},
+            "totalWaterTaskInit": {
+                "f": self.total_water_claimed,
+                "totalWaterTaskLimit": 10,
+                "totalWaterTaskTimes": self.water_times.min(10),
+            },
+            "waterFriendTaskInit": {
+                "waterFriendMax": 2,
+                "waterFriendCountKey": self.friend_watered,
+                "f": self.friend_award,
+                "waterFriendGotAward": self.friend_award,
+            },
+            "gotBrowseTaskAdInit": {
+                "f": self.browse.iter().all(|(_, got)| *got),
+                "userBrowseTaskAds": ads,
+            },
+            "treasureBoxInit": {"line": "simulate", "f": self.treasure_box},
+            "waterRainInit": {
+                "f": self.rain_times >= 2,
+                "winTimes": self.rain_times,
+                "lastTime": self.rain_last_time,
+            },
+            "gotThreeMealInit": {"f": self.three_meal},
+        })
+    }
+
+    fn clock_in_info(&self) -> Value {
+        let themes: Vec<Value> = self
+            .follow
+            .iter()
+            .enumerate()
+            .map(|(i, (followed, got))| {
+                json!({
+                    "advertId": format!("simulate_theme_{}", i),
+                    "id": format!("{}", i),
+                    "name": format!("模拟店铺{}", i + 1),
+                    "hadGot": got,
+                    "hadFollow": followed,
+                })
+            })
+            .collect();
+        json!({"code": "0", "todaySigned": self.clock_in, "themes": themes})
+    }
+
+    fn respond(&mut self, function_id: &str, body: &Value) -> Value {
+        let failed = |code: &str, message: &str| json!({"code": code, "message": message});
+        match function_id {
+            // 携带shareCode时为助力好友
+            "initForFarm" if body.get("shareCode").is_some() => {
+                json!({"code": "0", "helpResult": {"code": "0"}})
+            }
+            "initForFarm" => self.farm_info(),
+            "taskInitForFarm" => self.task_info(),
+            "myCardInfoForFarm" => json!({
+                "code": "0",
+                "doubleCard": self.cards["doubleCard"],
+                "fastCard": self.cards["fastCard"],
+                "signCard": self.cards["signCard"],
+                "beanCard": self.cards["beanCard"],
+            }),
+            "gotWaterGoalTaskForFarm" if self.can_pop => {
+                self.can_pop = false;
+                json!({"code": "0", "addEnergy": self.gain(10)})
+            }
+            "waterGoodForFarm" if self.total_energy >= 10 => {
+                self.total_energy -= 10;
+                self.tree_energy += 10;
+                self.water_times += 1;
+                if self.tree_energy >= self.tree_total_energy {
+                    self.tree_state = 2;
+                }
+                json!({"code": "0", "totalEnergy": self.total_energy})
+            }
+            "waterGoodForFarm" => failed("6", "水滴不足"),
+            "firstWaterTaskForFarm" if self.water_times >= 1 && !self.first_water_claimed => {
+                self.first_water_claimed = true;
+                json!({"code": "0", "amount": self.gain(25)})
+            }
+            "totalWaterTaskForFarm" if self.water_times >= 10 && !self.total_water_claimed => {
+                self.total_water_claimed = true;
+                self.can_pop = true;
+                json!({
+                    "code": "0",
+                    "totalWaterTaskEnergy": self.gain(50),
+                    "todayGotWaterGoalTask": {"canPop": true},
+                })
+            }
+            "gotThreeMealForFarm" if !self.three_meal => {
+                self.three_meal = true;
+                json!({"code": "0", "amount": self.gain(15)})
+            }
+            "ddnc_getTreasureBoxAward" if body["type"] == 2 && !self.treasure_box => {
+                self.treasure_box = true;
+                json!({"code": "0", "waterGram": self.gain(20)})
+            }
+            "ddnc_getTreasureBoxAward" if body["type"] == 1 => json!({"code": "0"}),
+            "browseAdTaskForFarm" => {
+                let index = body["advertId"]
+                    .as_str()
+                    .and_then(|id| id.strip_prefix("simulate_ad_"))
+                    .and_then(|i| i.parse::<usize>().ok());
+                match index.and_then(|i| self.browse.get_mut(i)) {
+                    Some((finished, _)) if body["type"] == 0 => {
+                        *finished = true;
+                        json!({"code": "0"})
+                    }
+                    Some((true, got @ false)) => {
+                        *got = true;
+                        json!({"code": "0", "amount": self.gain(5)})
+                    }
+                    _ => failed("7", "任务已完成"),
+                }
+            }
+            "waterRainForFarm" if self.rain_times < 2 => {
+                self.rain_times += 1;
+                self.rain_last_time = chrono::Utc::now().timestamp_millis() as u64;
+                json!({"code": "0", "addEnergy": self.gain(20)})
+            }
+            "friendListInitForFarm" => {
+                let friends: Vec<Value> = (1..=FRIENDS)
+                    .map(|i| {
+                        json!({
+                            "nickName": format!("模拟好友{}", i),
+                            "shareCode": format!("simulate_friend_{}", i),
+                            "friendState": 1,
+                        })
+                    })
+                    .collect();
+                json!({"code": "0", "friends": friends})
+            }
+            // 排在前面的好友浇水较多, 最后一位好友未浇水
+            "friendWaterRecordForFarm" => {
+                let now = chrono::Utc::now().timestamp_millis() as u64;
+                let records: Vec<Value> = (1..=FRIENDS)
+                    .flat_map(|i| {
+                        (0..FRIENDS - i).map(move |j| {
+                            json!({
+                                "nickName": format!("模拟好友{}", i),
+                                "shareCode": format!("simulate_friend_{}", i),
+                                "time": now - j as u64 * 20 * 3600 * 1000,
+                            })
+                        })
+                    })
+                    .collect();
+                json!({"code": "0", "records": records})
+            }
+            "waterFriendForFarm" if self.total_energy >= 10 => {
+                self.total_energy -= 10;
+                self.friend_watered += 1;
+                json!({"code": "0"})
+            }
+            "waterFriendGotAwardForFarm" if self.friend_watered >= 2 && !self.friend_award => {
+                self.friend_award = true;
+                json!({"code": "0", "addWater": self.gain(30)})
+            }
+            "clockInInitForFarm" => self.clock_in_info(),
+            "clockInForFarm" if !self.clock_in => {
+                self.clock_in = true;
+                json!({"code": "0", "amount": self.gain(10)})
+            }
+            "clockInFollowForFarm" => {
+                let index = body["id"].as_str().and_then(|i| i.parse::<usize>().ok());
+                match index.and_then(|i| self.follow.get_mut(i)) {
+                    Some((followed, _)) if body["step"] == 1 => {
+                        *followed = true;
+                        json!({"code": "0"})
+                    }
+                    Some((true, got @ false)) => {
+                        *got = true;
+                        json!({"code": "0", "amount": self.gain(5)})
+                    }
+                    _ => failed("7", "任务已完成"),
+                }
+            }
+            "userMyCardForFarm" => {
+                let card = body["cardType"].as_str().unwrap_or_default();
+                match self.cards.get_mut(card) {
+                    Some(count) if *count > 0 => {
+                        *count -= 1;
+                        json!({"code": "0"})
+                    }
+                    _ => failed("20", "道具卡不足"),
+                }
+            }
+            "getFullCollectionReward" if self.duck_times < DUCK_LIMIT => {
+                self.duck_times += 1;
+                // 最后一次获得优惠券, 其余获得水滴
+                match self.duck_times == DUCK_LIMIT {
+                    true => json!({"code": "0", "title": "模拟优惠券", "addWater": 0}),
+                    false => json!({
+                        "code": "0",
+                        "title": "获得水滴",
+                        "addWater": self.gain(5),
+                    }),
+                }
+            }
+            "getFullCollectionReward" => failed("10", "今日次数已用完"),
+            "initPetTown" | "taskInit" | "getSignReward" | "getThreeMealReward" | "feedPets"
+            | "getFirstFeedReward" | "petSport" | "getSportReward" | "energyCollect" => {
+                self.pet.respond(function_id)
+            }
+            "plantBeanIndex"
+            | "receivedBean"
+            | "receiveNutrients"
+            | "receiveNutrientsTask"
+            | "cultureBean" => self.bean.respond(function_id),
+            _ => failed("7", "任务已完成或不满足条件"),
+        }
+    }
+}
+
+// 模拟的京东接口, 按账号保存农场状态并返回与真实接口格式相同的数据, 不发送任何网络请求
+// 按失败概率随机返回网络错误或风控提示, 名称包含expired的账号返回cookie已失效, 包含verify的账号要求安全验证
+pub struct Simulator {
+    farms: Mutex<HashMap<String, FakeFarm>>,
+    failure_rate: f64,
+    rng: Mutex<u64>,
+}
+
+impl Simulator {
+    pub fn new() -> Self {
+        Self {
+            farms: Mutex::new(HashMap::new()),
+            failure_rate: DEFAULT_FAILURE_RATE,
+            rng: Mutex::new(0x2545_f491_4f6c_dd1d),
+        }
+    }
+
+    // 随机失败的概率, 0~1, 默认0.05, 设置为0时所有请求均按农场状态正常返回
+    pub fn failure_rate(mut self, rate: f64) -> Self {
+        self.failure_rate = rate.clamp(0.0, 1.0);
+        self
+    }
+
+    // 随机数种子, 相同种子的两次模拟运行结果相同
+    pub fn seed(self, seed: u64) -> Self {
+        *self.rng.lock().unwrap() = seed.max(1);
+        self
+    }
+
+    // SIMULATE_FAILURE_RATE: 随机失败的概率, 默认0.05
+    // SIMULATE_SEED: 随机数种子
+    pub fn from_env() -> Option<Self> {
+        if !is_enabled() {
+            return None;
+        }
+        let mut simulator = Self::new();
+        if let Some(rate) = env::var("SIMULATE_FAILURE_RATE")
+            .ok()
+            .and_then(|v| v.trim().parse().ok())
+        {
+            simulator = simulator.failure_rate(rate);
+        }
+        if let Some(seed) = env::var("SIMULATE_SEED")
+            .ok()
+            .and_then(|v| v.trim().parse().ok())
+        {
+            simulator = simulator.seed(seed);
+        }
+        Some(simulator)
+    }
+
+    // xorshift64, 返回0~1之间的随机数
+    fn random(&self) -> f64 {
+        let mut state = self.rng.lock().unwrap();
+        *state ^= *state << 13;
+        *state ^= *state >> 7;
+        *state ^= *state << 17;
+        (*state >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    // 处理一次请求
+    pub fn respond(&self, account: &str, function_id: &str, body: &str) -> Result<Value, JError> {
+        if account.contains("expired") {
+            return Ok(json!({"code": "3", "message": "用户未登录"}));
+        }
+        if account.contains("verify") {
+            return Ok(json!({
+                "code": "605",
+                "message": "请完成安全验证",
+                "url": "https://cfe.m.jd.com/privatedomain/risk_handler/03101900/",
+            }));
+        }
+        let roll = self.random();
+        if roll < self.failure_rate / 2.0 {
+            return Err(JError::Network(t!("模拟网络错误: {}", function_id)));
+        }
+        if roll < self.failure_rate {
+            return Ok(json!({"code": "403", "message": "活动太火爆, 请稍后再试"}));
+        }
+        let body: Value = serde_json::from_str(body).unwrap_or_default();
+        let mut farms = self.farms.lock().unwrap();
+        let farm = farms.entry(account.to_string()).or_insert_with(|| {
+            let seed = account.bytes().fold(0xcbf2_9ce4_8422_2325u64, |h, b| {
+                (h ^ b as u64).wrapping_mul(0x100_0000_01b3)
+            });
+            FakeFarm::new(seed)
+        });
+        Ok(farm.respond(function_id, &body))
+    }
+}
+
+impl Default for Simulator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
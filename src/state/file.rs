@@ -0,0 +1,82 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::Duration;
+
+use anyhow::Result;
+use async_trait::async_trait;
+use serde_json::Value;
+
+use super::{Entry, StateStore};
+
+// 保存在JSON文件中的运行状态, 每次读写时重新读取文件, 多个进程可共用同一个文件
+pub struct FileStateStore {
+    path: PathBuf,
+    // 同一进程内的读写依次进行
+    lock: Mutex<()>,
+}
+
+impl FileStateStore {
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref().to_path_buf();
+        if let Some(dir) = path.parent().filter(|d| !d.as_os_str().is_empty()) {
+            fs::create_dir_all(dir)?;
+        }
+        Ok(Self {
+            path,
+            lock: Mutex::new(()),
+        })
+    }
+
+    fn read(&self) -> Result<BTreeMap<String, Entry>> {
+        match fs::read_to_string(&self.path) {
+            Ok(content) if !content.trim().is_empty() => Ok(serde_json::from_str(&content)?),
+            Ok(_) => Ok(BTreeMap::new()),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(BTreeMap::new()),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    // 先写入临时文件再重命名, 避免写入中途退出时损坏文件
+    fn write(&self, entries: &BTreeMap<String, Entry>) -> Result<()> {
+        let tmp = self.path.with_extension("tmp");
+        fs::write(&tmp, serde_json::to_string_pretty(entries)?)?;
+        fs::rename(tmp, &self.path)?;
+        Ok(())
+    }
+
+    // 读取文件, 修改后写回, 同时清理已过期的状态
+    fn update<T>(&self, f: impl FnOnce(&mut BTreeMap<String, Entry>) -> T) -> Result<T> {
+        let _lock = self.lock.lock().unwrap();
+        let mut entries = self.read()?;
+        entries.retain(|_, entry| !entry.is_expired());
+        let res = f(&mut entries);
+        self.write(&entries)?;
+        Ok(res)
+    }
+}
+
+#[async_trait]
+impl StateStore for FileStateStore {
+    async fn get(&self, key: &str) -> Result<Option<Value>> {
+        let _lock = self.lock.lock().unwrap();
+        Ok(self
+            .read()?
+            .remove(key)
+            .filter(|entry| !entry.is_expired())
+            .map(|entry| entry.value))
+    }
+
+    async fn set(&self, key: &str, value: Value, ttl: Option<Duration>) -> Result<()> {
+        self.update(|entries| {
+            entries.insert(key.to_string(), Entry::new(value, ttl));
+        })
+    }
+
+    async fn remove(&self, key: &str) -> Result<()> {
+        self.update(|entries| {
+            entries.remove(key);
+        })
+    }
+}
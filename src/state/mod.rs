@@ -0,0 +1,140 @@
+mod file;
+#[cfg(feature = "history")]
+mod sqlite;
+
+use std::collections::HashMap;
+use std::env;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use anyhow::Result;
+use async_trait::async_trait;
+use chrono::{FixedOffset, Utc};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tracing::info;
+
+pub use self::file::FileStateStore;
+#[cfg(feature = "history")]
+pub use self::sqlite::SqliteStateStore;
+
+// 运行状态存储, 如中断运行的检查点、已浇水的好友、助力次数, 服务端部署时可集中保存
+#[async_trait]
+pub trait StateStore: Send + Sync {
+    // 读取状态, 不存在或已过期时为空
+    async fn get(&self, key: &str) -> Result<Option<Value>>;
+
+    // 保存状态, ttl为空时一直保留
+    async fn set(&self, key: &str, value: Value, ttl: Option<Duration>) -> Result<()>;
+
+    // 删除状态
+    async fn remove(&self, key: &str) -> Result<()>;
+}
+
+// 读取并解析状态, 不存在时为默认值
+pub async fn load<T: DeserializeOwned + Default>(store: &dyn StateStore, key: &str) -> Result<T> {
+    Ok(match store.get(key).await? {
+        Some(value) => serde_json::from_value(value)?,
+        None => T::default(),
+    })
+}
+
+// 序列化并保存状态
+pub async fn save<T: Serialize>(
+    store: &dyn StateStore,
+    key: &str,
+    value: &T,
+    ttl: Option<Duration>,
+) -> Result<()> {
+    store.set(key, serde_json::to_value(value)?, ttl).await
+}
+
+// 京东活动按北京时间计算日期, 使用服务器时间
+pub(crate) fn jd_date() -> String {
+    let china_timezone = FixedOffset::east_opt(8 * 3600).unwrap();
+    crate::clock::now()
+        .with_timezone(&china_timezone)
+        .format("%Y%m%d")
+        .to_string()
+}
+
+// 一条状态及其过期时间(unix时间戳, 秒)
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub(crate) struct Entry {
+    pub value: Value,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expires_at: Option<i64>,
+}
+
+impl Entry {
+    pub fn new(value: Value, ttl: Option<Duration>) -> Self {
+        Self {
+            value,
+            expires_at: ttl.map(|ttl| Utc::now().timestamp() + ttl.as_secs() as i64),
+        }
+    }
+
+    pub fn is_expired(&self) -> bool {
+        self.expires_at
+            .is_some_and(|at| at <= Utc::now().timestamp())
+    }
+}
+
+// 内存中的状态, 进程退出后丢失, 未设置STATE_STORE时使用
+#[derive(Default)]
+pub struct MemoryStateStore {
+    entries: Mutex<HashMap<String, Entry>>,
+}
+
+#[async_trait]
+impl StateStore for MemoryStateStore {
+    async fn get(&self, key: &str) -> Result<Option<Value>> {
+        let mut entries = self.entries.lock().unwrap();
+        entries.retain(|_, entry| !entry.is_expired());
+        Ok(entries.get(key).map(|entry| entry.value.clone()))
+    }
+
+    async fn set(&self, key: &str, value: Value, ttl: Option<Duration>) -> Result<()> {
+        self.entries
+            .lock()
+            .unwrap()
+            .insert(key.to_string(), Entry::new(value, ttl));
+        Ok(())
+    }
+
+    async fn remove(&self, key: &str) -> Result<()> {
+        self.entries.lock().unwrap().remove(key);
+        Ok(())
+    }
+}
+
+// STATE_STORE: 运行状态保存位置, 以.db/.sqlite/.sqlite3结尾时使用SQLite(需启用history功能), 否则为JSON文件
+pub fn from_env() -> Option<Arc<dyn StateStore>> {
+    let path = env::var("STATE_STORE").ok().filter(|p| !p.is_empty())?;
+    let is_sqlite = [".db", ".sqlite", ".sqlite3"]
+        .iter()
+        .any(|ext| path.ends_with(ext));
+    let store: Result<Arc<dyn StateStore>> = match is_sqlite {
+        #[cfg(feature = "history")]
+        true => SqliteStateStore::open(&path).map(|s| Arc::new(s) as _),
+        #[cfg(not(feature = "history"))]
+        true => Err(anyhow::anyhow!(
+            "{}",
+            t!("使用SQLite保存运行状态需启用history功能")
+        )),
+        false => FileStateStore::open(&path).map(|s| Arc::new(s) as _),
+    };
+    match store {
+        Ok(store) => Some(store),
+        Err(e) => {
+            info!("{}", t!("打开运行状态存储{}失败, {}", path, e));
+            None
+        }
+    }
+}
+
+// 设置STATE_STORE时使用对应的存储, 否则保存在内存中
+pub fn from_env_or_memory() -> Arc<dyn StateStore> {
+    from_env().unwrap_or_else(|| Arc::new(MemoryStateStore::default()))
+}
@@ -0,0 +1,76 @@
+use std::path::Path;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use anyhow::Result;
+use async_trait::async_trait;
+use chrono::Utc;
+use rusqlite::{params, Connection, OptionalExtension};
+use serde_json::Value;
+
+use super::{Entry, StateStore};
+
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS state (
+    key TEXT PRIMARY KEY,
+    value TEXT NOT NULL,
+    expires_at INTEGER
+);
+";
+
+// 保存在SQLite数据库中的运行状态, 可与运行历史使用同一个数据库
+pub struct SqliteStateStore {
+    conn: Mutex<Connection>,
+}
+
+impl SqliteStateStore {
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self> {
+        if let Some(dir) = path.as_ref().parent() {
+            if !dir.as_os_str().is_empty() {
+                std::fs::create_dir_all(dir)?;
+            }
+        }
+        let conn = Connection::open(path)?;
+        conn.execute_batch(SCHEMA)?;
+        Ok(Self {
+            conn: Mutex::new(conn),
+        })
+    }
+}
+
+#[async_trait]
+impl StateStore for SqliteStateStore {
+    async fn get(&self, key: &str) -> Result<Option<Value>> {
+        let conn = self.conn.lock().unwrap();
+        let value: Option<String> = conn
+            .query_row(
+                "SELECT value FROM state WHERE key = ?1 AND (expires_at IS NULL OR expires_at > ?2)",
+                params![key, Utc::now().timestamp()],
+                |row| row.get(0),
+            )
+            .optional()?;
+        Ok(value.map(|v| serde_json::from_str(&v)).transpose()?)
+    }
+
+    async fn set(&self, key: &str, value: Value, ttl: Option<Duration>) -> Result<()> {
+        let entry = Entry::new(value, ttl);
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            "DELETE FROM state WHERE expires_at <= ?1",
+            params![Utc::now().timestamp()],
+        )?;
+        conn.execute(
+            "INSERT OR REPLACE INTO state (key, value, expires_at) VALUES (?1, ?2, ?3)",
+            params![key, entry.value.to_string(), entry.expires_at],
+        )?;
+        Ok(())
+    }
+
+    async fn remove(&self, key: &str) -> Result<()> {
+        self.conn
+            .lock()
+            .unwrap()
+            .execute("DELETE FROM state WHERE key = ?1", params![key])?;
+        Ok(())
+    }
+}
@@ -0,0 +1,1500 @@
+use std::collections::HashSet;
+use std::time::Duration;
+
+use anyhow::{anyhow, Result};
+use chrono::{Datelike, FixedOffset, Local, TimeZone, Timelike};
+use serde_json::json;
+use tracing::{info, info_span, instrument, Instrument};
+
+use crate::assist::AssistStore;
+use crate::clock;
+use crate::error::{ErrorPolicy, JError};
+use crate::error_codes;
+use crate::event::RunEvent;
+use crate::i18n;
+use crate::models::{
+    BrowseTaskItem, Card, CardInfo, ClaimResponse, ClockInResponse, DuckResponse, EnergyResponse,
+    FollowTask, Response, TaskInfo, TotalWaterTask, TreasureBoxResponse, TreasureBoxTask, Validate,
+    WaterFriendAwardResponse, WaterFriendTask, WaterRainTask, WaterResponse,
+    WaterTaskAwardResponse,
+};
+use crate::options::RunOptions;
+use crate::plan::RunPlan;
+use crate::redact;
+use crate::report::{AccountReport, FriendWatering, TaskError};
+use crate::shutdown;
+use crate::state::{self, jd_date};
+use crate::JClient;
+
+// 每日水滴雨轮数
+const WATER_RAIN_TIMES: u8 = 2;
+
+// 两轮水滴雨的间隔(毫秒)
+const WATER_RAIN_INTERVAL: u64 = 3 * 60 * 60 * 1000;
+
+// 三餐定时领水的时间段(北京时间, 开始及结束的小时)
+pub(crate) const THREE_MEAL_WINDOWS: [(u32, u32); 3] = [(6, 9), (11, 14), (17, 21)];
+
+impl JClient {
+    // 完成弹出的领水任务
+    #[instrument(name = "task", skip_all, fields(task = "弹出任务", outcome, gain))]
+    pub async fn do_pop_task(&self) -> Result<()> {
+        self.task_started("弹出任务");
+        let res: Response<EnergyResponse> = self
+            .call(
+                "gotWaterGoalTaskForFarm",
+                r#"{"type":3,"version":18,"channel":1,"babelChannel":"121"}"#,
+            )
+            .await?;
+
+        if res.is_success() {
+            let energy = res.data.add_energy;
+            info!(
+                "{}",
+                t!(
+                    "{}, 成功完成弹出任务, 获得水滴:{}g!",
+                    self.account.name(),
+                    energy
+                )
+            );
+            self.record_task("弹出任务", true, energy);
+        } else {
+            info!(
+                "{}",
+                t!("{}, 无法完成弹出任务, {}", self.account.name(), res)
+            );
+            self.record_task("弹出任务", false, 0);
+        }
+        Ok(())
+    }
+
+    // 浇水一次
+    pub async fn water(&self) -> Result<bool> {
+        let res: Response<WaterResponse> = self
+            .call(
+                "waterGoodForFarm",
+                r#"{"type":"","version":18,"channel":1,"babelChannel":"121"}"#,
+            )
+            .await?;
+
+        Ok(match res.is_success() {
+            true => {
+                let total_energy = res.data.total_energy;
+                info!(
+                    "{}",
+                    t!(
+                        "{}, 成功浇水一次, 剩余水滴:{}g!",
+                        self.account.name(),
+                        total_energy
+                    )
+                );
+                true
+            }
+            false => {
+                info!("{}", t!("{}, 浇水失败, {}", self.account.name(), res));
+                false
+            }
+        })
+    }
+
+    // 签到任务
+    async fn sign_in(&self) -> Result<()> {
+        // api 已不存在 signForFarm
+        Ok(())
+    }
+
+    // 十次浇水任务
+    #[instrument(name = "task", skip_all, fields(task = "十次浇水", outcome, gain))]
+    pub async fn do_total_water_task(&self, task: &TotalWaterTask) -> Result<()> {
+        self.task_started("十次浇水");
+        for _ in task.total_water_task_times..task.total_water_task_limit {
+            let _ = self.water().await?;
+            self.pause(Duration::from_secs(1)).await;
+        }
+        self.got_water_task_award("totalWaterTaskForFarm").await
+    }
+
+    // 领取浇水任务奖励
+    async fn got_water_task_award(&self, function_id: &str) -> Result<()> {
+        let task_name = match function_id {
+            "firstWaterTaskForFarm" => "首次浇水",
+            _ => "十次浇水",
+        };
+        let res: Response<WaterTaskAwardResponse> = self
+            .call(
+                function_id,
+                r#"{"version":18,"channel":1,"babelChannel":"121"}"#,
+            )
+            .await?;
+
+        match res.is_success() {
+            true => {
+                let mut amount = res.data.amount;
+                if amount == 0 {
+                    amount = res.data.total_water_task_energy;
+                }
+                info!(
+                    "{}",
+                    t!(
+                        "{}, 成功领取浇水任务奖励, 获得水滴:{}g!",
+                        self.account.name(),
+                        amount
+                    )
+                );
+                self.record_task(task_name, true, amount);
+
+                if res.data.today_got_water_goal_task.can_pop {
+                    let _ = self.do_pop_task().await;
+                };
+            }
+            false => {
+                info!(
+                    "{}",
+                    t!("{}, 领取浇水任务奖励失败, {}", self.account.name(), res)
+                );
+                self.record_task(task_name, false, 0);
+            }
+        }
+
+        Ok(())
+    }
+
+    // 首次浇水任务
+    #[instrument(name = "task", skip_all, fields(task = "首次浇水", outcome, gain))]
+    pub async fn do_first_water_task(&self) -> Result<()> {
+        self.task_started("首次浇水");
+        let bool = self.water().await?;
+        match bool {
+            true => self.got_water_task_award("firstWaterTaskForFarm").await?,
+            false => {
+                info!("{}", t!("{}, 首次浇水任务失败.", self.account.name()));
+                self.record_task("首次浇水", false, 0);
+            }
+        }
+        Ok(())
+    }
+
+    // 从APP首页免费水果进入东东农场任务
+    #[instrument(
+        name = "task",
+        skip_all,
+        fields(task = "通过“免费水果”访问农场", outcome)
+    )]
+
+    pub async fn do_treasure_box_task(&self, task: &TreasureBoxTask) -> Result<()> {
+        self.task_started("通过“免费水果”访问农场");
+        task.validate("taskInitForFarm")?;
+        let body = json!({
+            "type":1,
+            "babelChannel":"121",
+            "version":18,
+            "channel":1
+        });
+
+        let _ = self
+            .request("ddnc_getTreasureBoxAward", body.to_string().as_str())
+            .await;
+
+        self.pause(Duration::from_secs(1)).await;
+
+        let body = json!({
+            "babelChannel":"10",
+            "line": task.line,
+            "channel":3,
+            "type":2,
+            "version":18});
+
+        let res: Response<TreasureBoxResponse> = self
+            .call("ddnc_getTreasureBoxAward", body.to_string().as_str())
+            .await?;
+
+        match res.is_success() {
+            true => {
+                let amount = res.data.water_gram;
+                info!(
+                    "{}",
+                    t!(
+                        "{}, 完成任务:《通过“免费水果”访问农场》, 获得水滴:{}g!",
+                        self.account.name(),
+                        amount
+                    )
+                );
+                self.record_task("通过“免费水果”访问农场", true, amount);
+            }
+            false => {
+                info!(
+                    "{}",
+                    t!(
+                        "{}, 无法完成任务:《通过“免费水果”访问农场》,{}",
+                        self.account.name(),
+                        res
+                    )
+                );
+                self.record_task("通过“免费水果”访问农场", false, 0);
+            }
+        };
+        Ok(())
+    }
+
+    // 浏览任务
+    #[instrument(name = "task", skip_all, fields(task = "浏览广告", outcome, gain))]
+    pub async fn do_browse_task(&self, task_list: &[BrowseTaskItem]) -> Result<()> {
+        self.task_started("浏览广告");
+        for task in task_list {
+            if task.had_finished_times >= task.limit {
+                info!(
+                    "{}",
+                    t!(
+                        "{}, 今日已完成任务《{}》!",
+                        self.account.name(),
+                        task.main_title
+                    )
+                );
+                continue;
+            }
+            if let Err(e) = task.validate("taskInitForFarm") {
+                info!(
+                    "{}",
+                    t!(
+                        "{}, 执行任务:《{}》失败, {}",
+                        self.account.name(),
+                        task.main_title,
+                        e
+                    )
+                );
+                self.record_task(&task.main_title, false, 0);
+                continue;
+            }
+            let data = json!({
+                "babelChannel":"10",
+                "advertId": task.advert_id,
+                "type": 0,
+                "channel":3,
+                "version":18
+            });
+
+            let _ = self
+                .request("browseAdTaskForFarm", data.to_string().as_str())
+                .await;
+
+            info!(
+                "{}",
+                t!(
+                    "{}, 正在进行任务:《{}》, 等待{}秒...",
+                    self.account.name(),
+                    task.main_title,
+                    task.time
+                )
+            );
+            // 浏览时长由接口校验, 不受等待策略影响
+            tokio::time::sleep(Duration::from_secs(task.time.into())).await;
+
+            let data = json!({
+                "babelChannel":"10",
+                "advertId": task.advert_id,
+                "type": 1,
+                "channel":3,
+                "version":18
+            });
+            let res: Result<Response<ClaimResponse>> = self
+                .call("browseAdTaskForFarm", data.to_string().as_str())
+                .await;
+            if res.is_err() {
+                info!(
+                    "{}",
+                    t!(
+                        "{}, 执行任务:《{}》失败.",
+                        self.account.name(),
+                        task.main_title
+                    )
+                );
+                self.record_task(&task.main_title, false, 0);
+                continue;
+            }
+            let data = res.unwrap();
+
+            match data.is_success() {
+                true => {
+                    let amount = data.data.amount;
+                    info!(
+                        "{}",
+                        t!(
+                            "{}, 执行任务:《{}》成功, 获得水滴:{}g!",
+                            self.account.name(),
+                            task.main_title,
+                            amount
+                        )
+                    );
+                    self.record_task(&task.main_title, true, amount);
+                    if data.data.today_got_water_goal_task.can_pop {
+                        let _ = self.do_pop_task().await;
+                    }
+                }
+                false => {
+                    info!(
+                        "{}",
+                        t!(
+                            "{}, 执行任务:《{}》失败.",
+                            self.account.name(),
+                            task.main_title
+                        )
+                    );
+                    self.record_task(&task.main_title, false, 0);
+                    continue;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    // 水滴雨任务
+    #[instrument(name = "task", skip_all, fields(task = "收集水滴雨", outcome, gain))]
+    pub async fn do_water_rain_task(&self, task: &WaterRainTask) -> Result<()> {
+        self.task_started("收集水滴雨");
+        // 上次收集时间为服务器时间, 本机时间不准确时按服务器时间判断冷却
+        let time = clock::now().timestamp().max(0) as u64 * 1000;
+
+        if time < task.last_time + WATER_RAIN_INTERVAL {
+            self.set_next_water_rain(task.last_time + WATER_RAIN_INTERVAL);
+            info!(
+                "{}",
+                t!(
+                    "{}, 第{}次水滴雨任务未到时间!",
+                    self.account.name(),
+                    task.win_times + 1
+                )
+            );
+            return Ok(());
+        }
+        let score = self.options().water_rain.pick(&self.account.name(), time);
+        self.report.lock().unwrap().water_rain_score = Some(score);
+        let body = json!({
+            "type":1,
+            "hongBaoTimes": score,
+            "version":14,
+            "channel":1
+        });
+        let res: Response<EnergyResponse> = self
+            .call("waterRainForFarm", body.to_string().as_str())
+            .await?;
+
+        match res.is_success() {
+            true => {
+                let amount = res.data.add_energy;
+                info!(
+                    "{}",
+                    t!(
+                        "{}, 成功完成第{}次水滴雨任务, 获得水滴:{}g!",
+                        self.account.name(),
+                        task.win_times + 1,
+                        amount
+                    )
+                );
+                self.record_task("收集水滴雨", true, amount);
+                if task.win_times + 1 < WATER_RAIN_TIMES {
+                    self.set_next_water_rain(time + WATER_RAIN_INTERVAL);
+                }
+            }
+            false => {
+                info!(
+                    "{}",
+                    t!(
+                        "{}, 执行第{}次水滴雨任务失败.",
+                        self.account.name(),
+                        task.win_times + 1
+                    )
+                );
+                self.record_task("收集水滴雨", false, 0);
+            }
+        }
+        Ok(())
+    }
+
+    // 记录下一轮水滴雨开始时间, timestamp为服务器时间(毫秒时间戳), 换算为本机时间供常驻运行定时
+    fn set_next_water_rain(&self, timestamp: u64) {
+        let local = timestamp as i64 - clock::skew().num_milliseconds();
+        self.report.lock().unwrap().next_water_rain = Local.timestamp_millis_opt(local).single();
+    }
+
+    // 只执行水滴雨任务, 常驻运行时在新一轮水滴雨开始后调用
+    pub async fn run_water_rain(&self) -> Result<AccountReport> {
+        if !self.should_run("收集水滴雨") {
+            return Ok(self.report.lock().unwrap().clone());
+        }
+        self.get_farm_data().await?;
+        let task_info = self.get_task_info().await?;
+        let water_rain = task_info
+            .water_rain_init
+            .ok_or_else(|| JError::parse("taskInitForFarm", t!("缺少字段{}", "waterRainInit")))?;
+        if !water_rain.f {
+            self.do_water_rain_task(&water_rain).await?;
+        }
+        Ok(self.report.lock().unwrap().clone())
+    }
+
+    // 为两位好友浇水任务
+    #[instrument(
+        name = "task",
+        skip_all,
+        fields(task = "为两位好友浇水", outcome, gain)
+    )]
+
+    pub async fn do_water_friend_task(&self, task: &WaterFriendTask) -> Result<()> {
+        self.task_started("为两位好友浇水");
+        if task.water_friend_count_key < task.water_friend_max {
+            let friends = self.get_friends().await?;
+            let mut count = task.water_friend_max - task.water_friend_count_key;
+            // 今日已浇过水的好友, 多次运行时不再重复浇水
+            let watered_key = format!("watered:{}:{}", self.account.name(), jd_date());
+            let mut watered: HashSet<String> =
+                match state::load(self.state.as_ref(), &watered_key).await {
+                    Ok(watered) => watered,
+                    Err(e) => {
+                        info!(
+                            "{}",
+                            t!("{}, 读取已浇水的好友失败, {}", self.account.name(), e)
+                        );
+                        HashSet::new()
+                    }
+                };
+
+            for friend in friends {
+                if friend.friend_state == 0 || watered.contains(&friend.share_code) {
+                    continue;
+                }
+                let body = json!({
+                    "shareCode": friend.share_code,
+                    "version": 18,
+                    "channel": 1,
+                    "babelChannel": "121"
+                });
+                let res = self
+                    .request("waterFriendForFarm", body.to_string().as_str())
+                    .await;
+                if matches!(&res, Ok(data) if self.is_success(data)) {
+                    watered.insert(friend.share_code.clone());
+                    let ttl = Some(Duration::from_secs(2 * 24 * 3600));
+                    if let Err(e) =
+                        state::save(self.state.as_ref(), &watered_key, &watered, ttl).await
+                    {
+                        info!(
+                            "{}",
+                            t!("{}, 保存已浇水的好友失败, {}", self.account.name(), e)
+                        );
+                    }
+                }
+                count -= 1;
+                if count == 0 {
+                    break;
+                }
+                self.pause(Duration::from_secs(1)).await;
+            }
+
+            let res: Response<WaterFriendAwardResponse> = self
+                .call(
+                    "waterFriendGotAwardForFarm",
+                    r#"{"version":18,"channel":1,"babelChannel":"121"}"#,
+                )
+                .await?;
+
+            match res.is_success() {
+                true => {
+                    let amount = res.data.add_water;
+                    info!(
+                        "{}",
+                        t!(
+                            "{}, 成功领取任务:《为两位好友浇水》奖励, 获得水滴:{}g!",
+                            self.account.name(),
+                            amount
+                        )
+                    );
+                    self.record_task("为两位好友浇水", true, amount);
+                }
+                false => {
+                    info!(
+                        "{}",
+                        t!(
+                            "{}, 领取任务:《为两位好友浇水》奖励失败!",
+                            self.account.name()
+                        )
+                    );
+                    self.record_task("为两位好友浇水", false, 0);
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    // 统计今日及本周(北京时间, 周一起)为我浇水的好友, 以及本周未浇水的好友
+    pub async fn collect_watered_by(&self) -> Result<()> {
+        let records = self.get_water_records().await?;
+        let friends = self.get_friends().await?;
+        let china_timezone = FixedOffset::east_opt(8 * 3600).unwrap();
+        let today = clock::now().with_timezone(&china_timezone).date_naive();
+        let week_start =
+            today - chrono::Duration::days(today.weekday().num_days_from_monday() as i64);
+
+        let mut watered_by: Vec<FriendWatering> = Vec::new();
+        for record in records {
+            let Some(date) = china_timezone
+                .timestamp_millis_opt(record.time as i64)
+                .single()
+                .map(|t| t.date_naive())
+            else {
+                continue;
+            };
+            if date < week_start || date > today {
+                continue;
+            }
+            let index = watered_by
+                .iter()
+                .position(|f| f.share_code == record.share_code && f.name == record.nick_name);
+            let friend = match index {
+                Some(index) => &mut watered_by[index],
+                None => {
+                    watered_by.push(FriendWatering {
+                        name: record.nick_name,
+                        share_code: record.share_code,
+                        today: 0,
+                        week: 0,
+                    });
+                    watered_by.last_mut().unwrap()
+                }
+            };
+            friend.week += 1;
+            if date == today {
+                friend.today += 1;
+            }
+        }
+        watered_by.sort_by(|a, b| b.week.cmp(&a.week).then(b.today.cmp(&a.today)));
+        let idle_friends: Vec<String> = friends
+            .into_iter()
+            .filter(|f| !watered_by.iter().any(|w| w.share_code == f.share_code))
+            .map(|f| f.nick_name)
+            .collect();
+
+        info!(
+            "{}",
+            t!(
+                "{}, 本周有{}位好友为你浇水, {}位好友未浇水",
+                self.account.name(),
+                watered_by.len(),
+                idle_friends.len()
+            )
+        );
+        let mut report = self.report.lock().unwrap();
+        report.watered_by = watered_by;
+        report.idle_friends = idle_friends;
+        Ok(())
+    }
+
+    // 助力好友任务, 发布本账号的助力码并为助力码池中的其他账号助力
+    #[instrument(name = "task", skip_all, fields(task = "助力好友", outcome, gain))]
+    pub async fn do_assist_task(&self, store: &dyn AssistStore, share_code: &str) -> Result<()> {
+        self.task_started("助力好友");
+        let name = self.account.name();
+        store.publish(&name, share_code).await?;
+
+        for (target, code) in store.share_codes().await? {
+            if target == name || !store.reserve(&name, &target).await? {
+                continue;
+            }
+            let body = json!({
+                "shareCode": code,
+                "babelChannel": "3",
+                "version": 18,
+                "channel": 1
+            });
+            let res = self.request("initForFarm", body.to_string().as_str()).await;
+            let help_code = res
+                .as_ref()
+                .ok()
+                .and_then(|data| data["helpResult"]["code"].as_str())
+                .unwrap_or_default();
+            match help_code {
+                // 0: 助力成功, 9: 今日已助力过
+                "0" | "9" => {
+                    info!("{}", t!("{}, 成功助力好友: {}!", name, target));
+                    self.record_task("助力好友", true, 0);
+                }
+                // 8: 本账号今日助力次数已用完
+                "8" => {
+                    store.release(&name, &target).await?;
+                    info!("{}", t!("{}, 今日助力次数已用完!", name));
+                    break;
+                }
+                // 10: 对方今日已被助力满
+                "10" => {
+                    store.release(&name, &target).await?;
+                    store.mark_full(&target).await?;
+                }
+                _ => {
+                    store.release(&name, &target).await?;
+                    info!("{}", t!("{}, 助力好友: {}失败!", name, target));
+                    self.record_task("助力好友", false, 0);
+                }
+            }
+            self.pause(Duration::from_secs(2)).await;
+        }
+        Ok(())
+    }
+
+    // 签到领水->签到任务
+    #[instrument(
+        name = "task",
+        skip_all,
+        fields(task = "签到领水->签到", outcome, gain)
+    )]
+
+    pub async fn do_clock_in_sign_in_task(&self) -> Result<()> {
+        self.task_started("签到领水->签到");
+        let body = json!({
+            "version": 18,
+            "channel": 1,
+            "babelChannel": "121",
+            "type": 1
+        });
+        let res: Response<ClockInResponse> = self
+            .call("clockInForFarm", body.to_string().as_str())
+            .await?;
+
+        match res.is_success() {
+            true => {
+                info!(
+                    "{}",
+                    t!(
+                        "{}, 成功完成任务:《签到领水->签到》, {}",
+                        self.account.name(),
+                        res
+                    )
+                );
+                self.record_task("签到领水->签到", true, res.data.amount);
+                if self.options().cards.sign_card {
+                    if let Ok(card_info) = self.get_card_info().await {
+                        let use_num = card_info.sign_card.min(3);
+                        for _ in 0..use_num {
+                            let _ = self.use_card(Card::Sign).await;
+                            self.pause(Duration::from_secs(2)).await;
+                        }
+                    }
+                }
+            }
+            false => {
+                info!(
+                    "{}",
+                    t!("{}, 任务:《签到领水->签到》执行失败!", self.account.name())
+                );
+                self.record_task("签到领水->签到", false, 0);
+            }
+        }
+        Ok(())
+    }
+
+    // 签到领水->限时关注领水滴
+    #[instrument(
+        name = "task",
+        skip_all,
+        fields(task = "签到领水->关注", outcome, gain)
+    )]
+
+    pub async fn do_clock_in_follow_task(&self, tasks: &[FollowTask]) -> Result<()> {
+        self.task_started("签到领水->关注");
+        for task in tasks {
+            if task.had_got {
+                continue;
+            }
+            if let Err(e) = task.validate("clockInInitForFarm") {
+                info!(
+                    "{}",
+                    t!(
+                        "{}, 领取任务《关注{}》奖励失败, {}",
+                        self.account.name(),
+                        task.name,
+                        e
+                    )
+                );
+                self.record_task(&t!("关注{}", task.name), false, 0);
+                continue;
+            }
+
+            if !task.had_follow {
+                // 未关注
+                let body = json!({
+                    "id": task.id,
+                    "babelChannel": "10",
+                    "channel": 3,
+                    "type": "theme",
+                    "step":1,
+                    "version":18
+                });
+                let _ = self
+                    .request("clockInFollowForFarm", body.to_string().as_str())
+                    .await;
+                info!("{}", t!("{}, 关注《{}》!", self.account.name(), task.name));
+            }
+            let body = json!({"id": task.id,"babelChannel":"10","channel":3,"type":"theme","step":2,"version":18});
+            let res: Response<ClaimResponse> = self
+                .call("clockInFollowForFarm", body.to_string().as_str())
+                .await?;
+            match res.is_success() {
+                true => {
+                    let amount = res.data.amount;
+                    info!(
+                        "{}",
+                        t!(
+                            "{}, 成功领取任务《关注{}》奖励, 获得水滴:{}g!",
+                            self.account.name(),
+                            task.name,
+                            amount
+                        )
+                    );
+                    self.record_task(&t!("关注{}", task.name), true, amount);
+                }
+                false => {
+                    info!(
+                        "{}",
+                        t!(
+                            "{}, 领取任务《关注{}》奖励失败!",
+                            self.account.name(),
+                            task.name
+                        )
+                    );
+                    self.record_task(&t!("关注{}", task.name), false, 0);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    // 使用道具卡
+    #[instrument(name = "task", skip_all, fields(task = "使用道具卡", outcome, gain))]
+    pub async fn use_card(&self, card: Card) -> Result<()> {
+        self.task_started("使用道具卡");
+        let body = json!({
+            "cardType": card.card_type(),
+            "babelChannel":"10",
+            "channel":3,
+            "version":18
+        });
+
+        let res = self
+            .request("userMyCardForFarm", body.to_string().as_str())
+            .await?;
+        match self.is_success(&res) {
+            true => {
+                info!(
+                    "{}",
+                    t!(
+                        "{}, 使用{}成功!",
+                        self.account.name(),
+                        i18n::tr(card.name())
+                    )
+                );
+            }
+            false => {
+                info!(
+                    "{}",
+                    t!(
+                        "{}, 使用{}失败!",
+                        self.account.name(),
+                        i18n::tr(card.name())
+                    )
+                );
+            }
+        }
+        Ok(())
+    }
+
+    // 领取浇水阶段性奖励
+    // {"babelChannel":"10","channel":3,"type":4,"version":18} // 发芽
+    // {"type":1,"version":18,"channel":1,"babelChannel":"121"} // 开花
+    // {"type":3,"version":18,"channel":1,"babelChannel":"121"} // 结果
+    pub async fn got_stage_award(&self) -> Result<()> {
+        // let body = json!({"babelChannel":"10","channel":3,"type":1,"version":18});
+        // let res = self
+        //     .request("gotStageAwardForFarm", body.to_string().as_str())
+        //     .await?;
+
+        // match self.is_success(&res) {
+        //     true => {
+        //         let amount = res["addEnergy"].as_u64().unwrap_or(0);
+        //         info!(
+        //             "{}, 成功领取浇水阶段性奖励, 获得水滴:{}g!",
+        //             self.account.name(),
+        //             amount
+        //         );
+        //     }
+        //     false => {
+        //         info!("{}", t!("{}, 领取浇水阶段性奖励失败, {}", self.account.name(), res));
+        //     }
+        // }
+
+        Ok(())
+    }
+
+    // 点击小鸭子
+    #[instrument(name = "task", skip_all, fields(task = "点鸭子", outcome, gain))]
+    pub async fn click_duck(&self) -> Result<()> {
+        self.task_started("点鸭子");
+        for i in 0..10 {
+            let body = json!({"babelChannel":"10","channel":3,"type":2,"version":18});
+            let res: Response<DuckResponse> = self
+                .call("getFullCollectionReward", body.to_string().as_str())
+                .await?;
+            match res.is_success() {
+                true => {
+                    let title = res.data.title.as_str();
+                    info!(
+                        "{}",
+                        t!(
+                            "{}, 第{}次点鸭子成功, {}",
+                            self.account.name(),
+                            i + 1,
+                            title
+                        )
+                    );
+                    // 未获得水滴时为优惠券等奖品
+                    if res.data.add_water == 0 && !title.is_empty() {
+                        self.record_prize("点鸭子", title, None, None);
+                    }
+                }
+                false => {
+                    if res.code == "10" {
+                        info!("{}", t!("{}, 今日点鸭子次数已达上限!", self.account.name()));
+                        break;
+                    } else {
+                        info!(
+                            "{}",
+                            t!(
+                                "{}, 第{}次点击鸭子出错, {}!",
+                                self.account.name(),
+                                i + 1,
+                                res
+                            )
+                        );
+                    }
+                }
+            }
+            self.pause(Duration::from_secs(2)).await;
+        }
+        Ok(())
+    }
+
+    // 获取可更换种植的的商品列表
+    // getExchangeLevelList
+    // {"version":18,"channel":3,"babelChannel":"10"}
+    // async fn get_exchange_goods(&self) -> Result<()> {
+    //     //
+    //     Ok(())
+    // }
+
+    // 更换种植的商品
+    // exchangeGood
+    // {"afterSkuId":"100018093208","afterPrizeLevel":1,"babelChannel":"10","afterGoodsType":"qingjiebu5","channel":3,"version":18}
+    // async fn exchange_goods(&self) -> Result<()> {
+    //     Ok(())
+    // }
+
+    // 选择种植商品
+    // choiceGoodsForFarm
+    // {"afterSkuId":"100018093208","afterPrizeLevel":1,"babelChannel":"10","afterGoodsType":"qingjiebu5","channel":3,"version":18}
+    // async fn choic_goods(&self) -> Result<()> {
+    //     Ok(())
+    // }
+
+    // 三餐定时领水
+    #[instrument(name = "task", skip_all, fields(task = "定时领水", outcome, gain))]
+    pub async fn got_three_meal(&self) -> Result<()> {
+        self.task_started("定时领水");
+        let utc_time = clock::now();
+        let china_timezone = FixedOffset::east(8 * 3600);
+        let cur_hour = utc_time.with_timezone(&china_timezone).hour();
+        if !THREE_MEAL_WINDOWS
+            .iter()
+            .any(|(start, end)| (*start..*end).contains(&cur_hour))
+        {
+            info!(
+                "{}",
+                t!(
+                    "{}, 当前时间不在任务《定时领水》时间范围内!",
+                    self.account.name()
+                )
+            );
+        }
+        let body = json!({"type":0,"version":18,"channel":1,"babelChannel":"121"});
+
+        let res: Response<ClaimResponse> = self
+            .call("gotThreeMealForFarm", body.to_string().as_str())
+            .await?;
+        match res.is_success() {
+            true => {
+                let amount = res.data.amount;
+                info!(
+                    "{}",
+                    t!(
+                        "{}, 完成任务《定时领水》, 获得水滴:{}g!",
+                        self.account.name(),
+                        amount
+                    )
+                );
+                self.record_task("定时领水", true, amount);
+            }
+            false => {
+                info!(
+                    "{}",
+                    t!("{}, 无法完成任务《定时领水》, {}", self.account.name(), res)
+                );
+                self.record_task("定时领水", false, 0);
+            }
+        }
+
+        Ok(())
+    }
+
+    // 按错误处理策略处理任务错误, 继续执行时将错误记录到运行结果中
+    fn task_error(&self, task: &str, e: anyhow::Error) -> Result<()> {
+        match e.downcast_ref::<JError>() {
+            // 重复请求已在本次运行中成功, 不视为错误
+            Some(JError::DuplicateRequest) => return Ok(()),
+            // 完成验证前继续执行其他任务无意义, 直接停止运行该账号
+            Some(JError::NeedsVerification { .. }) => return Err(e),
+            _ => {}
+        }
+        match self.error_policy {
+            ErrorPolicy::FailFast => Err(e),
+            ErrorPolicy::ContinueOnError => {
+                self.report.lock().unwrap().task_errors.push(TaskError {
+                    task: task.to_string(),
+                    message: redact::scrub(&e.to_string()),
+                    advice: error_codes::advice(&e),
+                });
+                Ok(())
+            }
+        }
+    }
+
+    // 取出任务列表中该任务的数据, 缺少时只记录该任务的错误, 其他任务照常执行
+    fn task_data<'a, T>(
+        &self,
+        task: &str,
+        field: &str,
+        data: &'a Option<T>,
+    ) -> Result<Option<&'a T>> {
+        if data.is_none() && self.options().wants(task) {
+            let e = JError::parse("taskInitForFarm", t!("缺少字段{}", field));
+            self.task_error(task, anyhow!(e))?;
+        }
+        Ok(data.as_ref())
+    }
+
+    // 检查任务执行结果
+    fn check(&self, task: &str, res: Result<()>) -> Result<()> {
+        match res {
+            Ok(()) => Ok(()),
+            Err(e) => self.task_error(task, e),
+        }
+    }
+
+    // 是否执行该任务, 被任务过滤条件排除、试运行或正在退出时跳过
+    fn should_run(&self, task: &str) -> bool {
+        let options = self.options();
+        if !options.wants(task) {
+            return false;
+        }
+        if shutdown::is_requested() {
+            info!(
+                "{}",
+                t!(
+                    "{}, 正在退出, 跳过任务《{}》",
+                    self.account.name(),
+                    i18n::tr(task)
+                )
+            );
+            return false;
+        }
+        if options.dry_run {
+            info!(
+                "{}",
+                t!(
+                    "{}, 试运行, 跳过任务《{}》",
+                    self.account.name(),
+                    i18n::tr(task)
+                )
+            );
+            return false;
+        }
+        true
+    }
+
+    // 根据任务列表、背包及剩余水滴预计本次运行的结果
+    fn plan(&self, task_info: &TaskInfo, card_info: Option<&CardInfo>) -> RunPlan {
+        let options = self.options();
+        let water_before = self.report.lock().unwrap().water_before;
+        let now = clock::now().timestamp().max(0) as u64 * 1000;
+        let pending = [
+            (
+                "定时领水",
+                task_info.got_three_meal_init.as_ref().is_some_and(|t| !t.f),
+            ),
+            (
+                "通过“免费水果”访问农场",
+                task_info.treasure_box_init.as_ref().is_some_and(|t| !t.f),
+            ),
+            (
+                "收集水滴雨",
+                task_info
+                    .water_rain_init
+                    .as_ref()
+                    .is_some_and(|t| !t.f && now >= t.last_time + WATER_RAIN_INTERVAL),
+            ),
+            (
+                "首次浇水",
+                task_info.first_water_init.as_ref().is_some_and(|t| !t.f),
+            ),
+            (
+                "十次浇水",
+                task_info
+                    .total_water_task_init
+                    .as_ref()
+                    .is_some_and(|t| !t.f),
+            ),
+        ];
+        let tasks: Vec<String> = pending
+            .iter()
+            .filter(|(task, pending)| *pending && options.wants(task))
+            .map(|(task, _)| task.to_string())
+            .collect();
+        let mut waterings = 0;
+        if tasks.iter().any(|t| t == "首次浇水") {
+            waterings += 1;
+        }
+        if let Some(task) = task_info
+            .total_water_task_init
+            .as_ref()
+            .filter(|_| tasks.iter().any(|t| t == "十次浇水"))
+        {
+            waterings += task
+                .total_water_task_limit
+                .saturating_sub(task.total_water_task_times) as u32;
+        }
+        let double_card = options.wants("使用道具卡")
+            && options
+                .cards
+                .double_card_min_water
+                .is_some_and(|min| water_before >= min)
+            && card_info.is_some_and(|card| card.double_card >= 1);
+        RunPlan {
+            water_before,
+            tasks,
+            waterings,
+            double_card,
+        }
+    }
+
+    // 功能入口, 使用默认选项运行, 返回本次运行结果
+    pub async fn run(&self) -> Result<AccountReport> {
+        self.run_with(RunOptions::default()).await
+    }
+
+    // 按指定选项运行, 返回本次运行结果
+    pub async fn run_with(&self, opts: RunOptions) -> Result<AccountReport> {
+        let timeout = opts.account_timeout;
+        *self.options.lock().unwrap() = opts;
+        // 同一客户端再次运行时清空上次运行的结果及已成功的请求, 已改用的备用cookie继续使用
+        self.succeeded.lock().unwrap().clear();
+        {
+            let mut report = self.report.lock().unwrap();
+            let cookie_index = report.cookie_index;
+            *report = AccountReport::new(&self.account.name());
+            report.cookie_index = cookie_index;
+        }
+        self.restore_checkpoint().await;
+        let mut timed_out = false;
+        let res = match timeout {
+            Some(timeout) => tokio::time::timeout(timeout, self.run_tasks())
+                .await
+                .unwrap_or_else(|_| {
+                    timed_out = true;
+                    Err(anyhow!("{}", t!("账号运行超时({}秒)", timeout.as_secs())))
+                }),
+            None => self.run_tasks().await,
+        };
+        // 需要安全验证时部分任务可能只记录了错误, 该账号仍视为运行中断
+        let verification = self.report.lock().unwrap().verification_url.clone();
+        let res = match (res, verification) {
+            (Ok(()), Some(url)) => Err(anyhow!(JError::NeedsVerification { url })),
+            (res, _) => res,
+        };
+        // 出错、超时或被中断时保留检查点, 重新运行时继续
+        if res.is_ok() && !shutdown::is_requested() {
+            self.clear_checkpoint().await;
+        }
+        // 运行正常结束时对比预计与实际的运行结果, 试运行不执行任务, 不对比
+        let finished = res.is_ok() && !shutdown::is_requested() && !self.options().dry_run;
+        let plan = self.report.lock().unwrap().plan.clone();
+        if let Some(plan) = plan.filter(|_| finished) {
+            let mismatches = plan.verify(&self.report());
+            for mismatch in &mismatches {
+                info!(
+                    "{}",
+                    t!("{}, 与预计不符: {}", self.account.name(), mismatch)
+                );
+            }
+            self.report.lock().unwrap().plan_mismatches = mismatches;
+        }
+        let mut report = self.report.lock().unwrap().clone();
+        if let Err(e) = res {
+            // 超时及网络错误稍后重试可能成功, 风控、未登录等错误重试无意义
+            report.transient = timed_out
+                || e.chain()
+                    .any(|c| c.downcast_ref::<JError>().is_some_and(JError::is_retryable));
+            report.error = Some(redact::scrub(&e.to_string()));
+            report.advice = error_codes::advice(&e);
+        }
+        self.emit(RunEvent::AccountFinished {
+            report: Box::new(report.clone()),
+        });
+        Ok(report)
+    }
+
+    // 依次执行所有任务
+    async fn run_tasks(&self) -> Result<()> {
+        let farm_data = match self.get_farm_data().await {
+            Ok(data) => data,
+            Err(e) => {
+                info!("{}, {}", self.account.name(), e);
+                return Err(e);
+            }
+        };
+
+        // code为3时表示未登录
+        if let Some(JError::NotLoggedIn) = farm_data.error() {
+            info!("{}", t!("{}, cookie已失效!", self.account.name()));
+            self.report.lock().unwrap().cookie_expired = true;
+            return Ok(());
+        }
+
+        let can_do_pop_task = farm_data.data.today_got_water_goal_task.can_pop;
+
+        let share_code = match Self::parse_farm_info(&farm_data) {
+            Ok(farm_info) => {
+                if let Err(e) = farm_info.validate("initForFarm") {
+                    info!("{}", t!("{}, 果树信息不完整, {}", self.account.name(), e));
+                }
+                {
+                    let mut report = self.report.lock().unwrap();
+                    report.water_before = farm_info.total_energy;
+                    if !farm_info.share_code.is_empty() {
+                        report.share_code = Some(farm_info.share_code.clone());
+                    }
+                }
+                info!("{}", t!("{}: 奖品信息:\n\t奖品名称: {}\n\t奖品等级: {}\n\t剩余水滴(g): {}\n\t已浇水滴(g): {}\n\t还需浇水(g): {}\n\t果树进度(%): {}",
+                 self.account.name(),
+                 farm_info.name,
+                 farm_info.prize_level,
+                 farm_info.total_energy,
+                 farm_info.tree_energy,
+                 farm_info.tree_total_energy.saturating_sub(farm_info.tree_energy),
+                 format!("{:.1}", farm_info.progress())
+                ));
+                farm_info.share_code
+            }
+            Err(e) => {
+                info!("{}, {}", self.account.name(), e);
+                return Err(e);
+            }
+        };
+
+        let card_info = match self.get_card_info().await {
+            Ok(card) => {
+                info!(
+                    "{}", t!("{}, 背包信息: \n\t水滴换豆卡: {}\n\t快速浇水卡: {}\n\t水滴翻倍卡: {}\n\t加签卡: {}",
+                    self.account.name(),
+                    card.bean_card,
+                    card.fast_card,
+                    card.double_card,
+                    card.sign_card,
+                ));
+                Some(card)
+            }
+            Err(e) => {
+                info!("{}", t!("{}, 获取背包信息失败, {}", self.account.name(), e));
+                self.task_error("背包信息", e)?;
+                None
+            }
+        };
+
+        if can_do_pop_task && self.should_run("弹出任务") {
+            self.check("弹出任务", self.do_pop_task().await)?;
+        }
+
+        let task_info = match self.get_task_info().await {
+            Ok(info) => info,
+            Err(e) => {
+                info!("{}", t!("{}, 无法获取任务列表, {}", self.account.name(), e));
+                return Err(e);
+            }
+        };
+        if let Err(e) = task_info.validate("taskInitForFarm") {
+            info!("{}", t!("{}, 任务列表不完整, {}", self.account.name(), e));
+        }
+
+        // 运行前展示预计的运行结果, 运行结束后与实际结果对比
+        let plan = self.plan(&task_info, card_info.as_ref());
+        info!(
+            "{}",
+            t!("{}, 运行计划: {}", self.account.name(), plan.to_text())
+        );
+        self.report.lock().unwrap().plan = Some(plan);
+
+        if let Some(task) = self.task_data("签到", "signInit", &task_info.sign_init)? {
+            if !task.f {
+                if self.should_run("签到") {
+                    self.check("签到", self.sign_in().await)?;
+                }
+            } else {
+                info!("{}", t!("{}, 今日已完成《签到》任务!", self.account.name()));
+            }
+        }
+
+        if let Some(task) = self.task_data(
+            "定时领水",
+            "gotThreeMealInit",
+            &task_info.got_three_meal_init,
+        )? {
+            if !task.f {
+                if self.should_run("定时领水") {
+                    self.check("定时领水", self.got_three_meal().await)?;
+                }
+            } else {
+                info!(
+                    "{}",
+                    t!("{}, 今日已完成《定时领水》任务!", self.account.name())
+                );
+            }
+        }
+
+        if let Some(task) = self.task_data(
+            "通过“免费水果”访问农场",
+            "treasureBoxInit",
+            &task_info.treasure_box_init,
+        )? {
+            if !task.f {
+                if self.should_run("通过“免费水果”访问农场") {
+                    let res = self.do_treasure_box_task(task).await;
+                    self.check("通过“免费水果”访问农场", res)?;
+                }
+            } else {
+                info!(
+                    "{}",
+                    t!(
+                        "{}, 今日已完成《通过“免费水果”访问农场》任务!",
+                        self.account.name()
+                    )
+                );
+            }
+        }
+
+        if let Some(task) = self.task_data(
+            "浏览广告",
+            "gotBrowseTaskAdInit",
+            &task_info.got_browse_task_ad_init,
+        )? {
+            if !task.f {
+                if self.should_run("浏览广告") {
+                    let res = self.do_browse_task(&task.user_browse_task_ads).await;
+                    self.check("浏览广告", res)?;
+                }
+            } else {
+                info!(
+                    "{}",
+                    t!("{}, 今日已完成所有《浏览xxx》任务!", self.account.name())
+                );
+            }
+        }
+
+        if let Some(task) =
+            self.task_data("收集水滴雨", "waterRainInit", &task_info.water_rain_init)?
+        {
+            if !task.f {
+                if self.should_run("收集水滴雨") {
+                    let res = self.do_water_rain_task(task).await;
+                    self.check("收集水滴雨", res)?;
+                }
+            } else {
+                info!(
+                    "{}",
+                    t!("{}, 今日已完成《收集水滴雨》任务!", self.account.name())
+                );
+            }
+        }
+
+        if let Some(task) = self.task_data(
+            "为两位好友浇水",
+            "waterFriendTaskInit",
+            &task_info.water_friend_task_init,
+        )? {
+            if !task.f {
+                if self.should_run("为两位好友浇水") {
+                    let res = self.do_water_friend_task(task).await;
+                    self.check("为两位好友浇水", res)?;
+                }
+            } else {
+                info!(
+                    "{}",
+                    t!("{}, 今日已完成《为两位好友浇水》任务!", self.account.name())
+                );
+            }
+        }
+
+        if let Some(store) = self.assist.clone() {
+            if self.should_run("助力好友") {
+                // 缺少助力码时无法发布, 只跳过助力任务
+                let res = match share_code.is_empty() {
+                    true => Err(anyhow!(JError::parse(
+                        "initForFarm",
+                        t!("缺少字段{}", "shareCode")
+                    ))),
+                    false => self.do_assist_task(store.as_ref(), &share_code).await,
+                };
+                self.check("助力好友", res)?;
+            }
+        }
+
+        // 签到领水数据获取失败时只跳过签到领水相关任务
+        let clock_in_task = match self.get_clock_in_task(None).await {
+            Ok(task) => Some(task),
+            Err(e) => {
+                info!(
+                    "{}",
+                    t!("{}, 获取签到领水任务失败, {}", self.account.name(), e)
+                );
+                self.task_error("签到领水", e)?;
+                None
+            }
+        };
+        if let Some(clock_in_task) = clock_in_task {
+            if !clock_in_task.today_signed {
+                if self.should_run("签到领水->签到") {
+                    self.check("签到领水->签到", self.do_clock_in_sign_in_task().await)?;
+                }
+            } else {
+                info!(
+                    "{}",
+                    t!("{}, 今日已完成《签到领水->签到》任务!", self.account.name())
+                );
+            }
+
+            if self.should_run("签到领水->关注") {
+                let res = self.do_clock_in_follow_task(&clock_in_task.themes).await;
+                self.check("签到领水->关注", res)?;
+            }
+        }
+
+        if self.should_run("点鸭子") {
+            self.check("点鸭子", self.click_duck().await)?;
+        }
+
+        let min_water = self.options().cards.double_card_min_water;
+        if let Some(min_water) = min_water.filter(|_| self.should_run("使用道具卡")) {
+            if let Ok(farm_info) = self.get_farm_info().await {
+                if let Ok(card_info) = self.get_card_info().await {
+                    if farm_info.total_energy >= min_water && card_info.double_card >= 1 {
+                        self.check("使用道具卡", self.use_card(Card::Double).await)?;
+                    }
+                }
+            };
+        }
+
+        if let Some(task) =
+            self.task_data("首次浇水", "firstWaterInit", &task_info.first_water_init)?
+        {
+            if !task.f {
+                if self.should_run("首次浇水") {
+                    self.check("首次浇水", self.do_first_water_task().await)?;
+                }
+            } else {
+                info!(
+                    "{}",
+                    t!("{}, 今日已完成《首次浇水》任务!", self.account.name())
+                );
+            }
+        }
+
+        if let Some(task) = self.task_data(
+            "十次浇水",
+            "totalWaterTaskInit",
+            &task_info.total_water_task_init,
+        )? {
+            if !task.f {
+                if self.should_run("十次浇水") {
+                    let res = self.do_total_water_task(task).await;
+                    self.check("十次浇水", res)?;
+                }
+            } else {
+                info!(
+                    "{}",
+                    t!("{}, 今日已完成《十次浇水》任务!", self.account.name())
+                );
+            }
+        }
+
+        if self.should_run("浇水阶段奖励") {
+            self.check("浇水阶段奖励", self.got_stage_award().await)?;
+        }
+
+        // 只查询不执行操作, 试运行时同样统计; 获取失败不影响运行结果
+        if self.options().wants("好友浇水记录") && !shutdown::is_requested() {
+            if let Err(e) = self.collect_watered_by().await {
+                info!(
+                    "{}",
+                    t!("{}, 获取好友浇水记录失败, {}", self.account.name(), e)
+                );
+            }
+        }
+
+        for task in &self.plugins {
+            let name = task.name();
+            if self.should_run(name) {
+                self.task_started(name);
+                let res = task
+                    .run(self)
+                    .instrument(info_span!("task", task = name))
+                    .await;
+                self.check(name, res)?;
+            }
+        }
+
+        if let Ok(farm_info) = self.get_farm_info().await {
+            let progress = {
+                let mut report = self.report.lock().unwrap();
+                report.water_after = farm_info.total_energy;
+                report.tree_energy = farm_info.tree_energy;
+                report.tree_total_energy = farm_info.tree_total_energy;
+                // 2: 已成熟可领取, 3: 已领取未下单
+                report.harvest_ready = farm_info.tree_state == 2 || farm_info.tree_state == 3;
+                report.tree_progress()
+            };
+            if farm_info.tree_state == 2 || farm_info.tree_state == 3 {
+                self.record_prize(
+                    "果树成熟",
+                    &farm_info.name,
+                    Some(farm_info.prize_level),
+                    None,
+                );
+            }
+            info!("{}", t!("{}: 奖品信息:\n\t奖品名称: {}\n\t奖品等级: {}\n\t剩余水滴(g): {}\n\t已浇水滴(g): {}\n\t还需浇水(g): {}\n\t果树进度(%): {}",
+            self.account.name(),
+            farm_info.name,
+            farm_info.prize_level,
+            farm_info.total_energy,
+            farm_info.tree_energy,
+            farm_info.tree_total_energy.saturating_sub(farm_info.tree_energy),
+            format!("{:.1}", progress)
+           ));
+        };
+
+        Ok(())
+    }
+}
@@ -0,0 +1,123 @@
+use std::env;
+use std::sync::OnceLock;
+
+use opentelemetry::{
+    global,
+    metrics::{Counter, MeterProvider as _},
+    runtime::Tokio,
+    sdk::{metrics::MeterProvider, trace, Resource},
+    KeyValue,
+};
+use tracing::Subscriber;
+use tracing_opentelemetry::OpenTelemetryLayer;
+use tracing_subscriber::registry::LookupSpan;
+
+// 已启用的OTLP指标
+struct Instruments {
+    provider: MeterProvider,
+    water_gained: Counter<u64>,
+    tasks: Counter<u64>,
+    requests: Counter<u64>,
+}
+
+static INSTRUMENTS: OnceLock<Instruments> = OnceLock::new();
+
+fn resource() -> Resource {
+    let name = env::var("OTEL_SERVICE_NAME").unwrap_or_else(|_| "jd_farm".to_string());
+    Resource::new(vec![KeyValue::new("service.name", name)])
+}
+
+// 是否启用OTLP导出
+// OTEL_EXPORTER_OTLP_ENDPOINT: collector地址, 如http://localhost:4317
+fn enabled() -> bool {
+    env::var("OTEL_EXPORTER_OTLP_ENDPOINT").is_ok()
+}
+
+// 追踪导出layer, 未配置collector地址时返回None
+pub fn layer<S>() -> Option<OpenTelemetryLayer<S, trace::Tracer>>
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    if !enabled() {
+        return None;
+    }
+    let tracer = opentelemetry_otlp::new_pipeline()
+        .tracing()
+        .with_exporter(opentelemetry_otlp::new_exporter().tonic())
+        .with_trace_config(trace::config().with_resource(resource()))
+        .install_batch(Tokio);
+    match tracer {
+        Ok(tracer) => {
+            init_metrics();
+            Some(tracing_opentelemetry::layer().with_tracer(tracer))
+        }
+        Err(e) => {
+            eprintln!("{}", t!("OTLP追踪导出初始化失败, {}", e));
+            None
+        }
+    }
+}
+
+// 初始化指标导出
+fn init_metrics() {
+    let provider = opentelemetry_otlp::new_pipeline()
+        .metrics(Tokio)
+        .with_exporter(opentelemetry_otlp::new_exporter().tonic())
+        .with_resource(resource())
+        .build();
+    let provider = match provider {
+        Ok(provider) => provider,
+        Err(e) => {
+            eprintln!("{}", t!("OTLP指标导出初始化失败, {}", e));
+            return;
+        }
+    };
+    let meter = provider.meter("jd_farm");
+    let _ = INSTRUMENTS.set(Instruments {
+        water_gained: meter
+            .u64_counter("jd_farm.water_gained")
+            .with_description("获得的水滴(g)")
+            .init(),
+        tasks: meter
+            .u64_counter("jd_farm.tasks")
+            .with_description("任务执行次数")
+            .init(),
+        requests: meter
+            .u64_counter("jd_farm.requests")
+            .with_description("接口请求次数")
+            .init(),
+        provider,
+    });
+}
+
+// 记录一次接口请求
+pub fn record_request(function_id: &str, outcome: &'static str) {
+    if let Some(instruments) = INSTRUMENTS.get() {
+        instruments.requests.add(
+            1,
+            &[
+                KeyValue::new("function_id", function_id.to_string()),
+                KeyValue::new("outcome", outcome),
+            ],
+        );
+    }
+}
+
+// 记录一个任务结果
+pub fn record_task(account: &str, outcome: &'static str, gain: u64) {
+    if let Some(instruments) = INSTRUMENTS.get() {
+        let account = KeyValue::new("account", account.to_string());
+        instruments
+            .tasks
+            .add(1, &[account.clone(), KeyValue::new("outcome", outcome)]);
+        instruments.water_gained.add(gain, &[account]);
+    }
+}
+
+// 退出前导出剩余数据
+pub fn shutdown() {
+    if let Some(instruments) = INSTRUMENTS.get() {
+        let _ = instruments.provider.shutdown();
+    }
+    global::shutdown_tracer_provider();
+}
@@ -0,0 +1,179 @@
+use std::collections::{BTreeMap, HashMap};
+
+#[cfg(feature = "history")]
+use chrono::Local;
+use chrono::{Duration, NaiveDate};
+use serde::{Deserialize, Serialize};
+
+#[cfg(feature = "history")]
+use crate::history::History;
+use crate::report::RunReport;
+
+// 统计的历史天数
+pub const TREND_DAYS: i64 = 7;
+
+// 本次获得水滴低于历史平均值的该比例时视为骤降
+const DROP_RATIO: f64 = 0.5;
+
+// 历史平均值低于该值(g)时不判断骤降
+const DROP_MIN_AVERAGE: f64 = 10.0;
+
+// 单个账号的收益趋势
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct AccountTrend {
+    // 账号名称
+    pub name: String,
+    // 今日获得水滴(g)
+    pub today: u64,
+    // 近7日平均每日获得水滴(g)
+    pub daily_average: f64,
+    // 本次获得水滴(g)
+    pub this_run: u64,
+    // 近7日平均每次获得水滴(g)
+    pub run_average: f64,
+}
+
+impl AccountTrend {
+    // 本次收益是否骤降, 往往是账号被风控的前兆
+    pub fn dropped(&self) -> bool {
+        self.run_average >= DROP_MIN_AVERAGE
+            && (self.this_run as f64) < self.run_average * DROP_RATIO
+    }
+}
+
+// 收益趋势
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct Trends {
+    // 各账号趋势
+    pub accounts: Vec<AccountTrend>,
+}
+
+impl Trends {
+    // 根据历史运行结果计算本次运行的趋势, history不应包含本次运行
+    pub fn compute(history: &[RunReport], current: &RunReport) -> Self {
+        let today = current.started_at.date_naive();
+        let since = today - Duration::days(TREND_DAYS);
+
+        // 账号 -> 日期 -> 当日获得水滴, 以及账号 -> 每次获得水滴
+        let mut daily: HashMap<&str, BTreeMap<NaiveDate, u64>> = HashMap::new();
+        let mut runs: HashMap<&str, Vec<u64>> = HashMap::new();
+        for run in history {
+            let date = run.started_at.date_naive();
+            if date < since {
+                continue;
+            }
+            for account in &run.accounts {
+                if !account.is_ok() {
+                    continue;
+                }
+                *daily
+                    .entry(account.name.as_str())
+                    .or_default()
+                    .entry(date)
+                    .or_default() += account.water_gained();
+                if date < today {
+                    runs.entry(account.name.as_str())
+                        .or_default()
+                        .push(account.water_gained());
+                }
+            }
+        }
+
+        let accounts = current
+            .accounts
+            .iter()
+            .filter(|a| a.is_ok() && !a.skipped())
+            .map(|account| {
+                let days = daily.get(account.name.as_str());
+                let today_gained = days
+                    .and_then(|d| d.get(&today))
+                    .copied()
+                    .unwrap_or_default()
+                    + account.water_gained();
+                let past: Vec<u64> = days
+                    .map(|d| d.range(..today).map(|(_, v)| *v).collect())
+                    .unwrap_or_default();
+                let past_runs = runs.get(account.name.as_str()).cloned().unwrap_or_default();
+                AccountTrend {
+                    name: account.name.clone(),
+                    today: today_gained,
+                    daily_average: average(&past),
+                    this_run: account.water_gained(),
+                    run_average: average(&past_runs),
+                }
+            })
+            .collect();
+        Self { accounts }
+    }
+
+    // 从历史数据库计算趋势
+    #[cfg(feature = "history")]
+    pub fn from_history(history: &History, current: &RunReport) -> anyhow::Result<Self> {
+        let since = Local::now() - Duration::days(TREND_DAYS + 1);
+        let runs = history.runs_since(since)?;
+        Ok(Self::compute(&runs, current))
+    }
+
+    // 今日所有账号获得水滴(g)
+    pub fn today(&self) -> u64 {
+        self.accounts.iter().map(|a| a.today).sum()
+    }
+
+    // 近7日所有账号平均每日获得水滴(g)
+    pub fn daily_average(&self) -> f64 {
+        self.accounts.iter().map(|a| a.daily_average).sum()
+    }
+
+    // 收益骤降的账号
+    pub fn dropped_accounts(&self) -> Vec<&AccountTrend> {
+        self.accounts.iter().filter(|a| a.dropped()).collect()
+    }
+
+    // 只保留指定账号的趋势
+    pub fn filter(&self, names: &[&str]) -> Self {
+        Self {
+            accounts: self
+                .accounts
+                .iter()
+                .filter(|a| names.contains(&a.name.as_str()))
+                .cloned()
+                .collect(),
+        }
+    }
+
+    // 纯文本摘要
+    pub fn to_text(&self) -> String {
+        let mut lines = vec![t!(
+            "今日获得水滴: {}g, 近{}日平均: {}g",
+            self.today(),
+            TREND_DAYS,
+            format!("{:.0}", self.daily_average())
+        )];
+        let dropped: Vec<String> = self
+            .dropped_accounts()
+            .iter()
+            .map(|a| {
+                t!(
+                    "{}({}g, 平均{}g)",
+                    a.name,
+                    a.this_run,
+                    format!("{:.0}", a.run_average)
+                )
+            })
+            .collect();
+        if !dropped.is_empty() {
+            lines.push(t!(
+                "收益骤降账号: {}, 可能已被风控",
+                dropped.join(&t!("、"))
+            ));
+        }
+        lines.join("\n")
+    }
+}
+
+fn average(values: &[u64]) -> f64 {
+    if values.is_empty() {
+        return 0.0;
+    }
+    values.iter().sum::<u64>() as f64 / values.len() as f64
+}
@@ -0,0 +1,200 @@
+use std::env;
+use std::fmt;
+use std::sync::{OnceLock, RwLock};
+use std::time::Duration;
+
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tokio::sync::Mutex;
+use tracing::info;
+
+use crate::error::JError;
+use crate::models::{FarmData, Response};
+use crate::state::{self, StateStore};
+use crate::JClient;
+
+// 探测到的版本在运行状态中的保存时间, 过期后重新使用默认版本
+const STATE_KEY: &str = "api_version";
+const STATE_TTL: Duration = Duration::from_secs(7 * 24 * 3600);
+
+// 查询农场数据的请求参数, 探测版本时使用
+pub(crate) const INIT_FARM_BODY: &str =
+    r#"{"babelChannel":"121","sid":"","un_area":"","version":18,"channel":1}"#;
+
+// 接口版本, 即请求参数中的version及channel, 京东提高要求的版本后旧版本的请求会失败
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ApiVersion {
+    pub version: u32,
+    pub channel: u32,
+}
+
+impl ApiVersion {
+    // 请求参数中写死的版本
+    pub const DEFAULT: Self = Self::new(18, 1);
+
+    pub const fn new(version: u32, channel: u32) -> Self {
+        Self { version, channel }
+    }
+
+    // 替换请求参数中的version, channel只替换默认渠道1, 其他渠道的请求保持不变
+    pub fn apply(&self, body: &str) -> String {
+        if *self == Self::DEFAULT {
+            return body.to_string();
+        }
+        let mut data: Value = match serde_json::from_str(body) {
+            Ok(data @ Value::Object(_)) => data,
+            _ => return body.to_string(),
+        };
+        if data.get("version").is_some() {
+            data["version"] = self.version.into();
+        }
+        if data["channel"] == Self::DEFAULT.channel {
+            data["channel"] = self.channel.into();
+        }
+        data.to_string()
+    }
+}
+
+impl Default for ApiVersion {
+    fn default() -> Self {
+        Self::DEFAULT
+    }
+}
+
+impl fmt::Display for ApiVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "version={}, channel={}", self.version, self.channel)
+    }
+}
+
+// 依次探测的版本, 默认版本排在最前
+pub const CANDIDATES: [ApiVersion; 6] = [
+    ApiVersion::DEFAULT,
+    ApiVersion::new(22, 1),
+    ApiVersion::new(24, 1),
+    ApiVersion::new(25, 1),
+    ApiVersion::new(22, 3),
+    ApiVersion::new(24, 3),
+];
+
+fn cache() -> &'static RwLock<Option<ApiVersion>> {
+    static CACHE: OnceLock<RwLock<Option<ApiVersion>>> = OnceLock::new();
+    CACHE.get_or_init(|| RwLock::new(None))
+}
+
+// 同一进程只探测一次, 多个账号同时失败时等待第一个账号的探测结果
+fn probe_lock() -> &'static Mutex<bool> {
+    static PROBED: OnceLock<Mutex<bool>> = OnceLock::new();
+    PROBED.get_or_init(|| Mutex::new(false))
+}
+
+// API_VERSION/API_CHANNEL: 固定使用的接口版本, 设置后不再探测, API_CHANNEL默认为1
+pub fn fixed() -> Option<ApiVersion> {
+    let version = env::var("API_VERSION").ok()?.trim().parse().ok()?;
+    let channel = env::var("API_CHANNEL")
+        .ok()
+        .and_then(|c| c.trim().parse().ok())
+        .unwrap_or(ApiVersion::DEFAULT.channel);
+    Some(ApiVersion::new(version, channel))
+}
+
+// 当前使用的接口版本, 依次为固定的版本、探测到的版本、默认版本
+pub fn current() -> ApiVersion {
+    fixed().or(*cache().read().unwrap()).unwrap_or_default()
+}
+
+// 设置当前使用的接口版本
+pub fn set(version: ApiVersion) {
+    *cache().write().unwrap() = Some(version);
+}
+
+// 读取运行状态中保存的探测结果, 启动时调用
+pub async fn restore(store: &dyn StateStore) {
+    match state::load::<Option<ApiVersion>>(store, STATE_KEY).await {
+        Ok(Some(version)) => set(version),
+        Ok(None) => {}
+        Err(e) => info!("{}", t!("读取接口版本失败, {}", e)),
+    }
+}
+
+impl JClient {
+    // 依次使用候选版本查询农场数据, 返回第一个可用的版本并保存, 未登录或触发风控时无法判断, 直接返回错误
+    pub async fn probe_api_version(&self) -> Result<ApiVersion> {
+        if let Some(version) = fixed() {
+            return Ok(version);
+        }
+        for candidate in CANDIDATES {
+            let body = self.device.apply_body(&candidate.apply(INIT_FARM_BODY));
+            let url = format!(
+                "{}?{}&appid=signed_wh5",
+                self.base_url,
+                self.device.sign("initForFarm", &body)
+            );
+            let raw = self.send("initForFarm", &url, &body).await?;
+            let code = raw["code"].as_str().unwrap_or("999");
+            match JError::from_response(code, &raw) {
+                None if raw.get("farmUserPro").is_some() => {
+                    info!(
+                        "{}",
+                        t!("{}, 接口版本{}可用", self.account.name(), candidate)
+                    );
+                    set(candidate);
+                    if let Err(e) =
+                        state::save(self.state.as_ref(), STATE_KEY, &candidate, Some(STATE_TTL))
+                            .await
+                    {
+                        info!("{}", t!("保存接口版本失败, {}", e));
+                    }
+                    return Ok(candidate);
+                }
+                Some(e @ (JError::NotLoggedIn | JError::RiskControl { .. })) => {
+                    return Err(anyhow!(e))
+                }
+                _ => info!(
+                    "{}",
+                    t!(
+                        "{}, 接口版本{}不可用, {}",
+                        self.account.name(),
+                        candidate,
+                        raw
+                    )
+                ),
+            }
+        }
+        Err(anyhow!("{}", t!("没有可用的接口版本")))
+    }
+
+    // 查询农场数据返回异常时探测接口版本, 返回与本次请求不同的可用版本
+    pub(crate) async fn reprobe(&self, used: ApiVersion) -> Option<ApiVersion> {
+        if fixed().is_some() {
+            return None;
+        }
+        let mut probed = probe_lock().lock().await;
+        // 其他账号已探测到新版本
+        let version = current();
+        if version != used {
+            return Some(version);
+        }
+        if *probed {
+            return None;
+        }
+        *probed = true;
+        match self.probe_api_version().await {
+            Ok(version) => (version != used).then_some(version),
+            Err(e) => {
+                info!("{}", t!("{}, 探测接口版本失败, {}", self.account.name(), e));
+                None
+            }
+        }
+    }
+}
+
+// 农场数据异常是否可能由接口版本过低引起, 未登录、风控等错误与版本无关
+pub(crate) fn is_version_error(res: &Response<FarmData>) -> bool {
+    match res.error() {
+        Some(JError::BusinessError { .. }) => true,
+        None => res.data.farm_user_pro.is_none(),
+        _ => false,
+    }
+}
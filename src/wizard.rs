@@ -0,0 +1,335 @@
+use std::fs;
+use std::io::{self, BufRead, Write};
+use std::path::Path;
+use std::sync::Arc;
+
+use anyhow::{anyhow, Result};
+use jd_com::account::get_accounts;
+
+#[cfg(feature = "notify")]
+use crate::notify::{
+    bark::BarkNotifier, ding_talk::DingTalkNotifier, ntfy::NtfyNotifier,
+    push_plus::PushPlusNotifier, server_chan::ServerChanNotifier, telegram::TelegramNotifier,
+    wecom::WeComBotNotifier, Notifier,
+};
+use crate::simulate::Simulator;
+use crate::JClient;
+
+// 可配置的通知渠道: (名称, [(环境变量, 说明)])
+#[cfg(feature = "notify")]
+const CHANNELS: [(&str, &[(&str, &str)]); 7] = [
+    (
+        "Telegram",
+        &[
+            ("TG_BOT_TOKEN", "机器人token"),
+            ("TG_USER_ID", "接收消息的chat id"),
+        ],
+    ),
+    ("Server酱", &[("PUSH_KEY", "SendKey")]),
+    ("PushPlus", &[("PUSH_PLUS_TOKEN", "token")]),
+    ("Bark", &[("BARK_PUSH", "推送地址或设备key")]),
+    (
+        "钉钉机器人",
+        &[
+            ("DD_BOT_TOKEN", "access_token"),
+            ("DD_BOT_SECRET", "加签密钥, 未开启加签时留空"),
+        ],
+    ),
+    ("企业微信机器人", &[("QYWX_KEY", "机器人key")]),
+    ("ntfy", &[("NTFY_TOPIC", "主题")]),
+];
+
+// 运行方式
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RunMode {
+    // 单次运行, 由系统cron等定时调用
+    Once,
+    // 常驻运行, 每隔N分钟运行一次
+    Daemon(u64),
+    // 按定时规则常驻运行
+    Schedule(String),
+}
+
+// 初始化向导收集的配置
+#[derive(Debug, Clone)]
+pub struct InitConfig {
+    // 各账号的cookie
+    pub cookies: Vec<String>,
+    // 通知渠道名称及其环境变量
+    pub notify: Option<(String, Vec<(String, String)>)>,
+    // 运行方式
+    pub mode: RunMode,
+}
+
+impl InitConfig {
+    // 生成带注释的.env配置文件内容
+    pub fn render(&self) -> String {
+        let mut lines = vec![
+            t!("# 东东农场配置, 由`jd_farm init`生成, 可直接修改"),
+            t!("# 日志级别"),
+            "RUST_LOG=info".to_string(),
+            String::new(),
+            t!("# 京东cookie, 多个账号用&分隔, remark为可选的账号备注"),
+            format!("JD_COOKIE={}", quote(&self.cookies.join("&"))),
+        ];
+        if let Some((name, vars)) = &self.notify {
+            lines.push(String::new());
+            lines.push(t!("# 通知渠道: {}", name));
+            for (key, value) in vars {
+                lines.push(format!("{}={}", key, quote(value)));
+            }
+        }
+        lines.push(String::new());
+        match &self.mode {
+            RunMode::Once => {
+                lines.push(t!(
+                    "# 单次运行, 由系统cron、青龙面板等定时调用; 设置DAEMON_INTERVAL(分钟)后常驻运行"
+                ));
+                lines.push("# DAEMON_INTERVAL=360".to_string());
+            }
+            RunMode::Daemon(minutes) => {
+                lines.push(t!("# 常驻运行, 每隔DAEMON_INTERVAL分钟运行一次"));
+                lines.push(format!("DAEMON_INTERVAL={}", minutes));
+            }
+            RunMode::Schedule(schedule) => {
+                lines.push(t!(
+                    "# 按定时规则常驻运行, 以分号分隔, 每条为`分 时 日 月 星期 [任务1,任务2] [@账号1,账号2]`"
+                ));
+                lines.push(format!("SCHEDULE={}", quote(schedule)));
+            }
+        }
+        lines.push(String::new());
+        lines.join("\n")
+    }
+}
+
+// 以双引号包围, 转义其中的反斜杠及双引号
+fn quote(value: &str) -> String {
+    format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+// 交互式初始化向导, 从标准输入读取回答
+pub struct Wizard<R: BufRead> {
+    input: R,
+    simulator: Option<Arc<Simulator>>,
+}
+
+impl Wizard<io::StdinLock<'static>> {
+    pub fn stdin() -> Self {
+        Self::new(io::stdin().lock())
+    }
+}
+
+impl<R: BufRead> Wizard<R> {
+    pub fn new(input: R) -> Self {
+        Self {
+            input,
+            simulator: Simulator::from_env().map(Arc::new),
+        }
+    }
+
+    // 依次询问cookie、通知渠道及运行方式并实时校验, 将配置写入path
+    pub async fn run(&mut self, path: &Path) -> Result<InitConfig> {
+        if path.exists() && !self.confirm(&t!("{}已存在, 是否覆盖?", path.display()), false)?
+        {
+            return Err(anyhow!("{}", t!("已取消.")));
+        }
+        let config = InitConfig {
+            cookies: self.ask_cookies().await?,
+            notify: self.ask_notify().await?,
+            mode: self.ask_mode()?,
+        };
+        if let Some(dir) = path.parent() {
+            if !dir.as_os_str().is_empty() {
+                fs::create_dir_all(dir)?;
+            }
+        }
+        fs::write(path, config.render())?;
+        println!("{}", t!("配置已写入{}.", path.display()));
+        Ok(config)
+    }
+
+    // 读取一行回答, 已无输入时结束向导
+    fn ask(&mut self, question: &str) -> Result<String> {
+        print!("{}: ", question);
+        io::stdout().flush()?;
+        let mut line = String::new();
+        if self.input.read_line(&mut line)? == 0 {
+            return Err(anyhow!("{}", t!("输入已结束, 未写入配置.")));
+        }
+        Ok(line.trim().to_string())
+    }
+
+    // 是否确认, 直接回车时为default
+    fn confirm(&mut self, question: &str, default: bool) -> Result<bool> {
+        let hint = if default { "Y/n" } else { "y/N" };
+        let answer = self.ask(&format!("{} [{}]", question, hint))?;
+        Ok(match answer.to_lowercase().as_str() {
+            "y" | "yes" => true,
+            "n" | "no" => false,
+            _ => default,
+        })
+    }
+
+    // 询问各账号的cookie, 逐个请求接口检查是否有效
+    async fn ask_cookies(&mut self) -> Result<Vec<String>> {
+        println!(
+            "{}",
+            t!("请输入京东cookie(pt_pin=xxx;pt_key=xxx;remark=备注;), 每行一个, 直接回车结束:")
+        );
+        let mut cookies = Vec::new();
+        loop {
+            let cookie = self.ask(&t!("账号{}", cookies.len() + 1))?;
+            if cookie.is_empty() {
+                if cookies.is_empty() {
+                    println!("{}", t!("至少需要一个账号."));
+                    continue;
+                }
+                break;
+            }
+            let Some(account) = get_accounts(cookie.clone()).into_iter().next() else {
+                println!("{}", t!("cookie格式错误, 需包含pt_pin及pt_key."));
+                continue;
+            };
+            let name = account.name();
+            let mut client = JClient::new(account);
+            if let Some(simulator) = &self.simulator {
+                client = client.with_simulator(simulator.clone());
+            }
+            let keep = match client.check_cookie().await {
+                Ok(true) => {
+                    println!("{}", t!("{}, cookie有效.", name));
+                    true
+                }
+                Ok(false) => self.confirm(&t!("{}, cookie已失效, 是否仍然保存?", name), false)?,
+                Err(e) => {
+                    self.confirm(&t!("{}, 检查cookie失败, {}, 是否仍然保存?", name, e), false)?
+                }
+            };
+            if keep {
+                cookies.push(cookie.trim_end_matches('&').to_string());
+            }
+        }
+        Ok(cookies)
+    }
+
+    // 选择通知渠道并发送测试通知
+    #[cfg(feature = "notify")]
+    async fn ask_notify(&mut self) -> Result<Option<(String, Vec<(String, String)>)>> {
+        println!("{}", t!("通知渠道:"));
+        println!("  0. {}", t!("不发送通知"));
+        for (i, (name, _)) in CHANNELS.iter().enumerate() {
+            println!("  {}. {}", i + 1, name);
+        }
+        loop {
+            let answer = self.ask(&t!("请选择"))?;
+            let index: usize = match answer.parse() {
+                Ok(index) if index <= CHANNELS.len() => index,
+                _ if answer.is_empty() => 0,
+                _ => continue,
+            };
+            let Some((name, fields)) = index.checked_sub(1).map(|i| CHANNELS[i]) else {
+                return Ok(None);
+            };
+            let mut answers = Vec::new();
+            for (key, description) in fields {
+                let value = self.ask(&format!("{} ({})", key, t!(description)))?;
+                answers.push((*key, value));
+            }
+            let Some(notifier) = notifier(&answers) else {
+                println!("{}", t!("配置不完整, 请重新选择."));
+                continue;
+            };
+            match notifier
+                .send(
+                    &t!("东东农场"),
+                    &t!("测试通知, 收到该消息说明通知配置正确."),
+                )
+                .await
+            {
+                Ok(()) => println!("{}", t!("已发送测试通知, 请检查是否收到.")),
+                Err(e) => {
+                    println!("{}", t!("发送测试通知失败, {}", e));
+                    if !self.confirm(&t!("是否仍然保存?"), false)? {
+                        continue;
+                    }
+                }
+            }
+            let vars = answers
+                .into_iter()
+                .filter(|(_, value)| !value.is_empty())
+                .map(|(key, value)| (key.to_string(), value))
+                .collect();
+            return Ok(Some((name.to_string(), vars)));
+        }
+    }
+
+    #[cfg(not(feature = "notify"))]
+    async fn ask_notify(&mut self) -> Result<Option<(String, Vec<(String, String)>)>> {
+        println!(
+            "{}",
+            t!("未启用notify功能, 跳过通知配置, 请使用--features notify重新编译.")
+        );
+        Ok(None)
+    }
+
+    // 选择运行方式, 校验运行间隔及定时规则
+    fn ask_mode(&mut self) -> Result<RunMode> {
+        println!("{}", t!("运行方式:"));
+        println!("  1. {}", t!("单次运行, 由系统cron、青龙面板等定时调用"));
+        println!("  2. {}", t!("常驻运行, 每隔一段时间运行一次"));
+        #[cfg(feature = "scheduler")]
+        println!("  3. {}", t!("按定时规则常驻运行"));
+        loop {
+            match self.ask(&t!("请选择"))?.as_str() {
+                "" | "1" => return Ok(RunMode::Once),
+                "2" => match self.ask(&t!("运行间隔(分钟)"))?.parse::<u64>() {
+                    Ok(minutes) if minutes > 0 => return Ok(RunMode::Daemon(minutes)),
+                    _ => println!("{}", t!("请输入大于0的整数.")),
+                },
+                #[cfg(feature = "scheduler")]
+                "3" => {
+                    let schedule =
+                        self.ask(&t!("定时规则, 如`10 7 * * *; 0 */3 * * * 收集水滴雨`"))?;
+                    let entries: Vec<&str> = schedule
+                        .split(';')
+                        .map(str::trim)
+                        .filter(|s| !s.is_empty())
+                        .collect();
+                    match entries
+                        .iter()
+                        .try_for_each(|e| crate::scheduler::ScheduleEntry::parse(e).map(|_| ()))
+                    {
+                        Ok(()) if !entries.is_empty() => return Ok(RunMode::Schedule(schedule)),
+                        Ok(()) => {}
+                        Err(e) => println!("{}", e),
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+}
+
+// 按填写的环境变量创建通知渠道, 配置不完整时为空
+#[cfg(feature = "notify")]
+fn notifier(answers: &[(&str, String)]) -> Option<Box<dyn Notifier>> {
+    // 与各渠道的from_env使用相同的环境变量, 留空的项不使用已有的设置
+    for (key, value) in answers {
+        match value.is_empty() {
+            true => std::env::remove_var(key),
+            false => std::env::set_var(key, value),
+        }
+    }
+    let notifier: Box<dyn Notifier> = match answers.first()?.0 {
+        "TG_BOT_TOKEN" => Box::new(TelegramNotifier::from_env()?),
+        "PUSH_KEY" => Box::new(ServerChanNotifier::from_env()?),
+        "PUSH_PLUS_TOKEN" => Box::new(PushPlusNotifier::from_env()?),
+        "BARK_PUSH" => Box::new(BarkNotifier::from_env()?),
+        "DD_BOT_TOKEN" => Box::new(DingTalkNotifier::from_env()?),
+        "QYWX_KEY" => Box::new(WeComBotNotifier::from_env()?),
+        "NTFY_TOPIC" => Box::new(NtfyNotifier::from_env()?),
+        _ => return None,
+    };
+    Some(notifier)
+}
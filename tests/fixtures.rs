@@ -0,0 +1,264 @@
+// 接口返回数据解析的回归测试
+// tests/fixtures下为脱敏后的真实接口返回数据, 文件名为`functionId[_场景].json`
+// 京东修改接口格式后在此更新或补充对应的数据, 新增数据需在FIXTURES中登记并编写对应的测试
+use std::collections::BTreeSet;
+use std::fs;
+use std::path::PathBuf;
+
+use jd_farm::error::JError;
+use jd_farm::models::{
+    CardInfo, ClaimResponse, ClockInResponse, ClockInTask, DuckResponse, EnergyResponse, FarmData,
+    FriendInfoList, TaskInfo, TreasureBoxResponse, Validate, WaterFriendAwardResponse,
+    WaterResponse, WaterTaskAwardResponse,
+};
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+
+// 已编写测试的数据文件
+const FIXTURES: &[&str] = &[
+    "browseAdTaskForFarm",
+    "clockInFollowForFarm",
+    "clockInForFarm",
+    "clockInInitForFarm",
+    "ddnc_getTreasureBoxAward",
+    "firstWaterTaskForFarm",
+    "friendListInitForFarm",
+    "getFullCollectionReward",
+    "getFullCollectionReward_limit",
+    "gotThreeMealForFarm",
+    "gotWaterGoalTaskForFarm",
+    "initForFarm",
+    "initForFarm_help",
+    "initForFarm_not_logged_in",
+    "initForFarm_partial",
+    "myCardInfoForFarm",
+    "taskInitForFarm",
+    "taskInitForFarm_partial",
+    "totalWaterTaskForFarm",
+    "waterFriendGotAwardForFarm",
+    "waterGoodForFarm",
+    "waterGoodForFarm_risk",
+    "waterRainForFarm",
+];
+
+fn fixtures_dir() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures")
+}
+
+fn raw(name: &str) -> Value {
+    let path = fixtures_dir().join(format!("{}.json", name));
+    let content = fs::read_to_string(&path).unwrap_or_else(|e| panic!("{}: {}", path.display(), e));
+    serde_json::from_str(&content).unwrap_or_else(|e| panic!("{}: {}", path.display(), e))
+}
+
+fn parse<T: DeserializeOwned>(name: &str) -> T {
+    serde_json::from_value(raw(name)).unwrap_or_else(|e| panic!("{}: {}", name, e))
+}
+
+#[test]
+fn every_fixture_is_covered() {
+    let files: BTreeSet<String> = fs::read_dir(fixtures_dir())
+        .unwrap()
+        .filter_map(|entry| {
+            let path = entry.unwrap().path();
+            (path.extension()? == "json").then(|| path.file_stem()?.to_str().map(String::from))?
+        })
+        .collect();
+    let covered: BTreeSet<String> = FIXTURES.iter().map(|f| f.to_string()).collect();
+    assert_eq!(files, covered);
+}
+
+#[test]
+fn farm_data() {
+    let data: FarmData = parse("initForFarm");
+    let farm = data.farm_user_pro.expect("farmUserPro");
+    assert_eq!(farm.total_energy, 1234);
+    assert_eq!(farm.tree_state, 1);
+    assert_eq!(farm.tree_energy, 3620);
+    assert_eq!(farm.tree_total_energy, 7200);
+    assert_eq!(farm.share_code, "0123456789abcdef0123456789abcdef");
+    assert_eq!(farm.prize_level, 2);
+    assert!(data.today_got_water_goal_task.can_pop);
+}
+
+#[test]
+fn farm_data_not_logged_in() {
+    let raw = raw("initForFarm_not_logged_in");
+    let data: FarmData = serde_json::from_value(raw.clone()).unwrap();
+    assert!(data.farm_user_pro.is_none());
+    assert!(matches!(
+        JError::from_response(raw["code"].as_str().unwrap(), &raw),
+        Some(JError::NotLoggedIn)
+    ));
+}
+
+#[test]
+fn farm_data_help_result() {
+    let raw = raw("initForFarm_help");
+    assert_eq!(raw["helpResult"]["code"], "9");
+    let data: FarmData = serde_json::from_value(raw).unwrap();
+    assert!(data.farm_user_pro.is_some());
+    assert!(!data.today_got_water_goal_task.can_pop);
+}
+
+#[test]
+fn farm_data_partial() {
+    // 缺少字段时仍可解析, 由校验指出缺少的关键字段
+    let data: FarmData = parse("initForFarm_partial");
+    let farm = data.farm_user_pro.expect("farmUserPro");
+    assert_eq!(farm.total_energy, 1234);
+    assert_eq!(farm.tree_total_energy, 0);
+    assert_eq!(farm.missing_fields(), ["shareCode", "treeTotalEnergy"]);
+    assert!(farm.validate("initForFarm").is_err());
+    assert!(!data.today_got_water_goal_task.can_pop);
+}
+
+#[test]
+fn task_info() {
+    let info: TaskInfo = parse("taskInitForFarm");
+    assert!(info.missing_fields().is_empty());
+    assert!(info.sign_init.unwrap().f);
+    assert!(!info.first_water_init.unwrap().f);
+    let total_water = info.total_water_task_init.unwrap();
+    assert_eq!(total_water.total_water_task_limit, 10);
+    assert_eq!(total_water.total_water_task_times, 2);
+    let water_friend = info.water_friend_task_init.unwrap();
+    assert_eq!(water_friend.water_friend_max, 2);
+    assert_eq!(water_friend.water_friend_count_key, 1);
+    let ads = info.got_browse_task_ad_init.unwrap().user_browse_task_ads;
+    assert_eq!(ads.len(), 2);
+    assert_eq!(ads[0].advert_id, "4203181742");
+    assert_eq!(ads[0].time, 6);
+    assert_eq!(ads[1].had_finished_times, ads[1].limit);
+    let treasure_box = info.treasure_box_init.unwrap();
+    assert_eq!(treasure_box.line, "getBean");
+    assert!(treasure_box.validate("taskInitForFarm").is_ok());
+    let water_rain = info.water_rain_init.unwrap();
+    assert_eq!(water_rain.win_times, 1);
+    assert_eq!(water_rain.last_time, 1696190000000);
+    assert!(!info.got_three_meal_init.unwrap().f);
+}
+
+#[test]
+fn task_info_partial() {
+    // 缺少的任务为空, 其他任务不受影响
+    let info: TaskInfo = parse("taskInitForFarm_partial");
+    assert_eq!(
+        info.missing_fields(),
+        ["waterFriendTaskInit", "waterRainInit"]
+    );
+    assert!(!info.sign_init.unwrap().f);
+    let total_water = info.total_water_task_init.unwrap();
+    assert_eq!(total_water.total_water_task_limit, 10);
+    assert_eq!(total_water.total_water_task_times, 0);
+    let ads = info.got_browse_task_ad_init.unwrap().user_browse_task_ads;
+    assert_eq!(ads[0].missing_fields(), ["advertId"]);
+    assert_eq!(ads[0].had_got_times, 0);
+    assert!(info
+        .treasure_box_init
+        .unwrap()
+        .validate("taskInitForFarm")
+        .is_err());
+}
+
+#[test]
+fn clock_in_task() {
+    let task: ClockInTask = parse("clockInInitForFarm");
+    assert!(!task.today_signed);
+    assert_eq!(task.themes.len(), 2);
+    assert_eq!(task.themes[0].id, "1001");
+    assert!(!task.themes[0].had_follow);
+    assert!(task.themes[1].had_got);
+}
+
+#[test]
+fn friend_list() {
+    let list: FriendInfoList = parse("friendListInitForFarm");
+    assert_eq!(list.friends.len(), 2);
+    assert_eq!(list.friends[0].friend_state, 1);
+    assert_eq!(
+        list.friends[1].share_code,
+        "00112233445566778899aabbccddeeff"
+    );
+}
+
+#[test]
+fn card_info() {
+    let cards: CardInfo = parse("myCardInfoForFarm");
+    assert_eq!(cards.bean_card, 0);
+    assert_eq!(cards.fast_card, 1);
+    assert_eq!(cards.double_card, 2);
+    assert_eq!(cards.sign_card, 3);
+}
+
+#[test]
+fn water() {
+    let res: WaterResponse = parse("waterGoodForFarm");
+    assert_eq!(res.total_energy, 1224);
+}
+
+#[test]
+fn water_risk_control() {
+    let raw = raw("waterGoodForFarm_risk");
+    assert!(matches!(
+        JError::from_response(raw["code"].as_str().unwrap(), &raw),
+        Some(JError::RiskControl { .. })
+    ));
+}
+
+#[test]
+fn energy() {
+    let res: EnergyResponse = parse("gotWaterGoalTaskForFarm");
+    assert_eq!(res.add_energy, 10);
+    let res: EnergyResponse = parse("waterRainForFarm");
+    assert_eq!(res.add_energy, 54);
+}
+
+#[test]
+fn claim() {
+    let res: ClaimResponse = parse("browseAdTaskForFarm");
+    assert_eq!(res.amount, 5);
+    assert!(!res.today_got_water_goal_task.can_pop);
+    let res: ClaimResponse = parse("gotThreeMealForFarm");
+    assert_eq!(res.amount, 15);
+    let res: ClaimResponse = parse("clockInFollowForFarm");
+    assert_eq!(res.amount, 5);
+}
+
+#[test]
+fn water_task_award() {
+    let res: WaterTaskAwardResponse = parse("firstWaterTaskForFarm");
+    assert_eq!(res.amount, 10);
+    assert!(res.today_got_water_goal_task.can_pop);
+    let res: WaterTaskAwardResponse = parse("totalWaterTaskForFarm");
+    assert_eq!(res.amount, 0);
+    assert_eq!(res.total_water_task_energy, 15);
+}
+
+#[test]
+fn clock_in() {
+    let res: ClockInResponse = parse("clockInForFarm");
+    assert_eq!(res.amount, 5);
+}
+
+#[test]
+fn treasure_box() {
+    let res: TreasureBoxResponse = parse("ddnc_getTreasureBoxAward");
+    assert_eq!(res.water_gram, 20);
+}
+
+#[test]
+fn water_friend_award() {
+    let res: WaterFriendAwardResponse = parse("waterFriendGotAwardForFarm");
+    assert_eq!(res.add_water, 30);
+}
+
+#[test]
+fn duck() {
+    let res: DuckResponse = parse("getFullCollectionReward");
+    assert_eq!(res.add_water, 20);
+    assert!(!res.title.is_empty());
+    // 次数用完时只有返回码
+    let raw = raw("getFullCollectionReward_limit");
+    assert_eq!(raw["code"], "10");
+}